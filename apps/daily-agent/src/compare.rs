@@ -0,0 +1,140 @@
+use tracing::{info, warn};
+use llm_client::{CostTracker, LlmClient, LlmOptions, LlmProvider, Verdict};
+
+use crate::publish::Publisher;
+
+pub(crate) const COMPARE_PROVIDERS_ENV_VAR: &str = "COMPARE_PROVIDERS";
+
+/// `COMPARE_PROVIDERS`: comma-separated providers whose summaries are compared side by side,
+/// e.g. `gemini,claude`. Unknown names are skipped; fewer than two providers turns it off.
+pub(crate) fn compare_providers_from_env() -> Vec<LlmProvider> {
+    let Ok(raw) = std::env::var(COMPARE_PROVIDERS_ENV_VAR) else {
+        return Vec::new();
+    };
+    let mut providers = Vec::new();
+    for name in raw.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        match name.parse::<LlmProvider>() {
+            Ok(provider) if !providers.contains(&provider) => providers.push(provider),
+            Ok(_) => {}
+            Err(e) => warn!(value = %name, error = %e, "Invalid provider in {}, skipping", COMPARE_PROVIDERS_ENV_VAR),
+        }
+    }
+    if providers.len() < 2 {
+        warn!(value = %raw, "{} needs at least two providers, not comparing", COMPARE_PROVIDERS_ENV_VAR);
+        return Vec::new();
+    }
+    providers
+}
+
+/// Object path of `provider`'s copy of the summary of `date` in a comparison.
+pub(crate) fn compare_summary_path(date: &str, provider: LlmProvider) -> String {
+    format!("summaries/{}-{}.md", date, provider.as_str())
+}
+
+/// Judge for a comparison: an enabled provider that isn't compared, to avoid self-preference,
+/// else the first enabled one.
+pub(crate) fn pick_judge<'a>(enabled_providers: &'a [LlmClient], compared: &[LlmProvider]) -> Option<&'a LlmClient> {
+    enabled_providers
+        .iter()
+        .find(|llm| !compared.contains(&llm.provider()))
+        .or(enabled_providers.first())
+}
+
+/// Stage each of `summaries` (to `prompt`) under [`compare_summary_path`] and ask `judge`
+/// which is best. `None` when fewer than two summaries were produced or the judge failed.
+pub(crate) async fn compare_summaries(
+    judge: &LlmClient,
+    prompt: &str,
+    summaries: &[(LlmProvider, String)],
+    publisher: &mut Publisher,
+    date: &str,
+    cost: &mut CostTracker,
+) -> Option<Verdict> {
+    for (provider, summary) in summaries {
+        publisher.stage_leaf(&compare_summary_path(date, *provider), summary.clone());
+    }
+    if summaries.len() < 2 {
+        warn!(summaries = summaries.len(), "Too few summaries to compare, skipping the judge");
+        return None;
+    }
+
+    let responses: Vec<(LlmProvider, &str)> = summaries.iter().map(|(provider, summary)| (*provider, summary.as_str())).collect();
+    let options = LlmOptions { temperature: Some(0.0), ..Default::default() };
+    match judge.judge(prompt, &responses, &options, cost).await {
+        Ok(verdict) => {
+            info!(judge = %judge.provider().as_str(), winner = %verdict.winner.as_str(), "Compared summaries");
+            Some(verdict)
+        }
+        Err(e) => {
+            warn!(judge = %judge.provider().as_str(), error = %e, "Comparison judge failed");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn client(provider: LlmProvider, server: &MockServer) -> LlmClient {
+        LlmClient::builder(provider).api_key("key").model("test-model").base_url(server.uri()).build().unwrap()
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_compare_providers_from_env() {
+        for (value, expected) in [
+            (None, vec![]),
+            (Some("gemini,claude"), vec![LlmProvider::Gemini, LlmProvider::Claude]),
+            (Some(" Claude , llama, openai,claude "), vec![LlmProvider::Claude, LlmProvider::OpenAI]),
+            (Some("gemini,gemini"), vec![]),
+            (Some("claude"), vec![]),
+        ] {
+            match value {
+                Some(v) => std::env::set_var(COMPARE_PROVIDERS_ENV_VAR, v),
+                None => std::env::remove_var(COMPARE_PROVIDERS_ENV_VAR),
+            }
+            assert_eq!(compare_providers_from_env(), expected, "{:?}", value);
+        }
+        std::env::remove_var(COMPARE_PROVIDERS_ENV_VAR);
+    }
+
+    #[tokio::test]
+    async fn test_both_summaries_are_staged_and_judged() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/v1beta/models/test-model:generateContent"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "candidates": [{ "content": { "parts": [{ "text": "Gemini summary" }] }, "finishReason": "STOP" }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "content": [{ "text": "Claude summary" }] })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST")).and(path("/chat/completions")).and(body_string_contains("Claude summary"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{ "message": { "content": "{\"index\": 1, \"reason\": \"Sharper.\"}" } }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let compared = [LlmProvider::Gemini, LlmProvider::Claude];
+        let enabled: Vec<LlmClient> = [LlmProvider::Gemini, LlmProvider::Claude, LlmProvider::OpenAI].into_iter().map(|p| client(p, &server)).collect();
+        let mut summaries = Vec::new();
+        for llm in enabled.iter().filter(|llm| compared.contains(&llm.provider())) {
+            summaries.push((llm.provider(), llm.generate("Summarize").await.unwrap().text));
+        }
+        let judge = pick_judge(&enabled, &compared).unwrap();
+        assert_eq!(judge.provider(), LlmProvider::OpenAI);
+
+        let mut publisher = Publisher::new("2026-03-20");
+        let verdict = compare_summaries(judge, "Summarize", &summaries, &mut publisher, "2026-03-20", &mut CostTracker::default()).await;
+        assert_eq!(publisher.content("summaries/2026-03-20-gemini.md"), Some("Gemini summary"));
+        assert_eq!(publisher.content("summaries/2026-03-20-claude.md"), Some("Claude summary"));
+        assert_eq!(verdict, Some(Verdict { winner: LlmProvider::Claude, reason: Some("Sharper.".to_string()) }));
+    }
+}
@@ -0,0 +1,266 @@
+//! Multi-article digests: with `DIGEST_SIZE` above 1 the final pick names several articles,
+//! each is summarized, and the day's summary becomes one briefing with a section per article
+//! under a short "why it matters today" intro.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::fetcher::Article;
+use crate::manifest::DigestArticle;
+
+/// Environment variable with the number of articles in the day's summary
+pub(crate) const DIGEST_SIZE_ENV_VAR: &str = "DIGEST_SIZE";
+/// Articles in the summary when `DIGEST_SIZE` is unset or invalid: the single-article summary
+pub(crate) const DEFAULT_DIGEST_SIZE: usize = 1;
+/// Upper bound for `DIGEST_SIZE`, the size of the shortlist the picks come from
+pub(crate) const MAX_DIGEST_SIZE: usize = 5;
+/// Characters of each section's summary shown to the model writing the intro
+const INTRO_SUMMARY_CHARS: usize = 600;
+
+/// `DIGEST_SIZE`, clamped to 1–5. Unset or invalid is 1.
+pub(crate) fn digest_size_from_env() -> usize {
+    match std::env::var(DIGEST_SIZE_ENV_VAR) {
+        Ok(raw) => match raw.trim().parse::<usize>() {
+            Ok(size) if size > 0 => size.min(MAX_DIGEST_SIZE),
+            _ => {
+                warn!(value = %raw, "Invalid {}, summarizing one article", DIGEST_SIZE_ENV_VAR);
+                DEFAULT_DIGEST_SIZE
+            }
+        },
+        Err(_) => DEFAULT_DIGEST_SIZE,
+    }
+}
+
+/// Digest selection reply, e.g. `{"picks": [{"index": 3, "reason": "..."}]}`.
+#[derive(Deserialize, Debug, PartialEq)]
+pub(crate) struct DigestReply {
+    pub(crate) picks: Vec<DigestPick>,
+}
+
+/// One entry of the digest selection reply.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct DigestPick {
+    pub(crate) index: usize,
+    /// The model's one-line justification
+    #[serde(default)]
+    pub(crate) reason: Option<String>,
+}
+
+/// The first `size` picks that are on the shortlist, without repeats. When the reply names
+/// none, the shortlist's order stands in, without reasons.
+pub(crate) fn valid_picks(reply: Option<Vec<DigestPick>>, shortlist: &[usize], size: usize) -> Vec<DigestPick> {
+    let mut picks: Vec<DigestPick> = Vec::new();
+    for pick in reply.unwrap_or_default() {
+        if !shortlist.contains(&pick.index) {
+            warn!(picked = pick.index, "Digest pick not in shortlist, skipping");
+        } else if picks.len() < size && !picks.iter().any(|p| p.index == pick.index) {
+            let reason = pick.reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+            picks.push(DigestPick { index: pick.index, reason });
+        }
+    }
+    if picks.is_empty() {
+        warn!("No valid digest picks, using the shortlist order");
+        picks = shortlist.iter().take(size).map(|&index| DigestPick { index, reason: None }).collect();
+    }
+    picks
+}
+
+/// `picks` led by the article exploration chose instead of the model's top pick, keeping
+/// `size` articles.
+pub(crate) fn lead_with(picks: Vec<DigestPick>, explored: Option<usize>, size: usize) -> Vec<DigestPick> {
+    let Some(index) = explored else { return picks };
+    let lead = DigestPick { index, reason: Some("Exploration pick from a source not featured recently".to_string()) };
+    std::iter::once(lead).chain(picks.into_iter().filter(|p| p.index != index)).take(size).collect()
+}
+
+/// One summarized article of the digest.
+#[derive(Debug, Clone)]
+pub(crate) struct DigestSection {
+    pub(crate) title: String,
+    pub(crate) source: String,
+    pub(crate) url: String,
+    pub(crate) reason: Option<String>,
+    pub(crate) summary: String,
+}
+
+impl DigestSection {
+    pub(crate) fn new(article: &Article, reason: Option<String>, summary: String) -> Self {
+        Self { title: article.title.clone(), source: article.source_label().to_string(), url: article.url.clone(), reason, summary }
+    }
+}
+
+/// The `{articles}` of the intro prompt: each section's title, source and the start of its
+/// summary.
+pub(crate) fn intro_articles(sections: &[DigestSection]) -> String {
+    sections
+        .iter()
+        .enumerate()
+        .map(|(i, section)| {
+            let summary: String = section.summary.chars().take(INTRO_SUMMARY_CHARS).collect();
+            format!("{}. [{}] {}\n{}\n", i + 1, section.source, section.title, summary.trim())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The manifest's list of the articles in `sections`; empty for a single-article summary,
+/// whose entry already names its article. `feed_urls` maps resolved URLs to the feed links
+/// that redirected to them.
+pub(crate) fn manifest_articles(sections: &[DigestSection], feed_urls: &HashMap<String, String>) -> Vec<DigestArticle> {
+    if sections.len() < 2 {
+        return Vec::new();
+    }
+    sections.iter()
+        .map(|s| DigestArticle { title: s.title.clone(), original_url: s.url.clone(), feed_url: feed_urls.get(&s.url).cloned() })
+        .collect()
+}
+
+/// The day's summary: a single section's summary as is, or a briefing titled with `date`
+/// that opens with `intro` and has a section per article, each under its title with source,
+/// link and the reason it was picked. Headings inside each summary are pushed one level down.
+pub(crate) fn assemble_digest(date: &str, intro: Option<&str>, sections: &[DigestSection]) -> String {
+    if let [section] = sections {
+        return section.summary.clone();
+    }
+    let mut digest = format!("# Engineering Briefing — {}\n\n", date);
+    if let Some(intro) = intro.map(str::trim).filter(|intro| !intro.is_empty()) {
+        digest.push_str(&format!("{}\n\n", intro));
+    }
+    for (i, section) in sections.iter().enumerate() {
+        digest.push_str(&format!("## {}. {}\n\n*{}* · [Read the original]({})\n\n", i + 1, section.title, section.source, section.url));
+        if let Some(reason) = &section.reason {
+            digest.push_str(&format!("> Why it's here: {}\n\n", reason));
+        }
+        digest.push_str(demote_headings(&section.summary).trim());
+        digest.push_str("\n\n");
+    }
+    info!(articles = sections.len(), "Assembled digest");
+    digest.trim_end().to_string() + "\n"
+}
+
+/// `markdown` with every ATX heading outside code fences two levels deeper, so a summary's
+/// own `#` title sits below its `##` section heading.
+fn demote_headings(markdown: &str) -> String {
+    let mut in_fence = false;
+    markdown
+        .lines()
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                in_fence = !in_fence;
+            }
+            if !in_fence && line.starts_with('#') {
+                format!("##{}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(title: &str, reason: Option<&str>, summary: &str) -> DigestSection {
+        DigestSection {
+            title: title.to_string(),
+            source: "Blog".to_string(),
+            url: format!("https://blog.example.com/{}", title.to_lowercase().replace(' ', "-")),
+            reason: reason.map(str::to_string),
+            summary: summary.to_string(),
+        }
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_digest_size_from_env() {
+        for (value, expected) in [(None, 1), (Some("3"), 3), (Some(" 5 "), 5), (Some("9"), MAX_DIGEST_SIZE), (Some("0"), 1), (Some("few"), 1)] {
+            match value {
+                Some(v) => std::env::set_var(DIGEST_SIZE_ENV_VAR, v),
+                None => std::env::remove_var(DIGEST_SIZE_ENV_VAR),
+            }
+            assert_eq!(digest_size_from_env(), expected, "{:?}", value);
+        }
+        std::env::remove_var(DIGEST_SIZE_ENV_VAR);
+    }
+
+    #[test]
+    fn test_valid_picks() {
+        let shortlist = [3, 7, 12, 25, 41];
+        let pick = |index: usize, reason: &str| DigestPick { index, reason: Some(reason.to_string()) };
+        let reply: DigestReply = serde_json::from_str(
+            r#"{"picks": [{"index": 12, "reason": " Deep dive "}, {"index": 99, "reason": "Not shortlisted"}, {"index": 12}, {"index": 3}, {"index": 7, "reason": ""}]}"#,
+        )
+        .unwrap();
+
+        let picks = valid_picks(Some(reply.picks), &shortlist, 3);
+        assert_eq!(picks, vec![pick(12, "Deep dive"), DigestPick { index: 3, reason: None }, DigestPick { index: 7, reason: None }]);
+
+        // Nothing usable: the shortlist order
+        let indices: Vec<usize> = valid_picks(None, &shortlist, 2).iter().map(|p| p.index).collect();
+        assert_eq!(indices, [3, 7]);
+        let indices: Vec<usize> = valid_picks(Some(vec![pick(99, "x")]), &shortlist, 2).iter().map(|p| p.index).collect();
+        assert_eq!(indices, [3, 7]);
+    }
+
+    #[test]
+    fn test_lead_with_exploration() {
+        let picks = vec![DigestPick { index: 12, reason: None }, DigestPick { index: 3, reason: None }, DigestPick { index: 7, reason: None }];
+        assert_eq!(lead_with(picks.clone(), None, 3), picks);
+        let indices: Vec<usize> = lead_with(picks.clone(), Some(40), 3).iter().map(|p| p.index).collect();
+        assert_eq!(indices, [40, 12, 3]);
+        let indices: Vec<usize> = lead_with(picks, Some(3), 3).iter().map(|p| p.index).collect();
+        assert_eq!(indices, [3, 12, 7]);
+    }
+
+    #[test]
+    fn test_single_article_digest_is_the_summary() {
+        let sections = [section("Only One", Some("Best"), "# Only One\n\n## Summary\nText.")];
+        assert_eq!(assemble_digest("2026-03-20", Some("Ignored intro"), &sections), "# Only One\n\n## Summary\nText.");
+        assert!(manifest_articles(&sections, &HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn test_assemble_digest() {
+        let sections = [
+            section("Queue Design", Some("Concrete latency numbers"), "# Queue Design\n\n## Key Points\n- Batch writes\n\n```sh\n# not a heading\n```"),
+            section("Build Caches", None, "Remote caching halves CI time."),
+            section("On Call", Some("Leadership angle"), "## Summary\nRotations that stick."),
+        ];
+
+        let digest = assemble_digest("2026-03-20", Some(" Today is about throughput. \n"), &sections);
+
+        assert_eq!(
+            digest,
+            "# Engineering Briefing — 2026-03-20\n\n\
+             Today is about throughput.\n\n\
+             ## 1. Queue Design\n\n*Blog* · [Read the original](https://blog.example.com/queue-design)\n\n\
+             > Why it's here: Concrete latency numbers\n\n\
+             ### Queue Design\n\n#### Key Points\n- Batch writes\n\n```sh\n# not a heading\n```\n\n\
+             ## 2. Build Caches\n\n*Blog* · [Read the original](https://blog.example.com/build-caches)\n\n\
+             Remote caching halves CI time.\n\n\
+             ## 3. On Call\n\n*Blog* · [Read the original](https://blog.example.com/on-call)\n\n\
+             > Why it's here: Leadership angle\n\n\
+             #### Summary\nRotations that stick.\n"
+        );
+        // A failed intro call leaves the sections
+        assert!(assemble_digest("2026-03-20", None, &sections).starts_with("# Engineering Briefing — 2026-03-20\n\n## 1. Queue Design"));
+
+        let feed_urls = HashMap::from([("https://blog.example.com/build-caches".to_string(), "https://feeds.example.com/~r/blog/123".to_string())]);
+        let articles = manifest_articles(&sections, &feed_urls);
+        assert_eq!(articles.len(), 3);
+        assert_eq!(articles[0].feed_url, None);
+        assert_eq!(
+            articles[1],
+            DigestArticle {
+                title: "Build Caches".to_string(),
+                original_url: "https://blog.example.com/build-caches".to_string(),
+                feed_url: Some("https://feeds.example.com/~r/blog/123".to_string()),
+            }
+        );
+        assert!(intro_articles(&sections).starts_with("1. [Blog] Queue Design\n# Queue Design"));
+    }
+}
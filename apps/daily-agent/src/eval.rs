@@ -1,8 +1,8 @@
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 use gcloud_storage::client::Client;
-use gcloud_storage::http::objects::upload::{UploadObjectRequest, UploadType, Media};
-use llm_client::{call_llm, LlmProvider, LlmOptions};
+use llm_client::gcs_retry::upload_object;
+use llm_client::{parse_llm_json, CostTracker, LlmClient, LlmOptions};
 
 use crate::manifest::ManifestEntry;
 use crate::feedback::{FeedbackEntry, CALIBRATION_AGREEMENT_THRESHOLD};
@@ -70,37 +70,24 @@ pub(crate) struct EvalCriteria {
 }
 
 /// Run a single eval pass: send prompt to LLM, parse JSON response, upload report.
-#[allow(clippy::too_many_arguments)]
 pub(crate) async fn run_eval_pass(
-    http_client: &reqwest::Client,
-    provider: LlmProvider,
-    api_key: &str,
+    llm: &LlmClient,
     prompt: String,
     gcs_client: &Client,
     bucket_name: &str,
     today: &str,
     report_prefix: &str,
+    cost: &mut CostTracker,
 ) -> Option<serde_json::Value> {
     let eval_opts = LlmOptions { temperature: Some(0.3), ..Default::default() };
-    match call_llm(http_client, provider, api_key, prompt, &eval_opts).await {
+    match crate::call_llm_tracked(llm, prompt, &eval_opts, cost).await {
         Ok(eval_response) => {
-            let cleaned = eval_response
-                .trim()
-                .trim_start_matches("```json")
-                .trim_start_matches("```")
-                .trim_end_matches("```")
-                .trim();
-
-            match serde_json::from_str::<serde_json::Value>(cleaned) {
+            match parse_llm_json::<serde_json::Value>(&eval_response) {
                 Ok(json) => {
                     // Upload eval report
                     let eval_object = format!("{}/{}.json", report_prefix, today);
                     if let Ok(eval_json) = serde_json::to_vec_pretty(&json) {
-                        match gcs_client.upload_object(
-                            &UploadObjectRequest { bucket: bucket_name.to_string(), ..Default::default() },
-                            eval_json,
-                            &UploadType::Simple(Media::new(eval_object)),
-                        ).await {
+                        match upload_object(gcs_client, bucket_name, &eval_object, eval_json).await {
                             Ok(_) => info!(prefix = %report_prefix, "Eval report uploaded"),
                             Err(e) => warn!(prefix = %report_prefix, error = %e, "Failed to upload eval report"),
                         }
@@ -199,11 +186,20 @@ mod tests {
             title: "Test".to_string(),
             summary_snippet: "...".to_string(),
             original_url: None,
+            feed_url: None,
             model: model.map(|s| s.to_string()),
             selected_by: None,
             prompt_version: prompt_version.map(|s| s.to_string()),
             eval_score: None,
             format: None,
+            source: None,
+            exploratory: None,
+            run_id: None,
+            judged_best: None,
+            author: None,
+            categories: Vec::new(),
+            articles: Vec::new(),
+            selection_reason: None,
         }
     }
 
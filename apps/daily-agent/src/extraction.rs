@@ -0,0 +1,293 @@
+//! Per-domain extraction overrides (`config/extraction_overrides.json`) for sources whose
+//! layout the generic readability pass gets wrong.
+//!
+//! ```json
+//! {
+//!   "blog.example.com": {
+//!     "content_selector": "article .post-body",
+//!     "strip_selectors": [".comments", "#related-posts"],
+//!     "prefer_feed_content": false
+//!   }
+//! }
+//! ```
+//!
+//! Selectors support tag names, `.class`, `#id`, compounds of those (`div.post#main`) and
+//! descendant combinators (`article .body`).
+
+use std::collections::HashMap;
+
+use select::document::Document;
+use select::node::Node;
+use serde::Deserialize;
+use tracing::warn;
+
+use llm_client::{extract_domain, parse_json_strict, ObjectStore};
+
+pub(crate) const EXTRACTION_OVERRIDES_OBJECT: &str = "config/extraction_overrides.json";
+
+/// Extraction hints for one domain.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub(crate) struct ExtractionOverride {
+    /// Container holding the article body.
+    #[serde(default)]
+    pub(crate) content_selector: Option<String>,
+    /// Elements removed from the container before taking its text (comments, widgets).
+    #[serde(default)]
+    pub(crate) strip_selectors: Vec<String>,
+    /// Use the feed's inline content instead of fetching the page.
+    #[serde(default)]
+    pub(crate) prefer_feed_content: bool,
+}
+
+/// Overrides keyed by domain (`www.` is ignored when matching).
+pub(crate) type ExtractionOverrides = HashMap<String, ExtractionOverride>;
+
+/// Override for the domain of `url`, if any.
+pub(crate) fn override_for<'a>(overrides: &'a ExtractionOverrides, url: &str) -> Option<&'a ExtractionOverride> {
+    let domain = extract_domain(url);
+    let bare = domain.strip_prefix("www.").unwrap_or(&domain);
+    overrides.get(bare).or_else(|| overrides.get(&format!("www.{}", bare)))
+}
+
+/// Load overrides from the bucket. Missing or invalid config means no overrides.
+pub(crate) async fn load_extraction_overrides<S: ObjectStore>(store: &S) -> ExtractionOverrides {
+    match store.get(EXTRACTION_OVERRIDES_OBJECT).await {
+        Ok(Some(data)) => parse_json_strict(EXTRACTION_OVERRIDES_OBJECT, &data, None).unwrap_or_else(|e| {
+            warn!(error = %e, "Invalid extraction overrides, using generic extraction everywhere");
+            ExtractionOverrides::new()
+        }),
+        Ok(None) => ExtractionOverrides::new(),
+        Err(e) => {
+            warn!(error = %e, "Failed to load extraction overrides");
+            ExtractionOverrides::new()
+        }
+    }
+}
+
+/// One compound selector: `tag.class1.class2#id`.
+#[derive(Debug, Clone, PartialEq, Default)]
+struct SimpleSelector {
+    tag: Option<String>,
+    classes: Vec<String>,
+    id: Option<String>,
+}
+
+impl SimpleSelector {
+    fn parse(s: &str) -> Option<Self> {
+        let mut selector = SimpleSelector::default();
+        let mut rest = s;
+        let tag_end = rest.find(['.', '#']).unwrap_or(rest.len());
+        if tag_end > 0 {
+            selector.tag = Some(rest[..tag_end].to_ascii_lowercase());
+        }
+        rest = &rest[tag_end..];
+        while !rest.is_empty() {
+            let marker = rest.as_bytes()[0];
+            let end = rest[1..].find(['.', '#']).map(|i| i + 1).unwrap_or(rest.len());
+            let name = &rest[1..end];
+            if name.is_empty() {
+                return None;
+            }
+            match marker {
+                b'.' => selector.classes.push(name.to_string()),
+                _ => selector.id = Some(name.to_string()),
+            }
+            rest = &rest[end..];
+        }
+        let valid = |v: &str| v.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        let all_valid = selector.tag.as_deref().is_none_or(valid)
+            && selector.classes.iter().all(|c| valid(c))
+            && selector.id.as_deref().is_none_or(valid);
+        all_valid.then_some(selector)
+    }
+
+    fn matches(&self, node: &Node) -> bool {
+        let Some(name) = node.name() else { return false };
+        if self.tag.as_deref().is_some_and(|t| t != "*" && !t.eq_ignore_ascii_case(name)) {
+            return false;
+        }
+        if let Some(id) = &self.id {
+            if node.attr("id") != Some(id.as_str()) {
+                return false;
+            }
+        }
+        let node_classes: Vec<&str> = node.attr("class").map(|c| c.split_whitespace().collect()).unwrap_or_default();
+        self.classes.iter().all(|c| node_classes.contains(&c.as_str()))
+    }
+}
+
+/// Descendant chain of compound selectors, e.g. `article .body`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Selector(Vec<SimpleSelector>);
+
+impl Selector {
+    /// Parse the supported subset; returns `None` for anything else.
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        let parts: Option<Vec<SimpleSelector>> = s.split_whitespace().map(SimpleSelector::parse).collect();
+        parts.filter(|p| !p.is_empty()).map(Selector)
+    }
+
+    pub(crate) fn matches(&self, node: &Node) -> bool {
+        let Some((last, ancestors)) = self.0.split_last() else { return false };
+        if !last.matches(node) {
+            return false;
+        }
+        let mut remaining = ancestors.iter().rev().peekable();
+        let mut current = node.parent();
+        while let (Some(sel), Some(n)) = (remaining.peek(), current) {
+            if sel.matches(&n) {
+                remaining.next();
+            }
+            current = n.parent();
+        }
+        remaining.peek().is_none()
+    }
+}
+
+const BLOCK_TAGS: [&str; 14] = ["p", "div", "section", "article", "li", "ul", "ol", "pre", "blockquote", "h1", "h2", "h3", "h4", "br"];
+const NEVER_TEXT_TAGS: [&str; 3] = ["script", "style", "noscript"];
+
+fn collect_text(node: &Node, strip: &[Selector], out: &mut String) {
+    if let Some(text) = node.as_text() {
+        out.push_str(text);
+        return;
+    }
+    let Some(name) = node.name() else { return };
+    if NEVER_TEXT_TAGS.contains(&name) || strip.iter().any(|s| s.matches(node)) {
+        return;
+    }
+    for child in node.children() {
+        collect_text(&child, strip, out);
+    }
+    if BLOCK_TAGS.contains(&name) && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+fn normalize_whitespace(text: &str) -> String {
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Plain text of an HTML fragment (e.g. a feed's inline content).
+pub(crate) fn html_to_text(html: &str) -> String {
+    let document = Document::from(html);
+    let mut text = String::new();
+    if let Some(root) = document.nth(0) {
+        let root = root.parent().unwrap_or(root);
+        collect_text(&root, &[], &mut text);
+    }
+    normalize_whitespace(&text)
+}
+
+/// Extract text from the override's content container, minus stripped elements.
+/// Returns `None` (after a warning) when the override has no selector, the selector is
+/// unsupported, or nothing matches — callers then fall back to readability.
+pub(crate) fn extract_with_override(html: &str, extraction: &ExtractionOverride, url: &str) -> Option<String> {
+    let selector_str = extraction.content_selector.as_deref()?;
+    let Some(selector) = Selector::parse(selector_str) else {
+        warn!(url = %url, selector = %selector_str, "Unsupported content selector, using generic extraction");
+        return None;
+    };
+    let strip: Vec<Selector> = extraction.strip_selectors.iter()
+        .filter_map(|s| {
+            let parsed = Selector::parse(s);
+            if parsed.is_none() {
+                warn!(url = %url, selector = %s, "Unsupported strip selector, ignoring");
+            }
+            parsed
+        })
+        .collect();
+
+    let document = Document::from(html);
+    let Some(container) = document.find(|n: &Node| selector.matches(n)).next() else {
+        warn!(url = %url, selector = %selector_str, "Content selector matched nothing, using generic extraction");
+        return None;
+    };
+
+    let mut text = String::new();
+    collect_text(&container, &strip, &mut text);
+    Some(normalize_whitespace(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><head><title>Post</title><script>var x = 1;</script></head><body>
+        <nav><a href="/">Home</a></nav>
+        <main>
+          <article class="post">
+            <h1>Lock-free queues in practice</h1>
+            <div class="post-body">
+              <p>Ring buffers trade memory for predictable latency.</p>
+              <aside class="related-posts"><a href="/x">You may also like</a></aside>
+              <p>Cache-line padding prevents false sharing between producer and consumer.</p>
+            </div>
+          </article>
+          <section id="comments">
+            <div class="comment"><p>Great post! I disagree with everything, here is a very long rant that readability loves.</p></div>
+            <div class="comment"><p>Another very long comment that goes on and on about unrelated topics for many lines.</p></div>
+          </section>
+        </main></body></html>"#;
+
+    fn override_with(selector: &str, strip: &[&str]) -> ExtractionOverride {
+        ExtractionOverride {
+            content_selector: Some(selector.to_string()),
+            strip_selectors: strip.iter().map(|s| s.to_string()).collect(),
+            prefer_feed_content: false,
+        }
+    }
+
+    #[test]
+    fn test_override_extracts_container_and_strips_widgets() {
+        let ov = override_with("article.post .post-body", &[".related-posts", "#comments"]);
+        let text = extract_with_override(PAGE, &ov, "https://blog.example.com/post").unwrap();
+        assert_eq!(
+            text,
+            "Ring buffers trade memory for predictable latency.\nCache-line padding prevents false sharing between producer and consumer."
+        );
+        assert!(!text.contains("Great post"));
+        assert!(!text.contains("You may also like"));
+    }
+
+    #[test]
+    fn test_override_selector_without_match_falls_back() {
+        let ov = override_with("div.entry-content", &[]);
+        assert_eq!(extract_with_override(PAGE, &ov, "https://blog.example.com/post"), None);
+    }
+
+    #[test]
+    fn test_unsupported_selector_falls_back() {
+        let ov = override_with("article > div[data-x]", &[]);
+        assert_eq!(extract_with_override(PAGE, &ov, "https://blog.example.com/post"), None);
+    }
+
+    #[test]
+    fn test_html_to_text() {
+        assert_eq!(html_to_text("<p>One <b>two</b></p><p>three</p>"), "One two\nthree");
+    }
+
+    #[test]
+    fn test_override_for_ignores_www() {
+        let mut overrides = ExtractionOverrides::new();
+        overrides.insert("example.com".to_string(), override_with("article", &[]));
+        assert!(override_for(&overrides, "https://www.example.com/a").is_some());
+        assert!(override_for(&overrides, "https://example.com/a").is_some());
+        assert!(override_for(&overrides, "https://other.com/a").is_none());
+    }
+
+    #[test]
+    fn test_selector_parse() {
+        assert_eq!(
+            SimpleSelector::parse("div.a.b#main"),
+            Some(SimpleSelector { tag: Some("div".to_string()), classes: vec!["a".to_string(), "b".to_string()], id: Some("main".to_string()) })
+        );
+        assert!(SimpleSelector::parse(".").is_none());
+        assert!(Selector::parse("a:hover").is_none());
+        assert!(Selector::parse("   ").is_none());
+    }
+}
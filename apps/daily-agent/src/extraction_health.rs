@@ -0,0 +1,250 @@
+//! Per-article extraction metrics and the rolling per-domain rollup in
+//! `stats/extraction_health.json`, used to spot domains that need an extraction override.
+//!
+//! Metrics are computed from the HTML the run already fetched; nothing here does I/O
+//! except the final rollup write, whose failure only logs a warning.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use llm_client::state::{schema, unversioned};
+use llm_client::{extract_domain, update_state, Migration, ObjectStore};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::MIN_ARTICLE_CHARS;
+
+pub(crate) const EXTRACTION_HEALTH_OBJECT: &str = "stats/extraction_health.json";
+/// Schema migrations of `stats/extraction_health.json`; see [`llm_client::state`].
+const EXTRACTION_HEALTH_MIGRATIONS: [Migration; schema::EXTRACTION_HEALTH as usize] = [unversioned];
+/// Most recent samples kept per domain.
+pub(crate) const HEALTH_WINDOW: usize = 30;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ExtractionStrategy {
+    /// Per-domain selector from `config/extraction_overrides.json`.
+    Override,
+    Readability,
+    /// The feed's inline content, per override or because it holds the full post.
+    FeedContent,
+}
+
+/// Metrics for one extraction.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct ExtractionRecord {
+    pub(crate) url: String,
+    pub(crate) domain: String,
+    pub(crate) strategy: ExtractionStrategy,
+    pub(crate) html_bytes: usize,
+    pub(crate) text_chars: usize,
+    /// Extracted characters per input HTML byte.
+    pub(crate) text_ratio: f64,
+    pub(crate) has_code: bool,
+    pub(crate) elapsed_ms: u64,
+    /// Long enough to summarize (`MIN_ARTICLE_CHARS`).
+    pub(crate) usable: bool,
+}
+
+impl ExtractionRecord {
+    pub(crate) fn new(url: &str, strategy: ExtractionStrategy, html: &str, text: &str, elapsed: Duration) -> Self {
+        let text_chars = text.chars().count();
+        let text_ratio = if html.is_empty() { 0.0 } else { text_chars as f64 / html.len() as f64 };
+        Self {
+            url: url.to_string(),
+            domain: extract_domain(url),
+            strategy,
+            html_bytes: html.len(),
+            text_chars,
+            text_ratio,
+            has_code: html.contains("<pre") || html.contains("<code"),
+            elapsed_ms: elapsed.as_millis() as u64,
+            usable: text_chars >= MIN_ARTICLE_CHARS,
+        }
+    }
+}
+
+/// Extraction records collected during a run.
+#[derive(Debug, Default)]
+pub(crate) struct ExtractionLog {
+    pub(crate) records: Vec<ExtractionRecord>,
+}
+
+impl ExtractionLog {
+    pub(crate) fn push(&mut self, record: ExtractionRecord) {
+        debug!(
+            domain = %record.domain,
+            strategy = ?record.strategy,
+            html_bytes = record.html_bytes,
+            text_chars = record.text_chars,
+            ratio = record.text_ratio,
+            "Extraction metrics"
+        );
+        self.records.push(record);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct HealthSample {
+    pub(crate) date: String,
+    pub(crate) strategy: ExtractionStrategy,
+    pub(crate) text_ratio: f64,
+    pub(crate) text_chars: usize,
+    pub(crate) has_code: bool,
+    pub(crate) usable: bool,
+}
+
+/// Rolling extraction health of one domain over its last [`HEALTH_WINDOW`] samples.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub(crate) struct DomainHealth {
+    pub(crate) samples: Vec<HealthSample>,
+    pub(crate) mean_ratio: f64,
+    pub(crate) mean_text_chars: f64,
+    /// Share of samples too short to summarize.
+    pub(crate) failure_rate: f64,
+    pub(crate) code_rate: f64,
+}
+
+impl DomainHealth {
+    pub(crate) fn push(&mut self, sample: HealthSample) {
+        self.samples.push(sample);
+        if self.samples.len() > HEALTH_WINDOW {
+            let excess = self.samples.len() - HEALTH_WINDOW;
+            self.samples.drain(..excess);
+        }
+        self.recompute();
+    }
+
+    fn recompute(&mut self) {
+        let n = self.samples.len() as f64;
+        if n == 0.0 {
+            *self = Self::default();
+            return;
+        }
+        let share = |pred: fn(&HealthSample) -> bool| self.samples.iter().filter(|s| pred(s)).count() as f64 / n;
+        self.mean_ratio = self.samples.iter().map(|s| s.text_ratio).sum::<f64>() / n;
+        self.mean_text_chars = self.samples.iter().map(|s| s.text_chars as f64).sum::<f64>() / n;
+        self.failure_rate = share(|s| !s.usable);
+        self.code_rate = share(|s| s.has_code);
+    }
+}
+
+/// Extraction health keyed by domain.
+pub(crate) type ExtractionHealth = BTreeMap<String, DomainHealth>;
+
+/// Add a run's records to the per-domain rollup.
+pub(crate) fn rollup(health: &mut ExtractionHealth, records: &[ExtractionRecord], date: &str) {
+    for record in records {
+        health.entry(record.domain.clone()).or_default().push(HealthSample {
+            date: date.to_string(),
+            strategy: record.strategy,
+            text_ratio: record.text_ratio,
+            text_chars: record.text_chars,
+            has_code: record.has_code,
+            usable: record.usable,
+        });
+    }
+}
+
+/// Merge a run's records into `stats/extraction_health.json`. Failures only log.
+pub(crate) async fn update_extraction_health<S: ObjectStore>(store: &S, records: &[ExtractionRecord], date: &str) {
+    if records.is_empty() {
+        return;
+    }
+    let update = |health: &mut ExtractionHealth| rollup(health, records, date);
+    if let Err(e) = update_state(store, EXTRACTION_HEALTH_OBJECT, &EXTRACTION_HEALTH_MIGRATIONS, update).await {
+        warn!(error = %e, "Failed to update extraction health stats");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llm_client::MemoryStore;
+
+    fn record(url: &str, html_bytes: usize, text_chars: usize, has_code: bool) -> ExtractionRecord {
+        let html = "x".repeat(html_bytes);
+        let text = "y".repeat(text_chars);
+        let html = if has_code { format!("<pre>{}", html) } else { html };
+        ExtractionRecord::new(url, ExtractionStrategy::Readability, &html, &text, Duration::from_millis(3))
+    }
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_record_metrics() {
+        let r = ExtractionRecord::new("https://example.com/a", ExtractionStrategy::Override, "<p>hello</p>", "hello", Duration::from_millis(12));
+        assert_eq!(r.domain, "example.com");
+        assert_eq!(r.html_bytes, 12);
+        assert_eq!(r.text_chars, 5);
+        assert!(approx(r.text_ratio, 5.0 / 12.0));
+        assert!(!r.has_code);
+        assert!(!r.usable);
+        assert_eq!(r.elapsed_ms, 12);
+
+        let empty = ExtractionRecord::new("https://example.com/b", ExtractionStrategy::Readability, "", "", Duration::ZERO);
+        assert_eq!(empty.text_ratio, 0.0);
+    }
+
+    #[test]
+    fn test_rollup_per_domain_math() {
+        let records = vec![
+            record("https://good.example.com/1", 1000, 500, true),
+            record("https://good.example.com/2", 2000, 300, false),
+            record("https://bad.example.com/1", 10000, 50, false),
+        ];
+        let mut health = ExtractionHealth::new();
+        rollup(&mut health, &records, "2026-04-01");
+
+        let good = &health["good.example.com"];
+        assert_eq!(good.samples.len(), 2);
+        assert!(approx(good.mean_ratio, (500.0 / 1005.0 + 300.0 / 2000.0) / 2.0));
+        assert!(approx(good.mean_text_chars, 400.0));
+        assert!(approx(good.failure_rate, 0.0));
+        assert!(approx(good.code_rate, 0.5));
+
+        let bad = &health["bad.example.com"];
+        assert!(approx(bad.failure_rate, 1.0));
+        assert!(approx(bad.mean_ratio, 0.005));
+    }
+
+    #[test]
+    fn test_rollup_keeps_rolling_window() {
+        let mut health = ExtractionHealth::new();
+        for day in 0..HEALTH_WINDOW + 5 {
+            // Early samples are unusable, the last HEALTH_WINDOW are fine
+            let chars = if day < 5 { 10 } else { 1000 };
+            rollup(&mut health, &[record("https://a.example.com/x", 2000, chars, false)], &format!("day-{}", day));
+        }
+        let domain = &health["a.example.com"];
+        assert_eq!(domain.samples.len(), HEALTH_WINDOW);
+        assert_eq!(domain.samples[0].date, "day-5");
+        assert!(approx(domain.failure_rate, 0.0));
+        assert!(approx(domain.mean_ratio, 0.5));
+    }
+
+    #[tokio::test]
+    async fn test_update_extraction_health_accumulates_runs() {
+        let store = MemoryStore::new();
+        update_extraction_health(&store, &[record("https://a.example.com/1", 100, 10, false)], "2026-04-01").await;
+        update_extraction_health(&store, &[record("https://a.example.com/2", 100, 30, false)], "2026-04-02").await;
+        let health: ExtractionHealth = llm_client::load_or_default(&store, EXTRACTION_HEALTH_OBJECT, &EXTRACTION_HEALTH_MIGRATIONS).await.unwrap();
+        assert_eq!(health["a.example.com"].samples.len(), 2);
+        assert!(approx(health["a.example.com"].mean_ratio, 0.2));
+    }
+
+    #[tokio::test]
+    async fn test_unversioned_health_file_is_migrated() {
+        let store = MemoryStore::new();
+        let mut legacy = ExtractionHealth::new();
+        rollup(&mut legacy, &[record("https://a.example.com/1", 100, 10, false)], "2026-04-01");
+        store.put(EXTRACTION_HEALTH_OBJECT, serde_json::to_vec(&legacy).unwrap()).await.unwrap();
+
+        update_extraction_health(&store, &[record("https://a.example.com/2", 100, 30, false)], "2026-04-02").await;
+        let saved: serde_json::Value = serde_json::from_slice(&store.object(EXTRACTION_HEALTH_OBJECT).unwrap()).unwrap();
+        assert_eq!(saved["schema_version"], schema::EXTRACTION_HEALTH);
+        assert_eq!(saved["data"]["a.example.com"]["samples"].as_array().unwrap().len(), 2);
+    }
+}
@@ -2,8 +2,7 @@ use serde::Deserialize;
 use chrono::Utc;
 use tracing::{info, warn};
 use gcloud_storage::client::Client;
-use gcloud_storage::http::objects::download::Range;
-use gcloud_storage::http::objects::get::GetObjectRequest;
+use llm_client::gcs_retry::download_object;
 
 use crate::manifest::{ManifestEntry, gcs_object_path};
 
@@ -46,14 +45,7 @@ pub(crate) async fn load_recent_feedback(gcs_client: &Client, bucket_name: &str)
         let date = (now - chrono::Duration::days(days_ago)).format("%Y-%m-%d").to_string();
         let object = format!("feedback/{}.json", date);
 
-        match gcs_client.download_object(
-            &GetObjectRequest {
-                bucket: bucket_name.to_string(),
-                object,
-                ..Default::default()
-            },
-            &Range::default(),
-        ).await {
+        match download_object(gcs_client, bucket_name, &object).await {
             Ok(data) => {
                 match serde_json::from_slice::<Vec<FeedbackEntry>>(&data) {
                     Ok(mut entries) => all_feedback.append(&mut entries),
@@ -105,14 +97,7 @@ pub(crate) async fn download_feedback_excerpts(
             .map(|m| m.title.clone())
             .unwrap_or_else(|| "Unknown".to_string());
 
-        match gcs_client.download_object(
-            &GetObjectRequest {
-                bucket: bucket_name.to_string(),
-                object: gcs_path.to_string(),
-                ..Default::default()
-            },
-            &Range::default(),
-        ).await {
+        match download_object(gcs_client, bucket_name, gcs_path).await {
             Ok(data) => {
                 if let Ok(content) = String::from_utf8(data) {
                     results.push(format!(
@@ -303,11 +288,20 @@ mod tests {
                 title: "Great Article".to_string(),
                 summary_snippet: "...".to_string(),
                 original_url: None,
+                feed_url: None,
                 model: None,
                 selected_by: None,
                 prompt_version: None,
                 eval_score: None,
                 format: None,
+                source: None,
+                exploratory: None,
+                run_id: None,
+                judged_best: None,
+                author: None,
+                categories: Vec::new(),
+                articles: Vec::new(),
+                selection_reason: None,
             },
         ];
         let ctx = build_selection_context(&feedback, &manifest);
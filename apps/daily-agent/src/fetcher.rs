@@ -1,86 +1,837 @@
 use rss::Channel;
 use atom_syndication::Feed as AtomFeed;
-use serde::Deserialize;
-use std::error::Error;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::time::Duration as StdDuration;
 use chrono::{DateTime, Utc, Duration};
+use futures::stream::{self, StreamExt};
 use tracing::{warn, debug};
+use crate::extraction::html_to_text;
+use crate::scrape::scrape_articles;
+use url::Url;
+use llm_client::{
+    bluesky_feed_url, build_http_client, detect_language, fetch_bytes_with, is_future_dated, is_rdf_feed, max_future_skew_from_env, parse_feed_date,
+    parse_rdf_items, youtube_feed_url, youtube_feed_url_from_page, rss_item_date, rss_item_url, FetchOptions, HostLimiter, HttpClientConfig, RdfItem, RetryPolicy,
+};
 
 // Re-export from llm-client for convenience
-pub use llm_client::{SourceConfig, SourceType};
+pub use llm_client::{normalize_article_url, strip_tracking_params, SourceConfig, SourceType};
 
-/// HTTP timeout for fetching feeds
+/// HTTP timeout for fetching feeds, unless a source sets `timeout_secs`
 const FETCH_TIMEOUT_SECS: u64 = 30;
-/// Maximum number of items to fetch from each source
+/// Maximum number of items to fetch from each source, unless it sets `max_items`
 const MAX_ITEMS_PER_SOURCE: usize = 10;
+/// Feed pages read by following `rel="next"` links, unless a source sets `max_pages`
+const MAX_PAGES_PER_SOURCE: usize = 3;
+/// Hacker News stories fetched per story kept, so the score/comment filters have something to
+/// choose from
+const HN_CANDIDATES_PER_ITEM: usize = 3;
+/// Environment variable capping the size of any one feed or API response, in MB
+pub const FEED_MAX_RESPONSE_MB_ENV_VAR: &str = "FEED_MAX_RESPONSE_MB";
+/// Response cap when `FEED_MAX_RESPONSE_MB` is unset or invalid
+const DEFAULT_MAX_RESPONSE_MB: usize = 16;
+/// Environment variable capping the articles put in front of the selector, across sources
+pub const MAX_TOTAL_ARTICLES_ENV_VAR: &str = "MAX_TOTAL_ARTICLES";
+/// Article cap when `MAX_TOTAL_ARTICLES` is unset or invalid
+pub const DEFAULT_MAX_TOTAL_ARTICLES: usize = 100;
+/// Environment variable listing the languages articles are kept in, as comma-separated ISO
+/// 639-1 codes; `*` keeps every language
+pub const ALLOWED_LANGUAGES_ENV_VAR: &str = "ALLOWED_LANGUAGES";
+/// Languages kept when `ALLOWED_LANGUAGES` is unset or empty
+pub const DEFAULT_ALLOWED_LANGUAGES: &str = "en";
+/// Categories shown after a headline in selection prompts
+const HEADLINE_CATEGORIES: usize = 3;
+/// HN item requests in flight at once
+const HN_CONCURRENCY: usize = 10;
+/// Discussion page of an HN item, followed by its ID
+const HN_DISCUSSION_URL: &str = "https://news.ycombinator.com/item?id=";
+/// Characters of a Bluesky post's text kept as the title of the article it links
+const BLUESKY_TITLE_CHARS: usize = 120;
+/// `reason` of a post another account reposted into the author feed
+const BLUESKY_REPOST: &str = "app.bsky.feed.defs#reasonRepost";
+/// Media type recorded for YouTube videos, which have no downloadable enclosure
+const YOUTUBE_MIME_TYPE: &str = "video/youtube";
+/// Inline body length above which an item with an audio/video enclosure still counts as a
+/// written post (e.g. a full-text post that also ships an audio version).
+const WRITTEN_POST_MIN_CHARS: usize = 1500;
+
+/// Audio/video enclosure of a feed item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaInfo {
+    pub url: String,
+    pub mime_type: String,
+    pub length: Option<u64>,
+}
+
+impl MediaInfo {
+    fn new(url: &str, mime_type: &str, length: Option<&str>) -> Option<Self> {
+        if !(mime_type.starts_with("audio/") || mime_type.starts_with("video/")) {
+            return None;
+        }
+        Some(Self {
+            url: url.to_string(),
+            mime_type: mime_type.to_string(),
+            length: length.and_then(|l| l.trim().parse().ok()),
+        })
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Article {
     pub title: String,
     pub url: String,
     pub source: String,
+    /// Attribution for articles found in a newsletter issue ("{domain} via {newsletter}") or
+    /// listed by several sources ("Netflix TechBlog, Hacker News", see [`dedup_articles`]);
+    /// `source` stays the fetched source so per-source accounting still applies
+    pub via: Option<String>,
     #[allow(dead_code)] // Reserved for future filtering by date
     pub published_at: DateTime<Utc>,
+    /// Set when the item is a podcast/video episode rather than a written post
+    pub media: Option<MediaInfo>,
+    /// Episode description from the feed, used instead of the player page when summarizing
+    pub show_notes: Option<String>,
+    /// Inline body from the feed (content:encoded, description or Atom content/summary)
+    pub feed_content: Option<String>,
+    /// Feed summary as plain text (RSS description, Atom summary)
+    pub summary: Option<String>,
+    /// Full post from the feed as plain text (content:encoded, Atom content, the body of an
+    /// HN or Lobsters text post); used instead of scraping when long enough
+    pub content: Option<String>,
+    /// Points on the link aggregator that listed it (Hacker News, Lobsters)
+    pub score: Option<i64>,
+    /// 1-based position in the Hacker News story list it came from
+    pub rank: Option<usize>,
+    /// Byline from the feed (RSS `dc:creator`/`author`, Atom authors), or the HN submitter
+    pub author: Option<String>,
+    /// Categories the feed assigned (RSS/Atom categories, Lobsters tags)
+    pub categories: Vec<String>,
+    /// Scrape sources: the listing gave no readable date, so `published_at` is the fetch time
+    pub undated: bool,
+    /// The feed dated it further ahead than the allowed skew, so `published_at` is the fetch
+    /// time; see [`clamp_future_dates`]
+    pub future_dated: bool,
+    /// ISO 639-1 code of the title and feed summary, set by [`filter_languages`]; `None` when
+    /// there was too little text to tell
+    pub language: Option<String>,
+}
+
+impl Article {
+    /// Source as shown to the model and in summaries.
+    pub fn source_label(&self) -> &str {
+        self.via.as_deref().unwrap_or(&self.source)
+    }
+
+    /// "[source] title" as listed in selection prompts, with the aggregator rank and score, the
+    /// author and the first few categories when known:
+    /// "[Hacker News #3, 512 points — by pg] title (lisp, startups)". Scraped posts without a
+    /// date say so, since they may be old: "[Blog, date unknown] title".
+    pub fn headline(&self) -> String {
+        let mut label = self.source_label().to_string();
+        if let Some(rank) = self.rank {
+            label.push_str(&format!(" #{}", rank));
+        }
+        if let Some(score) = self.score {
+            label.push_str(&format!(", {} points", score));
+        }
+        if self.undated {
+            label.push_str(", date unknown");
+        }
+        if let Some(author) = &self.author {
+            label.push_str(&format!(" — by {}", author));
+        }
+        let mut headline = format!("[{}] {}", label, self.title);
+        if !self.categories.is_empty() {
+            let shown: Vec<&str> = self.categories.iter().take(HEADLINE_CATEGORIES).map(String::as_str).collect();
+            headline.push_str(&format!(" ({})", shown.join(", ")));
+        }
+        headline
+    }
+
+    /// An article fetched now with only `title`, `url` and `source` set, for tests to extend
+    /// with struct-update syntax.
+    #[cfg(test)]
+    pub(crate) fn for_test(title: &str, url: &str, source: &str) -> Self {
+        Article {
+            title: title.to_string(),
+            url: url.to_string(),
+            source: source.to_string(),
+            via: None,
+            published_at: Utc::now(),
+            media: None,
+            show_notes: None,
+            feed_content: None,
+            summary: None,
+            content: None,
+            score: None,
+            rank: None,
+            author: None,
+            categories: Vec::new(),
+            undated: false,
+            future_dated: false,
+            language: None,
+        }
+    }
+}
+
+/// Items kept from `source`: its `max_items`, else [`MAX_ITEMS_PER_SOURCE`].
+fn max_items(source: &SourceConfig) -> usize {
+    source.max_items.unwrap_or(MAX_ITEMS_PER_SOURCE)
+}
+
+/// Pages read from `source`: its `max_pages`, else [`MAX_PAGES_PER_SOURCE`].
+fn max_pages(source: &SourceConfig) -> usize {
+    source.max_pages.unwrap_or(MAX_PAGES_PER_SOURCE)
+}
+
+/// The `limit` newest of a feed's fresh articles. Feeds aren't reliably newest-first (some
+/// list oldest-first or pin evergreen posts on top), so the cap is applied only after sorting
+/// by date. Undated and future-dated articles, whose date is only the fetch time, come after
+/// the rest.
+fn newest_first(mut articles: Vec<Article>, limit: usize) -> Vec<Article> {
+    articles.sort_by_key(|a| (a.undated || a.future_dated, std::cmp::Reverse(a.published_at)));
+    articles.truncate(limit);
+    articles
+}
+
+/// [`newest_first`], with `outcome` counting the articles kept and the future-dated among them.
+fn finish(articles: Vec<Article>, outcome: FetchOutcome, limit: usize) -> Fetched {
+    let articles = newest_first(articles, limit);
+    let future_dated = articles.iter().filter(|a| a.future_dated).count();
+    let outcome = FetchOutcome { articles: articles.len(), future_dated, ..outcome };
+    (articles, outcome)
+}
+
+/// Date articles more than `max_skew` after `now` at `now` instead and mark them
+/// [`Article::future_dated`]; returns how many. A post a misconfigured blog dates years ahead
+/// would otherwise stay "within the last 24 hours", and the newest, on every run. Parsers
+/// clamp before capping a feed, so such a post can't push out genuinely recent ones.
+pub fn clamp_future_dates(articles: &mut [Article], now: DateTime<Utc>, max_skew: Duration) -> usize {
+    let mut clamped = 0;
+    for article in articles.iter_mut().filter(|a| is_future_dated(a.published_at, now, max_skew)) {
+        article.published_at = now;
+        article.future_dated = true;
+        clamped += 1;
+    }
+    clamped
+}
+
+/// A byline as feeds write it: RSS `author` is nominally an email, often "jane@example.com
+/// (Jane Doe)"; the name is kept and a bare address dropped.
+fn clean_author(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    let name = match raw.split_once('(') {
+        Some((email, name)) if email.contains('@') => name.trim_end_matches(')').trim(),
+        _ if raw.contains('@') && !raw.contains(' ') => return None,
+        _ => raw,
+    };
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Category or author names, trimmed, without blanks or repeats (ignoring case).
+fn clean_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut categories: Vec<String> = Vec::new();
+    for name in names.into_iter().map(str::trim).filter(|name| !name.is_empty()) {
+        if !categories.iter().any(|seen| seen.eq_ignore_ascii_case(name)) {
+            categories.push(name.to_string());
+        }
+    }
+    categories
+}
+
+/// Plain text of HTML from a feed (entities decoded, tags and scripts dropped); `None` when
+/// nothing readable is left.
+fn sanitize_feed_html(html: &str) -> Option<String> {
+    let text = html_to_text(html);
+    (!text.is_empty()).then_some(text)
+}
+
+/// An item is an episode when an audio/video enclosure is its only substantive content:
+/// no separate link, a link to the media file itself, or too little inline text.
+fn episode_media(enclosure: Option<MediaInfo>, link: Option<&str>, body: Option<&str>) -> Option<MediaInfo> {
+    let media = enclosure?;
+    let has_page = link.is_some_and(|l| l != media.url);
+    let has_body = body.is_some_and(|b| b.chars().count() >= WRITTEN_POST_MIN_CHARS);
+    if has_page && has_body {
+        None
+    } else {
+        Some(media)
+    }
+}
+
+/// Drop episode items unless the source opts in with `allow_media`.
+/// Returns the kept articles and how many were filtered out.
+pub fn filter_media(articles: Vec<Article>, allow_media: bool) -> (Vec<Article>, usize) {
+    if allow_media {
+        return (articles, 0);
+    }
+    let before = articles.len();
+    let kept: Vec<Article> = articles.into_iter().filter(|a| a.media.is_none()).collect();
+    let filtered = before - kept.len();
+    (kept, filtered)
 }
 
 // Hacker News Item Struct
 #[derive(Deserialize, Debug)]
 struct HnItem {
     title: Option<String>,
+    /// Absent on Ask HN/Show HN text posts
     url: Option<String>,
-    time: i64,
-    #[allow(dead_code)] // Required by HN API, may use for filtering in future
+    /// HTML body of a text post
+    text: Option<String>,
+    /// Unix timestamp; missing on some odd items
+    time: Option<i64>,
+    /// `story`, `job`, `poll`, `pollopt` or `comment`
+    #[serde(default)]
     r#type: String,
+    /// Submitter's username
+    by: Option<String>,
+    #[serde(default)]
+    dead: bool,
+    #[serde(default)]
+    deleted: bool,
+    #[serde(default)]
+    score: i64,
+    /// Total comment count
+    #[serde(default)]
+    descendants: i64,
+}
+
+/// An `app.bsky.feed.getAuthorFeed` response; only the fields used are read.
+#[derive(Deserialize, Debug)]
+struct BlueskyFeed {
+    feed: Vec<BlueskyFeedItem>,
+}
+
+#[derive(Deserialize, Debug)]
+struct BlueskyFeedItem {
+    post: BlueskyPost,
+    /// Thread context, present on replies
+    reply: Option<serde_json::Value>,
+    /// Why the post is in the feed when the author didn't just post it: a repost or a pin
+    reason: Option<BlueskyReason>,
+}
+
+#[derive(Deserialize, Debug)]
+struct BlueskyReason {
+    #[serde(rename = "$type")]
+    kind: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct BlueskyPost {
+    author: BlueskyAuthor,
+    record: BlueskyRecord,
+    /// The embed as rendered, e.g. `app.bsky.embed.external#view`
+    embed: Option<BlueskyEmbed>,
+    /// When the AppView indexed the post; unlike the record's `createdAt`, not set by the client
+    indexed_at: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct BlueskyAuthor {
+    handle: String,
+    display_name: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct BlueskyRecord {
+    #[serde(default)]
+    text: String,
+    /// Set on replies
+    reply: Option<serde_json::Value>,
+}
+
+/// A post's embed: a link card (`external`), or a quote post whose `media` may be one.
+#[derive(Deserialize, Debug)]
+struct BlueskyEmbed {
+    external: Option<BlueskyExternal>,
+    media: Option<Box<BlueskyEmbed>>,
+}
+
+impl BlueskyEmbed {
+    fn link_card(&self) -> Option<&BlueskyExternal> {
+        self.external.as_ref().or_else(|| self.media.as_ref().and_then(|media| media.external.as_ref()))
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct BlueskyExternal {
+    uri: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    description: String,
+}
+
+/// Why a Hacker News item isn't kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HnSkip {
+    /// Flagged or deleted: still listed, but nothing worth reading
+    Dead,
+    /// A job posting, poll or anything else that isn't a story
+    NotStory,
+    MissingTitle,
+    /// A text post, while the source leaves them out
+    TextPost,
+    /// No `time`, or one that isn't a valid timestamp
+    MissingTime,
+    Stale,
+    LowScore,
+    FewComments,
+}
+
+/// The article for story `id`, at `rank` in its list, or why it's skipped. Stories from before
+/// `yesterday` are stale.
+fn hn_article(source: &SourceConfig, id: u32, rank: usize, item: HnItem, yesterday: DateTime<Utc>) -> Result<Article, HnSkip> {
+    if item.dead || item.deleted {
+        return Err(HnSkip::Dead);
+    }
+    // YC job postings have a link and a recent time, so they'd pass every other check
+    if item.r#type != "story" {
+        return Err(HnSkip::NotStory);
+    }
+    let title = item.title.ok_or(HnSkip::MissingTitle)?;
+    // Ask HN/Show HN text posts have no link of their own; their discussion page is the post
+    let (url, feed_content) = match item.url {
+        Some(url) => (url, None),
+        None if source.include_self_posts && item.text.is_some() => (format!("{}{}", HN_DISCUSSION_URL, id), item.text),
+        None => return Err(HnSkip::TextPost),
+    };
+    let published_at = item.time.and_then(|time| DateTime::from_timestamp(time, 0)).ok_or(HnSkip::MissingTime)?;
+
+    // Apply same 24h freshness filter as RSS (>= to include boundary)
+    if published_at < yesterday {
+        return Err(HnSkip::Stale);
+    }
+    if source.min_score.is_some_and(|min| item.score < min) {
+        return Err(HnSkip::LowScore);
+    }
+    if source.min_comments.is_some_and(|min| item.descendants < min) {
+        return Err(HnSkip::FewComments);
+    }
+    Ok(Article {
+        title,
+        url,
+        source: source.name.clone(),
+        via: None,
+        published_at,
+        media: None,
+        show_notes: None,
+        content: feed_content.as_deref().and_then(sanitize_feed_html),
+        feed_content,
+        summary: None,
+        score: Some(item.score),
+        rank: Some(rank),
+        author: item.by.filter(|by| !by.is_empty()),
+        categories: Vec::new(),
+        undated: false,
+        future_dated: false,
+        language: None,
+    })
+}
+
+/// A story in a Lobsters JSON listing (`/hottest.json`, `/t/<tag>.json`, ...).
+#[derive(Deserialize, Debug)]
+struct LobstersStory {
+    title: String,
+    /// Empty for text posts
+    #[serde(default)]
+    url: String,
+    created_at: DateTime<chrono::FixedOffset>,
+    #[serde(default)]
+    score: i64,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    comments_url: String,
+    /// HTML body of a text post
+    #[serde(default)]
+    description: String,
+}
+
+/// Create a shared HTTP client for feed fetches; see [`HttpClientConfig::from_env`]
+pub fn create_http_client() -> Result<reqwest::Client, Box<dyn std::error::Error + Send + Sync>> {
+    build_http_client(HttpClientConfig::from_env(StdDuration::from_secs(FETCH_TIMEOUT_SECS))).map_err(|e| e.into())
+}
+
+/// `FEED_MAX_RESPONSE_MB` in bytes; unset or invalid is [`DEFAULT_MAX_RESPONSE_MB`].
+fn max_response_bytes_from_env() -> usize {
+    let mb = match std::env::var(FEED_MAX_RESPONSE_MB_ENV_VAR) {
+        Ok(raw) => raw.trim().parse().ok().filter(|&mb: &usize| mb > 0).unwrap_or_else(|| {
+            warn!(value = %raw, "Invalid {}, using {}", FEED_MAX_RESPONSE_MB_ENV_VAR, DEFAULT_MAX_RESPONSE_MB);
+            DEFAULT_MAX_RESPONSE_MB
+        }),
+        Err(_) => DEFAULT_MAX_RESPONSE_MB,
+    };
+    mb * 1024 * 1024
+}
+
+/// `MAX_TOTAL_ARTICLES`; unset or invalid is [`DEFAULT_MAX_TOTAL_ARTICLES`].
+pub fn max_total_articles_from_env() -> usize {
+    match std::env::var(MAX_TOTAL_ARTICLES_ENV_VAR) {
+        Ok(raw) => raw.trim().parse().ok().filter(|&cap: &usize| cap > 0).unwrap_or_else(|| {
+            warn!(value = %raw, "Invalid {}, using {}", MAX_TOTAL_ARTICLES_ENV_VAR, DEFAULT_MAX_TOTAL_ARTICLES);
+            DEFAULT_MAX_TOTAL_ARTICLES
+        }),
+        Err(_) => DEFAULT_MAX_TOTAL_ARTICLES,
+    }
+}
+
+/// `ALLOWED_LANGUAGES` as lowercase codes; unset or empty is [`DEFAULT_ALLOWED_LANGUAGES`].
+pub fn allowed_languages_from_env() -> Vec<String> {
+    let raw = std::env::var(ALLOWED_LANGUAGES_ENV_VAR).unwrap_or_default();
+    let languages = parse_languages(&raw);
+    if languages.is_empty() {
+        parse_languages(DEFAULT_ALLOWED_LANGUAGES)
+    } else {
+        languages
+    }
+}
+
+fn parse_languages(raw: &str) -> Vec<String> {
+    normalize_languages(raw.split(','))
+}
+
+/// Language codes trimmed and lowercased, empty ones dropped: how both `ALLOWED_LANGUAGES`
+/// and a source's `languages` are read.
+pub fn normalize_languages<S: AsRef<str>>(codes: impl IntoIterator<Item = S>) -> Vec<String> {
+    codes.into_iter().map(|code| code.as_ref().trim().to_lowercase()).filter(|code| !code.is_empty()).collect()
+}
+
+/// Tag each article with the language [`detect_language`] finds in its title and feed summary,
+/// and split off those in a language not in `allowed` (a source's `languages`, else
+/// `ALLOWED_LANGUAGES`). Articles whose language can't be told, such as short titles with no
+/// summary, are kept. Returns the kept articles and the filtered ones.
+pub fn filter_languages(articles: Vec<Article>, allowed: &[String]) -> (Vec<Article>, Vec<Article>) {
+    let any = allowed.iter().any(|code| code == "*");
+    articles
+        .into_iter()
+        .map(|mut article| {
+            let text = format!("{}\n{}", article.title, article.summary.as_deref().unwrap_or_default());
+            article.language = detect_language(&text).map(str::to_string);
+            article
+        })
+        .partition(|article| any || article.language.as_ref().is_none_or(|language| allowed.contains(language)))
+}
+
+/// Why a source yielded nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchErrorKind {
+    /// No usable response: DNS, connection or TLS failure, timeout, or a body over the size cap
+    Network,
+    /// The server answered with this non-success status
+    Http(u16),
+    /// The response isn't the feed, JSON or page the source type expects, or a scrape
+    /// selector found nothing on it
+    Parse,
+    /// A type this version can't fetch
+    UnknownType,
+    /// The source parsed but listed no items at all (as opposed to none fresh)
+    Empty,
+    /// The source entry itself can't be used: an invalid URL or a header whose variable is unset
+    Config,
+}
+
+impl fmt::Display for FetchErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchErrorKind::Network => f.write_str("network"),
+            FetchErrorKind::Http(status) => write!(f, "http {}", status),
+            FetchErrorKind::Parse => f.write_str("parse"),
+            FetchErrorKind::UnknownType => f.write_str("unknown type"),
+            FetchErrorKind::Empty => f.write_str("empty"),
+            FetchErrorKind::Config => f.write_str("config"),
+        }
+    }
+}
+
+/// A source that couldn't be fetched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFetchError {
+    pub source_name: String,
+    pub kind: FetchErrorKind,
+    pub detail: String,
+}
+
+impl SourceFetchError {
+    fn new(source: &SourceConfig, kind: FetchErrorKind, detail: impl ToString) -> Self {
+        Self { source_name: source.name.clone(), kind, detail: detail.to_string() }
+    }
+
+    /// A failed request: [`FetchErrorKind::Http`] when the server answered, else
+    /// [`FetchErrorKind::Network`] when the transport failed, else [`FetchErrorKind::Parse`]
+    /// (an answer without what was looked for, such as a channel page without its ID).
+    fn request(source: &SourceConfig, err: llm_client::FetchError) -> Self {
+        let kind = match (err.status, err.network) {
+            (Some(status), _) => FetchErrorKind::Http(status),
+            (None, Some(_)) => FetchErrorKind::Network,
+            (None, None) => FetchErrorKind::Parse,
+        };
+        Self::new(source, kind, err)
+    }
+}
+
+impl fmt::Display for SourceFetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.source_name, self.kind, self.detail)
+    }
+}
+
+impl std::error::Error for SourceFetchError {}
+
+/// What a source's items came to. Items counted in none of the fields fell to the source's
+/// own filters (score, comments, text posts, media) or its item cap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct FetchOutcome {
+    /// Articles kept
+    pub articles: usize,
+    /// Items the feed, story list or listing page had
+    pub items_seen: usize,
+    /// Items older than the 24-hour window
+    pub filtered_by_date: usize,
+    /// Items without a title, a link or a readable date
+    pub skipped_missing_fields: usize,
+    /// Articles kept whose date was too far in the future, clamped to the fetch time
+    pub future_dated: usize,
+}
+
+/// Articles of one source, along with what happened to the rest of its items.
+pub type Fetched = (Vec<Article>, FetchOutcome);
+
+/// A source's name and how its fetch went.
+pub type SourceResult = (String, Result<FetchOutcome, SourceFetchError>);
+
+/// The table logged after the fetch phase: a header, then one aligned line per source with its
+/// status (`ok` or the error kind) and item counts, or the error detail.
+pub fn outcome_table(results: &[SourceResult]) -> String {
+    let width = results.iter().map(|(name, _)| name.chars().count()).chain(["source".len()]).max().unwrap_or_default();
+    let mut lines = vec![format!("{:<width$}  {:<10}  {:>5}  {:>7}  {:>7}  {:>8}", "source", "status", "seen", "too old", "missing", "articles")];
+    for (name, result) in results {
+        lines.push(match result {
+            Ok(o) => format!(
+                "{:<width$}  {:<10}  {:>5}  {:>7}  {:>7}  {:>8}",
+                name, "ok", o.items_seen, o.filtered_by_date, o.skipped_missing_fields, o.articles
+            ),
+            Err(e) => format!("{:<width$}  {:<10}  {}", name, e.kind.to_string(), e.detail),
+        });
+    }
+    lines.join("\n")
+}
+
+/// The same results for the run report, one object per source with a `status` to alert on.
+pub fn outcome_report(results: &[SourceResult]) -> serde_json::Value {
+    results
+        .iter()
+        .map(|(name, result)| match result {
+            Ok(outcome) => serde_json::json!({ "source": name, "status": "ok", "outcome": outcome }),
+            Err(e) => serde_json::json!({ "source": name, "status": e.kind.to_string(), "detail": e.detail }),
+        })
+        .collect()
 }
 
-/// Create a shared HTTP client with configured timeout
-pub fn create_http_client() -> Result<reqwest::Client, Box<dyn Error + Send + Sync>> {
-    reqwest::Client::builder()
-        .timeout(StdDuration::from_secs(FETCH_TIMEOUT_SECS))
-        .build()
-        .map_err(|e| e.into())
+pub async fn fetch_from_source(source: &SourceConfig, client: &reqwest::Client) -> Result<Fetched, SourceFetchError> {
+    let limiter = HostLimiter::shared();
+    fetch_from_source_with(source, client, &RetryPolicy::feed_from_env(), &limiter, max_response_bytes_from_env(), max_future_skew_from_env()).await
 }
 
-pub async fn fetch_from_source(source: &SourceConfig, client: &reqwest::Client) -> Result<Vec<Article>, Box<dyn Error + Send + Sync>> {
-    match source.source_type {
-        SourceType::Rss => fetch_rss(source, client).await,
-        SourceType::Atom => fetch_atom(source, client).await,
-        SourceType::HackerNews => fetch_hackernews(source, client).await,
+/// [`fetch_from_source`] retrying every request under `policy`: timeouts, dropped connections
+/// and 5xx responses are retried, other 4xx responses fail the source at once. Each request
+/// first waits its turn at `limiter`, at least the source's `delay_ms` after the previous one
+/// to its host. Responses over `max_response_bytes` fail the source without being read
+/// further. A source with no items at all is a [`FetchErrorKind::Empty`] error; one with only
+/// stale items is not. Articles dated more than `max_future_skew` ahead are clamped to now and
+/// kept only after the rest, see [`clamp_future_dates`].
+pub async fn fetch_from_source_with(
+    source: &SourceConfig,
+    client: &reqwest::Client,
+    policy: &RetryPolicy,
+    limiter: &HostLimiter,
+    max_response_bytes: usize,
+    max_future_skew: Duration,
+) -> Result<Fetched, SourceFetchError> {
+    let options = FetchOptions {
+        headers: source.request_headers().map_err(|e| SourceFetchError::new(source, FetchErrorKind::Config, e))?,
+        timeout: source.timeout_secs.map(StdDuration::from_secs),
+        max_bytes: Some(max_response_bytes),
+    };
+    let fetch = SourceFetch { client, options, policy, limiter, delay: source.delay_ms.map(StdDuration::from_millis), max_future_skew };
+    let (articles, outcome) = match &source.source_type {
+        SourceType::Rss => fetch_rss(source, &fetch).await?,
+        SourceType::Atom => fetch_atom(source, &fetch).await?,
+        SourceType::HackerNews => fetch_hackernews(source, &fetch).await?,
+        SourceType::Lobsters => fetch_lobsters(source, &fetch).await?,
+        SourceType::Youtube => fetch_youtube(source, &fetch).await?,
+        SourceType::Scrape => fetch_scrape(source, &fetch).await?,
+        SourceType::Bluesky => fetch_bluesky(source, &fetch).await?,
+        SourceType::Unknown(name) => {
+            let detail = format!("unsupported source type '{}' (expected rss, atom, hackernews, lobsters, youtube, scrape or bluesky)", name);
+            return Err(SourceFetchError::new(source, FetchErrorKind::UnknownType, detail));
+        }
+    };
+    if outcome.items_seen == 0 {
+        return Err(SourceFetchError::new(source, FetchErrorKind::Empty, format!("{} lists no items", source.url)));
     }
+    if outcome.future_dated > 0 {
+        warn!(source = %source.name, future_dated = outcome.future_dated, "Items dated in the future, dated now");
+    }
+    Ok((articles, outcome))
+}
+
+/// How one source's requests go out: with its headers, timeout and size cap, under the
+/// retry policy, spaced out per host.
+struct SourceFetch<'a> {
+    client: &'a reqwest::Client,
+    options: FetchOptions,
+    policy: &'a RetryPolicy,
+    limiter: &'a HostLimiter,
+    /// The source's `delay_ms`
+    delay: Option<StdDuration>,
+    /// How far ahead an item may be dated before it's clamped to the fetch time
+    max_future_skew: Duration,
 }
 
-/// Try to parse various date formats commonly found in RSS feeds
-fn parse_rss_date(date_str: &str) -> Option<DateTime<Utc>> {
-    // Try RFC2822 first (standard RSS format)
-    if let Ok(dt) = DateTime::parse_from_rfc2822(date_str) {
-        return Some(dt.with_timezone(&Utc));
+impl SourceFetch<'_> {
+    async fn get(&self, url: &str) -> Result<Vec<u8>, llm_client::FetchError> {
+        self.limiter.wait(url, self.delay).await;
+        fetch_bytes_with(self.client, url, &self.options, self.policy).await
     }
 
-    // Try ThoughtWorks format: "Tue Nov 18 00:00:00 UTC 2025"
-    if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(date_str, "%a %b %d %H:%M:%S UTC %Y") {
-        return Some(dt.and_utc());
+    /// [`SourceFetch::get`] failing as `source`.
+    async fn get_for(&self, source: &SourceConfig, url: &str) -> Result<Vec<u8>, SourceFetchError> {
+        self.get(url).await.map_err(|e| SourceFetchError::request(source, e))
     }
+}
 
-    // Try ISO 8601 / RFC3339
-    if let Ok(dt) = DateTime::parse_from_rfc3339(date_str) {
-        return Some(dt.with_timezone(&Utc));
+/// A feed page's articles and the URL of the page after it.
+type Page = (Fetched, Option<String>);
+
+/// A feed paged per RFC 5005: `source.url`, then each page its predecessor links as
+/// `rel="next"`, up to [`max_pages`]. Paging stops at a page after the first that has nothing
+/// fresh, since newer posts come first, and once [`max_items`] fresh articles are in; the first
+/// page is always followed, as it may lead with old pinned posts (Discourse pins topics).
+/// Only the first page failing fails the source; a later one ends the paging with a warning.
+async fn fetch_pages(source: &SourceConfig, fetch: &SourceFetch<'_>, parse: impl Fn(&[u8]) -> Result<Page, SourceFetchError>) -> Result<Fetched, SourceFetchError> {
+    let (mut articles, mut outcome) = (Vec::new(), FetchOutcome::default());
+    let mut visited = HashSet::new();
+    let mut page_url = Some(source.url.clone());
+    for page in 1..=max_pages(source) {
+        let Some(url) = page_url.take().filter(|url| visited.insert(url.clone())) else { break };
+        let page_result = match fetch.get_for(source, &url).await {
+            Ok(content) => parse(&content),
+            Err(e) => Err(e),
+        };
+        let ((page_articles, page_outcome), next) = match page_result {
+            Ok(page) => page,
+            Err(e) if page == 1 => return Err(e),
+            Err(e) => {
+                warn!(source = %source.name, page, url = %url, kind = %e.kind, error = %e.detail, "Failed to fetch feed page, keeping earlier pages");
+                break;
+            }
+        };
+        let fresh = page_articles.len();
+        articles.extend(page_articles);
+        outcome.items_seen += page_outcome.items_seen;
+        outcome.filtered_by_date += page_outcome.filtered_by_date;
+        outcome.skipped_missing_fields += page_outcome.skipped_missing_fields;
+        if (page > 1 && fresh == 0) || articles.len() >= max_items(source) {
+            break;
+        }
+        // Relative links are resolved against the page they're on
+        page_url = next
+            .and_then(|next| Url::parse(&url).and_then(|base| base.join(&next)).ok())
+            .filter(|next| matches!(next.scheme(), "http" | "https"))
+            .map(String::from);
+        if page_url.is_some() {
+            debug!(source = %source.name, page, next = ?page_url, "Following next feed page");
+        }
     }
+    Ok(finish(articles, outcome, max_items(source)))
+}
+
+/// The `href` of a `rel="next"` link among `links`, given as `(rel, href)`.
+fn next_link<'a>(links: impl IntoIterator<Item = (Option<&'a str>, Option<&'a str>)>) -> Option<String> {
+    links
+        .into_iter()
+        .find(|(rel, _)| rel.is_some_and(|rel| rel.trim().eq_ignore_ascii_case("next")))
+        .and_then(|(_, href)| href.map(str::trim).filter(|href| !href.is_empty()).map(str::to_string))
+}
 
-    None
+/// The channel's `atom:link rel="next"`, which the `rss` crate keeps as an extension.
+fn rss_next_page(channel: &Channel) -> Option<String> {
+    let links = channel.extensions().get("atom").and_then(|atom| atom.get("link")).map(|links| links.as_slice()).unwrap_or_default();
+    next_link(links.iter().map(|link| (link.attrs().get("rel").map(String::as_str), link.attrs().get("href").map(String::as_str))))
 }
 
-async fn fetch_rss(source: &SourceConfig, client: &reqwest::Client) -> Result<Vec<Article>, Box<dyn Error + Send + Sync>> {
-    let content = client.get(&source.url).send().await?.bytes().await?;
-    let channel = Channel::read_from(&content[..])?;
+async fn fetch_rss(source: &SourceConfig, fetch: &SourceFetch<'_>) -> Result<Fetched, SourceFetchError> {
+    let now = Utc::now();
+    let fetched = fetch_pages(source, fetch, |content| {
+        let channel = read_rss_channel(content).map_err(|e| SourceFetchError::new(source, FetchErrorKind::Parse, e))?;
+        Ok((rss_articles(source, &channel, now, fetch.max_future_skew), rss_next_page(&channel)))
+    })
+    .await?;
+    debug!(source = %source.name, count = fetched.0.len(), "Fetched RSS articles");
+    Ok(fetched)
+}
+
+/// An RSS 0.9x/2.0 channel, or an RSS 1.0 (RDF) feed read into one, told apart by the root
+/// element. The `rss` crate reads RDF only when the root is spelled `rdf:RDF` and a
+/// `<channel>` comes first, so RDF goes through [`parse_rdf_items`] instead.
+fn read_rss_channel(content: &[u8]) -> Result<Channel, String> {
+    if !is_rdf_feed(content) {
+        return Channel::read_from(content).map_err(|e| e.to_string());
+    }
+    let items = parse_rdf_items(content).ok_or("malformed RSS 1.0 (RDF) feed")?;
+    let mut channel = Channel::default();
+    channel.set_items(items.into_iter().map(rdf_item).collect::<Vec<_>>());
+    Ok(channel)
+}
 
+/// An RDF item as the RSS item [`rss_articles`] reads: `dc:date` and `dc:creator` stay Dublin
+/// Core, `dc:subject`s become categories.
+fn rdf_item(rdf: RdfItem) -> rss::Item {
+    let mut dublin_core = rss::extension::dublincore::DublinCoreExtension::default();
+    dublin_core.set_dates(rdf.date.into_iter().collect::<Vec<_>>());
+    dublin_core.set_creators(rdf.creators);
+    let categories: Vec<rss::Category> = rdf
+        .subjects
+        .into_iter()
+        .map(|subject| {
+            let mut category = rss::Category::default();
+            category.set_name(subject);
+            category
+        })
+        .collect();
+
+    let mut item = rss::Item::default();
+    item.set_title(rdf.title);
+    item.set_link(rdf.link);
+    item.set_description(rdf.description);
+    item.set_content(rdf.content);
+    item.set_categories(categories);
+    item.set_dublin_core_ext(dublin_core);
+    item
+}
+
+/// The newest items from the 24 hours before `now`; items with no date we can read are skipped.
+fn rss_articles(source: &SourceConfig, channel: &Channel, now: DateTime<Utc>, max_future_skew: Duration) -> Fetched {
     let mut articles = Vec::new();
-    let yesterday = Utc::now() - Duration::hours(24);
+    let yesterday = now - Duration::hours(24);
     let mut skipped_dates = 0;
+    let mut outcome = FetchOutcome { items_seen: channel.items().len(), ..FetchOutcome::default() };
 
-    for item in channel.items().iter().take(MAX_ITEMS_PER_SOURCE) {
-        if let (Some(title), Some(link), Some(pub_date)) = (item.title(), item.link(), item.pub_date()) {
-            // Parse date using multiple format attempts
-            let parsed_date = match parse_rss_date(pub_date) {
+    for item in channel.items() {
+        let enclosure = item.enclosure().and_then(|e| MediaInfo::new(e.url(), e.mime_type(), Some(e.length())));
+        let body = item.content().or(item.description());
+        let page_link = rss_item_url(item);
+        let media = episode_media(enclosure, page_link.as_deref(), body);
+        // Episodes without a page link are addressed by their media URL
+        let link = page_link.as_deref().or(media.as_ref().map(|m| m.url.as_str()));
+        if let (Some(title), Some(link)) = (item.title(), link) {
+            let parsed_date = match rss_item_date(item) {
                 Some(dt) => dt,
                 None => {
                     skipped_dates += 1;
@@ -94,33 +845,87 @@ async fn fetch_rss(source: &SourceConfig, client: &reqwest::Client) -> Result<Ve
                     title: title.to_string(),
                     url: link.to_string(),
                     source: source.name.clone(),
+                    via: None,
                     published_at: parsed_date,
+                    show_notes: media.as_ref().and(item.description()).map(|d| d.to_string()),
+                    media,
+                    feed_content: body.map(|b| b.to_string()),
+                    summary: item.description().and_then(sanitize_feed_html),
+                    content: item.content().and_then(sanitize_feed_html),
+                    score: None,
+                    rank: None,
+                    author: item
+                        .dublin_core_ext()
+                        .and_then(|dc| dc.creators().iter().find_map(|creator| clean_author(creator)))
+                        .or_else(|| item.author().and_then(clean_author)),
+                    categories: clean_names(item.categories().iter().map(|c| c.name())),
+                    undated: false,
+                    future_dated: false,
+                    language: None,
                 });
+            } else {
+                outcome.filtered_by_date += 1;
             }
+        } else {
+            outcome.skipped_missing_fields += 1;
         }
     }
 
     if skipped_dates > 0 {
-        warn!(source = %source.name, skipped = skipped_dates, "Skipped articles with unparseable dates");
+        warn!(source = %source.name, skipped = skipped_dates, "Skipped articles without a readable date");
     }
-    debug!(source = %source.name, count = articles.len(), "Fetched RSS articles");
+    outcome.skipped_missing_fields += skipped_dates;
+    clamp_future_dates(&mut articles, now, max_future_skew);
+    finish(articles, outcome, max_items(source))
+}
 
-    Ok(articles)
+async fn fetch_atom(source: &SourceConfig, fetch: &SourceFetch<'_>) -> Result<Fetched, SourceFetchError> {
+    let now = Utc::now();
+    let fetched = fetch_pages(source, fetch, |content| {
+        let feed = AtomFeed::read_from(content).map_err(|e| SourceFetchError::new(source, FetchErrorKind::Parse, e))?;
+        let next = next_link(feed.links().iter().map(|link| (Some(link.rel()), Some(link.href()))));
+        Ok((atom_articles(source, &feed, now, fetch.max_future_skew), next))
+    })
+    .await?;
+    debug!(source = %source.name, count = fetched.0.len(), "Fetched Atom articles");
+    Ok(fetched)
 }
 
-async fn fetch_atom(source: &SourceConfig, client: &reqwest::Client) -> Result<Vec<Article>, Box<dyn Error + Send + Sync>> {
-    let content = client.get(&source.url).send().await?.text().await?;
-    let feed = content.parse::<AtomFeed>()?;
+/// The link to an entry's web page: `rel="alternate"` as `text/html`, then an untyped alternate,
+/// then any link that isn't `self`, `edit` or an enclosure, since many feeds list the feed/API
+/// links first. `None` when only those remain.
+fn pick_entry_link(entry: &atom_syndication::Entry) -> Option<&atom_syndication::Link> {
+    let links = entry.links();
+    let is_alternate = |l: &&atom_syndication::Link| l.rel().trim().eq_ignore_ascii_case("alternate");
+    let is_html = |l: &&atom_syndication::Link| l.mime_type().is_some_and(|t| t.trim().eq_ignore_ascii_case("text/html"));
+    links
+        .iter()
+        .find(|l| is_alternate(l) && is_html(l))
+        .or_else(|| links.iter().find(|l| is_alternate(l) && l.mime_type().is_none()))
+        .or_else(|| links.iter().find(|l| !matches!(l.rel().trim().to_ascii_lowercase().as_str(), "self" | "edit" | "enclosure")))
+}
 
+/// The newest entries published (or, lacking that, updated) in the 24 hours before `now`.
+fn atom_articles(source: &SourceConfig, feed: &AtomFeed, now: DateTime<Utc>, max_future_skew: Duration) -> Fetched {
     let mut articles = Vec::new();
-    let yesterday = Utc::now() - Duration::hours(24);
+    let yesterday = now - Duration::hours(24);
     let mut skipped_dates = 0;
+    let mut outcome = FetchOutcome { items_seen: feed.entries().len(), ..FetchOutcome::default() };
 
-    for entry in feed.entries().iter().take(MAX_ITEMS_PER_SOURCE) {
+    for entry in feed.entries() {
         let title = entry.title().as_str();
 
-        // Get the first link (usually the alternate/html link)
-        let link = entry.links().first().map(|l| l.href());
+        let enclosure = entry.links().iter()
+            .find(|l| l.rel() == "enclosure")
+            .and_then(|l| MediaInfo::new(l.href(), l.mime_type().unwrap_or(""), l.length()));
+        let page_link = pick_entry_link(entry).map(|l| l.href());
+        if page_link.is_none() && enclosure.is_none() && !entry.links().is_empty() {
+            warn!(source = %source.name, title = %title, "Skipped entry with only self/edit links");
+        }
+        let summary = entry.summary().map(|s| s.as_str());
+        let body = entry.content().and_then(|c| c.value()).or(summary);
+        let media = episode_media(enclosure, page_link, body);
+        let link = page_link.or(media.as_ref().map(|m| m.url.as_str()));
 
         // Atom uses published or updated date
         let date_str = entry.published().or(Some(entry.updated()));
@@ -140,74 +945,481 @@ async fn fetch_atom(source: &SourceConfig, client: &reqwest::Client) -> Result<V
                     title: title.to_string(),
                     url: link.to_string(),
                     source: source.name.clone(),
+                    via: None,
                     published_at: parsed_date,
+                    show_notes: media.as_ref().and(summary).map(|s| s.to_string()),
+                    media,
+                    feed_content: body.map(|b| b.to_string()),
+                    summary: summary.and_then(sanitize_feed_html),
+                    content: entry.content().and_then(|c| c.value()).and_then(sanitize_feed_html),
+                    score: None,
+                    rank: None,
+                    author: Some(clean_names(entry.authors().iter().map(|a| a.name())).join(", ")).filter(|names| !names.is_empty()),
+                    categories: clean_names(entry.categories().iter().map(|c| c.label().unwrap_or(c.term()))),
+                    undated: false,
+                    future_dated: false,
+                    language: None,
                 });
+            } else {
+                outcome.filtered_by_date += 1;
             }
+        } else {
+            outcome.skipped_missing_fields += 1;
         }
     }
 
     if skipped_dates > 0 {
         warn!(source = %source.name, skipped = skipped_dates, "Skipped entries with unparseable dates");
     }
-    debug!(source = %source.name, count = articles.len(), "Fetched Atom articles");
+    outcome.skipped_missing_fields += skipped_dates;
+    clamp_future_dates(&mut articles, now, max_future_skew);
+    finish(articles, outcome, max_items(source))
+}
 
-    Ok(articles)
+/// One HN item; failures are logged and yield `None` so a bad item doesn't sink the source.
+async fn fetch_hn_item(fetch: &SourceFetch<'_>, item_base: &Url, id: u32) -> Option<HnItem> {
+    let url = match item_base.join(&format!("item/{}.json", id)) {
+        Ok(url) => url,
+        Err(e) => {
+            warn!(id = id, error = %e, "Invalid HN item URL");
+            return None;
+        }
+    };
+    let body = match fetch.get(url.as_str()).await {
+        Ok(body) => body,
+        Err(e) => {
+            warn!(id = id, error = %e, "Failed to fetch HN item");
+            return None;
+        }
+    };
+    match serde_json::from_slice(&body) {
+        Ok(item) => Some(item),
+        Err(e) => {
+            warn!(id = id, error = %e, "Failed to parse HN item");
+            None
+        }
+    }
 }
 
-async fn fetch_hackernews(source: &SourceConfig, client: &reqwest::Client) -> Result<Vec<Article>, Box<dyn Error + Send + Sync>> {
-    let top_ids: Vec<u32> = client.get(&source.url).send().await?.json().await?;
+async fn fetch_hackernews(source: &SourceConfig, fetch: &SourceFetch<'_>) -> Result<Fetched, SourceFetchError> {
+    // Any `v0/*stories.json` list works: top, best, new, ask, show
+    let story_ids: Vec<u32> = serde_json::from_slice(&fetch.get_for(source, &source.url).await?)
+        .map_err(|e| SourceFetchError::new(source, FetchErrorKind::Parse, e))?;
+
+    // Items live next to the story list: `/v0/beststories.json` -> `/v0/item/<id>.json`
+    let item_base = Url::parse(&source.url).map_err(|e| SourceFetchError::new(source, FetchErrorKind::Config, e))?;
 
     let mut articles = Vec::new();
-    let yesterday = Utc::now() - Duration::hours(24);
-    let mut skipped_timestamps = 0;
-
-    // Fetch top stories using the shared client
-    for id in top_ids.iter().take(MAX_ITEMS_PER_SOURCE) {
-        let url = format!("https://hacker-news.firebaseio.com/v0/item/{}.json", id);
-        let resp = match client.get(&url).send().await {
-            Ok(resp) => resp,
-            Err(e) => {
-                warn!(id = id, error = %e, "Failed to fetch HN item");
-                continue;
+    let now = Utc::now();
+    let yesterday = now - Duration::hours(24);
+    let (mut skipped_timestamps, mut dead, mut not_stories, mut low_score, mut few_comments) = (0, 0, 0, 0, 0);
+
+    // Fetch more stories than we keep so the score/comment filters have something to choose
+    // from. Items are requested concurrently but come back in ranking order.
+    let limit = max_items(source);
+    let items: Vec<(usize, u32, Option<HnItem>)> = stream::iter(story_ids.into_iter().take(limit * HN_CANDIDATES_PER_ITEM).enumerate())
+        .map(|(position, id)| {
+            let item_base = &item_base;
+            async move { (position + 1, id, fetch_hn_item(fetch, item_base, id).await) }
+        })
+        .buffered(HN_CONCURRENCY)
+        .collect()
+        .await;
+    let mut outcome = FetchOutcome { items_seen: items.len(), ..FetchOutcome::default() };
+
+    for (rank, id, item) in items {
+        let Some(item) = item else { continue };
+        match hn_article(source, id, rank, item, yesterday) {
+            Ok(article) => {
+                articles.push(article);
+                if articles.len() == limit {
+                    break;
+                }
             }
+            Err(HnSkip::Dead) => dead += 1,
+            Err(HnSkip::NotStory) => not_stories += 1,
+            Err(HnSkip::MissingTitle) => outcome.skipped_missing_fields += 1,
+            Err(HnSkip::MissingTime) => {
+                skipped_timestamps += 1;
+                outcome.skipped_missing_fields += 1;
+            }
+            Err(HnSkip::Stale) => outcome.filtered_by_date += 1,
+            Err(HnSkip::LowScore) => low_score += 1,
+            Err(HnSkip::FewComments) => few_comments += 1,
+            Err(HnSkip::TextPost) => {}
+        }
+    }
+
+    if skipped_timestamps > 0 {
+        warn!(source = %source.name, skipped = skipped_timestamps, "Skipped items with a missing or invalid time");
+    }
+    if dead > 0 || not_stories > 0 || low_score > 0 || few_comments > 0 {
+        debug!(source = %source.name, dead, not_stories, low_score, few_comments, "Skipped HackerNews items");
+    }
+    debug!(source = %source.name, count = articles.len(), "Fetched HackerNews articles");
+
+    outcome.future_dated = clamp_future_dates(&mut articles, now, fetch.max_future_skew);
+    outcome.articles = articles.len();
+    Ok((articles, outcome))
+}
+
+async fn fetch_lobsters(source: &SourceConfig, fetch: &SourceFetch<'_>) -> Result<Fetched, SourceFetchError> {
+    let stories: Vec<LobstersStory> = serde_json::from_slice(&fetch.get_for(source, &source.url).await?)
+        .map_err(|e| SourceFetchError::new(source, FetchErrorKind::Parse, e))?;
+    let fetched = lobsters_articles(source, stories, Utc::now(), fetch.max_future_skew);
+    debug!(source = %source.name, count = fetched.0.len(), "Fetched Lobsters articles");
+    Ok(fetched)
+}
+
+/// Stories from the 24 hours before `now` scoring at least the source's `min_score`. Text
+/// posts are skipped unless the source sets `allow_text_posts`; they then link to their
+/// discussion and carry their body as feed content.
+fn lobsters_articles(source: &SourceConfig, stories: Vec<LobstersStory>, now: DateTime<Utc>, max_future_skew: Duration) -> Fetched {
+    let yesterday = now - Duration::hours(24);
+    let (mut text_posts, mut low_score) = (0, 0);
+    let mut articles = Vec::new();
+    let mut outcome = FetchOutcome { items_seen: stories.len(), ..FetchOutcome::default() };
+
+    for story in stories {
+        let published_at = story.created_at.with_timezone(&Utc);
+        if published_at < yesterday {
+            outcome.filtered_by_date += 1;
+            continue;
+        }
+        if source.min_score.is_some_and(|min| story.score < min) {
+            low_score += 1;
+            continue;
+        }
+        let is_text_post = story.url.trim().is_empty();
+        if is_text_post && (!source.allow_text_posts || story.comments_url.is_empty()) {
+            text_posts += 1;
+            continue;
+        }
+        articles.push(Article {
+            title: story.title,
+            url: if is_text_post { story.comments_url } else { story.url },
+            source: source.name.clone(),
+            via: None,
+            published_at,
+            media: None,
+            show_notes: None,
+            content: is_text_post.then(|| sanitize_feed_html(&story.description)).flatten(),
+            feed_content: (is_text_post && !story.description.is_empty()).then_some(story.description),
+            summary: None,
+            score: Some(story.score),
+            rank: None,
+            author: None,
+            categories: clean_names(story.tags.iter().map(String::as_str)),
+            undated: false,
+            future_dated: false,
+            language: None,
+        });
+        if articles.len() == max_items(source) {
+            break;
+        }
+    }
+
+    if text_posts > 0 || low_score > 0 {
+        debug!(source = %source.name, text_posts, low_score, "Skipped Lobsters stories");
+    }
+    outcome.future_dated = clamp_future_dates(&mut articles, now, max_future_skew);
+    outcome.articles = articles.len();
+    (articles, outcome)
+}
+
+async fn fetch_bluesky(source: &SourceConfig, fetch: &SourceFetch<'_>) -> Result<Fetched, SourceFetchError> {
+    let feed_url = bluesky_feed_url(&source.url).ok_or_else(|| {
+        let detail = format!("'{}' names no Bluesky account; use a handle, DID, at:// URI or bsky.app profile URL", source.url);
+        SourceFetchError::new(source, FetchErrorKind::Config, detail)
+    })?;
+    let feed: BlueskyFeed = serde_json::from_slice(&fetch.get_for(source, &feed_url).await?)
+        .map_err(|e| SourceFetchError::new(source, FetchErrorKind::Parse, e))?;
+    let fetched = bluesky_articles(source, feed, Utc::now(), fetch.max_future_skew);
+    debug!(source = %source.name, count = fetched.0.len(), "Fetched Bluesky link posts");
+    Ok(fetched)
+}
+
+/// A post's text with whitespace collapsed, cut to [`BLUESKY_TITLE_CHARS`] characters.
+fn bluesky_title(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= BLUESKY_TITLE_CHARS {
+        return text;
+    }
+    let cut: String = text.chars().take(BLUESKY_TITLE_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// The author's posts indexed in the 24 hours before `now` that carry a link card, each
+/// becoming an article for the linked page, titled with the post's text (the card's title
+/// when the post has none). Reposts, replies and posts without an http(s) link are skipped.
+fn bluesky_articles(source: &SourceConfig, feed: BlueskyFeed, now: DateTime<Utc>, max_future_skew: Duration) -> Fetched {
+    let yesterday = now - Duration::hours(24);
+    let (mut reposts, mut replies, mut without_link) = (0, 0, 0);
+    let mut articles = Vec::new();
+    let mut outcome = FetchOutcome { items_seen: feed.feed.len(), ..FetchOutcome::default() };
+
+    for item in feed.feed {
+        if item.reason.as_ref().is_some_and(|reason| reason.kind == BLUESKY_REPOST) {
+            reposts += 1;
+            continue;
+        }
+        let post = item.post;
+        if item.reply.is_some() || post.record.reply.is_some() {
+            replies += 1;
+            continue;
+        }
+        let card = post.embed.as_ref().and_then(BlueskyEmbed::link_card);
+        let Some(card) = card.filter(|card| Url::parse(card.uri.trim()).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))) else {
+            without_link += 1;
+            continue;
+        };
+        let Some(published_at) = post.indexed_at.as_deref().and_then(parse_feed_date) else {
+            outcome.skipped_missing_fields += 1;
+            continue;
         };
+        if published_at < yesterday {
+            outcome.filtered_by_date += 1;
+            continue;
+        }
+        let title = [post.record.text.as_str(), card.title.as_str(), card.uri.as_str()].into_iter().map(bluesky_title).find(|t| !t.is_empty());
+        let author = post.author.display_name.map(|name| name.trim().to_string()).filter(|name| !name.is_empty());
+        articles.push(Article {
+            title: title.unwrap_or_default(),
+            url: card.uri.trim().to_string(),
+            source: source.name.clone(),
+            via: None,
+            published_at,
+            media: None,
+            show_notes: None,
+            feed_content: None,
+            summary: Some(card.description.trim().to_string()).filter(|d| !d.is_empty()),
+            content: None,
+            score: None,
+            rank: None,
+            author: author.or(Some(post.author.handle)),
+            categories: Vec::new(),
+            undated: false,
+            future_dated: false,
+            language: None,
+        });
+    }
 
-        let item: HnItem = match resp.json().await {
-            Ok(item) => item,
-            Err(e) => {
-                warn!(id = id, error = %e, "Failed to parse HN item");
-                continue;
-            }
+    if reposts > 0 || replies > 0 || without_link > 0 {
+        debug!(source = %source.name, reposts, replies, without_link, "Skipped Bluesky posts");
+    }
+    clamp_future_dates(&mut articles, now, max_future_skew);
+    finish(articles, outcome, max_items(source))
+}
+
+async fn fetch_scrape(source: &SourceConfig, fetch: &SourceFetch<'_>) -> Result<Fetched, SourceFetchError> {
+    let page_url = Url::parse(source.url.trim()).map_err(|e| SourceFetchError::new(source, FetchErrorKind::Config, e))?;
+    let content = fetch.get_for(source, page_url.as_str()).await?;
+    let now = Utc::now();
+    let (mut articles, outcome) = scrape_articles(source, &String::from_utf8_lossy(&content), &page_url, now)
+        .map_err(|e| SourceFetchError::new(source, FetchErrorKind::Parse, e))?;
+    clamp_future_dates(&mut articles, now, fetch.max_future_skew);
+    let undated = articles.iter().filter(|a| a.undated).count();
+    if undated > 0 {
+        warn!(source = %source.name, undated, "Scraped items without a readable date, dated now");
+    }
+    debug!(source = %source.name, count = articles.len(), "Scraped listing page");
+    Ok(finish(articles, outcome, max_items(source)))
+}
+
+async fn fetch_youtube(source: &SourceConfig, fetch: &SourceFetch<'_>) -> Result<Fetched, SourceFetchError> {
+    // A handle URL is resolved through the channel page, a request like any other of the source
+    let feed_url = match youtube_feed_url(&source.url) {
+        Some(feed_url) => feed_url,
+        None => {
+            let page = fetch.get_for(source, source.url.trim()).await?;
+            youtube_feed_url_from_page(&String::from_utf8_lossy(&page))
+                .ok_or_else(|| SourceFetchError::new(source, FetchErrorKind::Parse, "no YouTube channel ID on the channel page"))?
+        }
+    };
+    let content = fetch.get_for(source, &feed_url).await?;
+    let feed = AtomFeed::read_from(&content[..]).map_err(|e| SourceFetchError::new(source, FetchErrorKind::Parse, e))?;
+    let fetched = youtube_articles(source, &feed, Utc::now(), fetch.max_future_skew);
+    debug!(source = %source.name, count = fetched.0.len(), "Fetched YouTube videos");
+    Ok(fetched)
+}
+
+/// Videos of a channel feed published in the 24 hours before `now`. The watch page has
+/// nothing to extract, so each is an episode whose show notes are the feed's
+/// `media:description`, or just the title when the description is empty.
+fn youtube_articles(source: &SourceConfig, feed: &AtomFeed, now: DateTime<Utc>, max_future_skew: Duration) -> Fetched {
+    let yesterday = now - Duration::hours(24);
+    let mut articles = Vec::new();
+    let mut outcome = FetchOutcome { items_seen: feed.entries().len(), ..FetchOutcome::default() };
+
+    for entry in feed.entries() {
+        let published_at = entry.published().unwrap_or(entry.updated()).with_timezone(&Utc);
+        if published_at < yesterday {
+            outcome.filtered_by_date += 1;
+            continue;
+        }
+        let Some(link) = pick_entry_link(entry) else {
+            outcome.skipped_missing_fields += 1;
+            continue;
         };
+        let group = entry.extensions().get("media").and_then(|media| media.get("group")).and_then(|groups| groups.first());
+        let media_field = |name: &str| {
+            group
+                .and_then(|g| g.children().get(name))
+                .and_then(|values| values.first())
+                .and_then(|value| value.value())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        let title = media_field("title").unwrap_or(entry.title().as_str()).to_string();
+        let show_notes = media_field("description").map(str::to_string).unwrap_or_else(|| format!("Title: {}", title));
+        articles.push(Article {
+            title,
+            url: link.href().to_string(),
+            source: source.name.clone(),
+            via: None,
+            published_at,
+            media: Some(MediaInfo { url: link.href().to_string(), mime_type: YOUTUBE_MIME_TYPE.to_string(), length: None }),
+            show_notes: Some(show_notes),
+            feed_content: None,
+            summary: None,
+            content: None,
+            score: None,
+            rank: None,
+            author: None,
+            categories: Vec::new(),
+            undated: false,
+            future_dated: false,
+            language: None,
+        });
+    }
+    clamp_future_dates(&mut articles, now, max_future_skew);
+    finish(articles, outcome, max_items(source))
+}
 
-        if let (Some(title), Some(url)) = (item.title, item.url) {
-            // HN time is unix timestamp
-            let published_at = match DateTime::from_timestamp(item.time, 0) {
-                Some(dt) => dt,
-                None => {
-                    skipped_timestamps += 1;
-                    continue;
-                }
-            };
+/// Whether an article reached us through an aggregator (a link site or a newsletter) rather
+/// than from the blog that published it.
+fn is_aggregated(article: &Article, aggregators: &HashSet<String>) -> bool {
+    article.via.is_some() || aggregators.contains(&article.source)
+}
 
-            // Apply same 24h freshness filter as RSS (>= to include boundary)
-            if published_at >= yesterday {
-                articles.push(Article {
-                    title,
-                    url,
-                    source: source.name.clone(),
-                    published_at,
-                });
+/// Collapse articles whose URLs match after [`normalize_article_url`], keeping the first
+/// one's position. The entry from the original blog wins over aggregator ones (sources named
+/// in `aggregators`, newsletter items), and the others' labels are merged into its `via`.
+/// Score and rank come from a single aggregator, whose name is listed last so the headline
+/// reads "[Blog, Lobsters, Hacker News #3, 512 points]"; one site's rank is never paired with
+/// another's points. Returns the remaining articles and how many were dropped.
+pub fn dedup_articles(articles: Vec<Article>, aggregators: &HashSet<String>) -> (Vec<Article>, usize) {
+    let total = articles.len();
+    let mut kept: Vec<Article> = Vec::with_capacity(total);
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for article in articles {
+        let key = normalize_article_url(&article.url);
+        let Some(&at) = index.get(&key) else {
+            index.insert(key, kept.len());
+            kept.push(article);
+            continue;
+        };
+        let (mut winner, loser) = if is_aggregated(&kept[at], aggregators) && !is_aggregated(&article, aggregators) {
+            (article, kept[at].clone())
+        } else {
+            (kept[at].clone(), article)
+        };
+        let mut names: Vec<String> = winner.source_label().split(", ").map(String::from).collect();
+        for name in loser.source_label().split(", ") {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
             }
         }
+        // The aggregator's standing still says something about the original post; the
+        // article carrying it has its aggregator's name last
+        let has_signal = |a: &Article| a.score.is_some() || a.rank.is_some();
+        let signal = [&winner, &loser].into_iter().find(|a| has_signal(a)).map(|a| {
+            let name = a.source_label().rsplit(", ").next().unwrap_or_default().to_string();
+            (a.score, a.rank, name)
+        });
+        if let Some((score, rank, name)) = signal {
+            names.retain(|n| *n != name);
+            names.push(name);
+            (winner.score, winner.rank) = (score, rank);
+        }
+        winner.via = Some(names.join(", ")).filter(|label| *label != winner.source);
+        kept[at] = winner;
     }
+    let removed = total - kept.len();
+    (kept, removed)
+}
 
-    if skipped_timestamps > 0 {
-        warn!(source = %source.name, skipped = skipped_timestamps, "Skipped items with invalid timestamps");
+/// At most `cap` of `articles`, sampled round-robin across sources: each source in turn, in
+/// the order they first appear, gives up its newest article not yet taken, until the cap is
+/// reached or every source is exhausted. A prolific source then can't crowd out the others,
+/// and none loses out for having been fetched last. The kept articles stay in their original
+/// order. Returns them and how many were dropped.
+pub fn balance_articles(articles: Vec<Article>, cap: usize) -> (Vec<Article>, usize) {
+    let total = articles.len();
+    if total <= cap {
+        return (articles, 0);
     }
-    debug!(source = %source.name, count = articles.len(), "Fetched HackerNews articles");
+    // Positions of each source's articles, newest last so they can be popped
+    let mut by_source: Vec<(&str, Vec<usize>)> = Vec::new();
+    for (i, article) in articles.iter().enumerate() {
+        match by_source.iter_mut().find(|(source, _)| *source == article.source) {
+            Some((_, positions)) => positions.push(i),
+            None => by_source.push((&article.source, vec![i])),
+        }
+    }
+    for (_, positions) in &mut by_source {
+        // Oldest first so `pop` takes the newest; of equally dated ones, the first listed
+        positions.sort_by_key(|&i| (articles[i].published_at, std::cmp::Reverse(i)));
+    }
+    let mut keep = vec![false; total];
+    let mut remaining = cap;
+    // There are more articles than the cap, so the rounds end by reaching it
+    while remaining > 0 {
+        for (_, positions) in &mut by_source {
+            if remaining == 0 {
+                break;
+            }
+            if let Some(i) = positions.pop() {
+                keep[i] = true;
+                remaining -= 1;
+            }
+        }
+    }
+    let kept: Vec<Article> = articles.into_iter().zip(keep).filter_map(|(article, keep)| keep.then_some(article)).collect();
+    let dropped = total - kept.len();
+    (kept, dropped)
+}
 
-    Ok(articles)
+/// Where `url` ends up once redirects (feedburner, newsletter click trackers, ...) are followed,
+/// with tracking parameters stripped as in [`strip_tracking_params`]. The request waits its
+/// turn at `limiter` like the feed fetches, with the timeout of the `source` that listed the
+/// article, and its headers and `delay_ms` when the article is on the source's own host. When
+/// the request fails or ends in an error page (a 404, a login wall), `url` itself stripped.
+pub async fn resolve_final_url(client: &reqwest::Client, url: &str, source: Option<&SourceConfig>, limiter: &HostLimiter) -> String {
+    let url = url.trim();
+    let host = |url: &str| Url::parse(url).ok().and_then(|u| u.host_str().map(str::to_lowercase));
+    // Credentials for the source's host mustn't go to the sites it links to
+    let own_host = source.filter(|s| host(&s.url).is_some() && host(&s.url) == host(url));
+    limiter.wait(url, own_host.and_then(|s| s.delay_ms).map(StdDuration::from_millis)).await;
+    let mut request = client.get(url);
+    if let Some(headers) = own_host.and_then(|s| s.request_headers().ok()) {
+        request = request.headers(headers);
+    }
+    if let Some(timeout) = source.and_then(|s| s.timeout_secs) {
+        request = request.timeout(StdDuration::from_secs(timeout));
+    }
+    match request.send().await {
+        // The body isn't needed; dropping the response closes the connection early
+        Ok(response) if response.status().is_success() => strip_tracking_params(response.url().as_str()),
+        Ok(response) => {
+            debug!(url = %url, final_url = %response.url(), status = response.status().as_u16(), "Link ends in an error page, keeping the feed's");
+            strip_tracking_params(url)
+        }
+        Err(e) => {
+            debug!(url = %url, error = %e, "Failed to resolve final URL, keeping the feed's");
+            strip_tracking_params(url)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -215,13 +1427,28 @@ mod tests {
     use super::*;
     use chrono::{Datelike, Timelike};
 
+    const SKEW: Duration = Duration::hours(llm_client::DEFAULT_MAX_FUTURE_SKEW_HOURS);
+
     #[test]
     fn test_article_struct() {
         let article = Article {
             title: "Test Article".to_string(),
             url: "https://example.com/article".to_string(),
             source: "Test Source".to_string(),
+            via: None,
             published_at: Utc::now(),
+            media: None,
+            show_notes: None,
+            feed_content: None,
+            summary: None,
+            content: None,
+            score: None,
+            rank: None,
+            author: None,
+            categories: Vec::new(),
+            undated: false,
+            future_dated: false,
+            language: None,
         };
 
         assert_eq!(article.title, "Test Article");
@@ -236,9 +1463,9 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_rss_date_rfc2822() {
+    fn test_parse_feed_date_rfc2822() {
         // Standard RSS format
-        let date = parse_rss_date("Tue, 18 Nov 2025 00:00:00 +0000");
+        let date = parse_feed_date("Tue, 18 Nov 2025 00:00:00 +0000");
         assert!(date.is_some());
         let dt = date.unwrap();
         assert_eq!(dt.year(), 2025);
@@ -247,9 +1474,9 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_rss_date_thoughtworks_format() {
+    fn test_parse_feed_date_thoughtworks_format() {
         // ThoughtWorks custom format: "Tue Nov 18 00:00:00 UTC 2025"
-        let date = parse_rss_date("Tue Nov 18 00:00:00 UTC 2025");
+        let date = parse_feed_date("Tue Nov 18 00:00:00 UTC 2025");
         assert!(date.is_some());
         let dt = date.unwrap();
         assert_eq!(dt.year(), 2025);
@@ -258,9 +1485,9 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_rss_date_rfc3339() {
+    fn test_parse_feed_date_rfc3339() {
         // ISO 8601 / RFC3339 format
-        let date = parse_rss_date("2025-11-18T00:00:00+00:00");
+        let date = parse_feed_date("2025-11-18T00:00:00+00:00");
         assert!(date.is_some());
         let dt = date.unwrap();
         assert_eq!(dt.year(), 2025);
@@ -269,15 +1496,15 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_rss_date_invalid() {
-        assert!(parse_rss_date("not a date").is_none());
-        assert!(parse_rss_date("").is_none());
-        assert!(parse_rss_date("2025-13-45").is_none());
+    fn test_parse_feed_date_invalid() {
+        assert!(parse_feed_date("not a date").is_none());
+        assert!(parse_feed_date("").is_none());
+        assert!(parse_feed_date("2025-13-45").is_none());
     }
 
     #[test]
-    fn test_parse_rss_date_positive_timezone_offset() {
-        let date = parse_rss_date("Wed, 25 Dec 2024 10:00:00 +0530");
+    fn test_parse_feed_date_positive_timezone_offset() {
+        let date = parse_feed_date("Wed, 25 Dec 2024 10:00:00 +0530");
         assert!(date.is_some());
         let dt = date.unwrap();
         assert_eq!(dt.year(), 2024);
@@ -289,8 +1516,8 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_rss_date_negative_timezone_offset() {
-        let date = parse_rss_date("Wed, 25 Dec 2024 10:00:00 -0800");
+    fn test_parse_feed_date_negative_timezone_offset() {
+        let date = parse_feed_date("Wed, 25 Dec 2024 10:00:00 -0800");
         assert!(date.is_some());
         let dt = date.unwrap();
         // -0800 means 10:00 PST = 18:00 UTC
@@ -298,8 +1525,8 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_rss_date_boundary_year_end() {
-        let date = parse_rss_date("Tue, 31 Dec 2024 23:59:59 +0000");
+    fn test_parse_feed_date_boundary_year_end() {
+        let date = parse_feed_date("Tue, 31 Dec 2024 23:59:59 +0000");
         assert!(date.is_some());
         let dt = date.unwrap();
         assert_eq!(dt.year(), 2024);
@@ -308,8 +1535,8 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_rss_date_boundary_year_start() {
-        let date = parse_rss_date("Wed, 01 Jan 2025 00:00:00 +0000");
+    fn test_parse_feed_date_boundary_year_start() {
+        let date = parse_feed_date("Wed, 01 Jan 2025 00:00:00 +0000");
         assert!(date.is_some());
         let dt = date.unwrap();
         assert_eq!(dt.year(), 2025);
@@ -318,20 +1545,1196 @@ mod tests {
     }
 
     #[test]
-    fn test_source_config_clone() {
-        let source = SourceConfig {
-            name: "Blog".to_string(),
-            source_type: SourceType::Rss,
-            url: "https://blog.example.com/rss".to_string(),
-        };
-        let cloned = source.clone();
-        assert_eq!(source, cloned);
-    }
+    fn test_rss_dates_fall_back_to_dc_and_atom_extensions() {
+        let channel = Channel::read_from(include_str!("../tests/fixtures/rss_date_formats.xml").as_bytes()).unwrap();
+        let source = SourceConfig::new("Date Formats Blog", SourceType::Rss, "https://dates.example.com/feed");
+        let now = DateTime::parse_from_rfc3339("2025-06-10T18:00:00Z").unwrap().with_timezone(&Utc);
+        let noon = DateTime::parse_from_rfc3339("2025-06-10T12:30:00Z").unwrap().with_timezone(&Utc);
+        let midnight = DateTime::parse_from_rfc3339("2025-06-10T00:00:00Z").unwrap().with_timezone(&Utc);
 
-    #[tokio::test]
-    async fn test_fetch_rss_with_mock_server() {
-        use wiremock::{MockServer, Mock, ResponseTemplate};
-        use wiremock::matchers::{method, path};
+        let articles = rss_articles(&source, &channel, now, SKEW).0;
+
+        let dated: Vec<(&str, DateTime<Utc>)> = articles.iter().map(|a| (a.title.as_str(), a.published_at)).collect();
+        assert_eq!(dated, vec![
+            ("RFC 2822 pubDate", noon),
+            ("GMT pubDate without seconds", noon),
+            ("RFC 3339 pubDate", noon),
+            ("dc:date only", noon),
+            ("atom:updated only", noon),
+            ("Unreadable pubDate with dc:date", noon),
+            ("dc:date day only", midnight),
+        ]);
+    }
+
+    #[test]
+    fn test_rdf_feeds_are_read_as_rss() {
+        let feed = include_str!("../tests/fixtures/rdf_research_blog.rdf");
+        assert!(Channel::read_from(feed.as_bytes()).is_err());
+        let channel = read_rss_channel(feed.as_bytes()).unwrap();
+        let source = SourceConfig::new("Systems Group Blog", SourceType::Rss, "https://systems.cs.example.edu/blog/index.rdf");
+        let now = DateTime::parse_from_rfc3339("2025-06-10T18:00:00Z").unwrap().with_timezone(&Utc);
+
+        let (articles, outcome) = rss_articles(&source, &channel, now, SKEW);
+        assert_eq!(outcome, FetchOutcome { articles: 2, items_seen: 4, filtered_by_date: 1, skipped_missing_fields: 1, future_dated: 0 });
+        let dated: Vec<(&str, &str, String)> = articles.iter().map(|a| (a.title.as_str(), a.url.as_str(), a.published_at.to_rfc3339())).collect();
+        assert_eq!(dated, vec![
+            ("Where Raft's tail latency comes from", "https://systems.cs.example.edu/blog/2025/06/tail-latency-in-raft/", "2025-06-10T07:15:00+00:00".to_string()),
+            ("EuroSys 2025 recap", "https://systems.cs.example.edu/blog/2025/06/eurosys-recap/", "2025-06-10T00:00:00+00:00".to_string()),
+        ]);
+        assert_eq!(articles[0].author.as_deref(), Some("M. Okafor"));
+        assert_eq!(articles[0].categories, ["distributed systems", "consensus"]);
+        assert_eq!(articles[0].summary.as_deref(), Some("We traced p99.9 commit latency in a five-node cluster to leader disk stalls."));
+        assert!(articles[0].content.as_deref().is_some_and(|c| c.contains("leader disk stalls")));
+
+        // RSS 2.0 still goes through the rss crate, and garbage still fails
+        let rss2 = read_rss_channel(include_str!("../tests/fixtures/rss_date_formats.xml").as_bytes()).unwrap();
+        assert!(!rss2.items().is_empty());
+        assert!(read_rss_channel(b"<html><body>not a feed</body></html>").is_err());
+    }
+
+    #[test]
+    fn test_rss_fresh_posts_below_pinned_ones_are_kept() {
+        let channel = Channel::read_from(include_str!("../tests/fixtures/rss_pinned_evergreen.xml").as_bytes()).unwrap();
+        let source = SourceConfig::new("Pinned Evergreen Blog", SourceType::Rss, "https://pinned.example.com/feed");
+        let now = DateTime::parse_from_rfc3339("2025-06-10T18:00:00Z").unwrap().with_timezone(&Utc);
+
+        let titles: Vec<String> = rss_articles(&source, &channel, now, SKEW).0.into_iter().map(|a| a.title).collect();
+        assert_eq!(titles, ["Fresh post 13", "Fresh post 12", "Fresh post 11"]);
+    }
+
+    #[test]
+    fn test_atom_oldest_first_keeps_the_newest_entries() {
+        let feed = AtomFeed::read_from(include_str!("../tests/fixtures/atom_oldest_first.xml").as_bytes()).unwrap();
+        let source = SourceConfig::new("Oldest-First Jekyll Blog", SourceType::Atom, "https://jekyll.example.com/feed.xml");
+        let now = DateTime::parse_from_rfc3339("2025-06-10T18:00:00Z").unwrap().with_timezone(&Utc);
+
+        let titles: Vec<String> = atom_articles(&source, &feed, now, SKEW).0.into_iter().map(|a| a.title).collect();
+        // Twelve entries are fresh; the cap keeps the ten newest, newest first
+        let expected: Vec<String> = (4..=13).rev().map(|i| format!("Post {}", i)).collect();
+        assert_eq!(titles, expected);
+    }
+
+    #[test]
+    fn test_max_items_overrides_the_per_source_cap() {
+        let feed = AtomFeed::read_from(include_str!("../tests/fixtures/atom_oldest_first.xml").as_bytes()).unwrap();
+        let source = SourceConfig::new("Oldest-First Jekyll Blog", SourceType::Atom, "https://jekyll.example.com/feed.xml");
+        let now = DateTime::parse_from_rfc3339("2025-06-10T18:00:00Z").unwrap().with_timezone(&Utc);
+
+        for (max_items, newest, count) in [(Some(3), "Post 13", 3), (Some(30), "Post 13", 12), (None, "Post 13", 10)] {
+            let source = SourceConfig { max_items, ..source.clone() };
+            let articles = atom_articles(&source, &feed, now, SKEW).0;
+            assert_eq!((articles[0].title.as_str(), articles.len()), (newest, count), "{:?}", max_items);
+        }
+    }
+
+    #[test]
+    fn test_rss_authors_and_categories() {
+        let channel = Channel::read_from(include_str!("../tests/fixtures/rss_authors.xml").as_bytes()).unwrap();
+        let source = SourceConfig::new("increment.com", SourceType::Rss, "https://increment.example.com/feed");
+        let now = DateTime::parse_from_rfc3339("2025-06-10T18:00:00Z").unwrap().with_timezone(&Utc);
+
+        let articles = rss_articles(&source, &channel, now, SKEW).0;
+
+        let bylines: Vec<(Option<&str>, Vec<&str>)> =
+            articles.iter().map(|a| (a.author.as_deref(), a.categories.iter().map(String::as_str).collect())).collect();
+        assert_eq!(bylines, vec![
+            // dc:creator wins over author; categories deduplicated ignoring case
+            (Some("Julia Evans"), vec!["Networking", "linux"]),
+            (Some("Dan Luu"), vec!["hardware"]),
+            (None, vec![]),
+            (None, vec![]),
+        ]);
+        assert_eq!(articles[0].headline(), "[increment.com — by Julia Evans] What happens when you press a key (Networking, linux)");
+        assert_eq!(articles[3].headline(), "[increment.com] Anonymous post");
+    }
+
+    fn atom_entry(links: &[(&str, Option<&str>, &str)]) -> atom_syndication::Entry {
+        let mut entry = atom_syndication::Entry::default();
+        entry.set_links(
+            links
+                .iter()
+                .map(|(rel, mime_type, href)| {
+                    let mut link = atom_syndication::Link::default();
+                    link.set_rel(*rel);
+                    link.set_mime_type(mime_type.map(str::to_string));
+                    link.set_href(*href);
+                    link
+                })
+                .collect::<Vec<_>>(),
+        );
+        entry
+    }
+
+    #[test]
+    fn test_pick_entry_link() {
+        let html = ("alternate", Some("text/html"), "https://blog.example.com/post");
+        let untyped = ("alternate", None, "https://blog.example.com/untyped");
+        let json = ("alternate", Some("application/json"), "https://blog.example.com/post.json");
+        let self_link = ("self", Some("application/atom+xml"), "https://blog.example.com/feed/entry/1");
+        let edit = ("edit", None, "https://blog.example.com/api/entries/1");
+        let related = ("related", None, "https://elsewhere.example.com/");
+        let enclosure = ("enclosure", Some("audio/mpeg"), "https://cdn.example.com/ep.mp3");
+
+        for (links, expected) in [
+            (vec![self_link, edit, html], Some("https://blog.example.com/post")),
+            (vec![untyped, html], Some("https://blog.example.com/post")),
+            (vec![self_link, json, untyped], Some("https://blog.example.com/untyped")),
+            (vec![edit, self_link, json], Some("https://blog.example.com/post.json")),
+            (vec![self_link, enclosure, related], Some("https://elsewhere.example.com/")),
+            (vec![self_link], None),
+            (vec![self_link, edit, enclosure], None),
+            (vec![], None),
+        ] {
+            let entry = atom_entry(&links);
+            assert_eq!(pick_entry_link(&entry).map(|l| l.href()), expected, "{:?}", links);
+        }
+    }
+
+    #[test]
+    fn test_atom_skips_entries_with_only_a_self_link() {
+        let mut feed = AtomFeed::default();
+        let mut self_only = atom_entry(&[("self", Some("application/atom+xml"), "https://blog.example.com/feed/entry/1")]);
+        let mut listed = atom_entry(&[
+            ("edit", None, "https://blog.example.com/api/entries/2"),
+            ("alternate", Some("text/html"), "https://blog.example.com/posts/2"),
+        ]);
+        let now = DateTime::parse_from_rfc3339("2025-06-10T18:00:00Z").unwrap();
+        for (entry, title) in [(&mut self_only, "Self only"), (&mut listed, "Listed")] {
+            entry.set_title(title);
+            entry.set_updated(now - Duration::hours(1));
+        }
+        feed.set_entries(vec![self_only, listed]);
+        let source = SourceConfig::new("Self-Linked Blog", SourceType::Atom, "https://blog.example.com/feed");
+
+        let (articles, outcome) = atom_articles(&source, &feed, now.with_timezone(&Utc), SKEW);
+
+        let urls: Vec<&str> = articles.iter().map(|a| a.url.as_str()).collect();
+        assert_eq!(urls, ["https://blog.example.com/posts/2"]);
+        assert_eq!(outcome.skipped_missing_fields, 1);
+    }
+
+    #[test]
+    fn test_atom_authors_and_categories() {
+        let feed = AtomFeed::read_from(include_str!("../tests/fixtures/atom_authors.xml").as_bytes()).unwrap();
+        let source = SourceConfig::new("Atom Bylines", SourceType::Atom, "https://atom-bylines.example.com/feed");
+        let now = DateTime::parse_from_rfc3339("2025-06-10T18:00:00Z").unwrap().with_timezone(&Utc);
+
+        let articles = atom_articles(&source, &feed, now, SKEW).0;
+
+        assert_eq!(articles[0].author.as_deref(), Some("Alice Example, Bob Example"));
+        assert_eq!(articles[0].categories, ["Distributed Systems", "rust"]);
+        assert_eq!(articles[1].author, None);
+        assert!(articles[1].categories.is_empty());
+    }
+
+    #[test]
+    fn test_clean_author() {
+        for (raw, expected) in [
+            ("Julia Evans", Some("Julia Evans")),
+            (" dan@example.com (Dan Luu) ", Some("Dan Luu")),
+            ("noreply@example.com", None),
+            ("   ", None),
+            ("dan@example.com ()", None),
+        ] {
+            assert_eq!(clean_author(raw).as_deref(), expected, "{:?}", raw);
+        }
+    }
+
+    #[test]
+    fn test_rss_items_without_links_use_permalink_guids() {
+        let channel = Channel::read_from(include_str!("../tests/fixtures/rss_guid_permalinks.xml").as_bytes()).unwrap();
+        let source = SourceConfig::new("Platform Newsletter", SourceType::Rss, "https://newsletter.example.com/feed");
+        let now = DateTime::parse_from_rfc3339("2025-06-10T18:00:00Z").unwrap().with_timezone(&Utc);
+
+        let (articles, outcome) = rss_articles(&source, &channel, now, SKEW);
+
+        let urls: Vec<&str> = articles.iter().map(|a| a.url.as_str()).collect();
+        assert_eq!(urls, [
+            "https://newsletter.example.com/p/issue-42",
+            "https://newsletter.example.com/p/issue-41",
+            // The link wins over the GUID
+            "https://newsletter.example.com/p/issue-39",
+        ]);
+        // The tag: URI and the opaque GUID are skipped as before
+        assert_eq!(outcome.skipped_missing_fields, 2);
+    }
+
+    #[test]
+    fn test_rss_inline_content_is_sanitized() {
+        let channel = Channel::read_from(include_str!("../tests/fixtures/rss_inline_content.xml").as_bytes()).unwrap();
+        let source = SourceConfig::new("Inline Content Blog", SourceType::Rss, "https://inline.example.com/feed");
+        let now = DateTime::parse_from_rfc3339("2025-06-10T18:00:00Z").unwrap().with_timezone(&Utc);
+
+        let articles = rss_articles(&source, &channel, now, SKEW).0;
+
+        let fields: Vec<(&str, Option<&str>, Option<&str>)> =
+            articles.iter().map(|a| (a.title.as_str(), a.summary.as_deref(), a.content.as_deref())).collect();
+        assert_eq!(fields, vec![
+            (
+                "CDATA full post",
+                Some("How we cut p99 latency in half."),
+                Some("Background\nOur queue & cache tier served 40k req/s.\nif a < b { swap(a, b) }"),
+            ),
+            ("Entity-encoded excerpt", Some("Rust & C++ interop, continued…"), None),
+            ("Markup only", None, None),
+        ]);
+        // The raw HTML stays available for newsletter link extraction and overrides
+        assert!(articles[0].feed_content.as_deref().unwrap().contains("<script>"));
+    }
+
+    #[test]
+    fn test_atom_inline_content_is_sanitized() {
+        let feed = AtomFeed::read_from(include_str!("../tests/fixtures/atom_inline_content.xml").as_bytes()).unwrap();
+        let source = SourceConfig::new("Inline Atom Blog", SourceType::Atom, "https://atom.example.com/feed");
+        let now = DateTime::parse_from_rfc3339("2025-06-10T18:00:00Z").unwrap().with_timezone(&Utc);
+
+        let articles = atom_articles(&source, &feed, now, SKEW).0;
+
+        let fields: Vec<(&str, Option<&str>, Option<&str>)> =
+            articles.iter().map(|a| (a.title.as_str(), a.summary.as_deref(), a.content.as_deref())).collect();
+        assert_eq!(fields, vec![
+            ("Escaped HTML content", Some("Why we moved off etcd"), Some("Leases & watches\nwere the bottleneck.")),
+            ("CDATA content", None, Some("Compaction & tombstones")),
+        ]);
+    }
+
+    #[test]
+    fn test_source_config_clone() {
+        let source = SourceConfig::new("Blog", SourceType::Rss, "https://blog.example.com/rss");
+        let cloned = source.clone();
+        assert_eq!(source, cloned);
+    }
+
+    const LOBSTERS_HOTTEST: &str = include_str!("../tests/fixtures/lobsters_hottest.json");
+
+    fn lobsters_fixture(source: &SourceConfig) -> Vec<Article> {
+        let now = DateTime::parse_from_rfc3339("2026-03-20T12:00:00Z").unwrap().with_timezone(&Utc);
+        lobsters_articles(source, serde_json::from_str(LOBSTERS_HOTTEST).unwrap(), now, SKEW).0
+    }
+
+    #[test]
+    fn test_lobsters_skips_text_posts_and_stale_stories() {
+        let source = SourceConfig::new("Lobsters", SourceType::Lobsters, "https://lobste.rs/hottest.json");
+        let articles = lobsters_fixture(&source);
+        let titles: Vec<&str> = articles.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, ["Lock-free ring buffers in Rust", "What a Postgres vacuum actually does", "Goodbye, Makefiles"]);
+        assert_eq!(articles[0].url, "https://example.com/ring-buffers");
+        assert_eq!(articles[0].published_at.to_rfc3339(), "2026-03-20T11:00:00+00:00");
+        assert!(articles.iter().all(|a| a.feed_content.is_none() && a.source == "Lobsters"));
+
+        let source = SourceConfig { allow_text_posts: true, ..source };
+        let articles = lobsters_fixture(&source);
+        let text_post = articles.iter().find(|a| a.title.starts_with("Ask:")).unwrap();
+        assert_eq!(text_post.url, "https://lobste.rs/s/abc123/ask_how_do_you_review_schema_migrations");
+        assert_eq!(text_post.feed_content.as_deref(), Some("<p>We review every migration by hand. How do you do it?</p>"));
+    }
+
+    #[test]
+    fn test_lobsters_min_score() {
+        for (min_score, expected) in [(None, 3), (Some(10), 2), (Some(40), 1), (Some(100), 0)] {
+            let source = SourceConfig { min_score, ..SourceConfig::new("Lobsters", SourceType::Lobsters, "https://lobste.rs/t/programming.json") };
+            assert_eq!(lobsters_fixture(&source).len(), expected, "{:?}", min_score);
+        }
+    }
+
+    /// Records when each request arrives.
+    struct ArrivalRecorder {
+        arrivals: std::sync::Arc<std::sync::Mutex<Vec<std::time::Instant>>>,
+        body: String,
+    }
+
+    impl wiremock::Respond for ArrivalRecorder {
+        fn respond(&self, _request: &wiremock::Request) -> wiremock::ResponseTemplate {
+            self.arrivals.lock().unwrap().push(std::time::Instant::now());
+            wiremock::ResponseTemplate::new(200).set_body_string(self.body.clone())
+        }
+    }
+
+    /// Sorted arrival times, each at least `gap` after the one before (less a little jitter).
+    fn assert_spaced(arrivals: &std::sync::Mutex<Vec<std::time::Instant>>, count: usize, gap: StdDuration) {
+        let mut arrivals = arrivals.lock().unwrap().clone();
+        arrivals.sort();
+        assert_eq!(arrivals.len(), count);
+        for pair in arrivals.windows(2) {
+            let spacing = pair[1] - pair[0];
+            assert!(spacing >= gap - StdDuration::from_millis(20), "requests {:?} apart", spacing);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requests_to_one_host_are_rate_limited() {
+        use wiremock::{MockServer, Mock};
+        use wiremock::matchers::{method, path, path_regex};
+
+        let mock_server = MockServer::start().await;
+        let arrivals = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let feed = format!(r#"<rss version="2.0"><channel><title>r/x</title><item><title>Post</title><link>https://example.com/post</link><pubDate>{}</pubDate></item></channel></rss>"#, Utc::now().to_rfc2822());
+        Mock::given(method("GET"))
+            .and(path_regex(r"^/r/\w+/\.rss$"))
+            .respond_with(ArrivalRecorder { arrivals: arrivals.clone(), body: feed })
+            .mount(&mock_server)
+            .await;
+
+        // Several subreddit sources fetched at once share the host's 10 requests a second
+        let client = create_http_client().unwrap();
+        let once = RetryPolicy { max_attempts: Some(1), ..RetryPolicy::FEED_FETCH };
+        let limiter = HostLimiter::new(10.0);
+        let sources: Vec<SourceConfig> = ["rust", "golang", "programming", "databases"]
+            .iter()
+            .map(|sub| SourceConfig::new(*sub, SourceType::Rss, format!("{}/r/{}/.rss", mock_server.uri(), sub)))
+            .collect();
+        let results: Vec<_> = stream::iter(&sources)
+            .map(|source| fetch_from_source_with(source, &client, &once, &limiter, usize::MAX, SKEW))
+            .buffer_unordered(sources.len())
+            .collect()
+            .await;
+        assert!(results.iter().all(|r| r.is_ok()));
+        assert_spaced(&arrivals, 4, StdDuration::from_millis(100));
+
+        // The HN item loop waits its turn too, here a source's own 150ms delay
+        let hn_arrivals = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        Mock::given(method("GET"))
+            .and(path("/v0/topstories.json"))
+            .respond_with(ArrivalRecorder { arrivals: hn_arrivals.clone(), body: "[1, 2, 3]".to_string() })
+            .mount(&mock_server)
+            .await;
+        let story = serde_json::json!({ "id": 1, "type": "story", "title": "Story", "url": "https://example.com/1", "time": Utc::now().timestamp() });
+        Mock::given(method("GET"))
+            .and(path_regex(r"^/v0/item/\d+\.json$"))
+            .respond_with(ArrivalRecorder { arrivals: hn_arrivals.clone(), body: story.to_string() })
+            .mount(&mock_server)
+            .await;
+        let hn = SourceConfig {
+            delay_ms: Some(150),
+            ..SourceConfig::new("Hacker News", SourceType::HackerNews, format!("{}/v0/topstories.json", mock_server.uri()))
+        };
+        fetch_from_source_with(&hn, &client, &once, &HostLimiter::new(0.0), usize::MAX, SKEW).await.unwrap();
+        assert_spaced(&hn_arrivals, 4, StdDuration::from_millis(150));
+    }
+
+    #[tokio::test]
+    async fn test_hackernews_score_and_comment_filters() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let mock_server = MockServer::start().await;
+        let now = Utc::now().timestamp();
+        // (id, score, comments): a briefly-top middling story, a busy one, a quiet high scorer
+        let stories = [(1, 12, 3), (2, 250, 140), (3, 180, 4), (4, 95, 60)];
+        Mock::given(method("GET"))
+            .and(path("/v0/topstories.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(stories.map(|(id, _, _)| id)))
+            .mount(&mock_server)
+            .await;
+        for (id, score, descendants) in stories {
+            Mock::given(method("GET"))
+                .and(path(format!("/v0/item/{}.json", id)))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "id": id, "type": "story", "title": format!("Story {}", id),
+                    "url": format!("https://example.com/{}", id), "time": now, "by": format!("user{}", id),
+                    "score": score, "descendants": descendants
+                })))
+                .mount(&mock_server)
+                .await;
+        }
+
+        let client = create_http_client().unwrap();
+        let source = SourceConfig::new("Hacker News", SourceType::HackerNews, format!("{}/v0/topstories.json", mock_server.uri()));
+        for ((min_score, min_comments), expected) in [
+            ((None, None), vec!["Story 1", "Story 2", "Story 3", "Story 4"]),
+            ((Some(100), None), vec!["Story 2", "Story 3"]),
+            ((None, Some(50)), vec!["Story 2", "Story 4"]),
+            ((Some(100), Some(50)), vec!["Story 2"]),
+        ] {
+            let source = SourceConfig { min_score, min_comments, ..source.clone() };
+            let articles = fetch_from_source(&source, &client).await.unwrap().0;
+            let titles: Vec<&str> = articles.iter().map(|a| a.title.as_str()).collect();
+            assert_eq!(titles, expected, "{:?}", (min_score, min_comments));
+        }
+
+        let articles = fetch_from_source(&source, &client).await.unwrap().0;
+        assert_eq!(articles[1].score, Some(250));
+        assert_eq!(articles[1].rank, Some(2));
+        assert_eq!(articles[1].headline(), "[Hacker News #2, 250 points — by user2] Story 2");
+    }
+
+    #[tokio::test]
+    async fn test_hackernews_items_are_fetched_concurrently_in_ranking_order() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let mock_server = MockServer::start().await;
+        let now = Utc::now().timestamp();
+        // Later-ranked items answer sooner; item 23 is gone and is skipped
+        let delays_ms = [(21, 500), (22, 400), (23, 300), (24, 200), (25, 100)];
+        Mock::given(method("GET"))
+            .and(path("/v0/topstories.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(delays_ms.map(|(id, _)| id)))
+            .mount(&mock_server)
+            .await;
+        for (id, delay) in delays_ms {
+            let response = if id == 23 {
+                ResponseTemplate::new(404)
+            } else {
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "id": id, "type": "story", "title": format!("Story {}", id),
+                    "url": format!("https://example.com/{}", id), "time": now, "score": 10
+                }))
+            };
+            Mock::given(method("GET"))
+                .and(path(format!("/v0/item/{}.json", id)))
+                .respond_with(response.set_delay(StdDuration::from_millis(delay)))
+                .mount(&mock_server)
+                .await;
+        }
+
+        let source = SourceConfig::new("Hacker News", SourceType::HackerNews, format!("{}/v0/topstories.json", mock_server.uri()));
+        let once = RetryPolicy { max_attempts: Some(1), ..RetryPolicy::FEED_FETCH };
+        // The default limiter, with the mock standing in for the Hacker News API host
+        let api_host = mock_server.address().to_string();
+        let limiter = HostLimiter::default().with_host_rate(&api_host, llm_client::HACKER_NEWS_API_REQUESTS_PER_SECOND);
+        let started = std::time::Instant::now();
+        let articles = fetch_from_source_with(&source, &create_http_client().unwrap(), &once, &limiter, usize::MAX, SKEW).await.unwrap().0;
+        let elapsed = started.elapsed();
+
+        let titles: Vec<&str> = articles.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, vec!["Story 21", "Story 22", "Story 24", "Story 25"]);
+        // Serial requests would take the 1.5s sum of the delays; concurrent ones about the 0.5s max
+        assert!(elapsed < StdDuration::from_millis(1000), "took {:?}", elapsed);
+    }
+
+    #[tokio::test]
+    async fn test_hackernews_rank_is_the_position_in_any_story_list() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let mock_server = MockServer::start().await;
+        let now = Utc::now().timestamp();
+        Mock::given(method("GET"))
+            .and(path("/v0/beststories.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json([31, 32, 33]))
+            .mount(&mock_server)
+            .await;
+        for (id, item) in [
+            (31, serde_json::json!({"id": 31, "type": "story", "title": "Best", "url": "https://example.com/best", "time": now, "score": 512})),
+            (32, serde_json::json!({"id": 32, "type": "story", "deleted": true, "time": now})),
+            (33, serde_json::json!({"id": 33, "type": "story", "title": "Third", "url": "https://example.com/third", "time": now, "score": 97})),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(format!("/v0/item/{}.json", id)))
+                .respond_with(ResponseTemplate::new(200).set_body_json(item))
+                .mount(&mock_server)
+                .await;
+        }
+
+        let source = SourceConfig::new("HN Best", SourceType::HackerNews, format!("{}/v0/beststories.json", mock_server.uri()));
+        let articles = fetch_from_source(&source, &create_http_client().unwrap()).await.unwrap().0;
+        let ranked: Vec<(&str, Option<usize>)> = articles.iter().map(|a| (a.title.as_str(), a.rank)).collect();
+        // Skipped items keep their place in the list
+        assert_eq!(ranked, vec![("Best", Some(1)), ("Third", Some(3))]);
+    }
+
+    #[test]
+    fn test_hackernews_only_live_stories_are_kept() {
+        let source = SourceConfig::new("Hacker News", SourceType::HackerNews, "https://hacker-news.firebaseio.com/v0/topstories.json");
+        let yesterday = DateTime::from_timestamp(1749556800, 0).unwrap() - Duration::hours(24);
+        let item = |json: &str| serde_json::from_str::<HnItem>(json).unwrap();
+        let article = |json: &str| hn_article(&source, 44236000, 1, item(json), yesterday);
+
+        let story = article(include_str!("../tests/fixtures/hn_story.json")).unwrap();
+        assert_eq!((story.title.as_str(), story.url.as_str()), ("How we cut our Postgres vacuum time by 90%", "https://eng.example.com/postgres-vacuum"));
+        assert_eq!((story.score, story.author.as_deref()), (Some(311), Some("dhouston")));
+        for (fixture, expected) in [
+            (include_str!("../tests/fixtures/hn_job.json"), HnSkip::NotStory),
+            (include_str!("../tests/fixtures/hn_poll.json"), HnSkip::NotStory),
+            (include_str!("../tests/fixtures/hn_dead_story.json"), HnSkip::Dead),
+        ] {
+            assert_eq!(article(fixture).unwrap_err(), expected, "{}", fixture);
+        }
+
+        // A missing or null time skips the item instead of failing to parse it
+        for json in [
+            r#"{"id": 1, "type": "story", "title": "No time", "url": "https://example.com/1"}"#,
+            r#"{"id": 1, "type": "story", "title": "Null time", "url": "https://example.com/1", "time": null}"#,
+        ] {
+            assert_eq!(article(json).unwrap_err(), HnSkip::MissingTime, "{}", json);
+        }
+        assert_eq!(article(r#"{"id": 1, "title": "Untyped", "url": "https://example.com/1", "time": 1749556800}"#).unwrap_err(), HnSkip::NotStory);
+        assert_eq!(article(r#"{"id": 1, "type": "story", "title": "Old", "url": "https://example.com/1", "time": 1700000000}"#).unwrap_err(), HnSkip::Stale);
+    }
+
+    #[test]
+    fn test_headline() {
+        let hn = Article { score: Some(512), rank: Some(3), ..Article::for_test("Title", "https://example.com/a", "Hacker News") };
+        let hn_by = Article { author: Some("pg".to_string()), categories: vec!["lisp".to_string()], ..hn.clone() };
+        let tagged = Article {
+            categories: ["rust", "performance", "linux", "databases"].map(String::from).to_vec(),
+            ..Article::for_test("Title", "https://example.com/a", "Blog")
+        };
+        let lobsters = Article { score: Some(12), ..Article::for_test("Title", "https://example.com/a", "Lobsters") };
+        let merged = Article { via: Some("Netflix TechBlog, Hacker News".to_string()), ..Article::for_test("Title", "https://example.com/a", "Netflix TechBlog") };
+        let undated = Article { undated: true, author: Some("Jane".to_string()), ..Article::for_test("Title", "https://example.com/a", "Blog") };
+        for (article, expected) in [
+            (hn, "[Hacker News #3, 512 points] Title"),
+            (hn_by, "[Hacker News #3, 512 points — by pg] Title (lisp)"),
+            (tagged, "[Blog] Title (rust, performance, linux)"),
+            (lobsters, "[Lobsters, 12 points] Title"),
+            (merged, "[Netflix TechBlog, Hacker News] Title"),
+            (undated, "[Blog, date unknown — by Jane] Title"),
+        ] {
+            assert_eq!(article.headline(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hackernews_self_posts_link_to_their_discussion() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let mock_server = MockServer::start().await;
+        let now = Utc::now().timestamp();
+        let items = [
+            serde_json::json!({"id": 11, "type": "story", "title": "Ask HN: How do you review schema migrations?",
+                "text": "<p>We review every migration by hand.</p>", "time": now, "score": 80, "descendants": 45}),
+            serde_json::json!({"id": 12, "type": "story", "title": "Rolling out Zuul",
+                "url": "https://netflixtechblog.com/rolling-out-zuul", "time": now, "score": 120, "descendants": 30}),
+            serde_json::json!({"id": 13, "type": "story", "title": "Show HN: flagged", "text": "<p>spam</p>",
+                "time": now, "score": 1, "dead": true}),
+            serde_json::json!({"id": 14, "type": "story", "deleted": true, "time": now}),
+        ];
+        Mock::given(method("GET"))
+            .and(path("/v0/topstories.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json([11, 12, 13, 14]))
+            .mount(&mock_server)
+            .await;
+        for item in items {
+            Mock::given(method("GET"))
+                .and(path(format!("/v0/item/{}.json", item["id"])))
+                .respond_with(ResponseTemplate::new(200).set_body_json(item))
+                .mount(&mock_server)
+                .await;
+        }
+
+        let client = create_http_client().unwrap();
+        let source = SourceConfig::new("Hacker News", SourceType::HackerNews, format!("{}/v0/topstories.json", mock_server.uri()));
+        let articles = fetch_from_source(&source, &client).await.unwrap().0;
+        let urls: Vec<&str> = articles.iter().map(|a| a.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://news.ycombinator.com/item?id=11", "https://netflixtechblog.com/rolling-out-zuul"]);
+        assert_eq!(articles[0].feed_content.as_deref(), Some("<p>We review every migration by hand.</p>"));
+        assert!(articles[1].feed_content.is_none());
+
+        let links_only = SourceConfig { include_self_posts: false, ..source };
+        let articles = fetch_from_source(&links_only, &client).await.unwrap().0;
+        assert_eq!(articles.iter().map(|a| a.title.as_str()).collect::<Vec<_>>(), vec!["Rolling out Zuul"]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_lobsters_with_mock_server() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let mock_server = MockServer::start().await;
+        let story = serde_json::json!([{
+            "short_id": "xyz789", "title": "Fresh story", "url": "https://example.com/fresh",
+            "created_at": Utc::now().to_rfc3339(), "score": 12, "tags": ["rust"],
+            "comments_url": "https://lobste.rs/s/xyz789/fresh_story", "description": ""
+        }]);
+        Mock::given(method("GET"))
+            .and(path("/t/programming.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(story))
+            .mount(&mock_server)
+            .await;
+
+        let source = SourceConfig::new("Lobsters", SourceType::Lobsters, format!("{}/t/programming.json", mock_server.uri()));
+        let articles = fetch_from_source(&source, &create_http_client().unwrap()).await.unwrap().0;
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].url, "https://example.com/fresh");
+    }
+
+    const BLUESKY_AUTHOR_FEED: &str = include_str!("../tests/fixtures/bluesky_author_feed.json");
+
+    #[test]
+    fn test_bluesky_link_posts_become_articles() {
+        let source = SourceConfig::new("Simon Willison (Bluesky)", SourceType::Bluesky, "@simonwillison.net");
+        let now = DateTime::parse_from_rfc3339("2025-06-10T18:00:00Z").unwrap().with_timezone(&Utc);
+        let (articles, outcome) = bluesky_articles(&source, serde_json::from_str(BLUESKY_AUTHOR_FEED).unwrap(), now, SKEW);
+
+        let summary: Vec<(&str, &str, Option<&str>)> = articles.iter().map(|a| (a.url.as_str(), a.title.as_str(), a.summary.as_deref())).collect();
+        assert_eq!(summary, vec![
+            (
+                "https://simonwillison.net/2025/Jun/10/prompt-injection-design-patterns/",
+                "New paper with six design patterns for building LLM agents that are resistant to prompt injection - the plan-then-execu…",
+                Some("A new paper from researchers at IBM, Invariant Labs, ETH Zurich, Google and Microsoft."),
+            ),
+            // A quote post with a link card and no text of its own
+            (
+                "https://www.anthropic.com/engineering/built-multi-agent-research-system",
+                "How we built our multi-agent research system",
+                Some("On the engineering challenges of building a multi-agent research system."),
+            ),
+        ]);
+        assert_eq!(articles[0].published_at.to_rfc3339(), "2025-06-10T15:02:12.067+00:00");
+        assert_eq!(articles[0].title.chars().count(), BLUESKY_TITLE_CHARS);
+        assert!(articles.iter().all(|a| a.author.as_deref() == Some("Simon Willison") && a.source == source.name));
+        // The repost, the reply and the text and image posts are skipped; the pinned post is stale
+        assert_eq!(outcome, FetchOutcome { articles: 2, items_seen: 7, filtered_by_date: 1, ..FetchOutcome::default() });
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bluesky_with_mock_server() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path, query_param};
+
+        let mock_server = MockServer::start().await;
+        let mut feed: serde_json::Value = serde_json::from_str(BLUESKY_AUTHOR_FEED).unwrap();
+        feed["feed"][0]["post"]["indexedAt"] = Utc::now().to_rfc3339().into();
+        Mock::given(method("GET"))
+            .and(path("/xrpc/app.bsky.feed.getAuthorFeed"))
+            .and(query_param("actor", "simonwillison.net"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(feed))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let url = format!("{}/xrpc/app.bsky.feed.getAuthorFeed?actor=simonwillison.net", mock_server.uri());
+        let source = SourceConfig::new("Simon Willison (Bluesky)", SourceType::Bluesky, url);
+        let articles = fetch_from_source(&source, &create_http_client().unwrap()).await.unwrap().0;
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].url, "https://simonwillison.net/2025/Jun/10/prompt-injection-design-patterns/");
+
+        let source = SourceConfig::new("Nobody", SourceType::Bluesky, "https://bsky.app/search?q=rust");
+        let err = fetch_from_source(&source, &create_http_client().unwrap()).await.unwrap_err();
+        assert_eq!(err.kind, FetchErrorKind::Config);
+    }
+
+    #[test]
+    fn test_youtube_videos_are_episodes_with_their_descriptions() {
+        let feed = AtomFeed::read_from(include_str!("../tests/fixtures/youtube_channel.xml").as_bytes()).unwrap();
+        let now = DateTime::parse_from_rfc3339("2026-03-20T12:00:00Z").unwrap().with_timezone(&Utc);
+        let source = SourceConfig::new("Strange Loop", SourceType::Youtube, "UCP9g8dHw5VmPVJpy1Dqq4Lw");
+        let articles = youtube_articles(&source, &feed, now, SKEW).0;
+
+        let titles: Vec<&str> = articles.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, ["\"Designing Data Pipelines That Don't Lie\" by Ana Ruiz", "Lightning Talk: Rust in the Kernel"]);
+        assert_eq!(articles[0].url, "https://www.youtube.com/watch?v=dQ3bN8kEx1o");
+        assert_eq!(articles[0].published_at.to_rfc3339(), "2026-03-20T09:00:06+00:00");
+        assert_eq!(articles[0].media.as_ref().map(|m| m.mime_type.as_str()), Some(YOUTUBE_MIME_TYPE));
+        assert!(articles[0].show_notes.as_deref().unwrap().starts_with("Data pipelines fail silently."));
+        assert_eq!(articles[1].show_notes.as_deref(), Some("Title: Lightning Talk: Rust in the Kernel"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_youtube_with_mock_server() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path, query_param};
+
+        let mock_server = MockServer::start().await;
+        let feed = include_str!("../tests/fixtures/youtube_channel.xml").replace("2026-03-20T09:00:06+00:00", &Utc::now().to_rfc3339());
+        Mock::given(method("GET"))
+            .and(path("/feeds/videos.xml"))
+            .and(query_param("channel_id", "UCP9g8dHw5VmPVJpy1Dqq4Lw"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(feed))
+            .mount(&mock_server)
+            .await;
+
+        let url = format!("{}/feeds/videos.xml?channel_id=UCP9g8dHw5VmPVJpy1Dqq4Lw", mock_server.uri());
+        let source = SourceConfig::new("Strange Loop", SourceType::Youtube, url);
+        let articles = fetch_from_source(&source, &create_http_client().unwrap()).await.unwrap().0;
+        assert_eq!(articles.len(), 1);
+        assert!(articles[0].title.contains("Data Pipelines"));
+    }
+
+    #[test]
+    fn test_dedup_articles_prefers_the_original_blog() {
+        let aggregators: HashSet<String> = ["Hacker News".to_string(), "Lobsters".to_string()].into();
+        let articles = vec![
+            Article { score: Some(512), rank: Some(3), ..Article::for_test("Rolling out Zuul", "https://netflixtechblog.com/rolling-out-zuul?utm_source=hn", "Hacker News") },
+            Article::for_test("Unrelated", "https://example.com/post?id=1", "Hacker News"),
+            Article::for_test("Rolling out Zuul", "https://netflixtechblog.com/rolling-out-zuul/", "Netflix TechBlog"),
+            Article::for_test("Rolling out Zuul", "http://www.netflixtechblog.com/rolling-out-zuul#top", "Lobsters"),
+            Article::for_test("Unrelated", "https://example.com/post?id=2", "Lobsters"),
+            Article::for_test("Unrelated", "https://example.com/post?id=1&ref=lobste.rs", "Hacker News"),
+        ];
+
+        let (kept, removed) = dedup_articles(articles, &aggregators);
+
+        assert_eq!(removed, 3);
+        let summary: Vec<(&str, &str, &str)> = kept.iter().map(|a| (a.url.as_str(), a.source.as_str(), a.source_label())).collect();
+        assert_eq!(summary, vec![
+            ("https://netflixtechblog.com/rolling-out-zuul/", "Netflix TechBlog", "Netflix TechBlog, Lobsters, Hacker News"),
+            ("https://example.com/post?id=1", "Hacker News", "Hacker News"),
+            ("https://example.com/post?id=2", "Lobsters", "Lobsters"),
+        ]);
+        assert!(kept[1].via.is_none());
+        assert_eq!(kept[0].headline(), "[Netflix TechBlog, Lobsters, Hacker News #3, 512 points] Rolling out Zuul");
+    }
+
+    #[test]
+    fn test_dedup_keeps_one_aggregators_signal() {
+        let aggregators: HashSet<String> = ["Hacker News".to_string(), "Lobsters".to_string()].into();
+        let url = "https://blog.example.com/post";
+        // Lobsters' points, then a Hacker News rank: never "#7, 12 points"
+        let articles = vec![
+            Article { score: Some(12), ..Article::for_test("Post", url, "Lobsters") },
+            Article { score: Some(300), rank: Some(7), ..Article::for_test("Post", url, "Hacker News") },
+            Article::for_test("Post", url, "Blog"),
+        ];
+        let (kept, _) = dedup_articles(articles, &aggregators);
+        assert_eq!(kept[0].headline(), "[Blog, Hacker News, Lobsters, 12 points] Post");
+        assert_eq!((kept[0].score, kept[0].rank), (Some(12), None));
+
+        // The winner's own signal stays with its name
+        let articles = vec![
+            Article { score: Some(300), rank: Some(7), ..Article::for_test("Post", url, "Hacker News") },
+            Article { score: Some(12), ..Article::for_test("Post", url, "Lobsters") },
+        ];
+        let (kept, _) = dedup_articles(articles, &aggregators);
+        assert_eq!(kept[0].headline(), "[Lobsters, Hacker News #7, 300 points] Post");
+    }
+
+    /// `counts[i]` articles from source `i`, one source after another as a run appends them;
+    /// each source's Nth article is N hours old.
+    fn skewed(counts: &[usize]) -> Vec<Article> {
+        let now = Utc::now();
+        let mut articles = Vec::new();
+        for (source, &count) in counts.iter().enumerate() {
+            for n in 0..count {
+                let title = format!("s{}-{}", source, n);
+                articles.push(Article { published_at: now - Duration::hours(n as i64), ..Article::for_test(&title, &format!("https://example.com/{}", title), &format!("Source {}", source)) });
+            }
+        }
+        articles
+    }
+
+    #[test]
+    fn test_balance_articles_keeps_every_source() {
+        for (counts, cap, expected_per_source) in [
+            // Under the cap: untouched
+            (vec![3, 2], 10, vec![3, 2]),
+            (vec![5, 5], 10, vec![5, 5]),
+            // One aggregator with 300 items and a few small blogs
+            (vec![300, 4, 2, 1], 100, vec![93, 4, 2, 1]),
+            // The source fetched last keeps as much as the first
+            (vec![60, 60, 60], 100, vec![34, 33, 33]),
+            // More sources than the cap: one each from those listed first
+            (vec![2, 2, 2, 2, 2], 3, vec![1, 1, 1, 0, 0]),
+            (vec![1, 50, 3], 10, vec![1, 6, 3]),
+        ] {
+            let articles = skewed(&counts);
+            let total = articles.len();
+            let (kept, dropped) = balance_articles(articles, cap);
+            assert_eq!(kept.len(), total.min(cap), "{:?}", counts);
+            assert_eq!(dropped, total - kept.len(), "{:?}", counts);
+            let per_source: Vec<usize> = (0..counts.len()).map(|i| kept.iter().filter(|a| a.source == format!("Source {}", i)).count()).collect();
+            assert_eq!(per_source, expected_per_source, "{:?} cap {}", counts, cap);
+        }
+    }
+
+    #[test]
+    fn test_balance_articles_takes_the_newest_and_keeps_the_order() {
+        let now = Utc::now();
+        let dated = |title: &str, source: &str, hours: i64| Article { published_at: now - Duration::hours(hours), ..Article::for_test(title, &format!("https://example.com/{}", title), source) };
+        // The aggregator lists oldest first; the blog's two posts share a date
+        let articles = vec![
+            dated("agg-old", "Aggregator", 20),
+            dated("agg-mid", "Aggregator", 10),
+            dated("agg-new", "Aggregator", 1),
+            dated("blog-a", "Blog", 5),
+            dated("blog-b", "Blog", 5),
+        ];
+        let (kept, dropped) = balance_articles(articles, 3);
+        assert_eq!(dropped, 2);
+        let titles: Vec<&str> = kept.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, ["agg-mid", "agg-new", "blog-a"]);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_max_total_articles_from_env() {
+        for (value, expected) in [(None, DEFAULT_MAX_TOTAL_ARTICLES), (Some(" 40 "), 40), (Some("0"), DEFAULT_MAX_TOTAL_ARTICLES), (Some("lots"), DEFAULT_MAX_TOTAL_ARTICLES)] {
+            match value {
+                Some(v) => std::env::set_var(MAX_TOTAL_ARTICLES_ENV_VAR, v),
+                None => std::env::remove_var(MAX_TOTAL_ARTICLES_ENV_VAR),
+            }
+            assert_eq!(max_total_articles_from_env(), expected, "{:?}", value);
+        }
+        std::env::remove_var(MAX_TOTAL_ARTICLES_ENV_VAR);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_allowed_languages_from_env() {
+        for (value, expected) in [(None, vec!["en"]), (Some(" EN, de ,"), vec!["en", "de"]), (Some(" , "), vec!["en"]), (Some("*"), vec!["*"])] {
+            match value {
+                Some(v) => std::env::set_var(ALLOWED_LANGUAGES_ENV_VAR, v),
+                None => std::env::remove_var(ALLOWED_LANGUAGES_ENV_VAR),
+            }
+            assert_eq!(allowed_languages_from_env(), expected, "{:?}", value);
+        }
+        std::env::remove_var(ALLOWED_LANGUAGES_ENV_VAR);
+        // A source's `languages` are read the same way
+        assert_eq!(normalize_languages(["DE", " en ", ""]), ["de", "en"]);
+    }
+
+    #[test]
+    fn test_filter_languages() {
+        let article = |title: &str, summary: Option<&str>| Article {
+            title: title.to_string(),
+            summary: summary.map(str::to_string),
+            ..Article::for_test(title, "https://example.com/post", "Blog")
+        };
+        let articles = vec![
+            article("How we cut our p99 latency in half with a smarter connection pool", None),
+            article("Postgres vacuum, explained", Some("A walk through what autovacuum does and why the defaults are wrong for a busy table.")),
+            article("Warum wir unsere Build-Pipeline neu geschrieben haben", Some("Ein Bericht darüber, wie wir die Bauzeiten mit einem entfernten Cache halbiert haben und was nicht funktioniert hat.")),
+            article("新しいスケジューラでテールレイテンシを半分にした話", None),
+            // Mixed-language titles read as English or can't be told, and pass
+            article("Kubernetes 1.30 の新機能: what changed in the scheduler and why it matters for you", None),
+            article("Rust 2024 edition: der Überblick", None),
+        ];
+
+        let (kept, filtered) = filter_languages(articles.clone(), &["en".to_string()]);
+
+        let kept: Vec<(&str, Option<&str>)> = kept.iter().map(|a| (a.title.as_str(), a.language.as_deref())).collect();
+        assert_eq!(kept, vec![
+            ("How we cut our p99 latency in half with a smarter connection pool", Some("en")),
+            ("Postgres vacuum, explained", Some("en")),
+            ("Kubernetes 1.30 の新機能: what changed in the scheduler and why it matters for you", Some("en")),
+            ("Rust 2024 edition: der Überblick", None),
+        ]);
+        let filtered: Vec<Option<&str>> = filtered.iter().map(|a| a.language.as_deref()).collect();
+        assert_eq!(filtered, [Some("de"), Some("ja")]);
+
+        // A source's own languages, or `*`, keep them
+        assert_eq!(filter_languages(articles.clone(), &["en".to_string(), "de".to_string(), "ja".to_string()]).1.len(), 0);
+        assert_eq!(filter_languages(articles, &["*".to_string()]).1.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_final_url_follows_redirects_and_strips_tracking() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let mock_server = MockServer::start().await;
+        // feedburner-style hop, then a campaign link, then the post
+        for (from, to) in [
+            ("/feedburner/abc", "/r/xyz?utm_source=feedburner&utm_medium=feed".to_string()),
+            ("/r/xyz", format!("{}/blog/zero-downtime/?id=7&ref=newsletter&fbclid=f1&gclid=g1#comments", mock_server.uri())),
+        ] {
+            Mock::given(method("GET")).and(path(from))
+                .respond_with(ResponseTemplate::new(301).insert_header("Location", to.as_str()))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
+        Mock::given(method("GET")).and(path("/blog/zero-downtime/"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html>post</html>"))
+            .mount(&mock_server)
+            .await;
+
+        let client = create_http_client().unwrap();
+        let limiter = HostLimiter::new(0.0);
+        let expected = format!("{}/blog/zero-downtime?id=7", mock_server.uri());
+        assert_eq!(resolve_final_url(&client, &format!("{}/feedburner/abc", mock_server.uri()), None, &limiter).await, expected);
+
+        // Without redirects, and when the request fails, only the parameters go
+        for (url, expected) in [
+            (format!("{}/blog/zero-downtime/?utm_campaign=x", mock_server.uri()), format!("{}/blog/zero-downtime", mock_server.uri())),
+            ("http://127.0.0.1:1/post/?utm_source=rss&page=2".to_string(), "http://127.0.0.1:1/post?page=2".to_string()),
+        ] {
+            assert_eq!(resolve_final_url(&client, &url, None, &limiter).await, expected, "{}", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_final_url_keeps_the_feed_link_when_the_redirect_ends_in_an_error() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{header, method, path};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET")).and(path("/r/gone")).and(header("Authorization", "Bearer s3cret"))
+            .respond_with(ResponseTemplate::new(301).insert_header("Location", "/removed-post"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET")).and(path("/removed-post"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        // The source's headers go with the request to its own host
+        let source = SourceConfig {
+            headers: [("Authorization".to_string(), "Bearer s3cret".to_string())].into(),
+            ..SourceConfig::new("Private Blog", SourceType::Rss, format!("{}/feed.xml", mock_server.uri()))
+        };
+        let url = format!("{}/r/gone?utm_source=rss", mock_server.uri());
+        let resolved = resolve_final_url(&create_http_client().unwrap(), &url, Some(&source), &HostLimiter::new(0.0)).await;
+        assert_eq!(resolved, format!("{}/r/gone", mock_server.uri()));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_source_type_fails_without_a_request() {
+        let source = SourceConfig::new("Subreddit", SourceType::Unknown("reddit".to_string()), "http://127.0.0.1:1/r/rust");
+        let err = fetch_from_source(&source, &create_http_client().unwrap()).await.unwrap_err();
+        assert!(err.to_string().contains("unsupported source type 'reddit'"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_errors_are_classified_by_kind() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let mock_server = MockServer::start().await;
+        let old = "Mon, 02 Jun 2025 09:00:00 GMT";
+        for (route, status, body) in [
+            ("/gone.xml", 404, String::new()),
+            ("/maintenance.xml", 503, String::new()),
+            ("/not-a-feed.xml", 200, "<html><body>Moved to Substack!</body></html>".to_string()),
+            ("/empty.xml", 200, r#"<rss version="2.0"><channel><title>Quiet</title></channel></rss>"#.to_string()),
+            ("/stale.xml", 200, format!(r#"<rss version="2.0"><channel><title>Stale</title><item><title>Old</title><link>https://a.example.com/old</link><pubDate>{}</pubDate></item></channel></rss>"#, old)),
+            ("/hn/v0/topstories.json", 200, "{\"error\": \"rate limited\"}".to_string()),
+        ] {
+            Mock::given(method("GET")).and(path(route))
+                .respond_with(ResponseTemplate::new(status).set_body_string(body))
+                .mount(&mock_server)
+                .await;
+        }
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let unreachable = format!("http://{}/feed.xml", listener.local_addr().unwrap());
+        drop(listener);
+
+        let client = create_http_client().unwrap();
+        let once = RetryPolicy { max_attempts: Some(1), ..RetryPolicy::FEED_FETCH };
+        let rss = |route: &str| SourceConfig::new("Blog", SourceType::Rss, format!("{}{}", mock_server.uri(), route));
+        for (source, expected) in [
+            (rss("/gone.xml"), FetchErrorKind::Http(404)),
+            (rss("/maintenance.xml"), FetchErrorKind::Http(503)),
+            (SourceConfig::new("Blog", SourceType::Rss, unreachable), FetchErrorKind::Network),
+            (rss("/not-a-feed.xml"), FetchErrorKind::Parse),
+            (SourceConfig { source_type: SourceType::HackerNews, ..rss("/hn/v0/topstories.json") }, FetchErrorKind::Parse),
+            (rss("/empty.xml"), FetchErrorKind::Empty),
+            (SourceConfig { source_type: SourceType::Unknown("reddit".to_string()), ..rss("/stale.xml") }, FetchErrorKind::UnknownType),
+            (SourceConfig { headers: [("Authorization".to_string(), "Bearer ${ENG_PULSE_UNSET_TEST_TOKEN}".to_string())].into(), ..rss("/stale.xml") }, FetchErrorKind::Config),
+        ] {
+            let err = fetch_from_source_with(&source, &client, &once, &HostLimiter::new(0.0), usize::MAX, SKEW).await.unwrap_err();
+            assert_eq!((err.source_name.as_str(), err.kind), ("Blog", expected), "{}", err);
+        }
+
+        // A feed with nothing fresh is an outcome, not an error
+        let (articles, outcome) = fetch_from_source_with(&rss("/stale.xml"), &client, &once, &HostLimiter::new(0.0), usize::MAX, SKEW).await.unwrap();
+        assert!(articles.is_empty());
+        assert_eq!(outcome, FetchOutcome { articles: 0, items_seen: 1, filtered_by_date: 1, skipped_missing_fields: 0, future_dated: 0 });
+    }
+
+    #[test]
+    fn test_outcome_counts_and_table() {
+        let now = DateTime::parse_from_rfc3339("2025-06-10T18:00:00Z").unwrap().with_timezone(&Utc);
+        let item = |title: &str, link: &str, date: &str| format!("<item><title>{}</title><link>{}</link><pubDate>{}</pubDate></item>", title, link, date);
+        let feed = format!(
+            r#"<rss version="2.0"><channel><title>Blog</title>{}{}{}{}<item><link>https://a.example.com/untitled</link></item></channel></rss>"#,
+            item("Fresh", "https://a.example.com/fresh", "Tue, 10 Jun 2025 09:00:00 GMT"),
+            item("Stale", "https://a.example.com/stale", "Mon, 02 Jun 2025 09:00:00 GMT"),
+            item("Also stale", "https://a.example.com/stale-2", "Sun, 01 Jun 2025 09:00:00 GMT"),
+            item("Undated", "https://a.example.com/undated", "sometime in June"),
+        );
+        let channel = Channel::read_from(feed.as_bytes()).unwrap();
+        let source = SourceConfig::new("Blog", SourceType::Rss, "https://a.example.com/feed");
+        let (articles, outcome) = rss_articles(&source, &channel, now, SKEW);
+        assert_eq!(articles.len(), 1);
+        assert_eq!(outcome, FetchOutcome { articles: 1, items_seen: 5, filtered_by_date: 2, skipped_missing_fields: 2, future_dated: 0 });
+
+        let results: Vec<SourceResult> = vec![
+            ("Blog".to_string(), Ok(outcome)),
+            ("Netflix TechBlog".to_string(), Err(SourceFetchError::new(&source, FetchErrorKind::Http(404), "fetching https://a.example.com/feed returned 404"))),
+        ];
+        assert_eq!(outcome_table(&results).lines().collect::<Vec<_>>(), [
+            "source            status       seen  too old  missing  articles",
+            "Blog              ok              5        2        2         1",
+            "Netflix TechBlog  http 404    fetching https://a.example.com/feed returned 404",
+        ]);
+        let report = outcome_report(&results);
+        assert_eq!(report[0]["outcome"]["filtered_by_date"], 2);
+        assert_eq!((report[1]["status"].as_str(), report[1]["source"].as_str()), (Some("http 404"), Some("Netflix TechBlog")));
+    }
+
+    #[test]
+    fn test_future_dated_items_are_clamped_to_now() {
+        let source = SourceConfig::new("Misconfigured Blog", SourceType::Rss, "https://misconfigured.example.com/feed");
+        let channel = Channel::read_from(include_str!("../tests/fixtures/rss_future_dated.xml").as_bytes()).unwrap();
+        let now = parse_feed_date("Tue, 10 Jun 2025 12:00:00 GMT").unwrap();
+        let (mut articles, outcome) = rss_articles(&source, &channel, now, SKEW);
+
+        assert_eq!(outcome.future_dated, 1);
+        let dated: Vec<(&str, DateTime<Utc>, bool)> = articles.iter().map(|a| (a.title.as_str(), a.published_at, a.future_dated)).collect();
+        assert_eq!(dated, vec![
+            // Four hours ahead is within the skew
+            ("Post from a clock running ahead", parse_feed_date("Tue, 10 Jun 2025 16:00:00 GMT").unwrap(), false),
+            ("Regular post", parse_feed_date("Tue, 10 Jun 2025 08:00:00 GMT").unwrap(), false),
+            // Dated now, but after the posts with a real date
+            ("Post dated decades ahead", now, true),
+        ]);
+        assert_eq!(clamp_future_dates(&mut articles, now, SKEW), 0);
+
+        // Clamped before the cap, so it can't crowd out a genuinely recent post
+        let capped = SourceConfig { max_items: Some(2), ..source };
+        let (articles, outcome) = rss_articles(&capped, &channel, now, SKEW);
+        assert_eq!((outcome.articles, outcome.future_dated), (2, 0));
+        assert!(articles.iter().all(|a| !a.future_dated));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_clamps_future_dated_items() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let server = MockServer::start().await;
+        let now = Utc::now();
+        let feed = include_str!("../tests/fixtures/rss_future_dated.xml")
+            .replace("Tue, 10 Jun 2025 16:00:00 GMT", &(now + Duration::hours(4)).to_rfc2822())
+            .replace("Tue, 10 Jun 2025 08:00:00 GMT", &(now - Duration::hours(4)).to_rfc2822());
+        Mock::given(method("GET"))
+            .and(path("/feed.xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(feed))
+            .mount(&server)
+            .await;
+
+        let source = SourceConfig::new("Misconfigured Blog", SourceType::Rss, format!("{}/feed.xml", server.uri()));
+        let once = RetryPolicy { max_attempts: Some(1), ..RetryPolicy::FEED_FETCH };
+        let (articles, outcome) = fetch_from_source_with(&source, &create_http_client().unwrap(), &once, &HostLimiter::new(0.0), usize::MAX, SKEW).await.unwrap();
+        assert_eq!((outcome.articles, outcome.future_dated), (3, 1));
+        let clamped = articles.iter().find(|a| a.future_dated).unwrap();
+        assert_eq!(clamped.title, "Post dated decades ahead");
+        assert!(clamped.published_at >= now && clamped.published_at <= Utc::now());
+    }
+
+    fn rss_page(items: &[(&str, DateTime<Utc>)], next: Option<&str>) -> String {
+        let link = next.map(|href| format!(r#"<atom:link rel="next" href="{}"/>"#, href)).unwrap_or_default();
+        let items: String = items
+            .iter()
+            .map(|(title, date)| format!("<item><title>{0}</title><link>https://forum.example.com/t/{0}</link><pubDate>{1}</pubDate></item>", title, date.to_rfc2822()))
+            .collect();
+        format!(r#"<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom"><channel><title>Forum</title>{}{}</channel></rss>"#, link, items)
+    }
+
+    #[tokio::test]
+    async fn test_rss_next_pages_are_followed_while_fresh() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let server = MockServer::start().await;
+        let now = Utc::now();
+        let (fresh, stale) = (now - Duration::hours(2), now - Duration::days(5));
+        for (page_path, body, expected_requests) in [
+            // Page one is all pinned posts; page two has today's post
+            ("/latest.rss", rss_page(&[("pinned-faq", stale), ("pinned-rules", stale)], Some("/latest/page/2.rss")), 2),
+            ("/latest/page/2.rss", rss_page(&[("new-release", fresh), ("older-thread", stale)], Some(&format!("{}/latest/page/3.rss", server.uri()))), 1),
+            // Nothing fresh here, so page four is never read
+            ("/latest/page/3.rss", rss_page(&[("last-week", stale)], Some("/latest/page/4.rss")), 1),
+            ("/latest/page/4.rss", rss_page(&[("ancient", stale)], None), 0),
+        ] {
+            Mock::given(method("GET")).and(path(page_path)).respond_with(ResponseTemplate::new(200).set_body_string(body)).expect(expected_requests).mount(&server).await;
+        }
+
+        let source = SourceConfig { max_pages: Some(4), ..SourceConfig::new("Forum", SourceType::Rss, format!("{}/latest.rss", server.uri())) };
+        let client = create_http_client().unwrap();
+        let (articles, outcome) = fetch_from_source(&source, &client).await.unwrap();
+        assert_eq!(articles.iter().map(|a| a.title.as_str()).collect::<Vec<_>>(), ["new-release"]);
+        assert_eq!((outcome.items_seen, outcome.filtered_by_date, outcome.articles), (5, 4, 1));
+
+        // One page only: the fresh post on page two is out of reach
+        let first_page_only = SourceConfig { max_pages: Some(1), ..source.clone() };
+        let (articles, outcome) = fetch_from_source(&first_page_only, &client).await.unwrap();
+        assert!(articles.is_empty());
+        assert_eq!(outcome.items_seen, 2);
+    }
+
+    #[tokio::test]
+    async fn test_atom_paging_stops_at_loops_caps_and_failures() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let entry = |id: &str, date: DateTime<Utc>| {
+            format!(
+                r#"<entry><title>{0}</title><id>urn:{0}</id><link href="https://blog.example.com/{0}"/><updated>{1}</updated></entry>"#,
+                id,
+                date.to_rfc3339()
+            )
+        };
+        let feed = |next: &str, entries: String| {
+            format!(r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>Blog</title><id>urn:blog</id><updated>2025-06-10T00:00:00Z</updated><link rel="next" href="{}"/>{}</feed>"#, next, entries)
+        };
+        let now = Utc::now();
+        let server = MockServer::start().await;
+        // Page two links back to page one, which isn't read again
+        Mock::given(method("GET")).and(path("/atom.xml")).respond_with(ResponseTemplate::new(200).set_body_string(feed("atom-2.xml", entry("old", now - Duration::days(3))))).mount(&server).await;
+        let page_two = format!("{}{}", entry("fresh-1", now - Duration::hours(1)), entry("fresh-2", now - Duration::hours(3)));
+        Mock::given(method("GET")).and(path("/atom-2.xml")).respond_with(ResponseTemplate::new(200).set_body_string(feed("atom.xml", page_two))).mount(&server).await;
+        // A page that fails after a good one keeps what was read
+        Mock::given(method("GET")).and(path("/broken.xml")).respond_with(ResponseTemplate::new(200).set_body_string(feed("broken-2.xml", entry("fresh-3", now)))).mount(&server).await;
+        Mock::given(method("GET")).and(path("/broken-2.xml")).respond_with(ResponseTemplate::new(500)).mount(&server).await;
+
+        let client = create_http_client().unwrap();
+        let once = RetryPolicy { max_attempts: Some(1), ..RetryPolicy::FEED_FETCH };
+        let looping = SourceConfig::new("Blog", SourceType::Atom, format!("{}/atom.xml", server.uri()));
+        let articles = fetch_from_source_with(&looping, &client, &once, &HostLimiter::new(0.0), usize::MAX, SKEW).await.unwrap().0;
+        assert_eq!(articles.iter().map(|a| a.title.as_str()).collect::<Vec<_>>(), ["fresh-1", "fresh-2"]);
+
+        // Two fresh articles meet a cap of one on page two; nothing further is read
+        let capped = SourceConfig { max_items: Some(1), ..looping.clone() };
+        let articles = fetch_from_source_with(&capped, &client, &once, &HostLimiter::new(0.0), usize::MAX, SKEW).await.unwrap().0;
+        assert_eq!(articles.iter().map(|a| a.title.as_str()).collect::<Vec<_>>(), ["fresh-1"]);
+
+        let broken = SourceConfig::new("Blog", SourceType::Atom, format!("{}/broken.xml", server.uri()));
+        let articles = fetch_from_source_with(&broken, &client, &once, &HostLimiter::new(0.0), usize::MAX, SKEW).await.unwrap().0;
+        assert_eq!(articles.iter().map(|a| a.title.as_str()).collect::<Vec<_>>(), ["fresh-3"]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_scrape_with_mock_server() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let mock_server = MockServer::start().await;
+        let now = Utc::now();
+        let listing = format!(
+            r#"<html><body><ul id="posts">
+                <li><a href="/posts/fresh"><span class="title">Fresh post</span></a><time datetime="{}"></time></li>
+                <li><a href="/posts/old"><span class="title">Old post</span></a><time datetime="{}"></time></li>
+                <li><a href="https://other.example.com/undated"><span class="title">Undated post</span></a><time>someday</time></li>
+            </ul></body></html>"#,
+            (now - Duration::hours(2)).to_rfc3339(),
+            (now - Duration::days(3)).to_rfc3339(),
+        );
+        Mock::given(method("GET")).and(path("/engineering"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(listing))
+            .mount(&mock_server)
+            .await;
+
+        let client = create_http_client().unwrap();
+        let source = SourceConfig {
+            item_selector: Some("#posts li".to_string()),
+            title_selector: Some(".title".to_string()),
+            link_selector: Some("a".to_string()),
+            date_selector: Some("time".to_string()),
+            ..SourceConfig::new("Example Engineering", SourceType::Scrape, format!("{}/engineering", mock_server.uri()))
+        };
+        let articles = fetch_from_source(&source, &client).await.unwrap().0;
+        let summary: Vec<(&str, String, bool)> = articles.iter().map(|a| (a.title.as_str(), a.url.clone(), a.undated)).collect();
+        // Dated "now", but listed after the posts with a real date
+        assert_eq!(summary, vec![
+            ("Fresh post", format!("{}/posts/fresh", mock_server.uri()), false),
+            ("Undated post", "https://other.example.com/undated".to_string(), true),
+        ]);
+
+        let misconfigured = SourceConfig { item_selector: Some("article".to_string()), ..source };
+        let err = fetch_from_source(&misconfigured, &client).await.unwrap_err();
+        assert!(err.to_string().contains("item_selector 'article' matched nothing"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_rss_with_mock_server() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path};
 
         let mock_server = MockServer::start().await;
         
@@ -357,17 +2760,243 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let source = SourceConfig {
-            name: "Mock Source".to_string(),
-            source_type: SourceType::Rss,
-            url: format!("{}/feed.xml", mock_server.uri()),
-        };
+        let source = SourceConfig::new("Mock Source", SourceType::Rss, format!("{}/feed.xml", mock_server.uri()));
 
         let client = create_http_client().unwrap();
-        let articles = fetch_from_source(&source, &client).await.unwrap();
+        let articles = fetch_from_source(&source, &client).await.unwrap().0;
 
         assert_eq!(articles.len(), 1);
         assert_eq!(articles[0].title, "Mock Article");
         assert_eq!(articles[0].url, "https://example.com/mock");
     }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_source_headers_go_with_every_request() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{header, method, path};
+
+        // Requests without the headers match no mock and get a 404
+        let mock_server = MockServer::start().await;
+        let now = Utc::now().timestamp();
+        Mock::given(method("GET"))
+            .and(path("/v0/topstories.json"))
+            .and(header("authorization", "Bearer hn-t0ken"))
+            .and(header("referer", "https://eng.example.com/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json([41]))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v0/item/41.json"))
+            .and(header("authorization", "Bearer hn-t0ken"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 41, "type": "story", "title": "Private story", "url": "https://eng.example.com/41", "time": now
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let mut source = SourceConfig::new("Internal HN", SourceType::HackerNews, format!("{}/v0/topstories.json", mock_server.uri()));
+        source.headers.insert("Authorization".to_string(), "Bearer ${TEST_SOURCE_TOKEN}".to_string());
+        source.headers.insert("Referer".to_string(), "https://eng.example.com/".to_string());
+        let client = create_http_client().unwrap();
+
+        std::env::set_var("TEST_SOURCE_TOKEN", "hn-t0ken");
+        let articles = fetch_from_source(&source, &client).await;
+        std::env::remove_var("TEST_SOURCE_TOKEN");
+        let titles: Vec<String> = articles.unwrap().0.into_iter().map(|a| a.title).collect();
+        assert_eq!(titles, vec!["Private story"]);
+
+        // Without the variable the source fails before any request
+        let err = fetch_from_source(&source, &client).await.unwrap_err();
+        assert!(err.to_string().contains("TEST_SOURCE_TOKEN is not set"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_source_timeout_and_response_size_cap() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let mock_server = MockServer::start().await;
+        let item = format!("<item><title>Episode</title><link>https://example.com/ep</link><pubDate>{}</pubDate></item>", Utc::now().to_rfc2822());
+        let feed = |items: usize| format!(r#"<rss version="2.0"><channel><title>Podcast</title>{}</channel></rss>"#, item.repeat(items));
+        Mock::given(method("GET"))
+            .and(path("/slow.xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(feed(1)).set_delay(StdDuration::from_millis(1500)))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/huge.xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(feed(2000)))
+            .mount(&mock_server)
+            .await;
+
+        let client = create_http_client().unwrap();
+        let once = RetryPolicy { max_attempts: Some(1), ..RetryPolicy::FEED_FETCH };
+        let slow = SourceConfig::new("Slow", SourceType::Rss, format!("{}/slow.xml", mock_server.uri()));
+        // The 30s client default waits it out; a 1s source timeout doesn't
+        assert_eq!(fetch_from_source_with(&slow, &client, &once, &HostLimiter::new(0.0), usize::MAX, SKEW).await.unwrap().0.len(), 1);
+        let hasty = SourceConfig { timeout_secs: Some(1), ..slow };
+        assert!(fetch_from_source_with(&hasty, &client, &once, &HostLimiter::new(0.0), usize::MAX, SKEW).await.is_err());
+
+        let huge = SourceConfig::new("Huge", SourceType::Rss, format!("{}/huge.xml", mock_server.uri()));
+        let err = fetch_from_source_with(&huge, &client, &once, &HostLimiter::new(0.0), 64 * 1024, SKEW).await.unwrap_err();
+        assert!(err.to_string().contains("larger than 65536 bytes"), "{}", err);
+        let articles = fetch_from_source_with(&huge, &client, &once, &HostLimiter::new(0.0), 1024 * 1024, SKEW).await.unwrap().0;
+        assert_eq!(articles.len(), MAX_ITEMS_PER_SOURCE);
+    }
+
+    #[tokio::test]
+    async fn test_transient_feed_failures_are_retried() {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let mock_server = MockServer::start().await;
+        let feed = format!(
+            r#"<rss version="2.0"><channel><title>Flaky</title><item><title>After the blip</title><link>https://example.com/blip</link><pubDate>{}</pubDate></item></channel></rss>"#,
+            Utc::now().to_rfc2822()
+        );
+        // Mocks answer in mount order, so the 503 is served once, then the feed
+        Mock::given(method("GET"))
+            .and(path("/flaky.xml"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/flaky.xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(feed))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/gone.xml"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let policy = RetryPolicy { initial_delay: StdDuration::from_millis(10), max_delay: StdDuration::from_millis(10), ..RetryPolicy::FEED_FETCH };
+        let client = create_http_client().unwrap();
+        let flaky = SourceConfig::new("Flaky", SourceType::Rss, format!("{}/flaky.xml", mock_server.uri()));
+        let articles = fetch_from_source_with(&flaky, &client, &policy, &HostLimiter::new(0.0), usize::MAX, SKEW).await.unwrap().0;
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].title, "After the blip");
+
+        // A 4xx is not retried
+        let gone = SourceConfig::new("Gone", SourceType::Rss, format!("{}/gone.xml", mock_server.uri()));
+        let err = fetch_from_source_with(&gone, &client, &policy, &HostLimiter::new(0.0), usize::MAX, SKEW).await.unwrap_err();
+        assert!(err.to_string().contains("404"), "{}", err);
+    }
+
+    fn mixed_rss_feed() -> String {
+        let now = Utc::now().to_rfc2822();
+        let long_body = "Full text of the post. ".repeat(100);
+        format!(r#"<rss version="2.0"><channel><title>Mixed</title>
+            <item>
+                <title>Written Post</title>
+                <link>https://example.com/post</link>
+                <description>A regular post.</description>
+                <pubDate>{now}</pubDate>
+            </item>
+            <item>
+                <title>Episode 42</title>
+                <link>https://example.com/episodes/42</link>
+                <description>Show notes: we talk about queues.</description>
+                <enclosure url="https://cdn.example.com/ep42.mp3" length="12345" type="audio/mpeg"/>
+                <pubDate>{now}</pubDate>
+            </item>
+            <item>
+                <title>Post With Audio Version</title>
+                <link>https://example.com/narrated</link>
+                <description>{long_body}</description>
+                <enclosure url="https://cdn.example.com/narrated.mp3" length="999" type="audio/mpeg"/>
+                <pubDate>{now}</pubDate>
+            </item>
+            <item>
+                <title>Post With Image</title>
+                <link>https://example.com/image</link>
+                <enclosure url="https://cdn.example.com/cover.png" length="10" type="image/png"/>
+                <pubDate>{now}</pubDate>
+            </item>
+        </channel></rss>"#)
+    }
+
+    async fn fetch_fixture(feed: String, feed_path: &str, source_type: SourceType) -> Vec<Article> {
+        use wiremock::{MockServer, Mock, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(feed_path))
+            .respond_with(ResponseTemplate::new(200).set_body_string(feed))
+            .mount(&mock_server)
+            .await;
+        let source = SourceConfig::new("Mixed", source_type, format!("{}{}", mock_server.uri(), feed_path));
+        fetch_from_source(&source, &create_http_client().unwrap()).await.unwrap().0
+    }
+
+    #[tokio::test]
+    async fn test_rss_mixed_feed_media_detection() {
+        let articles = fetch_fixture(mixed_rss_feed(), "/mixed.xml", SourceType::Rss).await;
+        assert_eq!(articles.len(), 4);
+
+        let episode = articles.iter().find(|a| a.title == "Episode 42").unwrap();
+        assert_eq!(episode.media, Some(MediaInfo {
+            url: "https://cdn.example.com/ep42.mp3".to_string(),
+            mime_type: "audio/mpeg".to_string(),
+            length: Some(12345),
+        }));
+        assert_eq!(episode.show_notes.as_deref(), Some("Show notes: we talk about queues."));
+
+        for title in ["Written Post", "Post With Audio Version", "Post With Image"] {
+            let article = articles.iter().find(|a| a.title == title).unwrap();
+            assert!(article.media.is_none(), "{} flagged as media", title);
+            assert!(article.show_notes.is_none());
+        }
+
+        let (kept, filtered) = filter_media(articles.clone(), false);
+        assert_eq!(filtered, 1);
+        assert!(kept.iter().all(|a| a.title != "Episode 42"));
+
+        let (kept, filtered) = filter_media(articles, true);
+        assert_eq!((kept.len(), filtered), (4, 0));
+    }
+
+    #[tokio::test]
+    async fn test_atom_mixed_feed_media_detection() {
+        let now = Utc::now().to_rfc3339();
+        let feed = format!(r#"<?xml version="1.0" encoding="utf-8"?>
+            <feed xmlns="http://www.w3.org/2005/Atom">
+                <title>Mixed</title><id>urn:mixed</id><updated>{now}</updated>
+                <entry>
+                    <title>Written Post</title><id>urn:1</id><updated>{now}</updated>
+                    <link rel="alternate" href="https://example.com/post"/>
+                </entry>
+                <entry>
+                    <title>Episode 7</title><id>urn:2</id><updated>{now}</updated>
+                    <link rel="enclosure" type="video/mp4" length="777" href="https://cdn.example.com/ep7.mp4"/>
+                    <link rel="alternate" href="https://example.com/episodes/7"/>
+                    <summary>Notes for episode 7</summary>
+                </entry>
+            </feed>"#);
+        let articles = fetch_fixture(feed, "/atom.xml", SourceType::Atom).await;
+        assert_eq!(articles.len(), 2);
+
+        let episode = articles.iter().find(|a| a.title == "Episode 7").unwrap();
+        assert_eq!(episode.url, "https://example.com/episodes/7");
+        let media = episode.media.as_ref().unwrap();
+        assert_eq!((media.mime_type.as_str(), media.length), ("video/mp4", Some(777)));
+        assert_eq!(episode.show_notes.as_deref(), Some("Notes for episode 7"));
+
+        let (kept, filtered) = filter_media(articles, false);
+        assert_eq!((kept.len(), filtered), (1, 1));
+    }
+
+    #[test]
+    fn test_episode_media_without_link_uses_enclosure() {
+        let media = MediaInfo::new("https://cdn.example.com/a.mp3", "audio/mpeg", None);
+        assert!(episode_media(media.clone(), None, None).is_some());
+        assert!(episode_media(media.clone(), Some("https://cdn.example.com/a.mp3"), Some(&"x".repeat(5000))).is_some());
+        assert!(episode_media(media, Some("https://example.com/post"), Some(&"x".repeat(5000))).is_none());
+    }
 }
@@ -1,63 +1,159 @@
+mod compare;
+mod digest;
 mod fetcher;
+mod newsletter;
 mod prompts;
 mod eval;
+mod extraction;
+mod extraction_health;
 mod feedback;
 mod manifest;
+mod publish;
+mod robots;
+mod scrape;
+mod seen;
+mod selection;
+
+/// Shortlist reply, e.g. `{"indices": [3, 7, 12, 25, 41]}`.
+#[derive(serde::Deserialize, Debug, PartialEq)]
+struct ShortlistReply {
+    indices: Vec<usize>,
+}
 
-/// Parse an index from LLM response, extracting the first contiguous digit sequence.
-fn parse_selection_index(response: &str) -> Option<usize> {
-    let digits: String = response
-        .trim()
-        .chars()
-        .skip_while(|c| !c.is_ascii_digit())
-        .take_while(|c| c.is_ascii_digit())
-        .collect();
+impl ShortlistReply {
+    /// Indices within `0..max_index`, in the model's order.
+    fn valid_indices(self, max_index: usize) -> Vec<usize> {
+        self.indices.into_iter().filter(|&i| i < max_index).collect()
+    }
+}
 
-    if digits.is_empty() {
-        None
-    } else {
-        digits.parse().ok()
+/// Single-pick reply, e.g. `{"index": 3, "reason": "Concrete latency numbers"}`. A reply
+/// without a reason, or a bare index such as `3` or `"Article #3"`, is a pick with no reason.
+#[derive(serde::Deserialize, Debug, PartialEq)]
+#[serde(from = "PickJson")]
+struct PickReply {
+    index: usize,
+    /// The model's one-line justification, when it gave one
+    reason: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum PickJson {
+    Object {
+        #[serde(deserialize_with = "lenient_index")]
+        index: usize,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    #[serde(deserialize_with = "lenient_index")]
+    Bare(usize),
+}
+
+impl From<PickJson> for PickReply {
+    fn from(json: PickJson) -> Self {
+        match json {
+            PickJson::Object { index, reason } => {
+                PickReply { index, reason: reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()) }
+            }
+            PickJson::Bare(index) => PickReply { index, reason: None },
+        }
     }
 }
 
-/// Parse comma-separated indices from LLM shortlist response (e.g., "3,7,12,25,41").
-fn parse_shortlist_indices(response: &str, max_index: usize) -> Vec<usize> {
-    response
-        .trim()
-        .split(',')
-        .filter_map(|s| parse_selection_index(s.trim()))
-        .filter(|&i| i < max_index)
-        .collect()
+impl PickReply {
+    /// `text` as a pick: JSON as above, else the first integer in it, as replies were read
+    /// before selection asked for JSON.
+    fn parse(text: &str) -> Option<Self> {
+        parse_llm_json(text).ok().or_else(|| {
+            let index = extract_first_integer(text)?;
+            debug!(index, "Selection reply isn't JSON, using the first integer in it");
+            Some(PickReply { index, reason: None })
+        })
+    }
+}
+
+/// An index written as a number, or as text such as `"Article #3"`.
+fn lenient_index<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<usize, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Index {
+        Number(usize),
+        Text(String),
+    }
+    match <Index as serde::Deserialize>::deserialize(deserializer)? {
+        Index::Number(index) => Ok(index),
+        Index::Text(text) => extract_first_integer(&text)
+            .ok_or_else(|| serde::de::Error::custom(format!("no index in {:?}", text))),
+    }
 }
 
 /// Content snippet length for two-phase selection
 const SELECTION_SNIPPET_CHARS: usize = 1000;
 use readability::extractor;
 use std::io::Cursor;
-use crate::fetcher::{SourceConfig, Article};
+use crate::fetcher::{
+    SourceConfig, SourceType, Article, ALLOWED_LANGUAGES_ENV_VAR, DEFAULT_ALLOWED_LANGUAGES, DEFAULT_MAX_TOTAL_ARTICLES, MAX_TOTAL_ARTICLES_ENV_VAR,
+    max_total_articles_from_env,
+};
 use gcloud_storage::client::{Client, ClientConfig};
-use gcloud_storage::http::objects::download::Range;
-use gcloud_storage::http::objects::get::GetObjectRequest;
-use gcloud_storage::http::objects::upload::{UploadObjectRequest, UploadType, Media};
 use chrono::Utc;
 use tracing::{info, warn, error, debug, instrument};
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use llm_client::gcs_retry::{download_object, upload_object};
 use llm_client::{
-    call_llm_with_retry, call_llm, init_logging, extract_domain,
-    DEFAULT_BUCKET, LlmProvider, LlmOptions, get_api_key_env_var,
+    estimate_tokens, extract_first_integer, truncate_to_tokens, init_logging_with, LlmError, extract_domain,
+    BudgetGuard, MAX_CALLS_ENV_VAR, MAX_COST_ENV_VAR, DEFAULT_BUCKET, InMemoryMetrics, LlmClient, LlmProvider, LlmResponse, MetricsSnapshot, LlmOptions, get_api_key_env_var, get_model_env_var, PROVIDER_ENV_VAR, provider_from_env, resolve_api_key, SecretError, SourceRules,
+    EffectiveConfig, ConfigSource, GcsStore, PromptTemplates, RunReport, check_config_drift, save_config_snapshot, build_http_client, run_id, HttpClientConfig, DEFAULT_USER_AGENT, HTTP_USER_AGENT_ENV_VAR, DEFAULT_MAX_FUTURE_SKEW_HOURS, FEED_MAX_FUTURE_SKEW_HOURS_ENV_VAR,
+    DEFAULT_FEED_REQUESTS_PER_SECOND, FEED_REQUESTS_PER_SECOND_ENV_VAR,
+    ParsedList, SOURCE_SCHEMA_HINT, combine_prompt, parse_json_list, parse_json_strict, parse_llm_json, ResponseFormat,
+    RunContribution, CostTracker, PriceTable, record_daily_run, update_provenance, AuditSink, AUDIT_PREFIX_ENV_VAR, audit_sink_from_env, detect_language,
+    FetchDecision, QuarantinePolicy, SourceHealthMap, fetch_decision, load_source_health, prune_unlisted, save_source_health,
+    DEFAULT_QUARANTINE_AFTER_FAILURES, DEFAULT_QUARANTINE_PROBE_EVERY, QUARANTINE_AFTER_FAILURES_ENV_VAR, QUARANTINE_PROBE_EVERY_ENV_VAR,
+    HealthStatus, RetryPolicy,
 };
 
 use futures::future::join_all;
 use crate::manifest::{ManifestEntry, gcs_public_url, gcs_object_path, SUMMARY_SNIPPET_CHARS};
 use crate::eval::{run_eval_pass, apply_eval_scores, log_calibration_agreement};
 use crate::feedback::{load_recent_feedback, build_calibration_context, build_selection_context};
+use crate::extraction::{ExtractionOverride, ExtractionOverrides, extract_with_override, html_to_text, load_extraction_overrides, override_for};
+use crate::extraction_health::{ExtractionLog, ExtractionRecord, ExtractionStrategy, update_extraction_health};
+use crate::publish::Publisher;
+use crate::digest::{
+    DIGEST_SIZE_ENV_VAR, DigestPick, DigestReply, DigestSection, assemble_digest, digest_size_from_env, intro_articles, lead_with, manifest_articles, valid_picks,
+};
+use crate::compare::{COMPARE_PROVIDERS_ENV_VAR, compare_providers_from_env, compare_summaries, pick_judge};
+use crate::selection::{REPEAT_WINDOW_DAYS, EXPLORATION_MODE_ENV_VAR, Exploration, exclude_recent_picks, majority_vote, maybe_explore, selection_votes_from_env};
+use crate::robots::RobotsCache;
+use crate::seen::{SKIP_SEEN_ENV_VAR, exclude_seen, load_seen, record_seen, save_seen, skip_seen_from_env};
 
 // --- Configuration Constants ---
+/// Agent name used for run reports and config snapshots under `runs/`
+const AGENT_NAME: &str = "daily-agent";
 const HTTP_TIMEOUT_SECS: u64 = 60;
-const MAX_ARTICLE_CHARS: usize = 50_000;
+/// Per-attempt timeout of the index-selection calls, which have short replies.
+const SELECTION_TIMEOUT_SECS: u64 = 30;
+/// Per-attempt timeout of summary calls over a full article.
+const SUMMARY_TIMEOUT_SECS: u64 = 180;
+/// Estimated tokens of article text sent for summarization (about 50,000 characters).
+const MAX_ARTICLE_TOKENS: usize = 12_500;
+/// Longest article text summarized in parts; the rest is dropped to bound the calls.
+const MAX_LONG_ARTICLE_TOKENS: usize = 100_000;
+/// Environment variable with the article length above which it is summarized in parts.
+const LONG_ARTICLE_TOKENS_ENV_VAR: &str = "LONG_ARTICLE_TOKENS";
 /// Minimum extracted content length to attempt summarization.
 /// Pages below this threshold are likely JS-rendered SPAs or paywalled.
 const MIN_ARTICLE_CHARS: usize = 200;
+/// Feed content at least this long is taken as the full post and used instead of scraping;
+/// shorter content is usually an excerpt.
+const MIN_FEED_CONTENT_CHARS: usize = 1500;
+
+/// The article's full text from its feed, when the feed shipped more than an excerpt.
+fn full_feed_content(article: &Article) -> Option<&str> {
+    article.content.as_deref().filter(|content| content.chars().count() >= MIN_FEED_CONTENT_CHARS)
+}
 
 fn build_recent_picks_context(manifest: &[ManifestEntry], max_days: usize) -> Option<String> {
     let recent: Vec<&ManifestEntry> = manifest.iter()
@@ -77,23 +173,111 @@ fn build_recent_picks_context(manifest: &[ManifestEntry], max_days: usize) -> Op
     Some(context)
 }
 
-/// Get list of enabled LLM providers based on available API keys.
-/// Claude is first for article selection, others follow for summary generation.
-fn get_enabled_providers() -> Vec<(LlmProvider, String)> {
-    let providers = [LlmProvider::Claude, LlmProvider::Gemini];
+/// Get list of enabled LLM providers based on available API keys, read from Secret Manager
+/// when `<KEY VAR>_SECRET` is set ([`resolve_api_key`]).
+/// The first selects the article: the `LLM_PROVIDER` provider when set (its API key is then
+/// required), else Claude. Others with keys follow for summary generation.
+async fn get_enabled_providers(
+    http_client: &reqwest::Client,
+    metrics: &Arc<InMemoryMetrics>,
+    budget: Option<&Arc<BudgetGuard>>,
+    audit: Option<&Arc<dyn AuditSink>>,
+) -> Result<Vec<LlmClient>, Box<dyn std::error::Error + Send + Sync>> {
+    let primary = match std::env::var(PROVIDER_ENV_VAR) {
+        Ok(name) if !name.trim().is_empty() => Some(provider_from_env()?),
+        _ => None,
+    };
+    let client = |provider, api_key| {
+        let mut builder = LlmClient::builder(provider).http_client(http_client.clone()).metrics(metrics.clone()).api_key(api_key);
+        if let Some(budget) = budget {
+            builder = builder.budget(budget.clone());
+        }
+        if let Some(audit) = audit {
+            builder = builder.audit(audit.clone());
+        }
+        builder.build()
+    };
     let mut enabled = Vec::new();
+    if let Some(provider) = primary {
+        enabled.push(client(provider, resolve_api_key(provider).await?)?);
+    }
 
-    for provider in providers {
-        let env_var = get_api_key_env_var(provider);
-        if let Ok(key) = std::env::var(env_var) {
-            if !key.is_empty() {
-                info!(provider = %provider.as_str(), "Provider enabled");
-                enabled.push((provider, key));
-            }
+    for provider in [LlmProvider::Claude, LlmProvider::Gemini] {
+        if Some(provider) == primary {
+            continue;
+        }
+        // A configured secret that can't be read fails the run rather than dropping the provider
+        match resolve_api_key(provider).await {
+            Ok(api_key) => enabled.push(client(provider, api_key)?),
+            Err(SecretError::MissingCredentials(_)) => {}
+            Err(e) => return Err(e.into()),
         }
     }
 
-    enabled
+    for llm in &enabled {
+        info!(provider = %llm.provider().as_str(), "Provider enabled");
+    }
+    Ok(enabled)
+}
+
+/// Retries of a provider health check that found the provider unreachable or rate limited
+const HEALTH_CHECK_RETRY: RetryPolicy = RetryPolicy { name: "health-check", ..RetryPolicy::FEED_FETCH };
+
+/// `llm`'s health check, retried under [`HEALTH_CHECK_RETRY`] while the provider can't be
+/// reached; a rejected key or a missing model is final.
+async fn checked_health(llm: &LlmClient) -> Result<HealthStatus, LlmError> {
+    let mut attempt = 1;
+    loop {
+        let result = llm.health_check().await;
+        let transient = match &result {
+            Ok(status) => matches!(status, HealthStatus::Unreachable { .. }),
+            Err(e) => e.is_transient(),
+        };
+        if !transient || HEALTH_CHECK_RETRY.max_attempts.is_some_and(|max| attempt >= max) {
+            return result;
+        }
+        let delay = HEALTH_CHECK_RETRY.jittered_delay(attempt, &mut rand::rng());
+        warn!(provider = %llm.provider().as_str(), attempt, next_backoff_ms = delay.as_millis() as u64, "Provider health check failed transiently, retrying");
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// The enabled providers whose key and model pass a cheap request, so a bad key shows before
+/// any articles are fetched. Unhealthy providers are dropped with a warning; the run fails
+/// only when none is healthy. Also warns when a model isn't in the provider's list.
+async fn check_provider_health(enabled_providers: Vec<LlmClient>) -> Result<Vec<LlmClient>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut healthy = Vec::new();
+    let mut problems = Vec::new();
+    for llm in enabled_providers {
+        let provider = llm.provider();
+        let problem = match checked_health(&llm).await {
+            Ok(status) if status.is_ok() => {
+                info!(provider = %provider.as_str(), model = %llm.model(), "Provider health check passed");
+                // A stale model that still answers only warns, naming the closest available one
+                if let Err(e) = llm.validate_model().await {
+                    warn!(provider = %provider.as_str(), error = %e, "Failed to list models");
+                }
+                healthy.push(llm);
+                continue;
+            }
+            Ok(status) => status.to_string(),
+            Err(e) => e.to_string(),
+        };
+        let hint = format!("check {} and {}", get_api_key_env_var(provider), get_model_env_var(provider));
+        warn!(provider = %provider.as_str(), model = %llm.model(), problem = %problem, "Provider health check failed, disabling it; {}", hint);
+        problems.push(format!("{}: {} ({})", provider.display_name(), problem, hint));
+    }
+    if healthy.is_empty() {
+        error!(problems = ?problems, "No LLM provider passed its health check");
+        return Err(format!("No LLM provider passed its health check: {}", problems.join("; ")).into());
+    }
+    Ok(healthy)
+}
+
+/// The enabled client of `provider`, if any.
+fn find_provider(enabled_providers: &[LlmClient], provider: LlmProvider) -> Option<&LlmClient> {
+    enabled_providers.iter().find(|llm| llm.provider() == provider)
 }
 
 // --- Backfill Beta ---
@@ -104,9 +288,10 @@ fn get_enabled_providers() -> Vec<(LlmProvider, String)> {
 async fn backfill_beta(
     days: usize,
     http_client: &reqwest::Client,
+    robots: &RobotsCache,
     gcs_client: &Client,
     bucket_name: &str,
-    claude_key: &str,
+    claude: &LlmClient,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let now = Utc::now();
     let target_dates: Vec<String> = (1..=days)
@@ -117,18 +302,14 @@ async fn backfill_beta(
 
     // Download manifest
     let mut manifest: Vec<ManifestEntry> = {
-        let data = gcs_client.download_object(
-            &GetObjectRequest {
-                bucket: bucket_name.to_string(),
-                object: "manifest.json".to_string(),
-                ..Default::default()
-            },
-            &Range::default(),
-        ).await?;
-        serde_json::from_slice(&data)?
+        let data = download_object(gcs_client, bucket_name, "manifest.json").await?;
+        parse_json_strict(&format!("gs://{}/manifest.json", bucket_name), &data, None)?
     };
 
     let beta_config = prompts::PromptConfig::V2;
+    let store = GcsStore::new(gcs_client.clone(), bucket_name);
+    let extraction_overrides = load_extraction_overrides(&store).await;
+    let templates = PromptTemplates::load(&store, prompts::DEFAULT_PROMPTS).await;
 
     for date in &target_dates {
         // Find a prod entry for this date (prompt_version is None for v1)
@@ -147,28 +328,25 @@ async fn backfill_beta(
         info!(date = %date, title = %title, "Backfilling beta summary");
 
         // Fetch original article content
-        let article_text = match fetch_article_content(http_client, &original_url).await {
+        let article_text = match fetch_article_content(http_client, robots, &original_url, override_for(&extraction_overrides, &original_url), &mut ExtractionLog::default()).await {
             Ok(content) => content,
             Err(e) => {
                 warn!(date = %date, error = %e, "Failed to fetch article, skipping");
                 continue;
             }
         };
-        let truncated: String = article_text.chars().take(MAX_ARTICLE_CHARS).collect();
+        let truncated = truncate_to_tokens(&article_text, article_token_budget(&[LlmProvider::Claude]));
         let source = extract_domain(&original_url);
 
-        let prompt = beta_config.summary_prompt(&source, &title, &truncated);
-        match call_llm_with_retry(http_client, LlmProvider::Claude, claude_key, prompt).await {
-            Ok(summary) => {
+        let prompt = beta_config.summary_prompt(&templates, &source, &title, truncated)?;
+        match claude.generate(prompt).await {
+            Ok(response) => {
+                let summary = response.text;
                 let summary_snippet: String = summary.chars().take(SUMMARY_SNIPPET_CHARS).collect();
                 let object_name = format!("summaries/beta/claude/{}.md", date);
                 let summary_bytes = summary.into_bytes();
 
-                match gcs_client.upload_object(
-                    &UploadObjectRequest { bucket: bucket_name.to_string(), ..Default::default() },
-                    summary_bytes,
-                    &UploadType::Simple(Media::new(object_name.clone())),
-                ).await {
+                match upload_object(gcs_client, bucket_name, &object_name, summary_bytes).await {
                     Ok(_) => {
                         let public_url = gcs_public_url(bucket_name, &object_name);
 
@@ -183,11 +361,20 @@ async fn backfill_beta(
                             title: title.clone(),
                             summary_snippet,
                             original_url: Some(original_url.clone()),
+                            feed_url: None,
                             model: Some(LlmProvider::Claude.model_name().to_string()),
                             selected_by: None,
                             prompt_version: Some(beta_config.version().to_string()),
                             eval_score: None,
                             format: None,
+                            source: None,
+                            exploratory: None,
+                            run_id: Some(run_id().to_string()),
+                            judged_best: None,
+                            author: None,
+                            categories: Vec::new(),
+                            articles: Vec::new(),
+                            selection_reason: None,
                         });
 
                         info!(date = %date, "Beta summary backfilled");
@@ -201,11 +388,7 @@ async fn backfill_beta(
 
     // Upload updated manifest
     let manifest_json = serde_json::to_vec_pretty(&manifest)?;
-    gcs_client.upload_object(
-        &UploadObjectRequest { bucket: bucket_name.to_string(), ..Default::default() },
-        manifest_json,
-        &UploadType::Simple(Media::new("manifest.json".to_string())),
-    ).await?;
+    upload_object(gcs_client, bucket_name, "manifest.json", manifest_json).await?;
 
     info!(days = days, "Beta backfill complete");
     Ok(())
@@ -216,99 +399,444 @@ async fn backfill_beta(
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     dotenvy::dotenv().ok();
-    init_logging();
+    let _log_guard = init_logging_with(&[("se_daily_agent::fetcher", tracing::Level::DEBUG)]);
+    // Root span of the run, so every log line carries its run_id
+    let _run_span = tracing::info_span!("run", run_id = %run_id()).entered();
 
-    let bucket_name = std::env::var("GCS_BUCKET").unwrap_or_else(|_| DEFAULT_BUCKET.to_string());
+    let mut effective_config = EffectiveConfig::new(AGENT_NAME, env!("CARGO_PKG_VERSION"));
+    let bucket_name = effective_config.env_or_default("bucket", "GCS_BUCKET", DEFAULT_BUCKET);
+    effective_config.env_or_default("http_user_agent", HTTP_USER_AGENT_ENV_VAR, DEFAULT_USER_AGENT);
+
+    // 0. Initialize shared HTTP client (reused for connection pooling)
+    let http_config = HttpClientConfig::from_env(Duration::from_secs(HTTP_TIMEOUT_SECS));
+    // Article pages are only scraped where robots.txt allows our User-Agent
+    let robots = RobotsCache::new(&http_config.user_agent);
+    let http_client = build_http_client(http_config)?;
+
+    // Initialize GCS Client
+    let config = ClientConfig::default().with_auth().await?;
+    let gcs_client = Client::new(config);
 
     // Get enabled providers
-    let enabled_providers = get_enabled_providers();
+    let llm_metrics = Arc::new(InMemoryMetrics::new());
+    // One budget for every provider, across selection and summarization
+    let llm_budget = BudgetGuard::from_env().map(Arc::new);
+    if let Some(budget) = &llm_budget {
+        info!(max_calls = ?budget.max_calls(), max_cost_usd = ?budget.max_cost_usd(), "LLM budget enabled");
+    }
+    // Every LLM call's prompt and reply excerpts, when LLM_AUDIT_PREFIX is set
+    let llm_audit = audit_sink_from_env(GcsStore::new(gcs_client.clone(), &bucket_name));
+    effective_config.env_or_default("llm_audit_prefix", AUDIT_PREFIX_ENV_VAR, "off");
+    let enabled_providers = match get_enabled_providers(&http_client, &llm_metrics, llm_budget.as_ref(), llm_audit.as_ref()).await {
+        Ok(enabled) => enabled,
+        Err(e) => {
+            error!(error = %e, "Invalid LLM provider configuration");
+            return Err(e);
+        }
+    };
     if enabled_providers.is_empty() {
-        error!("No LLM providers configured. Set at least one of: GEMINI_API_KEY, ANTHROPIC_API_KEY");
+        error!("No LLM providers configured. Set at least one of: GEMINI_API_KEY, ANTHROPIC_API_KEY, or LLM_PROVIDER with its API key (LLM_PROVIDER=echo needs none)");
         return Err("No LLM providers configured".into());
     }
+    let enabled_providers = check_provider_health(enabled_providers).await?;
+    record_provider_config(&mut effective_config, &enabled_providers);
+    let exploration = Exploration::from_env();
+    let epsilon_source = if std::env::var("EXPLORATION_EPSILON").is_ok() { ConfigSource::Env } else { ConfigSource::Default };
+    effective_config.record("exploration_epsilon", exploration.epsilon.to_string(), epsilon_source);
+    let mode_source = if std::env::var(EXPLORATION_MODE_ENV_VAR).is_ok() { ConfigSource::Env } else { ConfigSource::Default };
+    effective_config.record("exploration_mode", exploration.mode.as_str(), mode_source);
+    let votes_source = if std::env::var("SELECTION_VOTES").is_ok() { ConfigSource::Env } else { ConfigSource::Default };
+    effective_config.record("selection_votes", selection_votes_from_env().to_string(), votes_source);
+    let long_article_source = if std::env::var(LONG_ARTICLE_TOKENS_ENV_VAR).is_ok() { ConfigSource::Env } else { ConfigSource::Default };
+    effective_config.record("long_article_tokens", long_article_tokens_from_env().to_string(), long_article_source);
+    let digest_size_source = if std::env::var(DIGEST_SIZE_ENV_VAR).is_ok() { ConfigSource::Env } else { ConfigSource::Default };
+    effective_config.record("digest_size", digest_size_from_env().to_string(), digest_size_source);
+    effective_config.env_or_default("compare_providers", COMPARE_PROVIDERS_ENV_VAR, "off");
+    effective_config.env_or_default("skip_seen", SKIP_SEEN_ENV_VAR, "true");
+    effective_config.env_or_default("source_quarantine_after_failures", QUARANTINE_AFTER_FAILURES_ENV_VAR, &DEFAULT_QUARANTINE_AFTER_FAILURES.to_string());
+    effective_config.env_or_default("source_quarantine_probe_every", QUARANTINE_PROBE_EVERY_ENV_VAR, &DEFAULT_QUARANTINE_PROBE_EVERY.to_string());
+    effective_config.env_or_default("max_total_articles", MAX_TOTAL_ARTICLES_ENV_VAR, &DEFAULT_MAX_TOTAL_ARTICLES.to_string());
+    effective_config.env_or_default("allowed_languages", ALLOWED_LANGUAGES_ENV_VAR, DEFAULT_ALLOWED_LANGUAGES);
+    effective_config.env_or_default("feed_max_future_skew_hours", FEED_MAX_FUTURE_SKEW_HOURS_ENV_VAR, &DEFAULT_MAX_FUTURE_SKEW_HOURS.to_string());
+    effective_config.env_or_default("feed_requests_per_second", FEED_REQUESTS_PER_SECOND_ENV_VAR, &DEFAULT_FEED_REQUESTS_PER_SECOND.to_string());
+    effective_config.env_or_default("llm_max_calls_per_run", MAX_CALLS_ENV_VAR, "unlimited");
+    effective_config.env_or_default("llm_max_cost_per_run", MAX_COST_ENV_VAR, "unlimited");
 
     info!(
         bucket = %bucket_name,
-        providers = ?enabled_providers.iter().map(|(p, _)| p.as_str()).collect::<Vec<_>>(),
+        providers = ?enabled_providers.iter().map(|llm| llm.provider().as_str()).collect::<Vec<_>>(),
         "Starting SE Daily Agent"
     );
 
-    // 0. Initialize shared HTTP client (reused for connection pooling)
-    let http_client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(HTTP_TIMEOUT_SECS))
-        .build()?;
-
-    // Initialize GCS Client
-    let config = ClientConfig::default().with_auth().await?;
-    let gcs_client = Client::new(config);
-
     // --- Backfill mode: regenerate V2 beta summaries for recent days ---
     if let Ok(days_str) = std::env::var("BACKFILL_BETA_DAYS") {
         let days: usize = days_str.parse().unwrap_or(3);
-        let claude_key = enabled_providers.iter()
-            .find(|(p, _)| *p == LlmProvider::Claude)
-            .map(|(_, k)| k.as_str())
+        let claude = find_provider(&enabled_providers, LlmProvider::Claude)
             .ok_or("BACKFILL_BETA_DAYS requires ANTHROPIC_API_KEY")?;
-        return backfill_beta(days, &http_client, &gcs_client, &bucket_name, claude_key).await;
+        let result = backfill_beta(days, &http_client, &robots, &gcs_client, &bucket_name, claude).await;
+        if let Some(audit) = &llm_audit {
+            audit.flush().await;
+        }
+        return result;
+    }
+
+    // Compare resolved config against the previous run before doing any work
+    let store = GcsStore::new(gcs_client.clone(), &bucket_name);
+    let mut report = RunReport::new(AGENT_NAME, &Utc::now().format("%Y-%m-%d").to_string());
+    report.config_drift = check_config_drift(&store, &effective_config).await;
+    report.llm_usage = CostTracker::new(PriceTable::from_env());
+
+    // --force resumes an incomplete publish for today instead of regenerating
+    let force = std::env::args().skip(1).any(|arg| arg == "--force");
+    let result = run_daily(http_client, &robots, gcs_client, &store, enabled_providers, exploration, force, &mut report).await;
+    if let Some(audit) = &llm_audit {
+        audit.flush().await;
     }
 
+    report.llm_usage.log_summary();
+    let metrics = llm_metrics.snapshot();
+    log_llm_metrics(&metrics);
+    if let Some(budget) = &llm_budget {
+        report.set_section("llm_budget", &budget.usage());
+    }
+    report.set_section("llm_metrics", &metrics);
+
+    // Persist the run report and config snapshot whether or not the run succeeded
+    report.finish(result.as_ref().err().map(|e| e.to_string()));
+    if let Err(e) = report.save(&store).await {
+        warn!(error = %e, "Failed to upload run report");
+    }
+    if let Err(e) = save_config_snapshot(&store, &effective_config).await {
+        warn!(error = %e, "Failed to upload effective config snapshot");
+    }
+
+    result
+}
+
+/// Record provider-derived values (enabled providers, models, hashed keys) in the config snapshot.
+fn record_provider_config(config: &mut EffectiveConfig, enabled_providers: &[LlmClient]) {
+    let names: Vec<&str> = enabled_providers.iter().map(|llm| llm.provider().as_str()).collect();
+    config.record("providers", names.join(","), ConfigSource::Derived);
+    if let Some(selection) = enabled_providers.first() {
+        config.record("selection_provider", selection.provider().as_str(), ConfigSource::Derived);
+    }
+    for llm in enabled_providers {
+        let provider = llm.provider();
+        config.env_or_default(
+            &format!("{}_model", provider.as_str()),
+            get_model_env_var(provider),
+            provider.model_name(),
+        );
+        config.record_secret(&format!("{}_api_key", provider.as_str()), llm.api_key(), ConfigSource::Env);
+    }
+    let v3_enabled = find_provider(enabled_providers, LlmProvider::Claude).is_some();
+    config.record("v3_insight_brief", v3_enabled.to_string(), ConfigSource::Derived);
+    config.record("summaries_prefix", "summaries/", ConfigSource::Default);
+    config.record("eval_prefix", "eval/", ConfigSource::Default);
+    config.record("max_article_tokens", MAX_ARTICLE_TOKENS.to_string(), ConfigSource::Default);
+}
+
+/// `LONG_ARTICLE_TOKENS`: articles estimated above this many tokens are summarized in parts
+/// instead of truncated; 0 turns that off. Unset or invalid is [`MAX_ARTICLE_TOKENS`].
+fn long_article_tokens_from_env() -> usize {
+    let Ok(raw) = std::env::var(LONG_ARTICLE_TOKENS_ENV_VAR) else {
+        return MAX_ARTICLE_TOKENS;
+    };
+    raw.trim().parse().unwrap_or_else(|_| {
+        warn!(value = %raw, "Invalid {}, using {}", LONG_ARTICLE_TOKENS_ENV_VAR, MAX_ARTICLE_TOKENS);
+        MAX_ARTICLE_TOKENS
+    })
+}
+
+/// Token budget for article text: [`MAX_ARTICLE_TOKENS`], or half the smallest context
+/// window of `providers` when that is less, leaving room for the prompt and the reply.
+fn article_token_budget(providers: &[LlmProvider]) -> usize {
+    providers.iter().map(|p| p.model_context_tokens() / 2).fold(MAX_ARTICLE_TOKENS, usize::min)
+}
+
+/// Log the run's LLM call counts and latency.
+fn log_llm_metrics(metrics: &MetricsSnapshot) {
+    info!(
+        calls = metrics.calls,
+        retries = metrics.retries,
+        failures = metrics.failures,
+        failure_kinds = ?metrics.failure_kinds,
+        p50_latency_ms = ?metrics.p50_latency_ms,
+        p95_latency_ms = ?metrics.p95_latency_ms,
+        "LLM call metrics"
+    );
+}
+
+/// `LlmClient::generate_with`, recording the call's token usage and estimated cost.
+pub(crate) async fn call_llm_tracked(
+    llm: &LlmClient,
+    prompt: String,
+    options: &LlmOptions,
+    cost: &mut CostTracker,
+) -> Result<String, LlmError> {
+    let response = llm.generate_with(prompt, options).await?;
+    cost.record(&response);
+    Ok(response.text)
+}
+
+/// `summary` in English: when [`detect_language`] finds another language, `llm` is asked once
+/// to rewrite it. The original is kept if the rewrite fails.
+async fn ensure_english(llm: &LlmClient, summary: String, options: &LlmOptions, cost: &mut CostTracker) -> String {
+    let Some(language) = detect_language(&summary).filter(|language| *language != "en") else {
+        return summary;
+    };
+    warn!(provider = %llm.provider().as_str(), language, "Summary isn't in English, asking for a rewrite");
+    match call_llm_tracked(llm, prompts::english_rewrite_prompt(&summary, language), options, cost).await {
+        Ok(rewritten) => rewritten,
+        Err(e) => {
+            warn!(provider = %llm.provider().as_str(), error = %e, "English rewrite failed, keeping the original");
+            summary
+        }
+    }
+}
+
+/// `summary` by `llm`, or a retry when it failed in a way rewording fixes: a refused prompt
+/// is retried framed as a newsletter summary, and a blocked one (as worded or softened) from
+/// the title alone.
+async fn retry_summary(
+    llm: &LlmClient,
+    summary: Result<LlmResponse, LlmError>,
+    softened_prompt: String,
+    title_only_prompt: String,
+    options: &LlmOptions,
+) -> Result<LlmResponse, LlmError> {
+    let summary = match summary {
+        // The model declined the request as worded; one retry framing it as a newsletter summary
+        Err(e) if e.is_refused() => {
+            warn!(provider = %llm.provider().as_str(), error = %e, "Summary refused, retrying with a softened prompt");
+            llm.generate_with(softened_prompt, options).await
+        }
+        result => result,
+    };
+    match summary {
+        // The article text tripped the provider's safety filter; the title alone usually doesn't
+        Err(e) if e.is_blocked() => {
+            warn!(provider = %llm.provider().as_str(), error = %e, "Summary prompt blocked, summarizing from the title");
+            llm.generate_with(title_only_prompt, options).await
+        }
+        result => result,
+    }
+}
+
+/// `LlmClient::generate_json_with` for a selection reply, recording usage. Output that is
+/// still invalid JSON after the correction request is `None`, so the caller can fall back.
+async fn call_selection<T: serde::de::DeserializeOwned>(
+    llm: &LlmClient,
+    prompt: String,
+    options: &LlmOptions,
+    cost: &mut CostTracker,
+) -> Result<Option<T>, Box<dyn std::error::Error + Send + Sync>> {
+    match llm.generate_json_with(prompt, options, cost).await {
+        Ok(reply) => Ok(Some(reply)),
+        Err(LlmError::Parse(e)) => {
+            warn!(error = %e, "Unparseable selection reply");
+            Ok(None)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Pick one article index, with the model's reason when it gave one, read as in
+/// [`PickReply::parse`]. With more than one vote, samples `votes` completions in one request
+/// and takes the majority index, with the reason of the first completion that voted for it;
+/// unparseable completions don't vote.
+async fn call_pick(
+    llm: &LlmClient,
+    prompt: String,
+    options: &LlmOptions,
+    votes: u32,
+    cost: &mut CostTracker,
+) -> Result<Option<PickReply>, Box<dyn std::error::Error + Send + Sync>> {
+    let options = if votes <= 1 {
+        LlmOptions { response_format: ResponseFormat::Json(None), ..options.clone() }
+    } else {
+        // Sampling at temperature 0 would return the same answer n times
+        LlmOptions {
+            candidate_count: Some(votes),
+            response_format: ResponseFormat::Json(None),
+            temperature: None,
+            ..options.clone()
+        }
+    };
+    let response = llm.generate_with(prompt, &options).await?;
+    cost.record(&response);
+    let replies: Vec<PickReply> = response.candidates().into_iter().filter_map(PickReply::parse).collect();
+    let indices: Vec<usize> = replies.iter().map(|r| r.index).collect();
+    let picked = majority_vote(&indices);
+    if votes > 1 {
+        info!(votes = ?indices, picked = ?picked, "Selection votes");
+    } else if picked.is_none() {
+        warn!("Unparseable selection reply");
+    }
+    Ok(picked.and_then(|index| replies.into_iter().find(|r| r.index == index)))
+}
+
+/// Daily pipeline: fetch, select, summarize, evaluate and publish.
+#[allow(clippy::too_many_arguments)]
+async fn run_daily(
+    http_client: reqwest::Client,
+    robots: &RobotsCache,
+    gcs_client: Client,
+    store: &GcsStore,
+    enabled_providers: Vec<LlmClient>,
+    exploration: Exploration,
+    force: bool,
+    report: &mut RunReport,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     // Use first provider for article selection (Claude preferred)
-    let (selection_provider, selection_key) = enabled_providers.first().unwrap().clone();
+    let selection = enabled_providers.first().unwrap().clone();
+    let selection_provider = selection.provider();
+    let bucket_name = store.bucket().to_string();
+    let today = Utc::now().format("%Y-%m-%d").to_string();
+
+    if force {
+        if let Some(mut publisher) = Publisher::load_checkpoint(store, &today).await {
+            if !publisher.is_complete() {
+                info!(date = %today, "Resuming incomplete publish from checkpoint");
+                let result = publisher.publish(store).await;
+                report.set_section("publish", &publisher.progress());
+                return result.map_err(|e| e.into());
+            }
+        }
+        info!(date = %today, "No incomplete publish to resume, running normally");
+    }
 
     // 1. Load Sources from GCS
     info!("Fetching sources.json from GCS");
-    let sources_data = gcs_client.download_object(
-        &GetObjectRequest {
-            bucket: bucket_name.to_string(),
-            object: "config/sources.json".to_string(),
-            ..Default::default()
-        },
-        &Range::default()
-    ).await?;
+    let sources_data = download_object(&gcs_client, &bucket_name, "config/sources.json").await?;
+
+    let sources_path = format!("gs://{}/config/sources.json", bucket_name);
+    let parsed_sources: ParsedList<SourceConfig> = parse_json_list(&sources_path, &sources_data, &SOURCE_SCHEMA_HINT)?;
+    parsed_sources.warn_rejected(&sources_path);
+    // Invalid entries are reported and skipped rather than failing mid-fetch
+    let source_rules = SourceRules::from_env();
+    let (valid, invalid): (Vec<SourceConfig>, Vec<SourceConfig>) =
+        parsed_sources.entries.into_iter().partition(|s| s.validate_with(&source_rules).is_empty());
+    for source in &invalid {
+        let errors: Vec<String> = source.validate_with(&source_rules).iter().map(ToString::to_string).collect();
+        warn!(source = %source.name, url = %source.url, errors = %errors.join("; "), "Skipping invalid source in {}", sources_path);
+    }
+    // Disabled sources stay in the list but aren't fetched
+    let (sources, disabled): (Vec<SourceConfig>, Vec<SourceConfig>) = valid.into_iter()
+        .map(|source| SourceConfig { languages: fetcher::normalize_languages(&source.languages), ..source })
+        .partition(|s| s.enabled);
+    info!(count = sources.len(), disabled = disabled.len(), "Loaded sources from Cloud Storage");
+    if !disabled.is_empty() {
+        info!(sources = ?disabled.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), "Skipping disabled sources");
+    }
 
-    let sources: Vec<SourceConfig> = serde_json::from_slice(&sources_data)?;
-    info!(count = sources.len(), "Loaded sources from Cloud Storage");
+    // Sources failing run after run are quarantined and only probed now and then
+    let quarantine = QuarantinePolicy::from_env();
+    let mut source_health = load_source_health(store).await.unwrap_or_else(|e| {
+        warn!(error = %e, "Failed to load source health, fetching every source");
+        SourceHealthMap::new()
+    });
+    prune_unlisted(&mut source_health, sources.iter().chain(&disabled).chain(&invalid).map(|s| s.url.as_str()));
+    let mut quarantined: Vec<String> = Vec::new();
 
     // 2. Fetch Articles (use a dedicated client for fetching with appropriate timeout)
     let fetch_client = fetcher::create_http_client()?;
     info!("Fetching headlines from sources");
     let mut all_articles: Vec<Article> = Vec::new();
-    for source in sources {
-        debug!(source = %source.name, "Fetching from source");
-        match fetcher::fetch_from_source(&source, &fetch_client).await {
-            Ok(mut articles) => {
+    let mut skipped_media = 0;
+    let allowed_languages = fetcher::allowed_languages_from_env();
+    let mut skipped_language = 0;
+    let mut source_results: Vec<fetcher::SourceResult> = Vec::new();
+    // Link aggregators lose to the original blog when both list an article
+    let aggregators: std::collections::HashSet<String> = sources.iter()
+        .filter(|s| matches!(s.source_type, SourceType::HackerNews | SourceType::Lobsters | SourceType::Bluesky))
+        .map(|s| s.name.clone())
+        .collect();
+    for source in &sources {
+        let decision = fetch_decision(&source_health, &source.url, &quarantine);
+        let health = source_health.entry(source.url.trim().to_string()).or_default();
+        health.name = source.name.clone();
+        match decision {
+            FetchDecision::Skip => {
+                warn!(source = %source.name, failures = health.consecutive_failures, since = ?health.quarantined_since, "Skipping quarantined source");
+                health.record_skip();
+                quarantined.push(source.name.clone());
+                continue;
+            }
+            FetchDecision::Probe => info!(source = %source.name, failures = health.consecutive_failures, "Probing quarantined source"),
+            FetchDecision::Fetch => debug!(source = %source.name, "Fetching from source"),
+        }
+        match fetcher::fetch_from_source(source, &fetch_client).await {
+            Ok((articles, mut outcome)) => {
+                if health.is_quarantined() {
+                    info!(source = %source.name, "Quarantined source recovered");
+                }
+                health.record_success(&today);
+                // A YouTube channel is nothing but videos
+                let allow_media = source.allow_media || source.source_type == SourceType::Youtube;
+                let (mut articles, media_filtered) = fetcher::filter_media(articles, allow_media);
+                if media_filtered > 0 {
+                    info!(source = %source.name, skipped = media_filtered, "Skipped podcast/video episodes");
+                }
+                skipped_media += media_filtered;
+                if source.newsletter {
+                    let issues = articles.len();
+                    articles = newsletter::expand_issues(&fetch_client, articles, &source.name).await;
+                    info!(source = %source.name, issues = issues, items = articles.len(), "Split newsletter issues into linked articles");
+                }
+                let languages = if source.languages.is_empty() { &allowed_languages } else { &source.languages };
+                let (mut articles, other_languages) = fetcher::filter_languages(articles, languages);
+                for article in &other_languages {
+                    info!(source = %source.name, title = %article.title, language = article.language.as_deref().unwrap_or_default(), "Skipped article in a language not allowed");
+                }
+                skipped_language += other_languages.len();
                 info!(source = %source.name, count = articles.len(), "Found articles");
+                // Counted after the media filter and newsletter expansion
+                outcome.articles = articles.len();
+                source_results.push((source.name.clone(), Ok(outcome)));
                 all_articles.append(&mut articles);
             },
-            Err(e) => warn!(source = %source.name, error = %e, "Failed to fetch from source"),
+            Err(e) => {
+                warn!(source = %source.name, kind = %e.kind, error = %e.detail, "Failed to fetch from source");
+                if health.record_failure(&today, &format!("{}: {}", e.kind, e.detail), &quarantine) {
+                    warn!(source = %source.name, failures = health.consecutive_failures, probe_every = quarantine.probe_every, "Quarantining source after repeated failures");
+                }
+                source_results.push((source.name.clone(), Err(e)));
+            }
         }
     }
+    info!("Source fetch outcomes:\n{}", fetcher::outcome_table(&source_results));
+    report.set_section("sources", &fetcher::outcome_report(&source_results));
+    if !quarantined.is_empty() {
+        warn!(sources = ?quarantined, "Skipped quarantined sources");
+    }
+    // Saved now so later failures can't lose this run's fetch outcomes
+    save_health(store, &source_health).await;
 
     if all_articles.is_empty() {
         warn!("No recent articles found from any source");
         return Ok(());
     }
 
+    let (mut all_articles, duplicates) = fetcher::dedup_articles(all_articles, &aggregators);
+    if duplicates > 0 {
+        info!(removed = duplicates, remaining = all_articles.len(), "Merged articles listed by several sources");
+    }
+
     info!(total_articles = all_articles.len(), "Total articles collected");
+    report.set_section("articles_collected", &all_articles.len());
+    report.set_section("skipped", &serde_json::json!({ "media": skipped_media, "language": skipped_language, "duplicates": duplicates, "disabled_sources": disabled.len(), "invalid_sources": invalid.len(), "quarantined_sources": quarantined.len() }));
 
     // --- Manifest: download once, all stages append, single upload at the end ---
-    let today = Utc::now().format("%Y-%m-%d").to_string();
 
-    let mut manifest: Vec<ManifestEntry> = match gcs_client.download_object(
-        &GetObjectRequest {
-            bucket: bucket_name.to_string(),
-            object: "manifest.json".to_string(),
-            ..Default::default()
-        },
-        &Range::default()
-    ).await {
+    let mut manifest: Vec<ManifestEntry> = match download_object(&gcs_client, &bucket_name, "manifest.json").await {
         Ok(data) => {
-            serde_json::from_slice(&data).map_err(|e| {
+            parse_json_strict(&format!("gs://{}/manifest.json", bucket_name), &data, None).map_err(|e| {
                 error!(error = %e, "Failed to parse existing manifest.json - file may be corrupted");
                 e
             })?
         },
-        Err(e) if e.to_string().contains("No such object") || e.to_string().contains("404") => {
+        Err(e) if e.is_not_found() => {
             info!("No existing manifest.json found, creating new one");
             Vec::new()
         },
@@ -317,33 +845,49 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         }
     };
 
-    // Cross-day dedup: collect URLs selected in the last 7 days
-    let recent_urls: std::collections::HashSet<String> = manifest.iter()
-        .filter(|e| e.date >= Utc::now().checked_sub_signed(chrono::Duration::days(7))
-            .map(|d| d.format("%Y-%m-%d").to_string())
-            .unwrap_or_default())
-        .filter(|e| e.prompt_version.is_none()) // only dedup against v1 (prod) picks
-        .filter_map(|e| e.original_url.clone())
-        .collect();
-
-    let pre_dedup_count = all_articles.len();
-    all_articles.retain(|a| !recent_urls.contains(&a.url));
-    if all_articles.len() < pre_dedup_count {
+    // Cross-day dedup: articles summarized in the last month, by URL or a near-identical title
+    let repeat_since = (Utc::now() - chrono::Duration::days(REPEAT_WINDOW_DAYS)).format("%Y-%m-%d").to_string();
+    let repeats = exclude_recent_picks(&mut all_articles, &manifest, &repeat_since);
+    if repeats > 0 {
         info!(
-            removed = pre_dedup_count - all_articles.len(),
+            removed = repeats,
             remaining = all_articles.len(),
-            "Filtered articles already selected in last 7 days"
+            "Filtered articles already summarized in the last {} days", REPEAT_WINDOW_DAYS
         );
     }
 
+    // Articles fetched on earlier runs, whether or not they were picked
+    let seen_today = Utc::now().date_naive();
+    let mut seen_urls = load_seen(store, seen_today).await;
+    let seen_excluded = if skip_seen_from_env() { exclude_seen(&mut all_articles, &seen_urls, seen_today) } else { 0 };
+    if seen_excluded > 0 {
+        info!(removed = seen_excluded, remaining = all_articles.len(), "Filtered articles seen on earlier runs");
+    }
     if all_articles.is_empty() {
-        warn!("No articles remain after dedup — all recent articles were already selected");
+        warn!("No articles remain after dedup — all recent articles were seen on earlier runs");
         return Ok(());
     }
 
+    // One prolific source mustn't fill the selection prompt
+    let candidates_collected = all_articles.len();
+    let (mut all_articles, capped) = fetcher::balance_articles(all_articles, max_total_articles_from_env());
+    if capped > 0 {
+        info!(removed = capped, remaining = all_articles.len(), "Capped articles, sampling evenly across sources");
+    }
+    report.set_section("capped", &capped);
+    // Only the articles the model is shown count as seen; capped ones get another chance
+    record_seen(&mut seen_urls, &all_articles, seen_today);
+    report.set_section("seen", &serde_json::json!({ "excluded": seen_excluded, "tracked": seen_urls.len() }));
+
     // Remove existing entries for today (all models)
     manifest.retain(|e| e.date != today);
     let mut new_manifest_entries: Vec<ManifestEntry> = Vec::new();
+    // Output objects are staged here and written in dependency order at the end
+    let mut publisher = Publisher::new(&today);
+
+    let extraction_overrides: ExtractionOverrides = load_extraction_overrides(store).await;
+    let templates = PromptTemplates::load(store, prompts::DEFAULT_PROMPTS).await;
+    let mut extraction_log = ExtractionLog::default();
 
     // --- Load user feedback early (needed for selection context) ---
     let recent_feedback = load_recent_feedback(&gcs_client, &bucket_name).await;
@@ -353,164 +897,323 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     // 3. Two-phase selection: shortlist by headlines, then pick by content
     info!(provider = %selection_provider.as_str(), "Phase 1: Shortlisting top candidates from headlines");
 
-    let mut articles_text = String::new();
-    for (i, article) in all_articles.iter().enumerate() {
-        articles_text.push_str(&format!("{}. [{}] {}\n", i, article.source, article.title));
-    }
+    let headlines: Vec<String> = all_articles.iter().map(Article::headline).collect();
+    let articles_text = prompts::article_list(headlines.iter().map(String::as_str), candidates_collected);
 
     let prod_config = prompts::PromptConfig::V1;
-    let selection_opts = LlmOptions { temperature: Some(0.3), ..Default::default() };
+    let votes = selection_votes_from_env();
+    let digest_size = digest_size_from_env();
+    // Deterministic: the replies are article indices
+    let selection_opts = LlmOptions {
+        temperature: Some(0.0),
+        timeout: Some(Duration::from_secs(SELECTION_TIMEOUT_SECS)),
+        ..Default::default()
+    };
 
     // Phase 1: Shortlist top 5 from headlines
     let shortlist_prompt = prod_config.shortlist_prompt_with_context(
+        &templates,
         &articles_text,
         selection_context.as_deref(),
         recent_picks.as_deref(),
-    );
-    let shortlist_response = call_llm(&http_client, selection_provider, &selection_key, shortlist_prompt, &selection_opts).await?;
-    let mut shortlist = parse_shortlist_indices(&shortlist_response, all_articles.len());
+    )?;
+    let shortlist_reply: Option<ShortlistReply> =
+        call_selection(&selection, shortlist_prompt, &selection_opts, &mut report.llm_usage).await?;
+    let mut shortlist = shortlist_reply.map(|r| r.valid_indices(all_articles.len())).unwrap_or_default();
 
-    // Fallback: if shortlist parsing fails, use single-shot selection
+    // Fallback: if the shortlist is unusable, use single-shot selection
+    let mut fallback_reason = None;
     if shortlist.is_empty() {
-        warn!(response = %shortlist_response.trim(), "Failed to parse shortlist, falling back to single-shot");
-        let fallback_prompt = prod_config.selection_prompt(&articles_text);
-        let fallback = call_llm(&http_client, selection_provider, &selection_key, fallback_prompt, &selection_opts).await?;
-        let idx = parse_selection_index(&fallback).unwrap_or(0).min(all_articles.len().saturating_sub(1));
-        shortlist = vec![idx];
+        warn!("No valid shortlist, falling back to single-shot");
+        let fallback_prompt = prod_config.selection_prompt(&templates, &articles_text)?;
+        let fallback = call_pick(&selection, fallback_prompt, &selection_opts, votes, &mut report.llm_usage).await?;
+        match fallback.filter(|pick| pick.index < all_articles.len()) {
+            Some(pick) => {
+                fallback_reason = pick.reason;
+                shortlist = vec![pick.index];
+            }
+            None => shortlist = vec![0],
+        }
     }
 
     info!(candidates = ?shortlist, "Shortlisted candidates");
 
-    // Phase 2: Fetch content snippets for shortlisted articles, then final pick
-    let safe_index = if shortlist.len() == 1 {
-        shortlist[0]
+    // Phase 2: Fetch content snippets for shortlisted articles, then final pick (or picks, for a digest)
+    let picks = if shortlist.len() == 1 {
+        vec![DigestPick { index: shortlist[0], reason: fallback_reason }]
     } else {
         info!("Phase 2: Fetching content for {} candidates", shortlist.len());
         let mut candidates_text = String::new();
         for &idx in &shortlist {
             let article = &all_articles[idx];
-            let snippet = match fetch_article_content(&http_client, &article.url).await {
+            let content = match full_feed_content(article) {
+                Some(content) => Ok(content.to_string()),
+                None => fetch_article_content(&http_client, robots, &article.url, override_for(&extraction_overrides, &article.url), &mut extraction_log).await,
+            };
+            let snippet = match content {
                 Ok(content) => {
                     let s: String = content.chars().take(SELECTION_SNIPPET_CHARS).collect();
                     s
                 }
                 Err(e) => {
                     debug!(title = %article.title, error = %e, "Could not fetch content for candidate");
-                    "(content unavailable)".to_string()
+                    match &article.summary {
+                        Some(summary) => summary.chars().take(SELECTION_SNIPPET_CHARS).collect(),
+                        None => "(content unavailable)".to_string(),
+                    }
                 }
             };
             candidates_text.push_str(&format!(
-                "--- Article {} ---\n[{}] {}\n\n{}\n\n",
-                idx, article.source, article.title, snippet
+                "--- Article {} ---\n{}\n\n{}\n\n",
+                idx, article.headline(), snippet
             ));
         }
 
-        let final_prompt = prod_config.final_selection_prompt_with_context(
-            &candidates_text,
-            selection_context.as_deref(),
-            recent_picks.as_deref(),
-        );
-        let final_response = call_llm(&http_client, selection_provider, &selection_key, final_prompt, &selection_opts).await?;
-        let picked = parse_selection_index(&final_response).unwrap_or(shortlist[0]);
-
-        // Validate the pick is in our shortlist
-        if shortlist.contains(&picked) {
-            picked
+        if digest_size > 1 {
+            let digest_prompt = prod_config.digest_selection_prompt_with_context(
+                &templates,
+                &candidates_text,
+                digest_size,
+                selection_context.as_deref(),
+                recent_picks.as_deref(),
+            )?;
+            let digest_reply: Option<DigestReply> =
+                call_selection(&selection, digest_prompt, &selection_opts, &mut report.llm_usage).await?;
+            valid_picks(digest_reply.map(|r| r.picks), &shortlist, digest_size)
         } else {
-            warn!(picked = picked, "Final pick not in shortlist, using first candidate");
-            shortlist[0]
+            let final_prompt = prod_config.final_selection_prompt_with_context(
+                &templates,
+                &candidates_text,
+                selection_context.as_deref(),
+                recent_picks.as_deref(),
+            )?;
+            let final_reply = call_pick(&selection, final_prompt, &selection_opts, votes, &mut report.llm_usage).await?;
+            let picked = final_reply.unwrap_or(PickReply { index: shortlist[0], reason: None });
+
+            // Validate the pick is in our shortlist
+            if shortlist.contains(&picked.index) {
+                vec![DigestPick { index: picked.index, reason: picked.reason }]
+            } else {
+                warn!(picked = picked.index, "Final pick not in shortlist, using first candidate");
+                vec![DigestPick { index: shortlist[0], reason: None }]
+            }
         }
     };
 
+    // Exploration: occasionally override the pick with a source not featured recently
+    let explored_index = maybe_explore(&all_articles, &manifest, &exploration, &mut exploration.rng());
+    let exploratory = explored_index.is_some();
+    let picks = lead_with(picks, explored_index, digest_size);
+    let safe_index = picks[0].index;
+
+    // Store where the links really go, without redirectors or tracking parameters, and
+    // remember the feed's links so that later runs recognize them as repeats
+    let mut feed_urls = std::collections::HashMap::new();
+    let limiter = llm_client::HostLimiter::shared();
+    for pick in &picks {
+        let feed_url = all_articles[pick.index].url.clone();
+        let final_url = if robots.allows(&http_client, &feed_url).await {
+            let source = sources.iter().find(|s| s.name == all_articles[pick.index].source);
+            fetcher::resolve_final_url(&http_client, &feed_url, source, &limiter).await
+        } else {
+            fetcher::strip_tracking_params(&feed_url)
+        };
+        if final_url != feed_url {
+            info!(from = %feed_url, to = %final_url, "Resolved article URL");
+            feed_urls.insert(final_url.clone(), feed_url);
+            all_articles[pick.index].url = final_url;
+            record_seen(&mut seen_urls, std::slice::from_ref(&all_articles[pick.index]), seen_today);
+        }
+    }
+
     let best_article = &all_articles[safe_index];
+    let selection_reason = picks[0].reason.clone();
+    let extra_picks: Vec<(&Article, Option<String>)> =
+        picks[1..].iter().map(|pick| (&all_articles[pick.index], pick.reason.clone())).collect();
+    for (article, reason) in &extra_picks {
+        info!(title = %article.title, url = %article.url, source = %article.source, reason = reason.as_deref().unwrap_or_default(), "Selected digest article");
+    }
     info!(
         title = %best_article.title,
         url = %best_article.url,
         source = %best_article.source,
+        exploratory = exploratory,
+        reason = selection_reason.as_deref().unwrap_or_default(),
         "Selected best article"
     );
+    report.set_section("selected", &serde_json::json!({
+        "title": best_article.title,
+        "url": best_article.url,
+        "source": best_article.source,
+        "via": best_article.via,
+        "selected_by": selection_provider.as_str(),
+        "exploratory": exploratory,
+        "reason": selection_reason,
+        "digest": extra_picks.iter().map(|(article, reason)| serde_json::json!({ "title": article.title, "url": article.url, "reason": reason })).collect::<Vec<_>>(),
+    }));
+
+    // Onboarding counters for recently added sources (see explorer-agent)
+    let contributions = source_contributions(&all_articles, &shortlist, safe_index);
+    if let Err(e) = update_provenance(store, |provenance| {
+        let updated = record_daily_run(provenance, &today, &contributions);
+        debug!(updated = updated, "Recorded onboarding counters");
+    }).await {
+        warn!(error = %e, "Failed to update source onboarding counters");
+    }
 
     // 4. Fetch full article content (may reuse cached content from phase 2)
     info!("Fetching full article content");
 
-    let article_text = match fetch_article_content(&http_client, &best_article.url).await {
-        Ok(content) => content,
-        Err(e) => {
-            warn!(error = %e, "Failed to fetch article content, using title only");
-            format!("Title: {}, URL: {}", best_article.title, best_article.url)
+    let extraction = override_for(&extraction_overrides, &best_article.url);
+    let article_text = fetch_article_text(&http_client, robots, best_article, extraction, &mut extraction_log).await;
+    // The digest's other articles are fetched together
+    let extra_texts = join_all(extra_picks.iter().map(|(article, _)| {
+        let (http_client, extraction_overrides) = (&http_client, &extraction_overrides);
+        async move {
+            let mut log = ExtractionLog::default();
+            let text = fetch_article_text(http_client, robots, article, override_for(extraction_overrides, &article.url), &mut log).await;
+            (text, log)
         }
-    };
+    })).await;
+
+    // Truncate at a word boundary to fit every provider's context window
+    let providers: Vec<LlmProvider> = enabled_providers.iter().map(LlmClient::provider).collect();
+    let article_budget = article_token_budget(&providers);
+    let truncated_text = summary_input(best_article, &article_text, article_budget);
+    let long_article_tokens = long_article_tokens_from_env();
+    let long_text = long_article_text(best_article, &article_text, long_article_tokens, article_budget);
+    debug!(char_count = truncated_text.len(), estimated_tokens = estimate_tokens(&truncated_text), "Article text truncated");
+    let mut extra_prompts = Vec::new();
+    for ((article, _), (text, log)) in extra_picks.iter().zip(extra_texts) {
+        extraction_log.records.extend(log.records);
+        let input = summary_input(article, &text, article_budget);
+        let title_only_text = format!("Title: {}, URL: {}", article.title, article.url);
+        extra_prompts.push((
+            article,
+            prod_config.summary_prompt(&templates, article.source_label(), &article.title, &input)?,
+            prod_config.summary_prompt(&templates, article.source_label(), &article.title, &title_only_text)?,
+            long_article_text(article, &text, long_article_tokens, article_budget).map(str::to_string),
+        ));
+    }
 
-    // Truncate safely at character boundary to avoid UTF-8 split
-    let truncated_text: String = article_text.chars().take(MAX_ARTICLE_CHARS).collect();
-    debug!(char_count = truncated_text.len(), "Article text truncated");
+    report.set_section("extraction", &extraction_log.records);
+    update_extraction_health(store, &extraction_log.records, &today).await;
 
-    let summary_prompt = prod_config.summary_prompt(&best_article.source, &best_article.title, &truncated_text);
+    let summary_prompt = prod_config.summary_prompt(&templates, best_article.source_label(), &best_article.title, &truncated_text)?;
+    let title_only_text = format!("Title: {}, URL: {}", best_article.title, best_article.url);
+    let title_only_prompt = prod_config.summary_prompt(&templates, best_article.source_label(), &best_article.title, &title_only_text)?;
 
     // --- Stage 2: Prod (v1) — parallel LLM calls ---
 
     info!("Generating summaries in parallel across {} provider(s)", enabled_providers.len());
 
-    let summary_futures: Vec<_> = enabled_providers.iter().map(|(provider, api_key)| {
-        let client = http_client.clone();
-        let key = api_key.clone();
+    let summary_opts = LlmOptions {
+        temperature: Some(0.7),
+        timeout: Some(Duration::from_secs(SUMMARY_TIMEOUT_SECS)),
+        ..Default::default()
+    };
+    let summary_futures: Vec<_> = enabled_providers.iter().map(|llm| {
+        let summary_opts = &summary_opts;
         let prompt = summary_prompt.clone();
-        let p = *provider;
+        let softened_prompt = prompts::soften_prompt(&summary_prompt);
+        let title_only_prompt = title_only_prompt.clone();
+        let p = llm.provider();
+        let (templates, best_article, prod_config, extra_prompts) = (&templates, &best_article, &prod_config, &extra_prompts);
         async move {
-            let result = call_llm_with_retry(&client, p, &key, prompt).await;
-            (p, result)
+            let combine = summary_over_parts(templates, prod_config, best_article);
+            let summary = summarize_article(llm, prompt, long_text, article_budget, summary_opts, combine).await;
+            let lead = retry_summary(llm, summary, softened_prompt, title_only_prompt, summary_opts);
+            // The digest's other articles; the shared LLM limiter bounds how many run at once
+            let extras = join_all(extra_prompts.iter().map(|(article, prompt, title_only_prompt, long_text)| async move {
+                let combine = summary_over_parts(templates, prod_config, article);
+                let summary = summarize_article(llm, prompt.clone(), long_text.as_deref(), article_budget, summary_opts, combine).await;
+                retry_summary(llm, summary, prompts::soften_prompt(prompt), title_only_prompt.clone(), summary_opts).await
+            }));
+            let (result, extra_results) = futures::join!(lead, extras);
+            (p, result, extra_results)
         }
     }).collect();
 
     let llm_results = join_all(summary_futures).await;
-
-    // GCS uploads happen sequentially after all LLM calls complete
-    for (provider, result) in llm_results {
+    let compare_providers = compare_providers_from_env();
+    // Summaries of the compared providers, with the index of their manifest entry
+    let mut compared = Vec::new();
+
+    // Stage summaries; they are written before the manifest that references them
+    for (provider, result, extra_results) in llm_results {
+        let llm = find_provider(&enabled_providers, provider);
+        let mut extra_sections = Vec::new();
+        for ((article, reason), result) in extra_picks.iter().zip(extra_results) {
+            match result {
+                Ok(response) => {
+                    report.llm_usage.record(&response);
+                    let summary = match llm {
+                        Some(llm) => ensure_english(llm, response.text, &summary_opts, &mut report.llm_usage).await,
+                        None => response.text,
+                    };
+                    extra_sections.push(DigestSection::new(article, reason.clone(), summary));
+                }
+                Err(e) => warn!(provider = %provider.as_str(), title = %article.title, error = %e, "Digest article summary failed, leaving it out"),
+            }
+        }
+        let result = result.map(|response| {
+            report.llm_usage.record(&response);
+            response.text
+        });
         match result {
             Ok(summary) => {
+                let summary = match llm {
+                    Some(llm) => ensure_english(llm, summary, &summary_opts, &mut report.llm_usage).await,
+                    None => summary,
+                };
                 info!(provider = %provider.as_str(), "Summary generated successfully");
                 debug!(provider = %provider.as_str(), summary_length = summary.len(), "Summary details");
 
-                // Create snippet BEFORE converting summary to bytes
                 let summary_snippet: String = summary.chars().take(SUMMARY_SNIPPET_CHARS).collect();
+                if compare_providers.contains(&provider) {
+                    compared.push((provider, summary.clone(), new_manifest_entries.len()));
+                }
 
-                // Upload Summary to GCS (provider-specific path)
-                // Metadata (original_url, model, selected_by) lives in manifest.json
-                let object_name = format!("summaries/{}/{}.md", provider.as_str(), today);
-                let summary_bytes = summary.into_bytes();
-
-                info!(provider = %provider.as_str(), object = %object_name, "Uploading summary to GCS");
-
-                let upload_type = UploadType::Simple(Media::new(object_name.clone()));
-                match gcs_client.upload_object(
-                    &UploadObjectRequest {
-                        bucket: bucket_name.to_string(),
-                        ..Default::default()
-                    },
-                    summary_bytes,
-                    &upload_type
-                ).await {
-                    Ok(_) => {
-                        info!(provider = %provider.as_str(), "Summary upload complete");
-
-                        let public_url = gcs_public_url(&bucket_name, &object_name);
-                        new_manifest_entries.push(ManifestEntry {
-                            date: today.clone(),
-                            url: public_url,
-                            title: best_article.title.clone(),
-                            summary_snippet,
-                            original_url: Some(best_article.url.clone()),
-                            model: Some(provider.model_name().to_string()),
-                            selected_by: Some(selection_provider.model_name().to_string()),
-                            prompt_version: None,
-                            eval_score: None,
-                            format: None,
-                        });
-                    }
-                    Err(e) => {
-                        error!(provider = %provider.as_str(), error = %e, "Failed to upload summary");
+                // With several articles, the summary becomes a digest under a short intro
+                let sections: Vec<DigestSection> = std::iter::once(DigestSection::new(best_article, selection_reason.clone(), summary))
+                    .chain(extra_sections)
+                    .collect();
+                let intro = match llm.filter(|_| sections.len() > 1) {
+                    Some(llm) => {
+                        let intro_prompt = prod_config.digest_intro_prompt(&templates, &intro_articles(&sections))?;
+                        call_llm_tracked(llm, intro_prompt, &summary_opts, &mut report.llm_usage).await
+                            .map_err(|e| warn!(provider = %provider.as_str(), error = %e, "Digest intro failed, publishing without one"))
+                            .ok()
                     }
-                }
+                    None => None,
+                };
+                let summary = assemble_digest(&today, intro.as_deref(), &sections);
+
+                // Provider-specific path; metadata (original_url, model, selected_by) lives in manifest.json
+                let object_name = format!("summaries/{}/{}.md", provider.as_str(), today);
+                publisher.stage_leaf(&object_name, summary);
+
+                new_manifest_entries.push(ManifestEntry {
+                    date: today.clone(),
+                    url: gcs_public_url(&bucket_name, &object_name),
+                    title: best_article.title.clone(),
+                    summary_snippet,
+                    original_url: Some(best_article.url.clone()),
+                    feed_url: feed_urls.get(&best_article.url).cloned(),
+                    model: Some(provider.model_name().to_string()),
+                    selected_by: Some(selection_provider.model_name().to_string()),
+                    prompt_version: None,
+                    eval_score: None,
+                    format: None,
+                    source: Some(best_article.source.clone()),
+                    exploratory: exploratory.then_some(true),
+                    run_id: Some(run_id().to_string()),
+                    judged_best: None,
+                    author: best_article.author.clone(),
+                    categories: best_article.categories.clone(),
+                    articles: manifest_articles(&sections, &feed_urls),
+                    selection_reason: selection_reason.clone(),
+                });
             }
             Err(e) => {
                 warn!(provider = %provider.as_str(), error = %e, "Summary generation failed");
@@ -523,16 +1226,35 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         return Err("No summaries generated".into());
     }
 
+    if let Some(judge) = pick_judge(&enabled_providers, &compare_providers).filter(|_| !compare_providers.is_empty()) {
+        let summaries: Vec<_> = compared.iter().map(|(provider, summary, _)| (*provider, summary.clone())).collect();
+        let verdict = compare_summaries(judge, &summary_prompt, &summaries, &mut publisher, &today, &mut report.llm_usage).await;
+        if let Some(verdict) = &verdict {
+            for (provider, _, idx) in &compared {
+                new_manifest_entries[*idx].judged_best = Some(*provider == verdict.winner);
+            }
+        }
+        report.set_section("compare", &serde_json::json!({
+            "providers": compare_providers.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+            "compared": compared.iter().map(|(p, _, _)| p.as_str()).collect::<Vec<_>>(),
+            "judge": judge.provider().as_str(),
+            "verdict": verdict,
+        }));
+    }
+
     // --- Stage 3: V3 Insight Brief ---
     info!("=== Stage 3: V3 Insight Brief ===");
     let v3_config = prompts::PromptConfig::V3;
 
-    let claude_entry = enabled_providers.iter().find(|(p, _)| *p == LlmProvider::Claude);
-    if let Some((_, claude_key)) = claude_entry {
-        let v3_prompt = v3_config.summary_prompt(&best_article.source, &best_article.title, &truncated_text);
-        let v3_options = LlmOptions { temperature: Some(0.3), ..Default::default() };
+    if let Some(claude) = find_provider(&enabled_providers, LlmProvider::Claude) {
+        let v3_prompt = v3_config.summary_prompt(&templates, best_article.source_label(), &best_article.title, &truncated_text)?;
+        let v3_options = LlmOptions {
+            temperature: Some(0.3),
+            timeout: Some(Duration::from_secs(SUMMARY_TIMEOUT_SECS)),
+            ..Default::default()
+        };
 
-        match call_llm(&http_client, LlmProvider::Claude, claude_key, v3_prompt, &v3_options).await {
+        match call_llm_tracked(claude, v3_prompt, &v3_options, &mut report.llm_usage).await {
             Ok(response) => {
                 let json_str = response.trim();
                 // Strip markdown code fences if present
@@ -546,40 +1268,36 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
                 match serde_json::from_str::<serde_json::Value>(&clean_json) {
                     Ok(parsed) if parsed.get("key_idea").is_some() && parsed.get("deep_dive").is_some() => {
                         let object_path = format!("summaries/v3/{}.json", today);
-                        let public_url = gcs_public_url(&bucket_name, &object_path);
-
-                        match gcs_client.upload_object(
-                            &UploadObjectRequest {
-                                bucket: bucket_name.clone(),
-                                ..Default::default()
-                            },
-                            clean_json.as_bytes().to_vec(),
-                            &UploadType::Simple(Media::new(object_path.clone())),
-                        ).await {
-                            Ok(_) => {
-                                let snippet = parsed["key_idea"].as_str().unwrap_or("").to_string();
-                                let snippet_truncated = if snippet.chars().count() > SUMMARY_SNIPPET_CHARS {
-                                    format!("{}...", snippet.chars().take(SUMMARY_SNIPPET_CHARS - 3).collect::<String>())
-                                } else {
-                                    snippet
-                                };
-
-                                new_manifest_entries.push(ManifestEntry {
-                                    date: today.clone(),
-                                    url: public_url,
-                                    title: best_article.title.clone(),
-                                    summary_snippet: snippet_truncated,
-                                    original_url: Some(best_article.url.clone()),
-                                    model: Some(LlmProvider::Claude.model_name().to_string()),
-                                    selected_by: Some(selection_provider.model_name().to_string()),
-                                    prompt_version: Some("v3".to_string()),
-                                    eval_score: None,
-                                    format: Some("insight-brief-v3".to_string()),
-                                });
-                                info!("V3 Insight Brief uploaded to {}", object_path);
-                            }
-                            Err(e) => warn!(error = %e, "Failed to upload V3 Insight Brief"),
-                        }
+                        let snippet = parsed["key_idea"].as_str().unwrap_or("").to_string();
+                        let snippet_truncated = if snippet.chars().count() > SUMMARY_SNIPPET_CHARS {
+                            format!("{}...", snippet.chars().take(SUMMARY_SNIPPET_CHARS - 3).collect::<String>())
+                        } else {
+                            snippet
+                        };
+
+                        new_manifest_entries.push(ManifestEntry {
+                            date: today.clone(),
+                            url: gcs_public_url(&bucket_name, &object_path),
+                            title: best_article.title.clone(),
+                            summary_snippet: snippet_truncated,
+                            original_url: Some(best_article.url.clone()),
+                            feed_url: feed_urls.get(&best_article.url).cloned(),
+                            model: Some(LlmProvider::Claude.model_name().to_string()),
+                            selected_by: Some(selection_provider.model_name().to_string()),
+                            prompt_version: Some("v3".to_string()),
+                            eval_score: None,
+                            format: Some("insight-brief-v3".to_string()),
+                            source: Some(best_article.source.clone()),
+                            exploratory: exploratory.then_some(true),
+                            run_id: Some(run_id().to_string()),
+                            judged_best: None,
+                            author: best_article.author.clone(),
+                            categories: best_article.categories.clone(),
+                            articles: Vec::new(),
+                            selection_reason: selection_reason.clone(),
+                        });
+                        publisher.stage_leaf(&object_path, clean_json);
+                        info!("V3 Insight Brief staged for {}", object_path);
                     }
                     Ok(_) => warn!("V3 response missing required fields, skipping"),
                     Err(e) => warn!(error = %e, "V3 response is not valid JSON, skipping"),
@@ -596,10 +1314,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 
     // --- Stage 4: Eval (dual pass with calibration) ---
     // Use Gemini as judge to avoid self-preference bias (Claude judging Claude summaries)
-    let eval_entry = enabled_providers.iter().find(|(p, _)| *p == LlmProvider::Gemini)
-        .or(enabled_providers.iter().find(|(p, _)| *p == LlmProvider::Claude));
-    if let Some((eval_provider, eval_key)) = eval_entry {
-        info!(provider = %eval_provider.as_str(), "Starting eval stage");
+    let eval_llm = find_provider(&enabled_providers, LlmProvider::Gemini)
+        .or(find_provider(&enabled_providers, LlmProvider::Claude));
+    if let Some(eval_llm) = eval_llm {
+        info!(provider = %eval_llm.provider().as_str(), "Starting eval stage");
 
         // Collect all summaries generated today for evaluation
         let mut eval_summaries: Vec<(String, String)> = Vec::new(); // (summary_id, content)
@@ -607,21 +1325,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         for entry in &new_manifest_entries {
             let summary_id = entry.summary_id();
 
-            // Download the summary we just uploaded
-            match gcs_client.download_object(
-                &GetObjectRequest {
-                    bucket: bucket_name.to_string(),
-                    object: gcs_object_path(&entry.url, &bucket_name).to_string(),
-                    ..Default::default()
-                },
-                &Range::default()
-            ).await {
-                Ok(data) => {
-                    if let Ok(content) = String::from_utf8(data) {
-                        eval_summaries.push((summary_id, content));
-                    }
-                }
-                Err(e) => warn!(summary_id = %summary_id, error = %e, "Failed to download summary for eval"),
+            // Evaluate the staged content; nothing is published until eval scores are in
+            match publisher.content(gcs_object_path(&entry.url, &bucket_name)) {
+                Some(content) => eval_summaries.push((summary_id, content.to_string())),
+                None => warn!(summary_id = %summary_id, "Staged summary missing for eval"),
             }
         }
 
@@ -648,7 +1355,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
                 section.push_str(&format!("--- Summary: {} ---\n{}\n\n", id, content));
             }
             if let Some(json) = run_eval_pass(
-                &http_client, *eval_provider, eval_key, format!("{}{}", v1_prompt, section), &gcs_client, &bucket_name, &today, "eval"
+                eval_llm, format!("{}{}", v1_prompt, section), &gcs_client, &bucket_name, &today, "eval", &mut report.llm_usage
             ).await {
                 apply_eval_scores(&json, &mut new_manifest_entries);
             }
@@ -671,7 +1378,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
                 section.push_str(&format!("--- Summary: {} ---\n{}\n\n", id, content));
             }
             if let Some(json) = run_eval_pass(
-                &http_client, *eval_provider, eval_key, format!("{}{}", v3_prompt, section), &gcs_client, &bucket_name, &today, "eval-v3"
+                eval_llm, format!("{}{}", v3_prompt, section), &gcs_client, &bucket_name, &today, "eval-v3", &mut report.llm_usage
             ).await {
                 apply_eval_scores(&json, &mut new_manifest_entries);
             }
@@ -693,7 +1400,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
                 }
                 let calibrated_prompt = format!("{}{}\n{}", v1_prompt, cal_context, section);
                 if let Some(cal_json) = run_eval_pass(
-                    &http_client, *eval_provider, eval_key, calibrated_prompt, &gcs_client, &bucket_name, &today, "eval-calibrated"
+                    eval_llm, calibrated_prompt, &gcs_client, &bucket_name, &today, "eval-calibrated", &mut report.llm_usage
                 ).await {
                     apply_eval_scores(&cal_json, &mut new_manifest_entries);
                     log_calibration_agreement(&recent_feedback, &cal_json, &new_manifest_entries);
@@ -704,19 +1411,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         info!("No LLM provider available for eval, skipping eval stage");
     }
 
-    // --- Final: Upload manifest (all stages have appended to new_manifest_entries) ---
+    // --- Final: Publish summaries, then the manifest (all stages have appended to new_manifest_entries) ---
     for entry in new_manifest_entries.into_iter().rev() {
         manifest.insert(0, entry);
     }
-    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
-    gcs_client.upload_object(
-        &UploadObjectRequest {
-            bucket: bucket_name.to_string(),
-            ..Default::default()
-        },
-        manifest_json,
-        &UploadType::Simple(Media::new("manifest.json".to_string()))
-    ).await?;
+    publisher.stage_manifest(serde_json::to_string_pretty(&manifest)?);
+    let published = publisher.publish(store).await;
+    report.set_section("publish", &publisher.progress());
+    published?;
+    save_seen(store, &seen_urls).await;
 
     info!(date = %today, "Manifest updated successfully");
     info!("SE Daily Agent completed successfully");
@@ -724,19 +1427,173 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     Ok(())
 }
 
-#[instrument(skip(client, url), fields(url_domain = %extract_domain(url)))]
-async fn fetch_article_content(client: &reqwest::Client, url: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+/// Text to summarize for `article`. Episode pages are mostly a player, so the feed's show notes
+/// win when there are any; a source with a trusted override may opt into the feed's inline
+/// content, and a feed that ships the full post saves the scrape. Otherwise the page is
+/// scraped, falling back to whatever the feed carried and finally the title.
+async fn fetch_article_text(
+    client: &reqwest::Client,
+    robots: &RobotsCache,
+    article: &Article,
+    extraction: Option<&ExtractionOverride>,
+    log: &mut ExtractionLog,
+) -> String {
+    let show_notes = article.media.as_ref().and(article.show_notes.as_deref());
+    let feed_content = extraction
+        .filter(|o| o.prefer_feed_content)
+        .and(article.feed_content.as_deref())
+        .map(|html| {
+            let started = Instant::now();
+            let text = html_to_text(html);
+            log.push(ExtractionRecord::new(&article.url, ExtractionStrategy::FeedContent, html, &text, started.elapsed()));
+            text
+        })
+        .filter(|text| text.chars().count() >= MIN_ARTICLE_CHARS)
+        .map(|text| (text, "Using feed content per extraction override"))
+        .or_else(|| {
+            let content = full_feed_content(article)?;
+            let html = article.feed_content.as_deref().unwrap_or(content);
+            log.push(ExtractionRecord::new(&article.url, ExtractionStrategy::FeedContent, html, content, Duration::ZERO));
+            Some((content.to_string(), "Using full content from the feed"))
+        });
+    match (show_notes, feed_content) {
+        (Some(notes), _) => notes.to_string(),
+        (None, Some((content, reason))) => {
+            info!("{}", reason);
+            content
+        }
+        (None, None) => match fetch_article_content(client, robots, &article.url, extraction, log).await {
+            Ok(content) => content,
+            // Whatever the feed carried beats the title alone
+            Err(e) => match article.content.as_deref().or(article.summary.as_deref()) {
+                Some(text) => {
+                    warn!(error = %e, "Failed to fetch article content, using the feed's text");
+                    text.to_string()
+                }
+                None => {
+                    warn!(error = %e, "Failed to fetch article content, using title only");
+                    format!("Title: {}, URL: {}", article.title, article.url)
+                }
+            },
+        },
+    }
+}
+
+/// The text to summarize in parts of `chunk_tokens` when a written article is too long to
+/// summarize from a prefix without losing its conclusion: over `long_article_tokens` (0
+/// never), up to [`MAX_LONG_ARTICLE_TOKENS`]. `None` when a prefix will do.
+fn long_article_text<'a>(article: &Article, text: &'a str, long_article_tokens: usize, chunk_tokens: usize) -> Option<&'a str> {
+    if article.media.is_some() || long_article_tokens == 0 || estimate_tokens(text) <= long_article_tokens {
+        return None;
+    }
+    let text = truncate_to_tokens(text, MAX_LONG_ARTICLE_TOKENS);
+    info!(title = %article.title, estimated_tokens = estimate_tokens(text), chunk_tokens, "Long article, summarizing in parts");
+    Some(text)
+}
+
+/// The summary prompt over the partial summaries of `article`, for [`summarize_article`].
+fn summary_over_parts<'a>(templates: &'a PromptTemplates, config: &'a prompts::PromptConfig, article: &'a Article) -> impl FnOnce(&str) -> String + 'a {
+    move |partials| {
+        config.summary_prompt(templates, article.source_label(), &article.title, partials).unwrap_or_else(|e| {
+            warn!(error = %e, "Failed to render the summary prompt over the parts, using the default");
+            combine_prompt(partials)
+        })
+    }
+}
+
+/// Summary by `llm` from `prompt`, or, for a [`long_article_text`], from its parts combined
+/// with `combine`.
+async fn summarize_article(
+    llm: &LlmClient,
+    prompt: String,
+    long_text: Option<&str>,
+    chunk_tokens: usize,
+    options: &LlmOptions,
+    combine: impl FnOnce(&str) -> String,
+) -> Result<LlmResponse, LlmError> {
+    match long_text {
+        Some(text) => llm.summarize_long_text(text, chunk_tokens, options, combine).await,
+        None => llm.generate_with(prompt, options).await,
+    }
+}
+
+/// `text` truncated at a word boundary to `budget` tokens, with a note when `article` is an
+/// episode rather than a written article.
+fn summary_input(article: &Article, text: &str, budget: usize) -> String {
+    let truncated = truncate_to_tokens(text, budget);
+    match &article.media {
+        Some(media) => format!(
+            "Note: this is a podcast/video episode ({}), not a written article. The text below is its show notes or episode page; summarize what the episode covers.\n\n{}",
+            media.mime_type, truncated
+        ),
+        None => truncated.to_string(),
+    }
+}
+
+/// Write `state/source_health.json` back. Failures only log, as for the seen-URL index.
+async fn save_health(store: &GcsStore, health: &SourceHealthMap) {
+    if let Err(e) = save_source_health(store, health).await {
+        warn!(error = %e, "Failed to save source health");
+    }
+}
+
+/// Per-source counts for this run: candidate articles, shortlist appearances (two-phase
+/// selection only) and the final pick.
+fn source_contributions(
+    articles: &[Article],
+    shortlist: &[usize],
+    selected: usize,
+) -> std::collections::HashMap<String, RunContribution> {
+    let mut contributions: std::collections::HashMap<String, RunContribution> = std::collections::HashMap::new();
+    for article in articles {
+        contributions.entry(article.source.clone()).or_default().articles += 1;
+    }
+    if shortlist.len() > 1 {
+        for &idx in shortlist {
+            contributions.entry(articles[idx].source.clone()).or_default().shortlisted += 1;
+        }
+    }
+    contributions.entry(articles[selected].source.clone()).or_default().selected += 1;
+    contributions
+}
+
+/// Fetch a page and extract its article text, using the domain's extraction override
+/// when one is configured and falling back to readability otherwise.
+/// Pages robots.txt disallows are not requested.
+/// Every extraction attempt that got a page is recorded in `log`.
+#[instrument(skip(client, robots, url, extraction, log), fields(url_domain = %extract_domain(url)))]
+async fn fetch_article_content(
+    client: &reqwest::Client,
+    robots: &RobotsCache,
+    url: &str,
+    extraction: Option<&ExtractionOverride>,
+    log: &mut ExtractionLog,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    if !robots.allows(client, url).await {
+        return Err(format!("robots.txt disallows {}", url).into());
+    }
     let response = client.get(url).send().await?;
     let html_content = response.text().await?;
+    let started = Instant::now();
+
+    if let Some(text) = extraction.and_then(|ov| extract_with_override(&html_content, ov, url)) {
+        if text.chars().count() >= MIN_ARTICLE_CHARS {
+            log.push(ExtractionRecord::new(url, ExtractionStrategy::Override, &html_content, &text, started.elapsed()));
+            return Ok(text);
+        }
+        warn!(chars = text.chars().count(), "Override extraction too short, using generic extraction");
+    }
 
     let parsed_url = url::Url::parse(url)
         .map_err(|e| format!("URL parse error: {:?}", e))?;
 
     let mut reader = Cursor::new(html_content.as_bytes());
-    let product = extractor::extract(&mut reader, &parsed_url)
-        .map_err(|e| format!("Readability extract error: {:?}", e))?;
-
-    let text = product.text;
+    let extracted = extractor::extract(&mut reader, &parsed_url);
+    let text = extracted.as_ref().map(|p| p.text.as_str()).unwrap_or_default();
+    log.push(ExtractionRecord::new(url, ExtractionStrategy::Readability, &html_content, text, started.elapsed()));
+    let text = extracted
+        .map_err(|e| format!("Readability extract error: {:?}", e))?
+        .text;
     if text.chars().count() < MIN_ARTICLE_CHARS {
         return Err(format!(
             "Extracted content too short ({} chars, minimum {}). Page is likely JS-rendered or paywalled.",
@@ -751,72 +1608,276 @@ async fn fetch_article_content(client: &reqwest::Client, url: &str) -> Result<St
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_parse_selection_index_simple() {
-        assert_eq!(parse_selection_index("5"), Some(5));
-        assert_eq!(parse_selection_index("0"), Some(0));
-        assert_eq!(parse_selection_index("42"), Some(42));
+    fn article(source: &str) -> Article {
+        Article::for_test("t", "https://example.com", source)
     }
 
     #[test]
-    fn test_parse_selection_index_with_whitespace() {
-        assert_eq!(parse_selection_index("  3  "), Some(3));
-        assert_eq!(parse_selection_index("\n7\n"), Some(7));
-        assert_eq!(parse_selection_index("\t12"), Some(12));
+    fn test_full_feed_content_needs_more_than_an_excerpt() {
+        let excerpt = Article { content: Some("A short teaser.".to_string()), ..article("Blog") };
+        let full = Article { content: Some("x".repeat(MIN_FEED_CONTENT_CHARS)), ..article("Blog") };
+        assert_eq!(full_feed_content(&article("Blog")), None);
+        assert_eq!(full_feed_content(&excerpt), None);
+        assert_eq!(full_feed_content(&full).map(str::len), Some(MIN_FEED_CONTENT_CHARS));
     }
 
     #[test]
-    fn test_parse_selection_index_with_text() {
-        // Gemini sometimes returns text before/after the number
-        assert_eq!(parse_selection_index("I choose 5"), Some(5));
-        assert_eq!(parse_selection_index("Article 3 is best"), Some(3));
-        assert_eq!(parse_selection_index("The answer is: 7."), Some(7));
+    fn test_long_article_text_only_for_long_written_articles() {
+        let text = "word ".repeat(2_000);
+        let long_tokens = estimate_tokens(&text) - 1;
+        assert_eq!(long_article_text(&article("Blog"), &text, long_tokens, 500), Some(text.as_str()));
+        assert_eq!(long_article_text(&article("Blog"), &text, long_tokens + 1, 500), None);
+        assert_eq!(long_article_text(&article("Blog"), &text, 0, 500), None);
+        let episode = Article {
+            media: Some(fetcher::MediaInfo { url: "https://example.com/ep.mp3".to_string(), mime_type: "audio/mpeg".to_string(), length: None }),
+            ..article("Podcast")
+        };
+        assert_eq!(long_article_text(&episode, &text, long_tokens, 500), None);
+    }
+
+    fn article_page() -> String {
+        let body = "Ring buffers trade memory for predictable latency under load. ".repeat(6);
+        let comments = "This comment section is long and readability tends to prefer it over the post. ".repeat(20);
+        format!(r#"<html><head><title>Queues</title></head><body>
+            <article class="post"><div class="post-body"><p>{body}</p>
+              <div class="related-posts"><p>You may also like these posts</p></div></div></article>
+            <section id="comments"><div class="comment"><p>{comments}</p></div></section>
+            </body></html>"#)
+    }
+
+    async fn serve_page(html: String) -> wiremock::MockServer {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+        use wiremock::matchers::{method, path};
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/post"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(html))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn robots() -> RobotsCache {
+        RobotsCache::new(DEFAULT_USER_AGENT)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_article_content_respects_robots_txt() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/robots.txt"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("User-agent: eng-pulse-agent\nDisallow: /members/\n"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/members/post"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(article_page()))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let mut log = ExtractionLog::default();
+        let url = format!("{}/members/post", server.uri());
+        let err = fetch_article_content(&reqwest::Client::new(), &robots(), &url, None, &mut log).await.unwrap_err();
+        assert!(err.to_string().contains("robots.txt disallows"), "{}", err);
+        assert!(log.records.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_article_content_override_rescues_extraction() {
+        let server = serve_page(article_page()).await;
+        let extraction = ExtractionOverride {
+            content_selector: Some("article .post-body".to_string()),
+            strip_selectors: vec![".related-posts".to_string()],
+            prefer_feed_content: false,
+        };
+        let client = reqwest::Client::new();
+        let mut log = ExtractionLog::default();
+        let text = fetch_article_content(&client, &robots(), &format!("{}/post", server.uri()), Some(&extraction), &mut log).await.unwrap();
+        assert_eq!(log.records[0].strategy, ExtractionStrategy::Override);
+        assert!(text.starts_with("Ring buffers trade memory"));
+        assert!(!text.contains("comment section"));
+        assert!(!text.contains("You may also like"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_article_content_unmatched_override_falls_back() {
+        let server = serve_page(article_page()).await;
+        let extraction = ExtractionOverride {
+            content_selector: Some("div.entry-content".to_string()),
+            ..Default::default()
+        };
+        let client = reqwest::Client::new();
+        let url = format!("{}/post", server.uri());
+        let mut log = ExtractionLog::default();
+        let robots = robots();
+        let with_override = fetch_article_content(&client, &robots, &url, Some(&extraction), &mut log).await.unwrap();
+        let generic = fetch_article_content(&client, &robots, &url, None, &mut log).await.unwrap();
+        assert_eq!(with_override, generic);
+        // Both fell back to readability, and each extraction was recorded once
+        assert_eq!(log.records.len(), 2);
+        assert!(log.records.iter().all(|r| r.strategy == ExtractionStrategy::Readability && r.html_bytes > 0));
     }
 
     #[test]
-    fn test_parse_selection_index_invalid() {
-        assert_eq!(parse_selection_index("no number here"), None);
-        assert_eq!(parse_selection_index(""), None);
-        assert_eq!(parse_selection_index("   "), None);
+    fn test_source_contributions() {
+        let articles = vec![article("A"), article("A"), article("B"), article("C")];
+        let contributions = source_contributions(&articles, &[0, 2], 2);
+        assert_eq!(contributions["A"], RunContribution { articles: 2, shortlisted: 1, selected: 0 });
+        assert_eq!(contributions["B"], RunContribution { articles: 1, shortlisted: 1, selected: 1 });
+        assert_eq!(contributions["C"], RunContribution { articles: 1, shortlisted: 0, selected: 0 });
+
+        // Single-shot selection has no shortlist phase
+        let contributions = source_contributions(&articles, &[3], 3);
+        assert_eq!(contributions["C"], RunContribution { articles: 1, shortlisted: 0, selected: 1 });
     }
 
     #[test]
-    fn test_parse_selection_index_first_number_only() {
-        // Should only get the first contiguous digit sequence
-        assert_eq!(parse_selection_index("3 and 5"), Some(3));
-        assert_eq!(parse_selection_index("article 2, not 7"), Some(2));
+    #[serial_test::serial]
+    fn test_article_token_budget() {
+        assert_eq!(article_token_budget(&[]), MAX_ARTICLE_TOKENS);
+        assert_eq!(article_token_budget(&[LlmProvider::Gemini, LlmProvider::Claude]), MAX_ARTICLE_TOKENS);
+
+        // A small local model shrinks the budget for everyone
+        std::env::set_var("OPENAI_CONTEXT_TOKENS", "8192");
+        let with_local = article_token_budget(&[LlmProvider::Gemini, LlmProvider::OpenAI]);
+        std::env::remove_var("OPENAI_CONTEXT_TOKENS");
+        assert_eq!(with_local, 4096);
     }
 
     #[test]
-    fn test_parse_selection_index_large_number() {
-        assert_eq!(parse_selection_index("99999"), Some(99999));
-        assert_eq!(parse_selection_index("1000000"), Some(1000000));
+    #[serial_test::serial]
+    fn test_long_article_tokens_from_env() {
+        for (value, expected) in [(None, MAX_ARTICLE_TOKENS), (Some("40000"), 40_000), (Some("0"), 0), (Some("lots"), MAX_ARTICLE_TOKENS)] {
+            match value {
+                Some(value) => std::env::set_var(LONG_ARTICLE_TOKENS_ENV_VAR, value),
+                None => std::env::remove_var(LONG_ARTICLE_TOKENS_ENV_VAR),
+            }
+            assert_eq!(long_article_tokens_from_env(), expected, "{:?}", value);
+        }
+        std::env::remove_var(LONG_ARTICLE_TOKENS_ENV_VAR);
     }
 
     #[test]
-    fn test_parse_selection_index_zero() {
-        assert_eq!(parse_selection_index("0"), Some(0));
-        assert_eq!(parse_selection_index("The index is 0."), Some(0));
+    fn test_shortlist_reply_parsing() {
+        let reply: ShortlistReply = llm_client::parse_llm_json("```json\n{\"indices\": [3, 7, 12, 25, 41]}\n```").unwrap();
+        assert_eq!(reply.valid_indices(50), vec![3, 7, 12, 25, 41]);
+
+        // Out-of-range indices are dropped
+        let reply: ShortlistReply = llm_client::parse_llm_json(r#"{"indices": [3, 70, 12]}"#).unwrap();
+        assert_eq!(reply.valid_indices(20), vec![3, 12]);
+
+        assert!(llm_client::parse_llm_json::<ShortlistReply>("3,7,12,25,41").is_err());
     }
 
     #[test]
-    fn test_parse_selection_index_decimal_takes_integer_part() {
-        // "3.5" — should parse "3" as the first contiguous digit sequence
-        // since "." breaks the digit run
-        let result = parse_selection_index("3.5");
-        assert_eq!(result, Some(3));
+    fn test_pick_reply_parsing() {
+        let reply: PickReply = llm_client::parse_llm_json("The best is:\n{\"index\": 0}").unwrap();
+        assert_eq!(reply, PickReply { index: 0, reason: None });
+        assert!(llm_client::parse_llm_json::<PickReply>(r#"{"index": -5}"#).is_err());
+        assert!(llm_client::parse_llm_json::<PickReply>("no number here").is_err());
+
+        // An index written as text still counts
+        let reply: PickReply = llm_client::parse_llm_json(r#"{"index": "Article #3"}"#).unwrap();
+        assert_eq!(reply, PickReply { index: 3, reason: None });
+        assert!(llm_client::parse_llm_json::<PickReply>(r#"{"index": "the first one"}"#).is_err());
     }
 
     #[test]
-    fn test_parse_selection_index_negative_ignored() {
-        // "-5" — the minus sign is not a digit, so it should find "5"
-        let result = parse_selection_index("-5");
-        assert_eq!(result, Some(5));
+    fn test_pick_reply_reason() {
+        let reply: PickReply = llm_client::parse_llm_json(r#"{"index": 4, "reason": " Concrete latency numbers "}"#).unwrap();
+        assert_eq!(reply, PickReply { index: 4, reason: Some("Concrete latency numbers".to_string()) });
+        let reply: PickReply = llm_client::parse_llm_json(r#"{"index": 4, "reason": ""}"#).unwrap();
+        assert_eq!(reply.reason, None);
+        // A bare index is valid JSON too
+        assert_eq!(llm_client::parse_llm_json::<PickReply>("7").unwrap(), PickReply { index: 7, reason: None });
+        assert_eq!(llm_client::parse_llm_json::<PickReply>(r#""Article #2""#).unwrap(), PickReply { index: 2, reason: None });
     }
 
     #[test]
-    fn test_parse_selection_index_only_special_chars() {
-        assert_eq!(parse_selection_index("!@#$%^&*()"), None);
-        assert_eq!(parse_selection_index("..."), None);
+    fn test_pick_reply_falls_back_to_the_first_integer() {
+        assert_eq!(
+            PickReply::parse(r#"{"index": 1, "reason": "Deep dive"}"#),
+            Some(PickReply { index: 1, reason: Some("Deep dive".to_string()) })
+        );
+        assert_eq!(PickReply::parse("Article 12 is the strongest pick"), Some(PickReply { index: 12, reason: None }));
+        assert_eq!(PickReply::parse(r#"{"index": 3, "reason": "cut off"#), Some(PickReply { index: 3, reason: None }));
+        assert_eq!(PickReply::parse("none of these"), None);
+    }
+
+    #[tokio::test]
+    async fn test_single_vote_pick_reads_a_prose_reply() {
+        use wiremock::matchers::{method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path_regex(":generateContent$"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "candidates": [{ "content": { "parts": [{ "text": "Article 12 is the strongest pick" }] } }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let llm = LlmClient::builder(LlmProvider::Gemini).api_key("key").base_url(server.uri()).build().unwrap();
+
+        let reply = call_pick(&llm, "pick one".to_string(), &LlmOptions::default(), 1, &mut CostTracker::default()).await.unwrap();
+        assert_eq!(reply, Some(PickReply { index: 12, reason: None }));
+    }
+
+    #[tokio::test]
+    async fn test_softened_summary_that_is_blocked_falls_back_to_the_title() {
+        use wiremock::matchers::{body_string_contains, method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path_regex(":generateContent$")).and(body_string_contains("softened"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "promptFeedback": { "blockReason": "SAFETY" } })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST")).and(path_regex(":generateContent$")).and(body_string_contains("title only"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "candidates": [{ "content": { "parts": [{ "text": "From the title" }] } }] })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let llm = LlmClient::builder(LlmProvider::Gemini).api_key("key").base_url(server.uri()).build().unwrap();
+
+        let refused = Err(LlmError::Refused { provider: LlmProvider::Gemini, reason: "declined".to_string() });
+        let summary = retry_summary(&llm, refused, "softened".to_string(), "title only".to_string(), &LlmOptions::default()).await;
+        assert_eq!(summary.unwrap().text, "From the title");
+    }
+
+    #[tokio::test]
+    async fn test_health_check_retries_and_drops_unhealthy_providers() {
+        use wiremock::matchers::{method, path, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        // Claude is briefly unavailable, Gemini rejects its key
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "content": [{ "text": "pong" }] })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET")).and(path_regex("^/v1beta/models/"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+        let client = |provider| LlmClient::builder(provider).api_key("key").base_url(server.uri()).build().unwrap();
+
+        let healthy = check_provider_health(vec![client(LlmProvider::Gemini), client(LlmProvider::Claude)]).await.unwrap();
+        let providers: Vec<LlmProvider> = healthy.iter().map(LlmClient::provider).collect();
+        assert_eq!(providers, [LlmProvider::Claude]);
+
+        let err = check_provider_health(vec![client(LlmProvider::Gemini)]).await.unwrap_err();
+        assert!(err.to_string().contains("Gemini: API key rejected"), "{}", err);
     }
 }
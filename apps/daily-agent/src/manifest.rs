@@ -12,6 +12,16 @@ pub(crate) fn gcs_object_path<'a>(public_url: &'a str, bucket: &str) -> &'a str
 }
 
 // --- Manifest Struct ---
+/// An article of a multi-article digest, as listed in its manifest entry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct DigestArticle {
+    pub(crate) title: String,
+    pub(crate) original_url: String,
+    /// The feed's link, when it redirected to `original_url`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) feed_url: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub(crate) struct ManifestEntry {
     pub(crate) date: String,
@@ -20,6 +30,10 @@ pub(crate) struct ManifestEntry {
     pub(crate) summary_snippet: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) original_url: Option<String>,
+    /// The feed's link to the article, when it redirected to `original_url`; kept so the
+    /// same link in a later feed is recognized as a repeat
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) feed_url: Option<String>,
     /// Which model generated the summary
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) model: Option<String>,
@@ -35,6 +49,31 @@ pub(crate) struct ManifestEntry {
     /// Article format identifier (e.g. "insight-brief-v3" for V3; null for legacy markdown)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) format: Option<String>,
+    /// Name of the source the original article came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) source: Option<String>,
+    /// Set when the article was picked by the exploration path instead of the model's top pick
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) exploratory: Option<bool>,
+    /// ID of the agent run that wrote the entry, as sent in `x-request-id`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) run_id: Option<String>,
+    /// Set on summaries compared under `COMPARE_PROVIDERS`: whether the judge picked this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) judged_best: Option<bool>,
+    /// Byline of the original article, when its feed had one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) author: Option<String>,
+    /// Categories the original article's feed assigned
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) categories: Vec<String>,
+    /// Every article of a multi-article digest, lead article first; empty for single-article
+    /// summaries, whose `title` and `original_url` name the article
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) articles: Vec<DigestArticle>,
+    /// The selection model's one-line reason for picking the article
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) selection_reason: Option<String>,
 }
 
 impl ManifestEntry {
@@ -57,11 +96,20 @@ mod tests {
             title: "Test".to_string(),
             summary_snippet: "...".to_string(),
             original_url: None,
+            feed_url: None,
             model: model.map(|s| s.to_string()),
             selected_by: None,
             prompt_version: prompt_version.map(|s| s.to_string()),
             eval_score: None,
             format: None,
+            source: None,
+            exploratory: None,
+            run_id: None,
+            judged_best: None,
+            author: None,
+            categories: Vec::new(),
+            articles: Vec::new(),
+            selection_reason: None,
         }
     }
 
@@ -95,6 +143,55 @@ mod tests {
         assert_eq!(entry.summary_id(), "v1-unknown");
     }
 
+    #[test]
+    fn test_author_and_categories_are_optional_in_json() {
+        let entry = make_entry("summaries/gemini/2026-03-20.md", None, None);
+        let json = serde_json::to_value(&entry).unwrap();
+        assert!(json.get("author").is_none() && json.get("categories").is_none());
+        let old: ManifestEntry = serde_json::from_value(json).unwrap();
+        assert!(old.author.is_none() && old.categories.is_empty());
+
+        let entry = ManifestEntry { author: Some("Julia Evans".to_string()), categories: vec!["networking".to_string()], ..entry };
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["author"], "Julia Evans");
+        assert_eq!(json["categories"], serde_json::json!(["networking"]));
+    }
+
+    #[test]
+    fn test_digest_articles_round_trip() {
+        // Single-article entries, and entries written before digests, have no list
+        let entry = make_entry("summaries/gemini/2026-03-20.md", None, None);
+        let json = serde_json::to_value(&entry).unwrap();
+        assert!(json.get("articles").is_none());
+        let old: ManifestEntry = serde_json::from_str(r#"{"date": "2026-03-20", "url": "summaries/gemini/2026-03-20.md", "title": "Test", "summary_snippet": "...", "original_url": "https://blog.example.com/test"}"#).unwrap();
+        assert!(old.articles.is_empty());
+
+        let articles = vec![
+            DigestArticle { title: "Queue Design".to_string(), original_url: "https://blog.example.com/queues".to_string(), feed_url: None },
+            DigestArticle { title: "Build Caches".to_string(), original_url: "https://ci.example.com/caches".to_string(), feed_url: None },
+        ];
+        let entry = ManifestEntry { articles: articles.clone(), ..entry };
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["articles"][1], serde_json::json!({"title": "Build Caches", "original_url": "https://ci.example.com/caches"}));
+        let parsed: ManifestEntry = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.articles, articles);
+    }
+
+    #[test]
+    fn test_selection_reason_round_trip() {
+        let entry = make_entry("summaries/gemini/2026-03-20.md", None, None);
+        let json = serde_json::to_value(&entry).unwrap();
+        assert!(json.get("selection_reason").is_none());
+        let old: ManifestEntry = serde_json::from_value(json).unwrap();
+        assert!(old.selection_reason.is_none());
+
+        let entry = ManifestEntry { selection_reason: Some("Concrete latency numbers from production".to_string()), ..entry };
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["selection_reason"], "Concrete latency numbers from production");
+        let parsed: ManifestEntry = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.selection_reason.as_deref(), Some("Concrete latency numbers from production"));
+    }
+
     #[test]
     fn test_gcs_public_url() {
         assert_eq!(
@@ -0,0 +1,216 @@
+//! Newsletter sources (`"newsletter": true`): each issue is a wrapper post linking to many
+//! articles, so the issue is split into one candidate per linked article instead of being
+//! summarized itself.
+
+use std::collections::HashSet;
+
+use llm_client::{extract_domain, fetch_bytes, RetryPolicy};
+use select::document::Document;
+use select::predicate::Name;
+use tracing::{debug, warn};
+use url::Url;
+
+use crate::fetcher::Article;
+
+/// Linked articles taken from one issue, in issue order (newsletters lead with their picks).
+pub(crate) const MAX_ITEMS_PER_ISSUE: usize = 8;
+/// Anchor texts shorter than this ("here", "llogiq", "benchmarks") aren't article titles.
+const MIN_LINK_TEXT_CHARS: usize = 12;
+
+/// Domains whose links are never articles: social media, video, code hosting, donations.
+const BLOCKED_LINK_DOMAINS: [&str; 18] = [
+    "twitter.com", "x.com", "bsky.app", "mastodon.social", "facebook.com", "linkedin.com",
+    "instagram.com", "reddit.com", "news.ycombinator.com", "youtube.com", "youtu.be",
+    "github.com", "gitlab.com", "discord.gg", "discord.com", "patreon.com",
+    "opencollective.com", "t.me",
+];
+/// Path fragments of index, account and feed pages.
+const NON_ARTICLE_PATHS: [&str; 10] = [
+    "/tag/", "/tags/", "/category/", "/author/", "/subscribe", "/unsubscribe", "/login", "/signup", "/jobs", "/careers",
+];
+/// Links to files rather than pages.
+const NON_ARTICLE_EXTENSIONS: [&str; 11] = [
+    ".pdf", ".png", ".jpg", ".jpeg", ".gif", ".svg", ".webp", ".mp3", ".mp4", ".zip", ".xml",
+];
+
+/// Source label for an article found in a newsletter issue, e.g. `fasterthanli.me via This Week in Rust`.
+pub(crate) fn newsletter_attribution(article_url: &str, newsletter: &str) -> String {
+    format!("{} via {}", extract_domain(article_url), newsletter)
+}
+
+fn is_blocked_domain(host: &str) -> bool {
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    BLOCKED_LINK_DOMAINS.iter().any(|d| host == *d || host.ends_with(&format!(".{}", d)))
+}
+
+/// Resolve and vet one anchor. Returns the normalized article URL, or `None` for
+/// self-links, blocked domains and obvious non-article links.
+fn article_link(href: &str, issue_url: &Url) -> Option<Url> {
+    let mut url = issue_url.join(href.trim()).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    url.set_fragment(None);
+    let host = url.host_str()?.to_string();
+    if issue_url.host_str() == Some(host.as_str()) || is_blocked_domain(&host) {
+        return None;
+    }
+    let path = url.path().to_ascii_lowercase();
+    if path == "/" || NON_ARTICLE_PATHS.iter().any(|p| path.contains(p)) || NON_ARTICLE_EXTENSIONS.iter().any(|e| path.ends_with(e)) {
+        return None;
+    }
+    Some(url)
+}
+
+/// Outbound article links of an issue as `(title, url)`, deduplicated, in document order.
+pub(crate) fn extract_issue_links(html: &str, issue_url: &str) -> Vec<(String, String)> {
+    let Ok(issue_url) = Url::parse(issue_url) else {
+        return Vec::new();
+    };
+    let document = Document::from(html);
+    let mut seen = HashSet::new();
+    let mut links = Vec::new();
+    for anchor in document.find(Name("a")) {
+        let Some(url) = anchor.attr("href").and_then(|href| article_link(href, &issue_url)) else { continue };
+        let title = anchor.text().split_whitespace().collect::<Vec<_>>().join(" ");
+        if title.chars().count() < MIN_LINK_TEXT_CHARS {
+            continue;
+        }
+        if seen.insert(url.to_string()) {
+            links.push((title, url.to_string()));
+        }
+    }
+    links
+}
+
+/// Candidate articles for the links of one issue, capped at [`MAX_ITEMS_PER_ISSUE`].
+/// They carry the issue's published date and are attributed to the newsletter.
+pub(crate) fn split_issue(issue: &Article, html: &str, newsletter: &str) -> Vec<Article> {
+    extract_issue_links(html, &issue.url)
+        .into_iter()
+        .take(MAX_ITEMS_PER_ISSUE)
+        .map(|(title, url)| Article {
+            title,
+            source: newsletter.to_string(),
+            via: Some(newsletter_attribution(&url, newsletter)),
+            url,
+            published_at: issue.published_at,
+            media: None,
+            show_notes: None,
+            feed_content: None,
+            summary: None,
+            content: None,
+            score: None,
+            rank: None,
+            author: None,
+            categories: Vec::new(),
+            undated: false,
+            future_dated: false,
+            language: None,
+        })
+        .collect()
+}
+
+/// Replace newsletter issues with the articles they link to. Uses the feed's inline
+/// content when present, otherwise fetches the issue page. Issues without usable
+/// links are kept as they are.
+pub(crate) async fn expand_issues(client: &reqwest::Client, issues: Vec<Article>, newsletter: &str) -> Vec<Article> {
+    let mut articles = Vec::new();
+    for issue in issues {
+        let html = match &issue.feed_content {
+            Some(content) => content.clone(),
+            None => match fetch_bytes(client, &issue.url, &RetryPolicy::FAST_LOCAL).await {
+                Ok(body) => String::from_utf8_lossy(&body).into_owned(),
+                Err(e) => {
+                    warn!(issue = %issue.url, error = %e, "Failed to fetch newsletter issue, keeping it as one article");
+                    articles.push(issue);
+                    continue;
+                }
+            },
+        };
+        let items = split_issue(&issue, &html, newsletter);
+        if items.is_empty() {
+            debug!(issue = %issue.url, "No article links in newsletter issue, keeping it as one article");
+            articles.push(issue);
+        } else {
+            debug!(issue = %issue.url, items = items.len(), "Split newsletter issue");
+            articles.extend(items);
+        }
+    }
+    articles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    const ISSUE_HTML: &str = include_str!("../tests/fixtures/this_week_in_rust.html");
+    const ISSUE_URL: &str = "https://this-week-in-rust.org/blog/2026/04/02/this-week-in-rust-594/";
+
+    fn issue() -> Article {
+        Article {
+            published_at: Utc.with_ymd_and_hms(2026, 4, 2, 0, 0, 0).unwrap(),
+            feed_content: Some(ISSUE_HTML.to_string()),
+            ..Article::for_test("This Week in Rust 594", ISSUE_URL, "This Week in Rust")
+        }
+    }
+
+    #[test]
+    fn test_extract_issue_links_from_newsletter() {
+        let links = extract_issue_links(ISSUE_HTML, ISSUE_URL);
+        let titles: Vec<&str> = links.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(titles, vec![
+            "Announcing Rust 1.95.0",
+            "Project goals update — March 2026",
+            "This Week in Bevy",
+            "Pinned places",
+            "The case for sans-io",
+            "Pointer provenance, one year later",
+            "Lock-free ring buffers from first principles",
+            "Building a faster DNS resolver in Rust",
+            "Tokio and io_uring: where we are",
+        ]);
+        // Fragments are dropped, so the duplicate "#benchmarks" link collapses
+        assert_eq!(links[1].1, "https://blog.rust-lang.org/inside-rust/2026/04/01/project-goals-update.html");
+        assert!(links.iter().all(|(_, url)| !url.contains("github.com") && !url.contains("this-week-in-rust.org")));
+    }
+
+    #[test]
+    fn test_split_issue_caps_and_attributes() {
+        let items = split_issue(&issue(), ISSUE_HTML, "This Week in Rust");
+        assert_eq!(items.len(), MAX_ITEMS_PER_ISSUE);
+        let sans_io = items.iter().find(|a| a.title == "The case for sans-io").unwrap();
+        assert_eq!(sans_io.url, "https://fasterthanli.me/articles/the-case-for-sans-io");
+        assert_eq!(sans_io.source, "This Week in Rust");
+        assert_eq!(sans_io.via.as_deref(), Some("fasterthanli.me via This Week in Rust"));
+        assert_eq!(sans_io.source_label(), "fasterthanli.me via This Week in Rust");
+        assert!(items.iter().all(|a| a.published_at == issue().published_at));
+    }
+
+    #[test]
+    fn test_article_link_filters() {
+        let issue_url = Url::parse(ISSUE_URL).unwrap();
+        assert!(article_link("mailto:editors@example.com", &issue_url).is_none());
+        assert!(article_link("/blog/archives/index.html", &issue_url).is_none());
+        assert!(article_link("https://www.reddit.com/r/rust/", &issue_url).is_none());
+        assert!(article_link("https://old.reddit.com/r/rust/comments/x", &issue_url).is_none());
+        assert!(article_link("https://example.com/", &issue_url).is_none());
+        assert!(article_link("https://example.com/tag/rust/", &issue_url).is_none());
+        assert!(article_link("https://example.com/talk.pdf", &issue_url).is_none());
+        assert_eq!(
+            article_link("https://example.com/post#intro", &issue_url).map(|u| u.to_string()),
+            Some("https://example.com/post".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expand_issues_keeps_issue_without_links() {
+        let mut bare = issue();
+        bare.feed_content = Some("<p>No links this week.</p>".to_string());
+        let client = reqwest::Client::new();
+        let articles = expand_issues(&client, vec![bare], "This Week in Rust").await;
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].url, ISSUE_URL);
+    }
+}
@@ -1,3 +1,5 @@
+use llm_client::{PromptError, PromptTemplates};
+
 /// Prepend optional context blocks to a base prompt.
 fn inject_context(base: String, selection_context: Option<&str>, recent_picks: Option<&str>) -> String {
     let mut prompt = base;
@@ -10,90 +12,51 @@ fn inject_context(base: String, selection_context: Option<&str>, recent_picks: O
     prompt
 }
 
-/// Prompt configuration for article selection and summarization.
-/// V1 = production (current prompts). V2 = beta (persona-driven, structured). V3 = beta (persona-driven selection + structured JSON summary).
-pub enum PromptConfig {
-    V1,
-    V2,
-    V3,
-}
+/// Preamble for a prompt the model refused: the article is published and the summary is for
+/// engineers, so sensitive subject matter (security flaws, outages, layoffs) is fair to cover.
+const SOFTENING_PREAMBLE: &str = "Context: the article below is publicly published technical writing, and this is a factual summary for a professional software engineering newsletter. Describe sensitive topics it discusses (such as security vulnerabilities, incidents or policy) neutrally and at a high level, without operational detail. Do not reproduce harmful instructions.";
 
-impl PromptConfig {
-    /// Version string for manifest tagging.
-    pub fn version(&self) -> &'static str {
-        match self {
-            Self::V1 => "v1",
-            Self::V2 => "v2",
-            Self::V3 => "v3",
-        }
-    }
-
-    /// Build the article selection prompt (headline-only, single pick).
-    pub fn selection_prompt(&self, articles_text: &str) -> String {
-        match self {
-            Self::V1 => self.v1_selection_prompt(articles_text),
-            Self::V2 => self.v2_selection_prompt(articles_text),
-            Self::V3 => self.v2_selection_prompt(articles_text),
-        }
-    }
-
-    /// Build the shortlist prompt (pick top 5 candidates from headlines).
-    pub fn shortlist_prompt(&self, articles_text: &str) -> String {
-        match self {
-            Self::V1 => self.v1_shortlist_prompt(articles_text),
-            Self::V2 => self.v2_shortlist_prompt(articles_text),
-            Self::V3 => self.v2_shortlist_prompt(articles_text),
-        }
-    }
+/// `prompt` with [`SOFTENING_PREAMBLE`], to retry a request the model refused.
+pub fn soften_prompt(prompt: &str) -> String {
+    format!("{}\n\n{}", SOFTENING_PREAMBLE, prompt)
+}
 
-    /// Build the final selection prompt (pick 1 from shortlist with content snippets).
-    pub fn final_selection_prompt(&self, candidates_text: &str) -> String {
-        match self {
-            Self::V1 => self.v1_final_selection_prompt(candidates_text),
-            Self::V2 => self.v2_final_selection_prompt(candidates_text),
-            Self::V3 => self.v2_final_selection_prompt(candidates_text),
-        }
-    }
+/// Prompt asking for `summary`, written in `language` (an ISO 639-1 code), to be rewritten in
+/// English with its Markdown structure intact.
+pub fn english_rewrite_prompt(summary: &str, language: &str) -> String {
+    format!(
+        "The following newsletter summary was written in the wrong language (detected: {}). Rewrite it in English, keeping its meaning, Markdown structure, links and code unchanged. Reply ONLY with the rewritten summary.\n\n{}",
+        language, summary
+    )
+}
 
-    /// Build the article summarization prompt.
-    pub fn summary_prompt(&self, source: &str, title: &str, content: &str) -> String {
-        match self {
-            Self::V1 => self.v1_summary_prompt(source, title, content),
-            Self::V2 => self.v2_summary_prompt(source, title, content),
-            Self::V3 => self.v3_summary_prompt(source, title, content),
-        }
+/// The `{articles}` list: `headlines` numbered from 0, one per line. When they are a sample of
+/// `collected` articles, a last line says so, so the model knows the list was capped.
+pub fn article_list<'a>(headlines: impl IntoIterator<Item = &'a str>, collected: usize) -> String {
+    let mut text = String::new();
+    let mut shown = 0;
+    for (i, headline) in headlines.into_iter().enumerate() {
+        text.push_str(&format!("{}. {}\n", i, headline));
+        shown += 1;
     }
-
-    /// Build shortlist prompt with optional selection feedback and recent picks context.
-    pub fn shortlist_prompt_with_context(
-        &self,
-        articles_text: &str,
-        selection_context: Option<&str>,
-        recent_picks: Option<&str>,
-    ) -> String {
-        inject_context(self.shortlist_prompt(articles_text), selection_context, recent_picks)
+    if collected > shown {
+        text.push_str(&format!(
+            "\n(Showing {} of the {} articles collected today, sampled evenly across sources, newest first within each.)\n",
+            shown, collected
+        ));
     }
+    text
+}
 
-    /// Build final selection prompt with optional context.
-    pub fn final_selection_prompt_with_context(
-        &self,
-        candidates_text: &str,
-        selection_context: Option<&str>,
-        recent_picks: Option<&str>,
-    ) -> String {
-        inject_context(self.final_selection_prompt(candidates_text), selection_context, recent_picks)
-    }
+// --- Templates ---
+//
+// Placeholders: {articles} (numbered headlines, or the digest's summaries for its intro),
+// {candidates} (shortlist with content previews), {count} (articles in a digest), {source},
+// {title} and {content}.
 
-    fn v1_selection_prompt(&self, articles_text: &str) -> String {
-        format!(
-            "You are an expert Software Engineering Editor. Review the following list of article headlines collected today. Select the SINGLE most valuable, educational, and impactful article for a senior software engineer to read. Consider technical depth, novelty, and broad relevance.\n\n{}\n\nReply ONLY with the integer index number of the chosen article (e.g., '3'). Do not add any explanation.",
-            articles_text
-        )
-    }
+const V1_SELECTION: &str = "You are an expert Software Engineering Editor. Review the following list of article headlines collected today. Select the SINGLE most valuable, educational, and impactful article for a senior software engineer to read. Consider technical depth, novelty, and broad relevance.\n\n{articles}\n\nReply ONLY with JSON giving the index of the chosen article and a one-line reason, e.g. {{\"index\": 3, \"reason\": \"Concrete latency numbers from production\"}}. Do not add anything else.";
 
-    fn v2_selection_prompt(&self, articles_text: &str) -> String {
-        format!(
-            r#"You are curating a daily technical digest for this reader:
+const V2_SELECTION: &str = r#"You are curating a daily technical digest for this reader:
 
 Engineering leader building developer platforms at a hedge fund in London. Systems programmer (C++/Rust) with 20 years across low-latency trading, storage systems, and developer tooling.
 
@@ -113,23 +76,13 @@ When criteria conflict, prefer actionability over novelty, and depth over breadt
 
 Avoid: product announcements, vendor marketing, beginner tutorials, pure news without insight.
 
-{}
+{articles}
 
-Reply ONLY with the integer index number (e.g., '3'). No explanation."#,
-            articles_text
-        )
-    }
+Reply ONLY with JSON giving the chosen index and a one-line reason, e.g. {{"index": 3, "reason": "Concrete latency numbers from production"}}. Nothing else."#;
 
-    fn v1_shortlist_prompt(&self, articles_text: &str) -> String {
-        format!(
-            "You are an expert Software Engineering Editor. From the following headlines, shortlist the 5 most promising articles for a senior software engineer. Consider technical depth, novelty, and educational value.\n\n{}\n\nReply ONLY with 5 comma-separated index numbers (e.g., '3,7,12,25,41'). No explanation.",
-            articles_text
-        )
-    }
+const V1_SHORTLIST: &str = "You are an expert Software Engineering Editor. From the following headlines, shortlist the 5 most promising articles for a senior software engineer. Consider technical depth, novelty, and educational value.\n\n{articles}\n\nReply ONLY with JSON listing 5 indices, e.g. {{\"indices\": [3, 7, 12, 25, 41]}}. No explanation.";
 
-    fn v2_shortlist_prompt(&self, articles_text: &str) -> String {
-        format!(
-            r#"You are curating a daily technical digest for this reader:
+const V2_SHORTLIST: &str = r#"You are curating a daily technical digest for this reader:
 
 Engineering leader building developer platforms at a hedge fund in London. Systems programmer (C++/Rust) with 20 years across low-latency trading, storage systems, and developer tooling.
 
@@ -147,46 +100,47 @@ From today's articles, shortlist the 5 most promising candidates. Prioritize:
 
 Avoid: product announcements, vendor marketing, beginner tutorials, pure news without insight.
 
-{}
+{articles}
 
-Reply ONLY with 5 comma-separated index numbers (e.g., '3,7,12,25,41'). No explanation."#,
-            articles_text
-        )
-    }
+Reply ONLY with JSON listing 5 indices, e.g. {{"indices": [3, 7, 12, 25, 41]}}. No explanation."#;
 
-    fn v1_final_selection_prompt(&self, candidates_text: &str) -> String {
-        format!(
-            "You are an expert Software Engineering Editor. Below are 5 candidate articles with content previews. Select the SINGLE best article — the one with the most substantive, technically deep content (not just an appealing headline).\n\n{}\n\nReply ONLY with the index number of the chosen article (e.g., '3'). No explanation.",
-            candidates_text
-        )
-    }
+const V1_FINAL_SELECTION: &str = "You are an expert Software Engineering Editor. Below are 5 candidate articles with content previews. Select the SINGLE best article — the one with the most substantive, technically deep content (not just an appealing headline).\n\n{candidates}\n\nReply ONLY with JSON giving the index of the chosen article and a one-line reason, e.g. {{\"index\": 3, \"reason\": \"Concrete latency numbers from production\"}}. Nothing else.";
 
-    fn v2_final_selection_prompt(&self, candidates_text: &str) -> String {
-        format!(
-            r#"You are making the final pick for a daily technical digest. The reader is a senior engineering leader at a hedge fund (C++/Rust, low-latency, AI tooling).
+const V2_FINAL_SELECTION: &str = r#"You are making the final pick for a daily technical digest. The reader is a senior engineering leader at a hedge fund (C++/Rust, low-latency, AI tooling).
 
 Below are 5 candidate articles with content previews. Now that you can see the actual content, select the SINGLE best one. Look for:
 - Substantive technical depth (not just a catchy headline)
 - Actionable insight, not surface-level reporting
 - Content density — every paragraph teaches something
 
-{}
+{candidates}
 
-Reply ONLY with the index number (e.g., '3'). No explanation."#,
-            candidates_text
-        )
-    }
+Reply ONLY with JSON giving the chosen index and a one-line reason, e.g. {{"index": 3, "reason": "Concrete latency numbers from production"}}. Nothing else."#;
 
-    fn v1_summary_prompt(&self, source: &str, title: &str, content: &str) -> String {
-        format!(
-            "Please summarize the following software engineering article in a compact and educational format. Focus on key takeaways, core concepts, and why it matters to a software engineer. Ignore any promotional or fluff content.\n\nArticle Source: {}\nTitle: {}\nContent: {}",
-            source, title, content
-        )
-    }
+const V1_DIGEST_SELECTION: &str = "You are an expert Software Engineering Editor. Below are candidate articles with content previews. Select the {count} best articles for today's briefing — the ones with the most substantive, technically deep content (not just an appealing headline), best first. Prefer a varied set over several takes on one story.\n\n{candidates}\n\nReply ONLY with JSON listing {count} picks, each with the article's index and a one-line reason, e.g. {{\"picks\": [{{\"index\": 3, \"reason\": \"Concrete latency numbers from production\"}}]}}.";
+
+const V2_DIGEST_SELECTION: &str = r#"You are picking the articles for a daily technical briefing. The reader is a senior engineering leader at a hedge fund (C++/Rust, low-latency, AI tooling).
+
+Below are candidate articles with content previews. Now that you can see the actual content, select the {count} best, best first. Look for:
+- Substantive technical depth (not just a catchy headline)
+- Actionable insight, not surface-level reporting
+- Variety — several angles on the day, not one story told twice
+
+{candidates}
+
+Reply ONLY with JSON listing {count} picks, each with the article's index and a one-line reason, e.g. {{"picks": [{{"index": 3, "reason": "Concrete latency numbers from production"}}]}}."#;
+
+const DIGEST_INTRO: &str = r#"You are writing the opening of today's engineering briefing for a senior engineering leader (C++/Rust, low-latency, developer platforms, AI tooling). Below are the summaries of the articles it covers, in order.
 
-    fn v2_summary_prompt(&self, source: &str, title: &str, content: &str) -> String {
-        format!(
-            r#"Summarize this article for a senior engineering leader who builds developer platforms at a hedge fund (C++/Rust, low-latency, AI tooling). They'll read this on their phone in 2-3 minutes.
+Write 2-4 sentences on why these matter today: the common thread, if there is one, and which one to read first. Don't summarize each article again, don't use headings, and don't open with "Today's briefing".
+
+{articles}
+
+Reply ONLY with the intro paragraph."#;
+
+const V1_SUMMARY: &str = "Please summarize the following software engineering article in a compact and educational format. Focus on key takeaways, core concepts, and why it matters to a software engineer. Ignore any promotional or fluff content.\n\nArticle Source: {source}\nTitle: {title}\nContent: {content}";
+
+const V2_SUMMARY: &str = r#"Summarize this article for a senior engineering leader who builds developer platforms at a hedge fund (C++/Rust, low-latency, AI tooling). They'll read this on their phone in 2-3 minutes.
 
 Lead with a one-line hook: why this matters to them specifically. Then cover the key insights — use bold lead phrases and bullets for scannability, but match the structure to the content. Some articles warrant 3 bullets; others need 2 paragraphs.
 
@@ -198,16 +152,11 @@ Rules:
 - Be direct and opinionated — state what matters, skip the hedging
 - Ignore promotional content
 
-Article Source: {}
-Title: {}
-Content: {}"#,
-            source, title, content
-        )
-    }
+Article Source: {source}
+Title: {title}
+Content: {content}"#;
 
-    fn v3_summary_prompt(&self, source: &str, title: &str, content: &str) -> String {
-        format!(
-            r#"You are writing an insight brief for a senior engineering leader who builds developer platforms at a hedge fund (C++/Rust, low-latency, AI tooling). They'll read this on their phone in 2-3 minutes.
+const V3_SUMMARY: &str = r#"You are writing an insight brief for a senior engineering leader who builds developer platforms at a hedge fund (C++/Rust, low-latency, AI tooling). They'll read this on their phone in 2-3 minutes.
 
 Extract the single most important insight from this article and structure it as JSON.
 
@@ -231,11 +180,128 @@ Rules:
 - No fluff, no filler transitions, no "in conclusion"
 - Ignore promotional content
 
-Article Source: {}
-Title: {}
-Content: {}"#,
-            source, title, content
-        )
+Article Source: {source}
+Title: {title}
+Content: {content}"#;
+
+/// Built-in templates by name; `config/prompts.json` can override any of them.
+pub const DEFAULT_PROMPTS: &[(&str, &str)] = &[
+    ("daily_selection", V1_SELECTION),
+    ("daily_shortlist", V1_SHORTLIST),
+    ("daily_final_selection", V1_FINAL_SELECTION),
+    ("daily_summary", V1_SUMMARY),
+    ("daily_selection_v2", V2_SELECTION),
+    ("daily_shortlist_v2", V2_SHORTLIST),
+    ("daily_final_selection_v2", V2_FINAL_SELECTION),
+    ("daily_summary_v2", V2_SUMMARY),
+    ("daily_summary_v3", V3_SUMMARY),
+    ("daily_digest_selection", V1_DIGEST_SELECTION),
+    ("daily_digest_selection_v2", V2_DIGEST_SELECTION),
+    ("daily_digest_intro", DIGEST_INTRO),
+];
+
+/// Prompt configuration for article selection and summarization.
+/// V1 = production (current prompts). V2 = beta (persona-driven, structured). V3 = beta (persona-driven selection + structured JSON summary).
+pub enum PromptConfig {
+    V1,
+    V2,
+    V3,
+}
+
+impl PromptConfig {
+    /// Version string for manifest tagging.
+    pub fn version(&self) -> &'static str {
+        match self {
+            Self::V1 => "v1",
+            Self::V2 => "v2",
+            Self::V3 => "v3",
+        }
+    }
+
+    /// Build the article selection prompt (headline-only, single pick).
+    pub fn selection_prompt(&self, templates: &PromptTemplates, articles_text: &str) -> Result<String, PromptError> {
+        let name = match self {
+            Self::V1 => "daily_selection",
+            Self::V2 | Self::V3 => "daily_selection_v2",
+        };
+        templates.render(name, &[("articles", articles_text)])
+    }
+
+    /// Build the shortlist prompt (pick top 5 candidates from headlines).
+    pub fn shortlist_prompt(&self, templates: &PromptTemplates, articles_text: &str) -> Result<String, PromptError> {
+        let name = match self {
+            Self::V1 => "daily_shortlist",
+            Self::V2 | Self::V3 => "daily_shortlist_v2",
+        };
+        templates.render(name, &[("articles", articles_text)])
+    }
+
+    /// Build the final selection prompt (pick 1 from shortlist with content snippets).
+    pub fn final_selection_prompt(&self, templates: &PromptTemplates, candidates_text: &str) -> Result<String, PromptError> {
+        let name = match self {
+            Self::V1 => "daily_final_selection",
+            Self::V2 | Self::V3 => "daily_final_selection_v2",
+        };
+        templates.render(name, &[("candidates", candidates_text)])
+    }
+
+    /// Build the digest selection prompt (pick `count` from shortlist with content snippets,
+    /// each with a reason).
+    pub fn digest_selection_prompt(&self, templates: &PromptTemplates, candidates_text: &str, count: usize) -> Result<String, PromptError> {
+        let name = match self {
+            Self::V1 => "daily_digest_selection",
+            Self::V2 | Self::V3 => "daily_digest_selection_v2",
+        };
+        templates.render(name, &[("candidates", candidates_text), ("count", &count.to_string())])
+    }
+
+    /// Build the digest intro prompt over the summaries of its articles.
+    pub fn digest_intro_prompt(&self, templates: &PromptTemplates, articles_text: &str) -> Result<String, PromptError> {
+        templates.render("daily_digest_intro", &[("articles", articles_text)])
+    }
+
+    /// Build the article summarization prompt.
+    pub fn summary_prompt(&self, templates: &PromptTemplates, source: &str, title: &str, content: &str) -> Result<String, PromptError> {
+        let name = match self {
+            Self::V1 => "daily_summary",
+            Self::V2 => "daily_summary_v2",
+            Self::V3 => "daily_summary_v3",
+        };
+        templates.render(name, &[("source", source), ("title", title), ("content", content)])
+    }
+
+    /// Build shortlist prompt with optional selection feedback and recent picks context.
+    pub fn shortlist_prompt_with_context(
+        &self,
+        templates: &PromptTemplates,
+        articles_text: &str,
+        selection_context: Option<&str>,
+        recent_picks: Option<&str>,
+    ) -> Result<String, PromptError> {
+        Ok(inject_context(self.shortlist_prompt(templates, articles_text)?, selection_context, recent_picks))
+    }
+
+    /// Build final selection prompt with optional context.
+    pub fn final_selection_prompt_with_context(
+        &self,
+        templates: &PromptTemplates,
+        candidates_text: &str,
+        selection_context: Option<&str>,
+        recent_picks: Option<&str>,
+    ) -> Result<String, PromptError> {
+        Ok(inject_context(self.final_selection_prompt(templates, candidates_text)?, selection_context, recent_picks))
+    }
+
+    /// Build digest selection prompt with optional context.
+    pub fn digest_selection_prompt_with_context(
+        &self,
+        templates: &PromptTemplates,
+        candidates_text: &str,
+        count: usize,
+        selection_context: Option<&str>,
+        recent_picks: Option<&str>,
+    ) -> Result<String, PromptError> {
+        Ok(inject_context(self.digest_selection_prompt(templates, candidates_text, count)?, selection_context, recent_picks))
     }
 }
 
@@ -243,16 +309,43 @@ Content: {}"#,
 mod tests {
     use super::*;
 
+    fn templates() -> PromptTemplates {
+        PromptTemplates::new(DEFAULT_PROMPTS)
+    }
+
+    #[test]
+    fn test_soften_prompt_keeps_the_prompt() {
+        let prompt = PromptConfig::V1.summary_prompt(&templates(), "HN", "Exploit Chain", "Body").unwrap();
+        let softened = soften_prompt(&prompt);
+        assert!(softened.starts_with("Context: the article below is publicly published"));
+        assert!(softened.ends_with(&prompt));
+    }
+
+    #[test]
+    fn test_article_list_notes_the_cap() {
+        assert_eq!(article_list(["[Blog] First", "[HN #1] Second"], 2), "0. [Blog] First\n1. [HN #1] Second\n");
+        let capped = article_list(["[Blog] First", "[HN #1] Second"], 250);
+        assert!(capped.starts_with("0. [Blog] First\n1. [HN #1] Second\n\n"));
+        assert!(capped.contains("Showing 2 of the 250 articles collected today"));
+    }
+
+    #[test]
+    fn test_english_rewrite_prompt() {
+        let prompt = english_rewrite_prompt("## Resumen\n\nEl artículo explica...", "es");
+        assert!(prompt.contains("(detected: es)"));
+        assert!(prompt.ends_with("## Resumen\n\nEl artículo explica..."));
+    }
+
     #[test]
     fn test_v1_selection_prompt_contains_articles() {
-        let prompt = PromptConfig::V1.selection_prompt("0. [HN] Test Article");
+        let prompt = PromptConfig::V1.selection_prompt(&templates(), "0. [HN] Test Article").unwrap();
         assert!(prompt.contains("0. [HN] Test Article"));
         assert!(prompt.contains("expert Software Engineering Editor"));
     }
 
     #[test]
     fn test_v2_selection_prompt_contains_persona() {
-        let prompt = PromptConfig::V2.selection_prompt("0. [HN] Test Article");
+        let prompt = PromptConfig::V2.selection_prompt(&templates(), "0. [HN] Test Article").unwrap();
         assert!(prompt.contains("hedge fund"));
         assert!(prompt.contains("prefer actionability over novelty"));
         assert!(prompt.contains("0. [HN] Test Article"));
@@ -260,14 +353,14 @@ mod tests {
 
     #[test]
     fn test_v1_summary_prompt_contains_article() {
-        let prompt = PromptConfig::V1.summary_prompt("HN", "Title", "Content");
+        let prompt = PromptConfig::V1.summary_prompt(&templates(), "HN", "Title", "Content").unwrap();
         assert!(prompt.contains("Article Source: HN"));
         assert!(prompt.contains("Title: Title"));
     }
 
     #[test]
     fn test_v2_summary_prompt_has_persona_and_rules() {
-        let prompt = PromptConfig::V2.summary_prompt("HN", "Title", "Content");
+        let prompt = PromptConfig::V2.summary_prompt(&templates(), "HN", "Title", "Content").unwrap();
         assert!(prompt.contains("senior engineering leader who builds developer platforms"));
         assert!(prompt.contains("bold lead phrases and bullets"));
         assert!(prompt.contains("don't invent action items"));
@@ -282,37 +375,74 @@ mod tests {
 
     #[test]
     fn test_v3_selection_uses_v2_persona() {
-        let prompt = PromptConfig::V3.selection_prompt("0. [HN] Test Article");
+        let prompt = PromptConfig::V3.selection_prompt(&templates(), "0. [HN] Test Article").unwrap();
         assert!(prompt.contains("hedge fund"));
         assert!(prompt.contains("0. [HN] Test Article"));
     }
 
     #[test]
     fn test_v3_summary_prompt_requests_json() {
-        let prompt = PromptConfig::V3.summary_prompt("HN", "Title", "Content");
+        let prompt = PromptConfig::V3.summary_prompt(&templates(), "HN", "Title", "Content").unwrap();
         assert!(prompt.contains("key_idea"));
         assert!(prompt.contains("why_it_matters"));
         assert!(prompt.contains("what_to_change"));
         assert!(prompt.contains("deep_dive"));
         assert!(prompt.contains("Output ONLY valid JSON"));
         assert!(prompt.contains("Article Source: HN"));
+        assert!(prompt.contains("  \"meta\": {\n"));
+    }
+
+    #[test]
+    fn test_override_is_used() {
+        let overrides = std::collections::HashMap::from([(
+            "daily_summary".to_string(),
+            "Three bullets on '{title}':\n{content}".to_string(),
+        )]);
+        let templates = templates().with_overrides(overrides);
+        let prompt = PromptConfig::V1.summary_prompt(&templates, "HN", "Rust Perf", "Body").unwrap();
+        assert_eq!(prompt, "Three bullets on 'Rust Perf':\nBody");
+        // Other versions keep their built-in template
+        assert!(PromptConfig::V2.summary_prompt(&templates, "HN", "Rust Perf", "Body").unwrap().contains("hedge fund"));
+    }
+
+    #[test]
+    fn test_selection_prompts_request_json() {
+        for config in [PromptConfig::V1, PromptConfig::V2, PromptConfig::V3] {
+            assert!(config.selection_prompt(&templates(), "0. [HN] Test").unwrap().contains(r#"{"index": 3, "reason": "#));
+            assert!(config.final_selection_prompt(&templates(), "--- Article 0 ---").unwrap().contains(r#"{"index": 3, "reason": "#));
+            assert!(config.shortlist_prompt(&templates(), "0. [HN] Test").unwrap().contains(r#"{"indices": [3, 7, 12, 25, 41]}"#));
+        }
+    }
+
+    #[test]
+    fn test_digest_selection_prompt_asks_for_reasons() {
+        for config in [PromptConfig::V1, PromptConfig::V2, PromptConfig::V3] {
+            let prompt = config.digest_selection_prompt(&templates(), "--- Article 0 ---", 3).unwrap();
+            assert!(prompt.contains("select the 3 best") || prompt.contains("Select the 3 best"), "{}", prompt);
+            assert!(prompt.contains(r#"{"picks": [{"index": 3, "reason": "#));
+            assert!(prompt.contains("--- Article 0 ---"));
+        }
+        let intro = PromptConfig::V1.digest_intro_prompt(&templates(), "1. [Blog] Queue Design").unwrap();
+        assert!(intro.contains("why these matter today"));
+        assert!(intro.contains("1. [Blog] Queue Design"));
     }
 
     #[test]
     fn test_shortlist_with_context_includes_feedback() {
         let prompt = PromptConfig::V3.shortlist_prompt_with_context(
+            &templates(),
             "0. [HN] Test",
             Some("Recent reader feedback:\n- Liked: \"Rust Perf\"\n"),
             None,
-        );
+        ).unwrap();
         assert!(prompt.contains("Liked: \"Rust Perf\""));
         assert!(prompt.contains("0. [HN] Test"));
     }
 
     #[test]
     fn test_shortlist_with_context_none_is_base() {
-        let base = PromptConfig::V3.shortlist_prompt("0. [HN] Test");
-        let with_ctx = PromptConfig::V3.shortlist_prompt_with_context("0. [HN] Test", None, None);
+        let base = PromptConfig::V3.shortlist_prompt(&templates(), "0. [HN] Test").unwrap();
+        let with_ctx = PromptConfig::V3.shortlist_prompt_with_context(&templates(), "0. [HN] Test", None, None).unwrap();
         assert_eq!(base, with_ctx);
     }
 }
@@ -0,0 +1,275 @@
+//! Staged publishing of a run's output objects.
+//!
+//! Everything the run produces is staged in memory first, then written in a fixed order:
+//! leaf objects (summaries), then `manifest.json`, then views derived from the manifest.
+//! A failed leaf write aborts before the manifest is touched, so the manifest never
+//! references a missing object. Progress is checkpointed so `--force` can resume a
+//! failed publish without redoing the LLM work.
+
+use std::fmt;
+
+use llm_client::{ObjectStore, StorageError};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::AGENT_NAME;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum PublishStage {
+    /// Objects nothing else depends on existing (summaries, briefs).
+    Leaf,
+    /// `manifest.json`, which references the leaf objects.
+    Manifest,
+    /// Views rebuilt from the manifest (feeds, index pages, API snapshots).
+    Derived,
+}
+
+const STAGE_ORDER: [PublishStage; 3] = [PublishStage::Leaf, PublishStage::Manifest, PublishStage::Derived];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct StagedObject {
+    pub(crate) path: String,
+    pub(crate) stage: PublishStage,
+    pub(crate) content: String,
+    #[serde(default)]
+    pub(crate) written: bool,
+}
+
+/// A write that failed during [`Publisher::publish`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PublishError {
+    pub(crate) stage: PublishStage,
+    pub(crate) path: String,
+    pub(crate) source: StorageError,
+}
+
+impl fmt::Display for PublishError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "publish failed at {:?} object {}: {}", self.stage, self.path, self.source)
+    }
+}
+
+impl std::error::Error for PublishError {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct Publisher {
+    pub(crate) date: String,
+    pub(crate) objects: Vec<StagedObject>,
+}
+
+/// Object path of the publish checkpoint for a run date.
+pub(crate) fn checkpoint_path(date: &str) -> String {
+    format!("runs/{}/{}.publish.json", AGENT_NAME, date)
+}
+
+impl Publisher {
+    pub(crate) fn new(date: &str) -> Self {
+        Self { date: date.to_string(), objects: Vec::new() }
+    }
+
+    /// Stage an object, replacing any earlier version staged under the same path.
+    pub(crate) fn stage(&mut self, stage: PublishStage, path: &str, content: String) {
+        self.objects.retain(|o| o.path != path);
+        self.objects.push(StagedObject { path: path.to_string(), stage, content, written: false });
+    }
+
+    pub(crate) fn stage_leaf(&mut self, path: &str, content: String) {
+        self.stage(PublishStage::Leaf, path, content);
+    }
+
+    pub(crate) fn stage_manifest(&mut self, content: String) {
+        self.stage(PublishStage::Manifest, "manifest.json", content);
+    }
+
+    /// Content of a staged object, e.g. to evaluate a summary before it's published.
+    pub(crate) fn content(&self, path: &str) -> Option<&str> {
+        self.objects.iter().find(|o| o.path == path).map(|o| o.content.as_str())
+    }
+
+    pub(crate) fn is_complete(&self) -> bool {
+        self.objects.iter().all(|o| o.written)
+    }
+
+    /// Written and pending object paths, for the run report.
+    pub(crate) fn progress(&self) -> serde_json::Value {
+        let paths = |written: bool| -> Vec<&str> {
+            self.objects.iter().filter(|o| o.written == written).map(|o| o.path.as_str()).collect()
+        };
+        serde_json::json!({
+            "complete": self.is_complete(),
+            "written": paths(true),
+            "pending": paths(false),
+            "checkpoint": checkpoint_path(&self.date),
+        })
+    }
+
+    /// Load the checkpoint left by an earlier publish for `date`, if any.
+    pub(crate) async fn load_checkpoint<S: ObjectStore>(store: &S, date: &str) -> Option<Self> {
+        let path = checkpoint_path(date);
+        match store.get(&path).await {
+            Ok(Some(data)) => match serde_json::from_slice(&data) {
+                Ok(publisher) => Some(publisher),
+                Err(e) => {
+                    warn!(path = %path, error = %e, "Unreadable publish checkpoint, ignoring");
+                    None
+                }
+            },
+            Ok(None) => None,
+            Err(e) => {
+                warn!(path = %path, error = %e, "Failed to load publish checkpoint");
+                None
+            }
+        }
+    }
+
+    async fn save_checkpoint<S: ObjectStore>(&self, store: &S) {
+        let result = match serde_json::to_vec(self) {
+            Ok(data) => store.put(&checkpoint_path(&self.date), data).await,
+            Err(e) => Err(StorageError::new(None, e.to_string())),
+        };
+        if let Err(e) = result {
+            warn!(error = %e, "Failed to save publish checkpoint");
+        }
+    }
+
+    /// Write all pending objects stage by stage. Already-written objects are skipped,
+    /// so calling this again after a failure resumes from the failed object.
+    pub(crate) async fn publish<S: ObjectStore>(&mut self, store: &S) -> Result<(), PublishError> {
+        self.save_checkpoint(store).await;
+
+        for stage in STAGE_ORDER {
+            for i in 0..self.objects.len() {
+                let object = &self.objects[i];
+                if object.stage != stage || object.written {
+                    continue;
+                }
+                if let Err(source) = store.put(&object.path, object.content.clone().into_bytes()).await {
+                    let error = PublishError { stage, path: object.path.clone(), source };
+                    self.save_checkpoint(store).await;
+                    return Err(error);
+                }
+                info!(object = %object.path, stage = ?stage, "Published");
+                self.objects[i].written = true;
+            }
+        }
+
+        self.save_checkpoint(store).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llm_client::MemoryStore;
+    use std::sync::Mutex;
+
+    /// Store that fails writes to one path (checkpoints always succeed).
+    struct FailingStore {
+        inner: MemoryStore,
+        fail_path: Mutex<Option<String>>,
+    }
+
+    impl FailingStore {
+        fn new(fail_path: Option<&str>) -> Self {
+            Self { inner: MemoryStore::new(), fail_path: Mutex::new(fail_path.map(|s| s.to_string())) }
+        }
+
+        fn heal(&self) {
+            *self.fail_path.lock().unwrap() = None;
+        }
+    }
+
+    impl ObjectStore for FailingStore {
+        async fn get(&self, path: &str) -> Result<Option<Vec<u8>>, StorageError> {
+            self.inner.get(path).await
+        }
+
+        async fn put(&self, path: &str, data: Vec<u8>) -> Result<(), StorageError> {
+            if self.fail_path.lock().unwrap().as_deref() == Some(path) {
+                return Err(StorageError::new(Some(503), "injected failure"));
+            }
+            self.inner.put(path, data).await
+        }
+    }
+
+    const LEAVES: [&str; 3] = ["summaries/gemini/2026-04-01.md", "summaries/claude/2026-04-01.md", "summaries/v3/2026-04-01.json"];
+
+    fn staged() -> Publisher {
+        let mut publisher = Publisher::new("2026-04-01");
+        for leaf in LEAVES {
+            publisher.stage_leaf(leaf, format!("content of {}", leaf));
+        }
+        publisher.stage_manifest(serde_json::to_string(&LEAVES).unwrap());
+        publisher.stage(PublishStage::Derived, "feed.xml", "<rss/>".to_string());
+        publisher
+    }
+
+    /// The manifest, if written, must only reference objects that exist.
+    fn assert_manifest_consistent(store: &FailingStore) {
+        if let Some(data) = store.inner.object("manifest.json") {
+            let referenced: Vec<String> = serde_json::from_slice(&data).unwrap();
+            for path in referenced {
+                assert!(store.inner.object(&path).is_some(), "manifest references missing {}", path);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_writes_everything() {
+        let store = FailingStore::new(None);
+        let mut publisher = staged();
+        publisher.publish(&store).await.unwrap();
+        assert!(publisher.is_complete());
+        for path in LEAVES.iter().copied().chain(["manifest.json", "feed.xml"]) {
+            assert!(store.inner.object(path).is_some(), "{} not written", path);
+        }
+        assert_manifest_consistent(&store);
+    }
+
+    #[tokio::test]
+    async fn test_failure_at_each_step_keeps_manifest_consistent() {
+        for fail_path in LEAVES.iter().copied().chain(["manifest.json", "feed.xml"]) {
+            let store = FailingStore::new(Some(fail_path));
+            let mut publisher = staged();
+            let err = publisher.publish(&store).await.unwrap_err();
+            assert_eq!(err.path, fail_path);
+            assert_manifest_consistent(&store);
+
+            let manifest_written = store.inner.object("manifest.json").is_some();
+            assert_eq!(manifest_written, fail_path == "feed.xml", "failing {}", fail_path);
+            assert!(store.inner.object("feed.xml").is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_from_checkpoint_after_failure() {
+        let store = FailingStore::new(Some(LEAVES[1]));
+        let mut publisher = staged();
+        assert!(publisher.publish(&store).await.is_err());
+
+        let checkpoint = Publisher::load_checkpoint(&store, "2026-04-01").await.unwrap();
+        assert!(!checkpoint.is_complete());
+        assert!(checkpoint.objects.iter().find(|o| o.path == LEAVES[0]).unwrap().written);
+
+        // Overwrite the first leaf: a resumed publish must not rewrite it
+        store.inner.put(LEAVES[0], b"sentinel".to_vec()).await.unwrap();
+        store.heal();
+        let mut resumed = checkpoint;
+        resumed.publish(&store).await.unwrap();
+
+        assert_eq!(store.inner.object(LEAVES[0]), Some(b"sentinel".to_vec()));
+        assert!(store.inner.object("feed.xml").is_some());
+        assert!(Publisher::load_checkpoint(&store, "2026-04-01").await.unwrap().is_complete());
+    }
+
+    #[test]
+    fn test_stage_replaces_same_path() {
+        let mut publisher = Publisher::new("2026-04-01");
+        publisher.stage_leaf("a.md", "one".to_string());
+        publisher.stage_leaf("a.md", "two".to_string());
+        assert_eq!(publisher.objects.len(), 1);
+        assert_eq!(publisher.content("a.md"), Some("two"));
+    }
+}
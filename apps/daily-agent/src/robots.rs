@@ -0,0 +1,315 @@
+//! robots.txt checks before scraping article pages.
+//!
+//! Rules are read from `{origin}/robots.txt` once per origin and kept for [`ROBOTS_TTL`]. The
+//! group naming our User-Agent product token wins over `*`; within it the longest matching
+//! pattern decides, `Allow` winning ties. A robots.txt that can't be fetched allows everything,
+//! and `Crawl-delay` is ignored since a run reads at most a handful of pages per site.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tracing::{debug, warn};
+use url::Url;
+
+/// How long a fetched robots.txt is trusted within a run.
+const ROBOTS_TTL: Duration = Duration::from_secs(15 * 60);
+/// Bytes of robots.txt read; the rest is ignored, as major crawlers do.
+const MAX_ROBOTS_BYTES: usize = 512 * 1024;
+
+/// The `Allow`/`Disallow` rules that apply to one user agent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct RobotsRules {
+    /// `(allow, pattern)` in file order.
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsRules {
+    /// Rules of `robots_txt` for `agent`, a User-Agent product token such as `eng-pulse-agent`:
+    /// every group naming it, else every `*` group, else none.
+    pub(crate) fn parse(robots_txt: &str, agent: &str) -> Self {
+        let agent = agent.to_lowercase();
+        let (mut named, mut wildcard, mut named_group) = (Vec::new(), Vec::new(), false);
+        // Agents of the group being read, and whether its rules have started
+        let (mut group_agents, mut in_rules): (Vec<String>, bool) = (Vec::new(), false);
+        for line in robots_txt.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let Some((key, value)) = line.split_once(':') else { continue };
+            let value = value.trim();
+            match key.trim().to_lowercase().as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        group_agents.clear();
+                        in_rules = false;
+                    }
+                    let group_agent = value.to_lowercase();
+                    named_group |= agent_matches(&group_agent, &agent);
+                    group_agents.push(group_agent);
+                }
+                key @ ("allow" | "disallow") => {
+                    in_rules = true;
+                    // An empty Disallow allows everything, which no rule already does
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = (key == "allow", value.to_string());
+                    if group_agents.iter().any(|a| agent_matches(a, &agent)) {
+                        named.push(rule.clone());
+                    }
+                    if group_agents.iter().any(|a| a == "*") {
+                        wildcard.push(rule);
+                    }
+                }
+                _ => in_rules |= !group_agents.is_empty(),
+            }
+        }
+        Self { rules: if named_group { named } else { wildcard } }
+    }
+
+    /// Whether `url`'s path and query may be fetched.
+    pub(crate) fn allows(&self, url: &Url) -> bool {
+        let target = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| pattern_matches(pattern, &target))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+}
+
+/// A group's `User-agent` value names us: equal to our product token, ignoring any version.
+fn agent_matches(group_agent: &str, agent: &str) -> bool {
+    let group_agent = group_agent.split('/').next().unwrap_or_default().trim();
+    group_agent != "*" && !group_agent.is_empty() && group_agent == agent
+}
+
+/// robots.txt path matching: a prefix match where `*` is any run of characters and a trailing
+/// `$` anchors the end.
+fn pattern_matches(pattern: &str, target: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = target.strip_prefix(first) else { return false };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        // The last part of an anchored pattern must end the target
+        if anchored && i == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+/// The product token of a User-Agent header, e.g. `eng-pulse-agent` for
+/// `eng-pulse-agent/1.2 (+https://...)`.
+pub(crate) fn product_token(user_agent: &str) -> String {
+    user_agent.split(['/', ' ']).next().unwrap_or_default().trim().to_lowercase()
+}
+
+/// robots.txt rules per origin for one run.
+pub(crate) struct RobotsCache {
+    agent: String,
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, Arc<RobotsRules>)>>,
+}
+
+impl RobotsCache {
+    /// A cache checking rules for the product token of `user_agent`.
+    pub(crate) fn new(user_agent: &str) -> Self {
+        Self { agent: product_token(user_agent), ttl: ROBOTS_TTL, entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// Whether `url` may be scraped, fetching its origin's robots.txt unless a fresh copy is
+    /// cached. URLs that don't parse or have no origin are left to the fetch to reject.
+    pub(crate) async fn allows(&self, client: &reqwest::Client, url: &str) -> bool {
+        let Ok(parsed) = Url::parse(url) else { return true };
+        let origin = parsed.origin();
+        if !origin.is_tuple() {
+            return true;
+        }
+        let origin = origin.ascii_serialization();
+        let cached = self.entries.lock().unwrap().get(&origin).filter(|(at, _)| at.elapsed() < self.ttl).map(|(_, rules)| rules.clone());
+        let rules = match cached {
+            Some(rules) => rules,
+            None => {
+                let rules = Arc::new(self.fetch_rules(client, &origin).await);
+                self.entries.lock().unwrap().insert(origin, (Instant::now(), rules.clone()));
+                rules
+            }
+        };
+        rules.allows(&parsed)
+    }
+
+    async fn fetch_rules(&self, client: &reqwest::Client, origin: &str) -> RobotsRules {
+        let robots_url = format!("{}/robots.txt", origin);
+        let mut response = match client.get(&robots_url).send().await {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                debug!(url = %robots_url, status = response.status().as_u16(), "No robots.txt, allowing all");
+                return RobotsRules::default();
+            }
+            Err(e) => {
+                warn!(url = %robots_url, error = %e, "Failed to fetch robots.txt, allowing all");
+                return RobotsRules::default();
+            }
+        };
+        // Stop reading at the cap rather than buffering an oversized file
+        let mut body = Vec::new();
+        while body.len() < MAX_ROBOTS_BYTES {
+            match response.chunk().await {
+                Ok(Some(chunk)) => body.extend_from_slice(&chunk[..chunk.len().min(MAX_ROBOTS_BYTES - body.len())]),
+                Ok(None) => break,
+                Err(e) => {
+                    warn!(url = %robots_url, error = %e, "Failed to read robots.txt, allowing all");
+                    return RobotsRules::default();
+                }
+            }
+        }
+        RobotsRules::parse(&String::from_utf8_lossy(&body), &self.agent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const AGENT: &str = "eng-pulse-agent";
+
+    fn allowed(rules: &RobotsRules, path: &str) -> bool {
+        rules.allows(&Url::parse(&format!("https://blog.example.com{}", path)).unwrap())
+    }
+
+    #[test]
+    fn test_wildcards_and_allow_overrides() {
+        let rules = RobotsRules::parse(include_str!("../tests/fixtures/robots_wordpress.txt"), AGENT);
+        for (path, expected) in [
+            ("/2025/06/zero-downtime-deploys/", true),
+            ("/wp-admin/options.php", false),
+            // The longer Allow beats the shorter Disallow
+            ("/wp-admin/admin-ajax.php", true),
+            ("/2025/06/post/?replytocom=42", false),
+            ("/2025/06/post/?utm_source=rss", true),
+            ("/files/talk.pdf", false),
+            ("/files/talk.pdf?download=1", true),
+            ("/drafts-and-notes/", false),
+            ("/", true),
+        ] {
+            assert_eq!(allowed(&rules, path), expected, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_named_group_replaces_wildcard_group() {
+        let text = include_str!("../tests/fixtures/robots_agent_groups.txt");
+        let ours = RobotsRules::parse(text, AGENT);
+        for (path, expected) in [
+            // Only our groups apply, so the `*` group's /blog/ rule doesn't
+            ("/blog/2025/post", true),
+            ("/members/post", false),
+            ("/members/free/post", true),
+            // Both groups naming us are combined
+            ("/internal/roadmap", false),
+        ] {
+            assert_eq!(allowed(&ours, path), expected, "{}", path);
+        }
+
+        let other = RobotsRules::parse(text, "someone-else");
+        assert!(!allowed(&other, "/blog/2025/post"));
+        assert!(allowed(&other, "/blog/public-post"));
+        assert!(allowed(&other, "/members/post"));
+
+        let blocked = RobotsRules::parse(text, "GPTBot");
+        assert!(!allowed(&blocked, "/anything"));
+    }
+
+    #[test]
+    fn test_empty_and_unparseable_files_allow_everything() {
+        for text in ["", "Disallow: /orphan-rule-before-any-agent", "User-agent: *\nDisallow:\n", "<html>Not found</html>"] {
+            assert!(allowed(&RobotsRules::parse(text, AGENT), "/orphan-rule-before-any-agent"), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        for (pattern, target, expected) in [
+            ("/a", "/abc", true),
+            ("/a$", "/a", true),
+            ("/a$", "/ab", false),
+            ("/*.php$", "/x/index.php", true),
+            ("/*.php$", "/x/index.php5", false),
+            ("/*/private/*", "/team/private/doc", true),
+            ("/*", "/", true),
+            ("*", "/anything", true),
+            ("/b", "/a", false),
+        ] {
+            assert_eq!(pattern_matches(pattern, target), expected, "{} {}", pattern, target);
+        }
+    }
+
+    #[test]
+    fn test_product_token() {
+        assert_eq!(product_token(llm_client::DEFAULT_USER_AGENT), AGENT);
+        assert_eq!(product_token("Mozilla/5.0 (X11)"), "mozilla");
+    }
+
+    #[tokio::test]
+    async fn test_cache_fetches_each_origin_once_and_failures_allow() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/robots.txt"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("User-agent: *\nDisallow: /private/\n"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let cache = RobotsCache::new(llm_client::DEFAULT_USER_AGENT);
+        let client = reqwest::Client::new();
+        assert!(cache.allows(&client, &format!("{}/posts/1", server.uri())).await);
+        assert!(!cache.allows(&client, &format!("{}/private/2", server.uri())).await);
+
+        let failing = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/robots.txt"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&failing)
+            .await;
+        assert!(cache.allows(&client, &format!("{}/private/2", failing.uri())).await);
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let unreachable = format!("http://{}/private/2", listener.local_addr().unwrap());
+        drop(listener);
+        assert!(cache.allows(&client, &unreachable).await);
+    }
+
+    #[tokio::test]
+    async fn test_rules_past_the_size_cap_are_ignored() {
+        let mut text = "User-agent: *\nDisallow: /private/\n".to_string();
+        while text.len() < MAX_ROBOTS_BYTES {
+            text.push_str("# padding\n");
+        }
+        text.push_str("Disallow: /\n");
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/robots.txt"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(text))
+            .mount(&server)
+            .await;
+
+        let cache = RobotsCache::new(llm_client::DEFAULT_USER_AGENT);
+        let client = reqwest::Client::new();
+        assert!(!cache.allows(&client, &format!("{}/private/2", server.uri())).await);
+        assert!(cache.allows(&client, &format!("{}/posts/1", server.uri())).await);
+    }
+}
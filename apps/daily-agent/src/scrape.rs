@@ -0,0 +1,254 @@
+//! Scrape sources (`"type": "scrape"`): blogs with neither a feed nor a usable sitemap, read
+//! off their HTML listing page with the source's selectors.
+//!
+//! ```json
+//! {
+//!   "name": "Example Engineering", "type": "scrape", "url": "https://example.com/engineering",
+//!   "item_selector": "div.post-card", "title_selector": "h3", "link_selector": "a.post-link",
+//!   "date_selector": "time", "date_format": "%B %d, %Y"
+//! }
+//! ```
+//!
+//! Selectors are the subset [`crate::extraction`] supports. The title, link and date are looked
+//! up inside each item, the item itself included, so a listing of bare `<a>` cards works.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use llm_client::{parse_feed_date, SourceConfig};
+use select::document::Document;
+use select::node::Node;
+use url::Url;
+
+use crate::extraction::Selector;
+use crate::fetcher::{Article, FetchOutcome, Fetched};
+
+/// One configured selector, parsed, with the field it came from for error messages.
+struct FieldSelector<'a> {
+    field: &'static str,
+    text: &'a str,
+    selector: Selector,
+}
+
+impl<'a> FieldSelector<'a> {
+    /// `Ok(None)` when the field is unset; an error names the field when it's unsupported.
+    fn parse(field: &'static str, text: Option<&'a str>) -> Result<Option<Self>, String> {
+        let Some(text) = text.map(str::trim).filter(|t| !t.is_empty()) else { return Ok(None) };
+        match Selector::parse(text) {
+            Some(selector) => Ok(Some(Self { field, text, selector })),
+            None => Err(format!("{} '{}' is not supported; use tags, .class, #id and descendants, e.g. 'article .title'", field, text)),
+        }
+    }
+
+    fn required(field: &'static str, text: Option<&'a str>) -> Result<Self, String> {
+        Self::parse(field, text)?.ok_or_else(|| format!("{} is missing", field))
+    }
+
+    /// First match in `item`, `item` itself included.
+    fn first_in<'n>(&self, item: &Node<'n>) -> Option<Node<'n>> {
+        if self.selector.matches(item) {
+            return Some(*item);
+        }
+        item.find(|n: &Node| self.selector.matches(n)).next()
+    }
+
+    fn matched_nothing(&self, page_url: &Url) -> String {
+        format!("{} '{}' matched nothing on {}", self.field, self.text, page_url)
+    }
+}
+
+/// Text of `node` with runs of whitespace collapsed.
+fn clean_text(node: &Node) -> String {
+    node.text().split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A listing date: with `format`, read by chrono as a zoned timestamp, a timestamp or a day
+/// (midnight UTC); without, anything [`parse_feed_date`] reads.
+fn parse_listing_date(text: &str, format: Option<&str>) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    let Some(format) = format else { return parse_feed_date(text) };
+    DateTime::parse_from_str(text, format)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| NaiveDateTime::parse_from_str(text, format).ok().map(|dt| dt.and_utc()))
+        .or_else(|| NaiveDate::parse_from_str(text, format).ok().and_then(|day| day.and_hms_opt(0, 0, 0)).map(|dt| dt.and_utc()))
+}
+
+/// Posts on the listing page `html`, fetched from `page_url`, from the 24 hours before `now`.
+/// Relative links are resolved against `page_url`; items without a title or an http(s) link
+/// are skipped. Items whose date is missing or unreadable are kept, dated `now` and marked
+/// [`Article::undated`].
+///
+/// A selector that is missing or unsupported, or that matches nothing on the whole page, is an
+/// error naming it: that is a configuration problem rather than a quiet day.
+pub(crate) fn scrape_articles(source: &SourceConfig, html: &str, page_url: &Url, now: DateTime<Utc>) -> Result<Fetched, String> {
+    let item_selector = FieldSelector::required("item_selector", source.item_selector.as_deref())?;
+    let title_selector = FieldSelector::required("title_selector", source.title_selector.as_deref())?;
+    let link_selector = FieldSelector::required("link_selector", source.link_selector.as_deref())?;
+    let date_selector = FieldSelector::parse("date_selector", source.date_selector.as_deref())?;
+    let date_format = source.date_format.as_deref().map(str::trim).filter(|f| !f.is_empty());
+
+    let document = Document::from(html);
+    let items: Vec<Node> = document.find(|n: &Node| item_selector.selector.matches(n)).collect();
+    if items.is_empty() {
+        return Err(item_selector.matched_nothing(page_url));
+    }
+
+    let yesterday = now - Duration::hours(24);
+    let (mut titles_found, mut links_found, mut dates_found) = (false, false, false);
+    let mut articles = Vec::new();
+    let mut outcome = FetchOutcome { items_seen: items.len(), ..FetchOutcome::default() };
+    for item in &items {
+        let title = title_selector.first_in(item).map(|n| clean_text(&n)).filter(|t| !t.is_empty());
+        let link = link_selector
+            .first_in(item)
+            .and_then(|n| n.attr("href"))
+            .and_then(|href| page_url.join(href.trim()).ok())
+            .filter(|url| matches!(url.scheme(), "http" | "https"));
+        let date_node = date_selector.as_ref().and_then(|s| s.first_in(item));
+        titles_found |= title.is_some();
+        links_found |= link.is_some();
+        dates_found |= date_node.is_some();
+
+        let (Some(title), Some(link)) = (title, link) else {
+            outcome.skipped_missing_fields += 1;
+            continue;
+        };
+        let date_text = date_node.map(|n| n.attr("datetime").map(str::to_string).unwrap_or_else(|| clean_text(&n)));
+        let published_at = date_text.as_deref().and_then(|text| parse_listing_date(text, date_format));
+        if published_at.is_some_and(|date| date < yesterday) {
+            outcome.filtered_by_date += 1;
+            continue;
+        }
+        articles.push(Article {
+            title,
+            url: link.to_string(),
+            source: source.name.clone(),
+            via: None,
+            published_at: published_at.unwrap_or(now),
+            media: None,
+            show_notes: None,
+            feed_content: None,
+            summary: None,
+            content: None,
+            score: None,
+            rank: None,
+            author: None,
+            categories: Vec::new(),
+            undated: published_at.is_none(),
+            future_dated: false,
+            language: None,
+        });
+    }
+
+    if !titles_found {
+        return Err(title_selector.matched_nothing(page_url));
+    }
+    if !links_found {
+        return Err(format!("{} (no element with an http(s) href)", link_selector.matched_nothing(page_url)));
+    }
+    if let Some(date_selector) = date_selector.filter(|_| !dates_found) {
+        return Err(date_selector.matched_nothing(page_url));
+    }
+    outcome.articles = articles.len();
+    Ok((articles, outcome))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENGINEERING_BLOG: &str = include_str!("../tests/fixtures/scrape_engineering_blog.html");
+    const RESEARCH_LISTING: &str = include_str!("../tests/fixtures/scrape_research_listing.html");
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Utc)
+    }
+
+    fn engineering_source() -> SourceConfig {
+        SourceConfig {
+            item_selector: Some("div.post-card".to_string()),
+            title_selector: Some("h3.post-title".to_string()),
+            link_selector: Some("a.post-link".to_string()),
+            date_selector: Some("time".to_string()),
+            ..SourceConfig::new("Example Engineering", llm_client::SourceType::Scrape, "https://engineering.example.com/blog/")
+        }
+    }
+
+    fn research_source() -> SourceConfig {
+        SourceConfig {
+            item_selector: Some("ul.publications li".to_string()),
+            title_selector: Some("h2".to_string()),
+            link_selector: Some("h2 a".to_string()),
+            date_selector: Some(".published".to_string()),
+            date_format: Some("Published %d %B %Y".to_string()),
+            ..SourceConfig::new("Example Research", llm_client::SourceType::Scrape, "https://research.example.com/lab/papers/index.html")
+        }
+    }
+
+    #[test]
+    fn test_engineering_blog_listing() {
+        let source = engineering_source();
+        let now = at("2025-06-10T18:00:00Z");
+        let articles = scrape_articles(&source, ENGINEERING_BLOG, &Url::parse(&source.url).unwrap(), now).unwrap().0;
+
+        let summary: Vec<(&str, &str, DateTime<Utc>, bool)> =
+            articles.iter().map(|a| (a.title.as_str(), a.url.as_str(), a.published_at, a.undated)).collect();
+        assert_eq!(summary, vec![
+            ("Zero-downtime schema migrations at scale", "https://engineering.example.com/blog/2025/zero-downtime-migrations?utm_source=blog_index", at("2025-06-10T09:00:00Z"), false),
+            ("How we cut p99 latency in our edge proxy", "https://engineering.example.com/blog/2025/edge-proxy-latency", at("2025-06-09T20:30:00Z"), false),
+            // No <time>: kept, dated now and flagged
+            ("Hiring: Staff Engineer, Storage", "https://careers.example.com/jobs/42", now, true),
+        ]);
+        assert!(articles.iter().all(|a| a.source == "Example Engineering" && a.media.is_none()));
+    }
+
+    #[test]
+    fn test_research_listing_with_date_format() {
+        let source = research_source();
+        let now = at("2025-06-10T18:00:00Z");
+        let articles = scrape_articles(&source, RESEARCH_LISTING, &Url::parse(&source.url).unwrap(), now).unwrap().0;
+
+        let summary: Vec<(&str, &str, bool)> = articles.iter().map(|a| (a.title.as_str(), a.url.as_str(), a.undated)).collect();
+        assert_eq!(summary, vec![
+            ("Consistent hashing with bounded loads, revisited", "https://research.example.com/lab/papers/bounded-loads.html", false),
+            // "Published soon" doesn't fit the format
+            ("Tail-tolerant replication for log storage", "https://research.example.com/lab/drafts/tail-tolerant.pdf", true),
+        ]);
+        assert_eq!(articles[0].published_at, at("2025-06-10T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_parse_listing_date() {
+        for (text, format, expected) in [
+            ("2025-06-10T09:00:00Z", None, Some(at("2025-06-10T09:00:00Z"))),
+            ("June 10, 2025", Some("%B %d, %Y"), Some(at("2025-06-10T00:00:00Z"))),
+            ("10.06.2025 14:30", Some("%d.%m.%Y %H:%M"), Some(at("2025-06-10T14:30:00Z"))),
+            ("2025/06/10 14:30 +0200", Some("%Y/%m/%d %H:%M %z"), Some(at("2025-06-10T12:30:00Z"))),
+            ("June 10, 2025", None, None),
+            ("yesterday", Some("%B %d, %Y"), None),
+        ] {
+            assert_eq!(parse_listing_date(text, format), expected, "{} {:?}", text, format);
+        }
+    }
+
+    #[test]
+    fn test_misconfigured_selectors_are_named() {
+        let page = Url::parse("https://engineering.example.com/blog/").unwrap();
+        let now = at("2025-06-10T18:00:00Z");
+        for (source, expected) in [
+            (SourceConfig { item_selector: Some("article.post".to_string()), ..engineering_source() }, "item_selector 'article.post' matched nothing"),
+            (SourceConfig { title_selector: Some("h2".to_string()), ..engineering_source() }, "title_selector 'h2' matched nothing"),
+            (SourceConfig { link_selector: Some("a.read-more".to_string()), ..engineering_source() }, "link_selector 'a.read-more' matched nothing"),
+            (SourceConfig { date_selector: Some("span.date".to_string()), ..engineering_source() }, "date_selector 'span.date' matched nothing"),
+            (SourceConfig { title_selector: Some("div > h3".to_string()), ..engineering_source() }, "title_selector 'div > h3' is not supported"),
+            (SourceConfig { link_selector: None, ..engineering_source() }, "link_selector is missing"),
+        ] {
+            let err = scrape_articles(&source, ENGINEERING_BLOG, &page, now).unwrap_err();
+            assert!(err.contains(expected), "{}", err);
+        }
+        // The date is optional
+        let source = SourceConfig { date_selector: None, ..engineering_source() };
+        let articles = scrape_articles(&source, ENGINEERING_BLOG, &page, now).unwrap().0;
+        assert_eq!(articles.len(), 5);
+        assert!(articles.iter().all(|a| a.undated && a.published_at == now));
+    }
+}
@@ -0,0 +1,162 @@
+//! `state/seen_urls.json`: every article URL the daily agent has fetched in the last
+//! [`SEEN_RETENTION_DAYS`] days, keyed by normalized URL with the day it was first seen.
+//!
+//! Without it a post that stays in a feed's fetch window across runs is put in front of the
+//! selector every day. Only URLs first seen before today are excluded, so a rerun of the same
+//! day sees the same pool. Load and save failures only log: the index is an optimization, not
+//! something to fail a run over.
+
+use std::collections::BTreeMap;
+
+use chrono::{Days, NaiveDate};
+use llm_client::state::{schema, unversioned};
+use llm_client::{load_or_default, save_state, Migration, ObjectStore};
+use tracing::{info, warn};
+
+use crate::fetcher::{normalize_article_url, Article};
+
+pub(crate) const SEEN_URLS_OBJECT: &str = "state/seen_urls.json";
+/// Schema migrations of `state/seen_urls.json`; see [`llm_client::state`].
+const SEEN_URLS_MIGRATIONS: [Migration; schema::SEEN_URLS as usize] = [unversioned];
+/// Days a URL stays in the index after it was first seen.
+pub(crate) const SEEN_RETENTION_DAYS: u64 = 30;
+/// Environment variable turning the exclusion off (`false` or `0`); the index is kept either way.
+pub(crate) const SKIP_SEEN_ENV_VAR: &str = "SKIP_SEEN";
+
+/// Normalized article URL -> day it was first fetched.
+pub(crate) type SeenUrls = BTreeMap<String, NaiveDate>;
+
+/// Whether seen articles are left out of selection: `SKIP_SEEN`, on unless `false` or `0`.
+pub(crate) fn skip_seen_from_env() -> bool {
+    std::env::var(SKIP_SEEN_ENV_VAR).map_or(true, |v| !matches!(v.trim().to_lowercase().as_str(), "0" | "false"))
+}
+
+/// Drop URLs first seen more than [`SEEN_RETENTION_DAYS`] before `today`; returns how many.
+pub(crate) fn prune(seen: &mut SeenUrls, today: NaiveDate) -> usize {
+    let cutoff = today.checked_sub_days(Days::new(SEEN_RETENTION_DAYS)).unwrap_or(NaiveDate::MIN);
+    let before = seen.len();
+    seen.retain(|_, first_seen| *first_seen > cutoff);
+    before - seen.len()
+}
+
+/// Remove articles whose URL was first seen before `today`; returns how many.
+pub(crate) fn exclude_seen(articles: &mut Vec<Article>, seen: &SeenUrls, today: NaiveDate) -> usize {
+    let before = articles.len();
+    articles.retain(|a| seen.get(&normalize_article_url(&a.url)).is_none_or(|first_seen| *first_seen >= today));
+    before - articles.len()
+}
+
+/// Add the URLs of `articles` first seen `today`, keeping earlier dates of known ones.
+pub(crate) fn record_seen(seen: &mut SeenUrls, articles: &[Article], today: NaiveDate) {
+    for article in articles {
+        seen.entry(normalize_article_url(&article.url)).or_insert(today);
+    }
+}
+
+/// The index as of `today`, pruned; empty when it can't be read.
+pub(crate) async fn load_seen<S: ObjectStore>(store: &S, today: NaiveDate) -> SeenUrls {
+    let mut seen: SeenUrls = match load_or_default(store, SEEN_URLS_OBJECT, &SEEN_URLS_MIGRATIONS).await {
+        Ok(seen) => seen,
+        Err(e) => {
+            warn!(error = %e, "Failed to load seen URLs, treating every article as new");
+            return SeenUrls::new();
+        }
+    };
+    let pruned = prune(&mut seen, today);
+    info!(tracked = seen.len(), pruned, "Loaded seen URLs");
+    seen
+}
+
+/// Write the index back. Failures only log.
+pub(crate) async fn save_seen<S: ObjectStore>(store: &S, seen: &SeenUrls) {
+    if let Err(e) = save_state(store, SEEN_URLS_OBJECT, seen, schema::SEEN_URLS).await {
+        warn!(error = %e, "Failed to save seen URLs");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llm_client::MemoryStore;
+
+    fn day(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    fn article(url: &str) -> Article {
+        Article::for_test(url, url, "Blog")
+    }
+
+    #[test]
+    fn test_prune_keeps_the_last_30_days() {
+        let today = day("2025-06-30");
+        let mut seen: SeenUrls = [
+            ("https://a.example.com/today", "2025-06-30"),
+            ("https://a.example.com/29-days", "2025-06-01"),
+            ("https://a.example.com/30-days", "2025-05-31"),
+            ("https://a.example.com/old", "2025-01-15"),
+        ]
+        .into_iter()
+        .map(|(url, date)| (url.to_string(), day(date)))
+        .collect();
+
+        assert_eq!(prune(&mut seen, today), 2);
+        assert_eq!(seen.keys().collect::<Vec<_>>(), ["https://a.example.com/29-days", "https://a.example.com/today"]);
+        assert_eq!(prune(&mut seen, today), 0);
+    }
+
+    #[test]
+    fn test_seen_articles_are_excluded_by_normalized_url() {
+        let mut seen = SeenUrls::new();
+        record_seen(&mut seen, &[article("https://blog.example.com/post-1")], day("2025-06-29"));
+
+        let mut articles = vec![
+            article("http://blog.example.com/post-1/?utm_source=rss#comments"),
+            article("https://blog.example.com/post-2"),
+        ];
+        assert_eq!(exclude_seen(&mut articles, &seen, day("2025-06-30")), 1);
+        assert_eq!(articles[0].url, "https://blog.example.com/post-2");
+        // A rerun on the day a URL was first seen keeps it
+        let mut rerun = vec![article("https://blog.example.com/post-1")];
+        assert_eq!(exclude_seen(&mut rerun, &seen, day("2025-06-29")), 0);
+
+        // Known URLs keep the day they were first seen
+        record_seen(&mut seen, &[article("https://blog.example.com/post-1"), article("https://blog.example.com/post-2")], day("2025-06-30"));
+        assert_eq!(seen["https://blog.example.com/post-1"], day("2025-06-29"));
+        assert_eq!(seen["https://blog.example.com/post-2"], day("2025-06-30"));
+    }
+
+    #[tokio::test]
+    async fn test_index_round_trips_through_the_store() {
+        let store = MemoryStore::new();
+        assert!(load_seen(&store, day("2025-06-30")).await.is_empty());
+
+        let mut seen = SeenUrls::new();
+        record_seen(&mut seen, &[article("https://a.example.com/new")], day("2025-06-30"));
+        seen.insert("https://a.example.com/stale".to_string(), day("2025-04-01"));
+        save_seen(&store, &seen).await;
+
+        let raw: serde_json::Value = serde_json::from_slice(&store.object(SEEN_URLS_OBJECT).unwrap()).unwrap();
+        assert_eq!(raw["schema_version"], schema::SEEN_URLS);
+        assert_eq!(raw["data"]["https://a.example.com/new"], "2025-06-30");
+
+        // The next day's run skips the URL; loading prunes the stale one
+        let loaded = load_seen(&store, day("2025-07-01")).await;
+        assert_eq!(loaded.keys().collect::<Vec<_>>(), ["https://a.example.com/new"]);
+        let mut next_day = vec![article("https://a.example.com/new"), article("https://a.example.com/other")];
+        assert_eq!(exclude_seen(&mut next_day, &loaded, day("2025-07-01")), 1);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_skip_seen_from_env() {
+        for (value, expected) in [(None, true), (Some("true"), true), (Some("0"), false), (Some(" False "), false), (Some("1"), true)] {
+            match value {
+                Some(v) => std::env::set_var(SKIP_SEEN_ENV_VAR, v),
+                None => std::env::remove_var(SKIP_SEEN_ENV_VAR),
+            }
+            assert_eq!(skip_seen_from_env(), expected, "{:?}", value);
+        }
+        std::env::remove_var(SKIP_SEEN_ENV_VAR);
+    }
+}
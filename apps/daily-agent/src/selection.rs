@@ -0,0 +1,453 @@
+use std::collections::HashSet;
+
+use llm_client::extract_domain;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use tracing::{info, warn};
+
+use crate::fetcher::{normalize_article_url, Article};
+use crate::manifest::ManifestEntry;
+
+/// Environment variable choosing how the exploratory pick is drawn: `uniform` or `score`
+pub(crate) const EXPLORATION_MODE_ENV_VAR: &str = "EXPLORATION_MODE";
+/// Upper bound for `EXPLORATION_EPSILON`; above this the digest stops tracking the model's judgement.
+pub(crate) const MAX_EXPLORATION_EPSILON: f64 = 0.5;
+/// Default number of most recent manifest entries whose sources are excluded from exploration.
+pub(crate) const DEFAULT_EXPLORATION_RECENT_ENTRIES: usize = 10;
+/// Upper bound for `SELECTION_VOTES`; each vote is a full completion of the selection prompt.
+pub(crate) const MAX_SELECTION_VOTES: u32 = 8;
+/// Days of manifest entries whose articles aren't selected again.
+pub(crate) const REPEAT_WINDOW_DAYS: i64 = 30;
+/// Share of words two titles must have in common to count as the same article, e.g. a
+/// syndicated copy with the site name appended.
+const SIMILAR_TITLE_THRESHOLD: f64 = 0.8;
+/// Titles with fewer words are only matched by URL; short titles overlap by chance.
+const MIN_TITLE_WORDS: usize = 4;
+
+/// How the exploratory pick is drawn from the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum ExplorationMode {
+    /// Every candidate is equally likely
+    #[default]
+    Uniform,
+    /// Candidates are weighted by their aggregator score plus one, so a well-received story
+    /// is likelier while unscored posts keep a chance
+    ScoreWeighted,
+}
+
+impl ExplorationMode {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            ExplorationMode::Uniform => "uniform",
+            ExplorationMode::ScoreWeighted => "score",
+        }
+    }
+}
+
+/// Exploration settings: with probability `epsilon`, replace the model's pick with a draw
+/// (uniform or score-weighted, per `mode`) among candidates whose source wasn't featured in
+/// the last `recent_entries` manifest entries.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Exploration {
+    pub(crate) epsilon: f64,
+    pub(crate) recent_entries: usize,
+    pub(crate) mode: ExplorationMode,
+    pub(crate) seed: Option<u64>,
+}
+
+impl Default for Exploration {
+    fn default() -> Self {
+        Self { epsilon: 0.0, recent_entries: DEFAULT_EXPLORATION_RECENT_ENTRIES, mode: ExplorationMode::Uniform, seed: None }
+    }
+}
+
+impl Exploration {
+    /// Read `EXPLORATION_EPSILON`, `EXPLORATION_RECENT_ENTRIES`, `EXPLORATION_MODE` and
+    /// `EXPLORATION_SEED`. Epsilon is clamped to 0.0–0.5; invalid values fall back to the defaults.
+    pub(crate) fn from_env() -> Self {
+        let mut exploration = Self::default();
+        if let Ok(raw) = std::env::var("EXPLORATION_EPSILON") {
+            match raw.trim().parse::<f64>() {
+                Ok(eps) if eps.is_finite() => exploration.epsilon = eps.clamp(0.0, MAX_EXPLORATION_EPSILON),
+                _ => warn!(value = %raw, "Invalid EXPLORATION_EPSILON, exploration disabled"),
+            }
+        }
+        if let Some(n) = std::env::var("EXPLORATION_RECENT_ENTRIES").ok().and_then(|v| v.trim().parse().ok()) {
+            exploration.recent_entries = n;
+        }
+        if let Ok(raw) = std::env::var(EXPLORATION_MODE_ENV_VAR) {
+            match raw.trim().to_lowercase().as_str() {
+                "uniform" => exploration.mode = ExplorationMode::Uniform,
+                "score" => exploration.mode = ExplorationMode::ScoreWeighted,
+                _ => warn!(value = %raw, "Invalid {}, drawing uniformly", EXPLORATION_MODE_ENV_VAR),
+            }
+        }
+        exploration.seed = std::env::var("EXPLORATION_SEED").ok().and_then(|v| v.trim().parse().ok());
+        exploration
+    }
+
+    pub(crate) fn rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        }
+    }
+}
+
+/// `SELECTION_VOTES`: completions sampled per article pick, clamped to 1–8. Unset or invalid is 1.
+pub(crate) fn selection_votes_from_env() -> u32 {
+    let Ok(raw) = std::env::var("SELECTION_VOTES") else {
+        return 1;
+    };
+    match raw.trim().parse::<u32>() {
+        Ok(n) => n.clamp(1, MAX_SELECTION_VOTES),
+        Err(_) => {
+            warn!(value = %raw, "Invalid SELECTION_VOTES, using a single vote");
+            1
+        }
+    }
+}
+
+/// Most common index among `votes`. Ties go to the leader voted for first, so with no
+/// majority the provider's primary completion wins. `None` without votes.
+pub(crate) fn majority_vote(votes: &[usize]) -> Option<usize> {
+    let count = |idx: usize| votes.iter().filter(|&&v| v == idx).count();
+    let top = votes.iter().map(|&v| count(v)).max()?;
+    votes.iter().copied().find(|&v| count(v) == top)
+}
+
+/// Lowercased words of `title`, without punctuation.
+fn title_words(title: &str) -> HashSet<String> {
+    title.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).map(str::to_lowercase).collect()
+}
+
+/// Jaccard similarity of two titles' word sets, 0.0 when either is too short to compare.
+fn title_similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.len().min(b.len()) < MIN_TITLE_WORDS {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / a.union(b).count() as f64
+}
+
+/// Remove candidates already summarized in production manifest entries dated `since` or
+/// later, lead and digest articles alike: the same URL once normalized, or a title close
+/// enough to be a syndicated copy. Returns how many were removed. When every candidate is a
+/// repeat, none is removed, so the day still gets a summary.
+pub(crate) fn exclude_recent_picks(articles: &mut Vec<Article>, manifest: &[ManifestEntry], since: &str) -> usize {
+    let mut urls = HashSet::new();
+    let mut titles = Vec::new();
+    for entry in manifest.iter().filter(|e| e.date.as_str() >= since && e.prompt_version.is_none()) {
+        let lead = entry.original_url.as_deref().map(|url| (url, entry.feed_url.as_deref(), entry.title.as_str()));
+        let digest = entry.articles.iter().map(|a| (a.original_url.as_str(), a.feed_url.as_deref(), a.title.as_str()));
+        for (url, feed_url, title) in lead.into_iter().chain(digest) {
+            urls.extend(std::iter::once(url).chain(feed_url).map(normalize_article_url));
+            titles.push(title_words(title));
+        }
+    }
+    let is_repeat = |article: &Article| {
+        if urls.contains(&normalize_article_url(&article.url)) {
+            return true;
+        }
+        let words = title_words(&article.title);
+        titles.iter().any(|recent| title_similarity(&words, recent) >= SIMILAR_TITLE_THRESHOLD)
+    };
+    let repeat: Vec<bool> = articles.iter().map(is_repeat).collect();
+    let repeats = repeat.iter().filter(|&&r| r).count();
+    if repeats == articles.len() && repeats > 0 {
+        warn!(candidates = repeats, since = since, "Every candidate was already summarized recently, allowing repeats");
+        return 0;
+    }
+    let mut repeat = repeat.into_iter();
+    articles.retain(|_| !repeat.next().unwrap_or(false));
+    repeats
+}
+
+/// Sources (by name and by original-URL domain) featured in the most recent production
+/// manifest entries; beta prompt-version runs are skipped, as in [`exclude_recent_picks`].
+fn recently_featured(manifest: &[ManifestEntry], recent_entries: usize) -> (HashSet<String>, HashSet<String>) {
+    let mut names = HashSet::new();
+    let mut domains = HashSet::new();
+    for entry in manifest.iter().filter(|e| e.prompt_version.is_none()).take(recent_entries) {
+        if let Some(source) = &entry.source {
+            names.insert(source.clone());
+        }
+        if let Some(url) = &entry.original_url {
+            domains.insert(extract_domain(url));
+        }
+    }
+    (names, domains)
+}
+
+/// Indices of candidates whose source wasn't featured in the last `recent_entries` manifest entries.
+pub(crate) fn exploration_pool(articles: &[Article], manifest: &[ManifestEntry], recent_entries: usize) -> Vec<usize> {
+    let (names, domains) = recently_featured(manifest, recent_entries);
+    articles.iter()
+        .enumerate()
+        .filter(|(_, a)| !names.contains(&a.source) && !domains.contains(&extract_domain(&a.url)))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Weight of a candidate in [`ExplorationMode::ScoreWeighted`]: its score (points on Hacker
+/// News or Lobsters) plus one, so unscored posts and negative scores count as one.
+fn exploration_weight(article: &Article) -> f64 {
+    article.score.unwrap_or(0).max(0) as f64 + 1.0
+}
+
+/// Decide whether to replace the model's pick with an exploratory one.
+/// `articles` must already be deduplicated, so the exploratory pick obeys the same rules.
+/// Returns the index of the exploratory pick, or `None` to keep the model's pick.
+pub(crate) fn maybe_explore<R: Rng>(
+    articles: &[Article],
+    manifest: &[ManifestEntry],
+    exploration: &Exploration,
+    rng: &mut R,
+) -> Option<usize> {
+    if exploration.epsilon <= 0.0 || rng.random::<f64>() >= exploration.epsilon {
+        return None;
+    }
+    let pool = exploration_pool(articles, manifest, exploration.recent_entries);
+    let pick = match exploration.mode {
+        ExplorationMode::Uniform => pool.choose(rng),
+        ExplorationMode::ScoreWeighted => pool.choose_weighted(rng, |&idx| exploration_weight(&articles[idx])).ok(),
+    };
+    match pick {
+        Some(&idx) => {
+            info!(pool = pool.len(), mode = exploration.mode.as_str(), title = %articles[idx].title, source = %articles[idx].source, "Exploratory pick");
+            Some(idx)
+        }
+        None => {
+            info!("Exploration triggered but every candidate source was recently featured");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::DigestArticle;
+
+    fn article(source: &str, url: &str) -> Article {
+        Article::for_test(&format!("{} post", source), url, source)
+    }
+
+    fn entry(date: &str, source: Option<&str>, original_url: &str) -> ManifestEntry {
+        ManifestEntry {
+            date: date.to_string(),
+            url: format!("summaries/gemini/{}.md", date),
+            title: "Test".to_string(),
+            summary_snippet: "...".to_string(),
+            original_url: Some(original_url.to_string()),
+            feed_url: None,
+            model: None,
+            selected_by: None,
+            prompt_version: None,
+            eval_score: None,
+            format: None,
+            source: source.map(|s| s.to_string()),
+            exploratory: None,
+            run_id: None,
+            judged_best: None,
+            author: None,
+            categories: Vec::new(),
+            articles: Vec::new(),
+            selection_reason: None,
+        }
+    }
+
+    fn fixture() -> (Vec<Article>, Vec<ManifestEntry>) {
+        let articles = vec![
+            article("Big Blog", "https://big.example.com/a"),
+            article("Hacker News", "https://news.example.org/b"),
+            article("Small Blog", "https://small.example.net/c"),
+            article("Niche Blog", "https://niche.example.io/d"),
+        ];
+        // Newest first, like manifest.json
+        let manifest = vec![
+            entry("2026-04-03", Some("Big Blog"), "https://big.example.com/x"),
+            entry("2026-04-02", None, "https://news.example.org/y"),
+            entry("2026-04-01", Some("Niche Blog"), "https://niche.example.io/z"),
+        ];
+        (articles, manifest)
+    }
+
+    fn titled(title: &str, url: &str) -> Article {
+        Article::for_test(title, url, "Blog")
+    }
+
+    #[test]
+    fn test_exclude_recent_picks_by_url_and_title() {
+        let manifest = vec![
+            ManifestEntry { title: "How We Cut Tail Latency by 90%".to_string(), ..entry("2026-04-10", None, "https://eng.example.com/tail-latency/") },
+            ManifestEntry {
+                title: "Build Caches That Scale".to_string(),
+                articles: vec![
+                    DigestArticle { title: "Build Caches That Scale".to_string(), original_url: "https://ci.example.com/caches".to_string(), feed_url: None },
+                    DigestArticle { title: "On-Call Rotations That Actually Stick".to_string(), original_url: "https://lead.example.com/on-call".to_string(), feed_url: None },
+                ],
+                ..entry("2026-04-05", None, "https://ci.example.com/caches")
+            },
+            // Stored under the URL its feed link redirected to
+            ManifestEntry {
+                title: "Zero-Downtime Schema Changes".to_string(),
+                feed_url: Some("https://feeds.feedburner.com/~r/eng/~3/abc".to_string()),
+                ..entry("2026-04-08", None, "https://eng.example.com/schema-changes")
+            },
+            // Beta entries and entries outside the window don't count
+            ManifestEntry { prompt_version: Some("v3".to_string()), ..entry("2026-04-10", None, "https://beta.example.com/post") },
+            entry("2026-03-01", None, "https://old.example.com/post"),
+        ];
+        let mut articles = vec![
+            // Tracking parameters, www. and the trailing slash don't hide a repeat
+            titled("How We Cut Tail Latency by 90%", "https://www.eng.example.com/tail-latency?utm_source=hn"),
+            // A syndicated copy under another URL
+            titled("How we cut tail latency by 90% | Medium", "https://medium.com/@eng/tail-latency-1a2b"),
+            // The feed's redirector link, as a later run sees it again
+            titled("Schema changes without downtime", "https://feeds.feedburner.com/~r/eng/~3/abc?utm_medium=rss"),
+            // The second article of a digest
+            titled("On-call rotations that actually stick", "https://news.example.org/item?id=1"),
+            titled("Tail Latency Budgets for Storage Systems", "https://storage.example.com/budgets"),
+            titled("Rust 2024", "https://blog.example.com/rust-2024"),
+            titled("Beta Pick", "https://beta.example.com/post"),
+            titled("Old Pick", "https://old.example.com/post"),
+        ];
+
+        assert_eq!(exclude_recent_picks(&mut articles, &manifest, "2026-03-12"), 4);
+        let kept: Vec<&str> = articles.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(kept, ["Tail Latency Budgets for Storage Systems", "Rust 2024", "Beta Pick", "Old Pick"]);
+    }
+
+    #[test]
+    fn test_exclude_recent_picks_allows_repeats_rather_than_nothing() {
+        let manifest = vec![entry("2026-04-10", None, "https://eng.example.com/tail-latency")];
+        let mut articles = vec![titled("Tail Latency", "https://eng.example.com/tail-latency?ref=rss")];
+        assert_eq!(exclude_recent_picks(&mut articles, &manifest, "2026-03-12"), 0);
+        assert_eq!(articles.len(), 1);
+    }
+
+    #[test]
+    fn test_title_similarity() {
+        let similarity = |a: &str, b: &str| title_similarity(&title_words(a), &title_words(b));
+        assert_eq!(similarity("Inside the Linux Scheduler", "Inside the Linux scheduler!"), 1.0);
+        assert!(similarity("How We Cut Tail Latency by 90%", "How we cut tail latency by 90% | Medium") >= SIMILAR_TITLE_THRESHOLD);
+        assert!(similarity("How We Cut Tail Latency by 90%", "How We Cut Build Times by 90%") < SIMILAR_TITLE_THRESHOLD);
+        // Short titles aren't compared
+        assert_eq!(similarity("Rust 2024", "Rust 2024"), 0.0);
+    }
+
+    fn with_epsilon(epsilon: f64, recent_entries: usize) -> Exploration {
+        Exploration { epsilon, recent_entries, seed: Some(42), ..Exploration::default() }
+    }
+
+    #[test]
+    fn test_epsilon_zero_never_explores() {
+        let (articles, manifest) = fixture();
+        let exploration = with_epsilon(0.0, 2);
+        let mut rng = exploration.rng();
+        for _ in 0..100 {
+            assert_eq!(maybe_explore(&articles, &manifest, &exploration, &mut rng), None);
+        }
+    }
+
+    #[test]
+    fn test_epsilon_one_always_explores() {
+        let (articles, manifest) = fixture();
+        let exploration = with_epsilon(1.0, 2);
+        let mut rng = exploration.rng();
+        for _ in 0..100 {
+            let idx = maybe_explore(&articles, &manifest, &exploration, &mut rng).unwrap();
+            assert!(idx == 2 || idx == 3, "picked recently featured source {}", articles[idx].source);
+        }
+    }
+
+    #[test]
+    fn test_score_weighted_exploration_favours_high_scores() {
+        let (mut articles, manifest) = fixture();
+        // Small Blog and Niche Blog are eligible; only Niche Blog's story scored
+        articles[3].score = Some(99);
+        let count_niche = |mode: ExplorationMode| {
+            let exploration = Exploration { mode, ..with_epsilon(1.0, 2) };
+            let mut rng = exploration.rng();
+            (0..1000).filter(|_| maybe_explore(&articles, &manifest, &exploration, &mut rng) == Some(3)).count()
+        };
+        // Weights 1 and 100: Niche Blog about 99% of the time, against about half when uniform
+        assert!(count_niche(ExplorationMode::ScoreWeighted) > 950);
+        let uniform = count_niche(ExplorationMode::Uniform);
+        assert!((400..600).contains(&uniform), "{}", uniform);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_exploration_mode_from_env() {
+        for (value, expected) in [(None, ExplorationMode::Uniform), (Some("score"), ExplorationMode::ScoreWeighted), (Some(" Uniform "), ExplorationMode::Uniform), (Some("greedy"), ExplorationMode::Uniform)] {
+            match value {
+                Some(v) => std::env::set_var(EXPLORATION_MODE_ENV_VAR, v),
+                None => std::env::remove_var(EXPLORATION_MODE_ENV_VAR),
+            }
+            assert_eq!(Exploration::from_env().mode, expected, "{:?}", value);
+        }
+        std::env::remove_var(EXPLORATION_MODE_ENV_VAR);
+    }
+
+    #[test]
+    fn test_recently_featured_exclusion() {
+        let (articles, manifest) = fixture();
+        // Last two entries: Big Blog by name, news.example.org by domain
+        assert_eq!(exploration_pool(&articles, &manifest, 2), vec![2, 3]);
+        // Widening the window also excludes Niche Blog
+        assert_eq!(exploration_pool(&articles, &manifest, 3), vec![2]);
+        assert_eq!(exploration_pool(&articles, &manifest, 0), vec![0, 1, 2, 3]);
+
+        // Beta entries neither fill the window nor exclude their own sources
+        let beta = |source| ManifestEntry { prompt_version: Some("v3".to_string()), ..entry("2026-04-03", Some(source), "https://beta.example.com/post") };
+        let with_beta: Vec<ManifestEntry> = [beta("Small Blog"), beta("Small Blog")].into_iter().chain(manifest).collect();
+        assert_eq!(exploration_pool(&articles, &with_beta, 2), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_exploration_falls_back_when_pool_empty() {
+        let (articles, manifest) = fixture();
+        let exploration = with_epsilon(1.0, 3);
+        let mut rng = exploration.rng();
+        assert_eq!(maybe_explore(&articles[..2], &manifest, &exploration, &mut rng), None);
+    }
+
+    #[test]
+    fn test_majority_vote() {
+        assert_eq!(majority_vote(&[]), None);
+        assert_eq!(majority_vote(&[4]), Some(4));
+        assert_eq!(majority_vote(&[4, 7, 7]), Some(7));
+        // Ties go to the leader voted for first
+        assert_eq!(majority_vote(&[4, 7]), Some(4));
+        assert_eq!(majority_vote(&[1, 3, 2, 2, 3]), Some(3));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_selection_votes_from_env() {
+        std::env::remove_var("SELECTION_VOTES");
+        assert_eq!(selection_votes_from_env(), 1);
+        std::env::set_var("SELECTION_VOTES", "5");
+        assert_eq!(selection_votes_from_env(), 5);
+        std::env::set_var("SELECTION_VOTES", "50");
+        assert_eq!(selection_votes_from_env(), MAX_SELECTION_VOTES);
+        std::env::set_var("SELECTION_VOTES", "0");
+        assert_eq!(selection_votes_from_env(), 1);
+        std::env::set_var("SELECTION_VOTES", "many");
+        assert_eq!(selection_votes_from_env(), 1);
+        std::env::remove_var("SELECTION_VOTES");
+    }
+
+    #[test]
+    fn test_seeded_rng_is_reproducible() {
+        let (articles, manifest) = fixture();
+        let exploration = with_epsilon(0.5, 0);
+        let picks = |exploration: &Exploration| {
+            let mut rng = exploration.rng();
+            (0..20).map(|_| maybe_explore(&articles, &manifest, exploration, &mut rng)).collect::<Vec<_>>()
+        };
+        assert_eq!(picks(&exploration), picks(&exploration));
+    }
+}
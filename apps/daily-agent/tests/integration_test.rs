@@ -46,6 +46,7 @@ async fn test_provider_mock(
         LlmProvider::Gemini => "Mocked Gemini Response",
         LlmProvider::OpenAI => "Mocked OpenAI Response",
         LlmProvider::Claude => "Mocked Claude Response",
+        LlmProvider::Echo => unreachable!("echo sends no request"),
     };
     assert_eq!(result.unwrap(), expected_response);
 }
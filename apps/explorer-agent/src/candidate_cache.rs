@@ -0,0 +1,271 @@
+//! Memory of previously examined candidate URLs, so unchanged candidates aren't
+//! re-downloaded and re-judged by the LLM on every run.
+//!
+//! Each candidate is remembered with a hash of its normalized content (and the server's
+//! ETag when it sent one) plus the verdict it got. A later run reuses the verdict while
+//! the content is unchanged and re-validates as soon as it changes.
+
+use std::collections::BTreeMap;
+
+use llm_client::state::{schema, unversioned};
+use llm_client::{load_or_default, save_state, Migration, ObjectStore, SourceConfig, StorageError};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::AGENT_NAME;
+
+/// Object path of the candidate memory.
+pub(crate) fn memory_path() -> String {
+    format!("runs/{}/candidate_memory.json", AGENT_NAME)
+}
+
+/// Schema migrations of the candidate memory; see [`llm_client::state`].
+const CANDIDATE_MEMORY_MIGRATIONS: [Migration; schema::CANDIDATE_MEMORY as usize] = [unversioned];
+
+/// Outcome of validating a candidate.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CandidateVerdict {
+    Accepted,
+    /// Borderline relevance: looked at again once the content changes.
+    Deferred,
+    Rejected,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct CandidateRecord {
+    pub(crate) content_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) etag: Option<String>,
+    pub(crate) verdict: CandidateVerdict,
+    /// The validated feed, for accepted candidates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) source: Option<SourceConfig>,
+    pub(crate) checked_at: String,
+}
+
+/// Candidate records keyed by the candidate URL as recommended.
+pub(crate) type CandidateMemory = BTreeMap<String, CandidateRecord>;
+
+/// Candidate memory for one run, with hit/miss counts for the run report.
+#[derive(Debug, Default)]
+pub(crate) struct CandidateCache {
+    pub(crate) memory: CandidateMemory,
+    pub(crate) hits: usize,
+    pub(crate) misses: usize,
+}
+
+impl CandidateCache {
+    pub(crate) fn new(memory: CandidateMemory) -> Self {
+        Self { memory, ..Default::default() }
+    }
+}
+
+/// What a cheap fetch of the candidate URL found.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct Probe {
+    /// The server answered 304 to our `If-None-Match`.
+    pub(crate) not_modified: bool,
+    pub(crate) content_hash: Option<String>,
+    pub(crate) etag: Option<String>,
+}
+
+/// Channel/feed-level elements that change on every regeneration without new content.
+/// Item/entry timestamps are real content and are kept.
+const VOLATILE_HEADER_ELEMENTS: [&str; 5] = ["lastBuildDate", "pubDate", "updated", "generator", "ttl"];
+
+/// Remove every `<tag ...>...</tag>` (or self-closing `<tag/>`) from `text`.
+fn strip_element(text: &str, tag: &str) -> String {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        // Only whole tag names: `<updated>` but not `<updatedBy>`
+        if !after.starts_with(['>', '/', ' ', '\t', '\n', '\r']) {
+            out.push_str(&rest[..start + open.len()]);
+            rest = after;
+            continue;
+        }
+        out.push_str(&rest[..start]);
+        let self_closing = after.find('>').is_some_and(|gt| after[..gt].ends_with('/'));
+        let end = if self_closing {
+            after.find('>').map(|gt| start + open.len() + gt + 1)
+        } else {
+            rest[start..].find(&close).map(|i| start + i + close.len())
+        };
+        match end {
+            Some(end) => rest = &rest[end..],
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Normalize feed (or page) content for hashing: drop volatile header elements and
+/// collapse whitespace, so trivial regenerations hash the same.
+pub(crate) fn normalize_feed(content: &str) -> String {
+    let split = ["<item", "<entry"].iter().filter_map(|t| content.find(t)).min().unwrap_or(content.len());
+    let (header, body) = content.split_at(split);
+    let mut header = header.to_string();
+    for tag in VOLATILE_HEADER_ELEMENTS {
+        header = strip_element(&header, tag);
+    }
+    let body = strip_element(body, "lastBuildDate");
+    format!("{}{}", header, body).split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub(crate) fn content_hash(content: &str) -> String {
+    format!("sha256:{:x}", Sha256::digest(normalize_feed(content).as_bytes()))
+}
+
+/// The remembered record for `url`, if the probe shows its content is unchanged.
+pub(crate) fn reusable<'a>(memory: &'a CandidateMemory, url: &str, probe: &Probe) -> Option<&'a CandidateRecord> {
+    let record = memory.get(url)?;
+    let unchanged = probe.not_modified || probe.content_hash.as_deref() == Some(record.content_hash.as_str());
+    unchanged.then_some(record)
+}
+
+/// Remember a fresh verdict. Without a content hash there's nothing to compare next time.
+pub(crate) fn remember(
+    memory: &mut CandidateMemory,
+    url: &str,
+    probe: &Probe,
+    verdict: CandidateVerdict,
+    source: Option<SourceConfig>,
+    today: &str,
+) {
+    if let Some(hash) = &probe.content_hash {
+        memory.insert(url.to_string(), CandidateRecord {
+            content_hash: hash.clone(),
+            etag: probe.etag.clone(),
+            verdict,
+            source,
+            checked_at: today.to_string(),
+        });
+    }
+}
+
+/// Fetch the candidate URL, sending the remembered ETag so unchanged content costs a 304.
+pub(crate) async fn probe_candidate(client: &reqwest::Client, url: &str, known: Option<&CandidateRecord>) -> Result<Probe, reqwest::Error> {
+    let mut request = client.get(url);
+    if let Some(etag) = known.and_then(|r| r.etag.as_deref()) {
+        request = request.header("If-None-Match", etag);
+    }
+    let res = request.send().await?;
+    if res.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(Probe { not_modified: true, ..Default::default() });
+    }
+    let etag = res.headers().get("etag").and_then(|v| v.to_str().ok()).map(|s| s.to_string());
+    let text = res.error_for_status()?.text().await?;
+    Ok(Probe { not_modified: false, content_hash: Some(content_hash(&text)), etag })
+}
+
+/// Load the candidate memory. A missing, unreadable or newer-version file starts from empty.
+pub(crate) async fn load_candidate_memory<S: ObjectStore>(store: &S) -> CandidateMemory {
+    let path = memory_path();
+    load_or_default(store, &path, &CANDIDATE_MEMORY_MIGRATIONS).await.unwrap_or_else(|e| {
+        warn!(path = %path, error = %e, "Failed to load candidate memory");
+        CandidateMemory::new()
+    })
+}
+
+pub(crate) async fn save_candidate_memory<S: ObjectStore>(store: &S, memory: &CandidateMemory) -> Result<(), StorageError> {
+    save_state(store, &memory_path(), memory, schema::CANDIDATE_MEMORY).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llm_client::SourceType;
+
+    const FEED: &str = r#"<?xml version="1.0"?>
+<rss version="2.0"><channel>
+  <title>Systems Blog</title>
+  <lastBuildDate>Mon, 06 Apr 2026 10:00:00 GMT</lastBuildDate>
+  <generator>Hugo 0.120</generator>
+  <item><title>Lock-free queues</title><pubDate>Sun, 05 Apr 2026 08:00:00 GMT</pubDate></item>
+</channel></rss>"#;
+
+    fn probe_of(content: &str) -> Probe {
+        Probe { not_modified: false, content_hash: Some(content_hash(content)), etag: None }
+    }
+
+    fn memory_with(content: &str, verdict: CandidateVerdict) -> CandidateMemory {
+        let mut memory = CandidateMemory::new();
+        let source = SourceConfig::new("Systems Blog", SourceType::Rss, "https://sys.example.com/feed");
+        let source = (verdict == CandidateVerdict::Accepted).then_some(source);
+        remember(&mut memory, "https://sys.example.com", &probe_of(content), verdict, source, "2026-04-06");
+        memory
+    }
+
+    #[test]
+    fn test_unchanged_content_reuses_verdict() {
+        let memory = memory_with(FEED, CandidateVerdict::Accepted);
+        let record = reusable(&memory, "https://sys.example.com", &probe_of(FEED)).unwrap();
+        assert_eq!(record.verdict, CandidateVerdict::Accepted);
+        assert_eq!(record.source.as_ref().unwrap().url, "https://sys.example.com/feed");
+
+        let not_modified = Probe { not_modified: true, ..Default::default() };
+        assert!(reusable(&memory, "https://sys.example.com", &not_modified).is_some());
+        assert!(reusable(&memory, "https://other.example.com", &probe_of(FEED)).is_none());
+    }
+
+    #[test]
+    fn test_changed_content_misses() {
+        let changed = FEED.replace("<item>", "<item><title>New post</title></item><item>");
+        for verdict in [CandidateVerdict::Deferred, CandidateVerdict::Rejected, CandidateVerdict::Accepted] {
+            let memory = memory_with(FEED, verdict);
+            assert!(reusable(&memory, "https://sys.example.com", &probe_of(&changed)).is_none(), "{:?}", verdict);
+        }
+    }
+
+    #[test]
+    fn test_volatile_fields_do_not_change_hash() {
+        let regenerated = FEED
+            .replace("Mon, 06 Apr 2026 10:00:00 GMT", "Tue, 07 Apr 2026 03:12:45 GMT")
+            .replace("Hugo 0.120", "Hugo 0.121")
+            .replace("  <title>", "<title>");
+        assert_eq!(content_hash(FEED), content_hash(&regenerated));
+
+        // Item dates are content, not noise
+        let redated = FEED.replace("Sun, 05 Apr 2026", "Sat, 04 Apr 2026");
+        assert_ne!(content_hash(FEED), content_hash(&redated));
+    }
+
+    #[test]
+    fn test_atom_feed_level_updated_is_volatile() {
+        let atom = |feed_updated: &str, entry_updated: &str| format!(
+            "<feed><title>T</title><updated>{}</updated><entry><title>A</title><updated>{}</updated></entry></feed>",
+            feed_updated, entry_updated
+        );
+        assert_eq!(content_hash(&atom("2026-04-06", "2026-04-01")), content_hash(&atom("2026-04-07", "2026-04-01")));
+        assert_ne!(content_hash(&atom("2026-04-06", "2026-04-01")), content_hash(&atom("2026-04-06", "2026-04-02")));
+    }
+
+    #[test]
+    fn test_strip_element_keeps_longer_tag_names() {
+        assert_eq!(strip_element("<updatedBy>x</updatedBy><updated>y</updated>", "updated"), "<updatedBy>x</updatedBy>");
+        assert_eq!(strip_element("a<ttl/>b", "ttl"), "ab");
+    }
+
+    #[tokio::test]
+    async fn test_memory_round_trip_and_legacy_file() {
+        let store = llm_client::MemoryStore::new();
+        let memory = memory_with(FEED, CandidateVerdict::Deferred);
+        // Written before the versioned wrapper
+        store.put(&memory_path(), serde_json::to_vec(&memory).unwrap()).await.unwrap();
+        assert_eq!(load_candidate_memory(&store).await, memory);
+
+        save_candidate_memory(&store, &memory).await.unwrap();
+        let saved: serde_json::Value = serde_json::from_slice(&store.object(&memory_path()).unwrap()).unwrap();
+        assert_eq!(saved["schema_version"], schema::CANDIDATE_MEMORY);
+        assert_eq!(load_candidate_memory(&store).await, memory);
+    }
+}
@@ -1,8 +1,4 @@
-use serde::Deserialize;
 use gcloud_storage::client::{Client, ClientConfig};
-use gcloud_storage::http::objects::download::Range;
-use gcloud_storage::http::objects::get::GetObjectRequest;
-use gcloud_storage::http::objects::upload::{UploadObjectRequest, UploadType, Media};
 use select::document::Document;
 use select::predicate::{Name, Attr, Predicate};
 use std::collections::HashSet;
@@ -12,80 +8,230 @@ use rss::Channel;
 use atom_syndication::Feed;
 use tracing::{info, warn, error, debug, instrument};
 use std::time::Duration as StdDuration;
-use llm_client::{call_llm_with_retry, init_logging, SourceConfig, SourceType, extract_domain, DEFAULT_BUCKET, LlmProvider};
+use llm_client::{
+    init_logging, resolve_api_key, SourceRules, BudgetGuard, CircuitBreaker, LlmClient, SourceConfig, SourceType, extract_domain, DEFAULT_BUCKET, LlmProvider,
+    get_model_env_var, EffectiveConfig, ConfigSource, GcsStore, PromptTemplates, RunReport, check_config_drift, save_config_snapshot, build_http_client, run_id, HttpClientConfig, DEFAULT_USER_AGENT, HTTP_USER_AGENT_ENV_VAR, AUDIT_PREFIX_ENV_VAR, audit_sink_from_env,
+    ParsedList, SOURCE_SCHEMA_HINT, parse_json_list,
+    OnboardingThresholds, ProvenanceMap, SourceOrigin, SourceProvenance, close_onboarding_windows,
+    load_provenance, update_provenance, fetch_bytes_with, FetchOptions, RetryPolicy, CostTracker, LlmError, LlmOptions, PriceTable, parse_recommendations,
+    YesNo, normalize_yes_no, resolve_youtube_feed_url, rss_item_date, load_source_health, SourceHealth, SourceHealthMap,
+    is_future_dated, max_future_skew_from_env, is_rdf_feed, parse_rdf_items, parse_feed_date, rss_item_url, DEFAULT_MAX_FUTURE_SKEW_HOURS, FEED_MAX_FUTURE_SKEW_HOURS_ENV_VAR,
+};
+
+use llm_client::gcs_retry::{delete_object, download_object, upload_object};
+
+mod candidate_cache;
+
+use candidate_cache::{
+    CandidateCache, CandidateVerdict, load_candidate_memory, probe_candidate, remember, reusable, save_candidate_memory,
+};
 
 // --- Configuration Constants ---
+/// Agent name used for run reports and config snapshots under `runs/`
+const AGENT_NAME: &str = "explorer-agent";
 const HTTP_TIMEOUT_SECS: u64 = 30;
 const FRESHNESS_DAYS: i64 = 90;
 const MAX_FEED_DISCOVERY_ATTEMPTS: usize = 2;
+/// Freshness checks make a single attempt within `HTTP_TIMEOUT_SECS`
+const FRESHNESS_CHECK_RETRY: RetryPolicy = RetryPolicy { name: "freshness-check", max_attempts: Some(1), ..RetryPolicy::FAST_LOCAL };
+/// Consecutive permanent Gemini failures (e.g. a revoked key) before calls fail fast
+const CIRCUIT_BREAKER_FAILURES: u32 = 3;
+const CIRCUIT_BREAKER_COOL_DOWN_SECS: u64 = 600;
+
+// --- Prompt Templates ---
+// Defaults for the registry; `config/prompts.json` can override them.
+
+const RECOMMEND_PROMPT: &str = r#"You are discovering technical blogs for a senior engineering leader at a hedge fund who works on developer platforms, low-latency systems (C++/Rust), and AI tooling.
+
+Current sources: {current_sources}
+
+Recommend 5 NEW engineering blogs NOT in this list. Prioritize:
+1. Company engineering blogs with deep technical posts (systems, infrastructure, performance)
+2. Individual blogs by Staff/Principal engineers writing about architecture, leadership, or AI engineering
+3. Active blogs that published within the last 3 months
+4. Blogs with RSS or Atom feeds (most do — provide the feed URL if you know it, otherwise the main URL)
+
+Avoid: news aggregators, product marketing blogs, beginner tutorial sites.
+
+Return ONLY a valid JSON array: [{{"name": "Netflix TechBlog", "url": "https://netflixtechblog.com/feed"}}]
+Do not wrap in markdown fences."#;
+
+const RELEVANCE_PROMPT: &str = "Blog: '{name}' at {url}\n\n{content_context}\n\nIs this a technical engineering blog that publishes substantive, deep content relevant to a senior systems engineer (C++/Rust, infrastructure, AI tooling)? Not a news site, not marketing, not beginner tutorials.\n\nRespond ONLY with 'yes' or 'no', or 'maybe' if it is genuinely borderline.";
+
+const DEFAULT_PROMPTS: &[(&str, &str)] = &[
+    ("explorer_recommend", RECOMMEND_PROMPT),
+    ("explorer_relevance", RELEVANCE_PROMPT),
+];
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     dotenvy::dotenv().ok();
-    init_logging();
-
-    let api_key = std::env::var("GEMINI_API_KEY").map_err(|_| {
-        error!("GEMINI_API_KEY environment variable not set");
-        "GEMINI_API_KEY environment variable not set"
+    let _log_guard = init_logging();
+    // Root span of the run, so every log line carries its run_id
+    let _run_span = tracing::info_span!("run", run_id = %run_id()).entered();
+
+    // GEMINI_API_KEY, or the Secret Manager secret named by GEMINI_API_KEY_SECRET
+    let api_key = resolve_api_key(LlmProvider::Gemini).await.map_err(|e| {
+        error!(error = %e, "Failed to resolve the Gemini API key");
+        e
     })?;
-    let bucket_name = std::env::var("GCS_BUCKET").unwrap_or_else(|_| DEFAULT_BUCKET.to_string());
+    let mut effective_config = EffectiveConfig::new(AGENT_NAME, env!("CARGO_PKG_VERSION"));
+    let bucket_name = effective_config.env_or_default("bucket", "GCS_BUCKET", DEFAULT_BUCKET);
+    effective_config.record("provider", LlmProvider::Gemini.as_str(), ConfigSource::Default);
+    effective_config.env_or_default("gemini_model", get_model_env_var(LlmProvider::Gemini), LlmProvider::Gemini.model_name());
+    effective_config.record_secret("gemini_api_key", &api_key, ConfigSource::Env);
+    effective_config.record("sources_object", "config/sources.json", ConfigSource::Default);
+    effective_config.record("freshness_days", FRESHNESS_DAYS.to_string(), ConfigSource::Default);
+    effective_config.env_or_default("http_user_agent", HTTP_USER_AGENT_ENV_VAR, DEFAULT_USER_AGENT);
+    let thresholds = onboarding_thresholds(&mut effective_config);
 
     info!(bucket = %bucket_name, "Starting SE Explorer Agent");
 
     // 1. Initialize GCS Client
     let config = ClientConfig::default().with_auth().await?;
     let gcs_client = Client::new(config);
-    let http_client = reqwest::Client::builder()
-        .timeout(StdDuration::from_secs(HTTP_TIMEOUT_SECS))
-        .build()?;
+    let http_client = build_http_client(HttpClientConfig::from_env(StdDuration::from_secs(HTTP_TIMEOUT_SECS)))?;
+    let breaker = CircuitBreaker::new(CIRCUIT_BREAKER_FAILURES, StdDuration::from_secs(CIRCUIT_BREAKER_COOL_DOWN_SECS));
+    let mut llm = LlmClient::builder(LlmProvider::Gemini)
+        .http_client(http_client.clone())
+        .api_key(api_key)
+        .circuit_breaker(std::sync::Arc::new(breaker));
+    // Caps the run's relevance and recommendation calls
+    if let Some(budget) = BudgetGuard::from_env() {
+        info!(max_calls = ?budget.max_calls(), max_cost_usd = ?budget.max_cost_usd(), "LLM budget enabled");
+        llm = llm.budget(std::sync::Arc::new(budget));
+    }
+    // Every LLM call's prompt and reply excerpts, when LLM_AUDIT_PREFIX is set
+    let llm_audit = audit_sink_from_env(GcsStore::new(gcs_client.clone(), &bucket_name));
+    if let Some(audit) = &llm_audit {
+        llm = llm.audit(audit.clone());
+    }
+    effective_config.env_or_default("llm_audit_prefix", AUDIT_PREFIX_ENV_VAR, "off");
+    effective_config.env_or_default("feed_max_future_skew_hours", FEED_MAX_FUTURE_SKEW_HOURS_ENV_VAR, &DEFAULT_MAX_FUTURE_SKEW_HOURS.to_string());
+    let llm = llm.build()?;
+
+    // Compare resolved config against the previous run before doing any work
+    let store = GcsStore::new(gcs_client.clone(), &bucket_name);
+    let mut report = RunReport::new(AGENT_NAME, &Utc::now().format("%Y-%m-%d").to_string());
+    report.config_drift = check_config_drift(&store, &effective_config).await;
+    report.llm_usage = CostTracker::new(PriceTable::from_env());
+
+    let result = run_explorer(llm, bucket_name, gcs_client, &store, http_client, thresholds, &mut report).await;
+    if let Some(audit) = &llm_audit {
+        audit.flush().await;
+    }
 
+    report.llm_usage.log_summary();
+
+    // Persist the run report and config snapshot whether or not the run succeeded
+    report.finish(result.as_ref().err().map(|e| e.to_string()));
+    if let Err(e) = report.save(&store).await {
+        warn!(error = %e, "Failed to upload run report");
+    }
+    if let Err(e) = save_config_snapshot(&store, &effective_config).await {
+        warn!(error = %e, "Failed to upload effective config snapshot");
+    }
+
+    result
+}
+
+/// Onboarding verdict thresholds, overridable via `ONBOARDING_*` env vars.
+fn onboarding_thresholds(config: &mut EffectiveConfig) -> OnboardingThresholds {
+    let defaults = OnboardingThresholds::default();
+    let mut resolve = |key: &str, env_var: &str, default: u32| {
+        config.env_or_default(key, env_var, &default.to_string()).trim().parse().unwrap_or_else(|_| {
+            warn!(env_var = env_var, default = default, "Invalid threshold, using default");
+            default
+        })
+    };
+    OnboardingThresholds {
+        keep_min_selected: resolve("onboarding_keep_min_selected", "ONBOARDING_KEEP_MIN_SELECTED", defaults.keep_min_selected),
+        watch_min_articles: resolve("onboarding_watch_min_articles", "ONBOARDING_WATCH_MIN_ARTICLES", defaults.watch_min_articles),
+        watch_min_shortlisted: resolve("onboarding_watch_min_shortlisted", "ONBOARDING_WATCH_MIN_SHORTLISTED", defaults.watch_min_shortlisted),
+    }
+}
+
+/// Explorer pipeline: process user candidates, discover new sources, prune stale ones.
+async fn run_explorer(
+    llm: LlmClient,
+    bucket_name: String,
+    gcs_client: Client,
+    store: &GcsStore,
+    http_client: reqwest::Client,
+    thresholds: OnboardingThresholds,
+    report: &mut RunReport,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     // 2. Load Current Sources
     info!("Downloading current sources from GCS");
-    let sources_data = gcs_client.download_object(
-        &GetObjectRequest {
-            bucket: bucket_name.to_string(),
-            object: "config/sources.json".to_string(),
-            ..Default::default()
-        },
-        &Range::default()
-    ).await?;
-    let current_sources: Vec<SourceConfig> = serde_json::from_slice(&sources_data)?;
+    let sources_data = download_object(&gcs_client, &bucket_name, "config/sources.json").await?;
+    let sources_path = format!("gs://{}/config/sources.json", bucket_name);
+    let parsed_sources: ParsedList<SourceConfig> = parse_json_list(&sources_path, &sources_data, &SOURCE_SCHEMA_HINT)?;
+    parsed_sources.warn_rejected(&sources_path);
+    // Rewriting sources.json would silently drop the rejected entries, so leave it untouched
+    let sources_had_rejects = !parsed_sources.rejected.is_empty();
+    let current_sources = parsed_sources.entries;
     let initial_source_count = current_sources.len();
     info!(count = initial_source_count, "Loaded current sources");
 
     let mut all_sources: HashSet<SourceConfig> = current_sources.iter().cloned().collect();
+    let mut added_sources: Vec<(SourceConfig, SourceOrigin)> = Vec::new();
+    // Candidates breaking these are never added
+    let source_rules = SourceRules::from_env();
+
+    // Close onboarding windows of recently added sources; weak ones are re-validated below
+    let mut provenance = load_provenance(store).await.unwrap_or_else(|e| {
+        warn!(error = %e, "Failed to load source provenance");
+        ProvenanceMap::new()
+    });
+    let onboarding_outcomes = close_onboarding_windows(&mut provenance, &thresholds);
+    for outcome in &onboarding_outcomes {
+        info!(name = %outcome.name, verdict = ?outcome.verdict, stats = ?outcome.stats, "Onboarding window closed");
+    }
+    let revalidation_queue: HashSet<String> = provenance.iter()
+        .filter(|(_, p)| p.revalidate)
+        .map(|(name, _)| name.clone())
+        .collect();
+    let mut revalidated: Vec<String> = Vec::new();
+    let mut failed_revalidation: Vec<String> = Vec::new();
+
+    // Candidates examined on earlier runs are only re-validated when their content changed
+    let today = Utc::now().format("%Y-%m-%d").to_string();
+    let mut candidate_cache = CandidateCache::new(load_candidate_memory(store).await);
+    let templates = PromptTemplates::load(store, DEFAULT_PROMPTS).await;
 
     // 3. Process User Candidates (if any)
     let user_candidates_object_name = "config/user_candidates.json";
-    match gcs_client.download_object(
-        &GetObjectRequest {
-            bucket: bucket_name.to_string(),
-            object: user_candidates_object_name.to_string(),
-            ..Default::default()
-        },
-        &Range::default()
-    ).await {
+    match download_object(&gcs_client, &bucket_name, user_candidates_object_name).await {
         Ok(candidates_data) => {
             info!("Found user_candidates.json, processing new sources");
-            let user_recs: Vec<SourceConfig> = serde_json::from_slice(&candidates_data)?;
+            let candidates_path = format!("gs://{}/{}", bucket_name, user_candidates_object_name);
+            let parsed_recs: ParsedList<SourceConfig> = parse_json_list(&candidates_path, &candidates_data, &SOURCE_SCHEMA_HINT)?;
+            parsed_recs.warn_rejected(&candidates_path);
+            let user_recs = parsed_recs.entries;
 
             for rec in user_recs {
                 if !all_sources.contains(&rec) {
                     info!(name = %rec.name, url = %rec.url, "Investigating user candidate");
-                    match discover_and_validate_feed(&http_client, &api_key, &rec.url, &rec.name).await {
-                        Ok(Some(validated_source)) => {
-                            if !all_sources.contains(&validated_source) {
+                    match validate_candidate(&http_client, &llm, &templates, &rec.url, &rec.name, &mut candidate_cache, &today, &mut report.llm_usage).await {
+                        Ok(FeedValidation::Valid(validated_source)) => {
+                            // Keep the category, tags and other settings given with the candidate
+                            let validated_source = SourceConfig { source_type: validated_source.source_type, url: validated_source.url, ..rec.clone() };
+                            if let Some(errors) = source_errors(&validated_source, &source_rules) {
+                                warn!(name = %rec.name, url = %validated_source.url, errors = %errors, "Refusing invalid source");
+                            } else if !all_sources.contains(&validated_source) {
                                 info!(
                                     name = %validated_source.name,
                                     url = %validated_source.url,
                                     "Valid and relevant source found"
                                 );
+                                added_sources.push((validated_source.clone(), SourceOrigin::User));
                                 all_sources.insert(validated_source);
                             } else {
                                 debug!(name = %rec.name, "Validated source already exists, skipping");
                             }
                         },
-                        Ok(None) => debug!(name = %rec.name, "Invalid or irrelevant, skipping"),
+                        Ok(FeedValidation::Borderline) => info!(name = %rec.name, "Borderline relevance, deferring"),
+                        Ok(FeedValidation::Invalid) => debug!(name = %rec.name, "Invalid or irrelevant, skipping"),
                         Err(e) => warn!(name = %rec.name, error = %e, "Error processing candidate"),
                     }
                 } else {
@@ -94,15 +240,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
             }
             // Delete user_candidates.json after processing
             info!("Deleting user_candidates.json from GCS");
-            gcs_client.delete_object(
-                &gcloud_storage::http::objects::delete::DeleteObjectRequest {
-                    bucket: bucket_name.to_string(),
-                    object: user_candidates_object_name.to_string(),
-                    ..Default::default()
-                }
-            ).await?;
+            delete_object(&gcs_client, &bucket_name, user_candidates_object_name).await?;
         },
-        Err(e) if e.to_string().contains("No such object") => {
+        Err(e) if e.is_not_found() => {
             debug!("No user_candidates.json found, skipping");
         },
         Err(e) => error!(error = %e, "Error downloading user_candidates.json"),
@@ -112,63 +252,48 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     {
         info!("Asking Gemini for new recommendations (Explorer mode)");
         let existing_names_for_gemini: HashSet<String> = all_sources.iter().map(|s| s.name.clone()).collect();
-        let json_example = r#"[{"name": "Netflix TechBlog", "url": "https://netflixtechblog.com/feed"}]"#;
-        let prompt = format!(
-            r#"You are discovering technical blogs for a senior engineering leader at a hedge fund who works on developer platforms, low-latency systems (C++/Rust), and AI tooling.
-
-Current sources: {:?}
-
-Recommend 5 NEW engineering blogs NOT in this list. Prioritize:
-1. Company engineering blogs with deep technical posts (systems, infrastructure, performance)
-2. Individual blogs by Staff/Principal engineers writing about architecture, leadership, or AI engineering
-3. Active blogs that published within the last 3 months
-4. Blogs with RSS or Atom feeds (most do — provide the feed URL if you know it, otherwise the main URL)
-
-Avoid: news aggregators, product marketing blogs, beginner tutorial sites.
-
-Return ONLY a valid JSON array: {}
-Do not wrap in markdown fences."#,
-            existing_names_for_gemini, json_example
-        );
-
-        let response_text = call_llm_with_retry(&http_client, LlmProvider::Gemini, &api_key, prompt).await?;
-
-        let clean_json = clean_gemini_json(&response_text);
-
-        #[derive(Deserialize)]
-        struct Recommendation {
-            name: String,
-            url: String,
+        let current_sources = format!("{:?}", existing_names_for_gemini);
+        let prompt = templates.render("explorer_recommend", &[("current_sources", &current_sources)])?;
+
+        // Grounded in Google Search, so the feed URLs come from real pages rather than memory.
+        // Gemini doesn't allow JSON mode with search, so the prompt asks for JSON instead.
+        let grounded = LlmOptions { google_search: true, ..Default::default() };
+        let response = llm.generate_with(prompt, &grounded).await?;
+        report.llm_usage.record(&response);
+        if let Some(grounding) = &response.grounding {
+            info!(
+                queries = ?grounding.queries,
+                sources = ?grounding.sources.iter().map(|s| s.title.as_deref().unwrap_or(&s.uri)).collect::<Vec<_>>(),
+                "Recommendations grounded in search results"
+            );
         }
-
-        let recommendations: Vec<Recommendation> = match serde_json::from_str(clean_json) {
-            Ok(recs) => recs,
-            Err(e) => {
-                warn!(error = %e, raw_response = %clean_json, "Failed to parse Gemini JSON");
-                Vec::new()
-            }
-        };
+        let recommendations = parse_recommendations(&response.text);
 
         info!(count = recommendations.len(), "Gemini recommended new sources");
 
         for rec in recommendations {
-            let temp_source = SourceConfig { name: rec.name.clone(), source_type: SourceType::Rss, url: rec.url.clone() };
+            let temp_source = SourceConfig::new(rec.name.clone(), SourceType::Rss, rec.url.clone());
             if !all_sources.contains(&temp_source) {
                 info!(name = %rec.name, url = %rec.url, "Investigating Gemini recommendation");
-                match discover_and_validate_feed(&http_client, &api_key, &rec.url, &rec.name).await {
-                    Ok(Some(validated_source)) => {
-                        if !all_sources.contains(&validated_source) {
+                match validate_candidate(&http_client, &llm, &templates, &rec.url, &rec.name, &mut candidate_cache, &today, &mut report.llm_usage).await {
+                    Ok(FeedValidation::Valid(validated_source)) => {
+                        let validated_source = *validated_source;
+                        if let Some(errors) = source_errors(&validated_source, &source_rules) {
+                            warn!(name = %rec.name, url = %validated_source.url, errors = %errors, "Refusing invalid source");
+                        } else if !all_sources.contains(&validated_source) {
                             info!(
                                 name = %validated_source.name,
                                 url = %validated_source.url,
                                 "Valid and relevant source found"
                             );
+                            added_sources.push((validated_source.clone(), SourceOrigin::Explorer));
                             all_sources.insert(validated_source);
                         } else {
                             debug!(name = %rec.name, "Validated source already exists, skipping");
                         }
                     },
-                    Ok(None) => debug!(name = %rec.name, "Invalid or irrelevant, skipping"),
+                    Ok(FeedValidation::Borderline) => info!(name = %rec.name, "Borderline relevance, deferring"),
+                    Ok(FeedValidation::Invalid) => debug!(name = %rec.name, "Invalid or irrelevant, skipping"),
                     Err(e) => warn!(name = %rec.name, error = %e, "Error processing Gemini recommendation"),
                 }
             } else {
@@ -181,10 +306,26 @@ Do not wrap in markdown fences."#,
     info!(count = all_sources.len(), "Reviewing existing sources for freshness");
     let mut reviewed_sources = HashSet::new();
     let three_months_ago = Utc::now() - Duration::days(FRESHNESS_DAYS);
+    let max_future_skew = max_future_skew_from_env();
+    // Sources the daily agent quarantined for failing run after run are reviewed first
+    let source_health = load_source_health(store).await.unwrap_or_else(|e| {
+        warn!(error = %e, "Failed to load source health");
+        SourceHealthMap::new()
+    });
+    let review_order = quarantined_first(all_sources.iter(), &source_health);
+    let quarantined: Vec<&str> = review_order.iter()
+        .filter(|s| source_health.get(s.url.trim()).is_some_and(SourceHealth::is_quarantined))
+        .map(|s| s.name.as_str())
+        .collect();
+    if !quarantined.is_empty() {
+        info!(sources = ?quarantined, "Reviewing sources quarantined by the daily agent first");
+    }
+    report.set_section("quarantined_sources", &quarantined);
 
-    for source in all_sources.iter() {
-        // HN is always fresh - skip freshness check for it
-        if source.source_type == SourceType::HackerNews {
+    for source in review_order {
+        // Aggregators are always fresh, and a scrape source or Bluesky account has no feed to
+        // date - skip freshness check for them
+        if matches!(source.source_type, SourceType::HackerNews | SourceType::Lobsters | SourceType::Scrape | SourceType::Bluesky) {
             reviewed_sources.insert(source.clone());
             continue;
         }
@@ -196,8 +337,42 @@ Do not wrap in markdown fences."#,
             continue;
         }
 
+        if revalidation_queue.contains(&source.name) {
+            info!(name = %source.name, "Re-validating source after weak onboarding");
+            match discover_and_validate_feed(&http_client, &llm, &templates, &source.url, &source.name, &mut report.llm_usage).await {
+                Ok(FeedValidation::Valid(_)) => revalidated.push(source.name.clone()),
+                Ok(FeedValidation::Borderline) => {
+                    info!(name = %source.name, "Borderline on re-validation, keeping for another look next run");
+                }
+                Ok(FeedValidation::Invalid) => {
+                    info!(name = %source.name, "Source failed re-validation, removing");
+                    failed_revalidation.push(source.name.clone());
+                    continue;
+                }
+                Err(e) => warn!(name = %source.name, error = %e, "Error re-validating source, will retry next run"),
+            }
+        }
+
+        // A source needing a secret this job doesn't have can't be checked, which says
+        // nothing about the source itself
+        let options = match source.request_headers() {
+            Ok(headers) => FetchOptions { headers, timeout: source.timeout_secs.map(StdDuration::from_secs), ..Default::default() },
+            Err(e) => {
+                warn!(name = %source.name, error = %e, "Cannot build source headers, keeping without freshness check");
+                reviewed_sources.insert(source.clone());
+                continue;
+            }
+        };
         debug!(name = %source.name, url = %source.url, "Checking freshness");
-        match fetch_latest_pub_date(&http_client, &source.url).await {
+        let latest = match source.source_type {
+            // A YouTube channel is listed by URL or ID; its Atom feed has the upload dates
+            SourceType::Youtube => match resolve_youtube_feed_url(&http_client, &source.url, &options, &FRESHNESS_CHECK_RETRY).await {
+                Ok(feed_url) => fetch_latest_pub_date(&http_client, &feed_url, &options, max_future_skew).await,
+                Err(e) => Err(e.into()),
+            },
+            _ => fetch_latest_pub_date(&http_client, &source.url, &options, max_future_skew).await,
+        };
+        match latest {
             Ok(Some(latest_date)) => {
                 if latest_date > three_months_ago {
                     debug!(
@@ -228,33 +403,153 @@ Do not wrap in markdown fences."#,
     let sources_changed = updated_sources_vec.len() != initial_source_count
         || !updated_sources_vec.iter().all(|s| current_sources.contains(s));
 
-    if sources_changed {
+    report.set_section("sources", &serde_json::json!({
+        "before": initial_source_count,
+        "after": updated_sources_vec.len(),
+        "changed": sources_changed,
+    }));
+
+    report.set_section("onboarding", &serde_json::json!({
+        "verdicts": onboarding_outcomes,
+        "revalidated": revalidated,
+        "failed_revalidation": failed_revalidation,
+        "thresholds": thresholds,
+    }));
+
+    let mut sources_written = false;
+    if sources_changed && sources_had_rejects {
+        warn!("sources.json contains invalid entries; not rewriting it until they are fixed");
+    } else if sources_changed {
         info!(
             total = updated_sources_vec.len(),
             "Updating sources.json in GCS"
         );
         let updated_json = serde_json::to_vec_pretty(&updated_sources_vec)?;
 
-        gcs_client.upload_object(
-            &UploadObjectRequest {
-                bucket: bucket_name.to_string(),
-                ..Default::default()
-            },
-            updated_json,
-            &UploadType::Simple(Media::new("config/sources.json".to_string()))
-        ).await?;
+        upload_object(&gcs_client, &bucket_name, "config/sources.json", updated_json).await?;
         info!("Successfully updated sources.json in GCS");
+        sources_written = true;
     } else {
         info!("No changes to sources.json");
     }
 
+    report.set_section("candidate_memory", &serde_json::json!({
+        "hits": candidate_cache.hits,
+        "misses": candidate_cache.misses,
+        "remembered": candidate_cache.memory.len(),
+    }));
+    if let Err(e) = save_candidate_memory(store, &candidate_cache.memory).await {
+        warn!(error = %e, "Failed to save candidate memory");
+    }
+
+    // 7. Update provenance: verdicts, re-validation results, added and removed sources
+    let final_names: HashSet<&str> = updated_sources_vec.iter().map(|s| s.name.as_str()).collect();
+    if let Err(e) = update_provenance(store, |map| {
+        close_onboarding_windows(map, &thresholds);
+        for name in &revalidated {
+            if let Some(record) = map.get_mut(name) {
+                record.revalidate = false;
+            }
+        }
+        if sources_written {
+            for (source, origin) in &added_sources {
+                map.entry(source.name.clone())
+                    .or_insert_with(|| SourceProvenance::new(&source.url, &today, *origin));
+            }
+            map.retain(|name, _| final_names.contains(name.as_str()));
+        }
+    }).await {
+        warn!(error = %e, "Failed to update source provenance");
+    }
+
     info!("SE Explorer Agent completed successfully");
     Ok(())
 }
 
-#[instrument(skip(client, api_key), fields(source_name = %name, url_domain = %extract_domain(url)))]
-async fn discover_and_validate_feed(client: &reqwest::Client, api_key: &str, url: &str, name: &str) -> Result<Option<SourceConfig>, Box<dyn std::error::Error + Send + Sync>> {
+/// Why `source` may not be added to `sources.json`, if it breaks any rule.
+fn source_errors(source: &SourceConfig, rules: &SourceRules) -> Option<String> {
+    let errors = source.validate_with(rules);
+    (!errors.is_empty()).then(|| errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))
+}
+
+/// `sources` in review order: those quarantined in `health` first, the most failed runs
+/// first, then the rest, each group by name.
+fn quarantined_first<'a>(sources: impl IntoIterator<Item = &'a SourceConfig>, health: &SourceHealthMap) -> Vec<&'a SourceConfig> {
+    let mut sources: Vec<&SourceConfig> = sources.into_iter().collect();
+    sources.sort_by_cached_key(|s| {
+        let failures = health.get(s.url.trim()).filter(|h| h.is_quarantined()).map(|h| h.consecutive_failures);
+        (failures.is_none(), std::cmp::Reverse(failures), s.name.clone())
+    });
+    sources
+}
+
+/// Result of looking for a relevant feed behind a URL.
+#[derive(Debug, Clone, PartialEq)]
+enum FeedValidation {
+    Valid(Box<SourceConfig>),
+    /// A feed was found but the relevance judge was on the fence.
+    Borderline,
+    Invalid,
+}
+
+impl FeedValidation {
+    fn verdict(&self) -> CandidateVerdict {
+        match self {
+            FeedValidation::Valid(_) => CandidateVerdict::Accepted,
+            FeedValidation::Borderline => CandidateVerdict::Deferred,
+            FeedValidation::Invalid => CandidateVerdict::Rejected,
+        }
+    }
+}
+
+/// Validate a candidate, reusing the remembered verdict when its content hasn't changed
+/// since it was last examined.
+#[allow(clippy::too_many_arguments)]
+async fn validate_candidate(
+    client: &reqwest::Client,
+    llm: &LlmClient,
+    templates: &PromptTemplates,
+    url: &str,
+    name: &str,
+    cache: &mut CandidateCache,
+    today: &str,
+    cost: &mut CostTracker,
+) -> Result<FeedValidation, Box<dyn std::error::Error + Send + Sync>> {
+    let probe = match probe_candidate(client, url, cache.memory.get(url)).await {
+        Ok(probe) => probe,
+        Err(e) => {
+            debug!(url = %url, error = %e, "Candidate probe failed, validating without memory");
+            Default::default()
+        }
+    };
+    if let Some(record) = reusable(&cache.memory, url, &probe) {
+        let reused = match (record.verdict, &record.source) {
+            (CandidateVerdict::Accepted, Some(source)) => Some(FeedValidation::Valid(Box::new(source.clone()))),
+            (CandidateVerdict::Accepted, None) => None,
+            (CandidateVerdict::Deferred, _) => Some(FeedValidation::Borderline),
+            (CandidateVerdict::Rejected, _) => Some(FeedValidation::Invalid),
+        };
+        if let Some(validation) = reused {
+            info!(name = %name, verdict = ?record.verdict, checked_at = %record.checked_at, "Candidate unchanged, reusing verdict");
+            cache.hits += 1;
+            return Ok(validation);
+        }
+    }
+
+    cache.misses += 1;
+    let validation = discover_and_validate_feed(client, llm, templates, url, name, cost).await?;
+    let source = match &validation {
+        FeedValidation::Valid(source) => Some(SourceConfig::clone(source)),
+        _ => None,
+    };
+    remember(&mut cache.memory, url, &probe, validation.verdict(), source, today);
+    Ok(validation)
+}
+
+#[instrument(skip(client, llm, templates, cost), fields(source_name = %name, url_domain = %extract_domain(url)))]
+async fn discover_and_validate_feed(client: &reqwest::Client, llm: &LlmClient, templates: &PromptTemplates, url: &str, name: &str, cost: &mut CostTracker) -> Result<FeedValidation, Box<dyn std::error::Error + Send + Sync>> {
     let mut current_url_str = url.to_string();
+    let mut borderline = false;
 
     for _ in 0..MAX_FEED_DISCOVERY_ATTEMPTS {
         let res = client.get(&current_url_str).send().await?;
@@ -268,22 +563,26 @@ async fn discover_and_validate_feed(client: &reqwest::Client, api_key: &str, url
         let text = res.text().await?;
 
         let is_feed_content_type = content_type.contains("xml") || content_type.contains("rss") || content_type.contains("atom");
-        let is_rss = rss::Channel::read_from(text.as_bytes()).is_ok();
+        let is_rss = is_rss_feed(text.as_bytes());
         let is_atom = atom_syndication::Feed::read_from(text.as_bytes()).is_ok();
 
-        if is_feed_content_type
-            && (is_rss || is_atom)
-            && is_relevant_with_gemini(client, api_key, name, &final_url_str, &text).await?
-        {
-            let feed_type = if is_atom { SourceType::Atom } else { SourceType::Rss };
-            return Ok(Some(SourceConfig { name: name.to_string(), source_type: feed_type, url: final_url_str }));
+        if is_feed_content_type && (is_rss || is_atom) {
+            match is_relevant_with_gemini(llm, templates, name, &final_url_str, &text, cost).await? {
+                Relevance::Relevant => {
+                    let feed_type = if is_atom { SourceType::Atom } else { SourceType::Rss };
+                    return Ok(FeedValidation::Valid(Box::new(SourceConfig::new(name, feed_type, final_url_str))));
+                }
+                Relevance::Borderline => borderline = true,
+                Relevance::Irrelevant => {}
+            }
         }
 
         // HTML Discovery — find <link rel="alternate"> feed URLs
         let document = Document::from(text.as_str());
         for node in document.find(Name("link").and(Attr("rel", "alternate"))
                                    .and(Attr("type", "application/rss+xml")
-                                        .or(Attr("type", "application/atom+xml")))) {
+                                        .or(Attr("type", "application/atom+xml"))
+                                        .or(Attr("type", "application/rdf+xml")))) {
             if let Some(href) = node.attr("href") {
                 let Ok(base_url) = Url::parse(&final_url_str) else { continue };
                 let Ok(resolved_url) = base_url.join(href) else { continue };
@@ -299,8 +598,12 @@ async fn discover_and_validate_feed(client: &reqwest::Client, api_key: &str, url
                         } else {
                             SourceType::Rss
                         };
-                        if is_relevant_with_gemini(client, api_key, name, &resolved_url_str, &sample).await.unwrap_or(false) {
-                            return Ok(Some(SourceConfig { name: name.to_string(), source_type: feed_type, url: resolved_url_str }));
+                        match is_relevant_with_gemini(llm, templates, name, &resolved_url_str, &sample, cost).await.unwrap_or(Relevance::Irrelevant) {
+                            Relevance::Relevant => {
+                                return Ok(FeedValidation::Valid(Box::new(SourceConfig::new(name, feed_type, resolved_url_str))));
+                            }
+                            Relevance::Borderline => borderline = true,
+                            Relevance::Irrelevant => {}
                         }
                     }
                 }
@@ -331,77 +634,125 @@ async fn discover_and_validate_feed(client: &reqwest::Client, api_key: &str, url
             if let Ok(resp) = client.get(&candidate_url_str).send().await {
                 if resp.status().is_success() {
                     let feed_text = resp.text().await.unwrap_or_default();
-                    let is_rss = rss::Channel::read_from(feed_text.as_bytes()).is_ok();
+                    let is_rss = is_rss_feed(feed_text.as_bytes());
                     let is_atom = atom_syndication::Feed::read_from(feed_text.as_bytes()).is_ok();
                     if is_rss || is_atom {
                         let sample: String = feed_text.chars().take(2000).collect();
-                        if is_relevant_with_gemini(client, api_key, name, &candidate_url_str, &sample).await.unwrap_or(false) {
-                            let feed_type = if is_atom { SourceType::Atom } else { SourceType::Rss };
-                            return Ok(Some(SourceConfig { name: name.to_string(), source_type: feed_type, url: candidate_url_str }));
+                        match is_relevant_with_gemini(llm, templates, name, &candidate_url_str, &sample, cost).await.unwrap_or(Relevance::Irrelevant) {
+                            Relevance::Relevant => {
+                                let feed_type = if is_atom { SourceType::Atom } else { SourceType::Rss };
+                                return Ok(FeedValidation::Valid(Box::new(SourceConfig::new(name, feed_type, candidate_url_str))));
+                            }
+                            Relevance::Borderline => borderline = true,
+                            Relevance::Irrelevant => {}
                         }
                     }
                 }
             }
         }
     }
-    Ok(None)
+    Ok(if borderline { FeedValidation::Borderline } else { FeedValidation::Invalid })
 }
 
-#[instrument(skip(client), fields(url_domain = %extract_domain(feed_url)))]
-async fn fetch_latest_pub_date(client: &reqwest::Client, feed_url: &str) -> Result<Option<DateTime<Utc>>, Box<dyn std::error::Error + Send + Sync>> {
-    let content = client.get(feed_url).send().await?.bytes().await?;
-
-    // Try parsing as RSS
-    if let Ok(channel) = Channel::read_from(&content[..]) {
-        if let Some(latest_item) = channel.items().iter()
-            .filter_map(|item| item.pub_date())
-            .filter_map(|pub_date_str| DateTime::parse_from_rfc2822(pub_date_str).ok())
-            .max_by_key(|dt| *dt)
-        {
-            return Ok(Some(latest_item.with_timezone(&Utc)));
-        }
-    }
+#[instrument(skip(client, options), fields(url_domain = %extract_domain(feed_url)))]
+async fn fetch_latest_pub_date(
+    client: &reqwest::Client,
+    feed_url: &str,
+    options: &FetchOptions,
+    max_future_skew: Duration,
+) -> Result<Option<DateTime<Utc>>, Box<dyn std::error::Error + Send + Sync>> {
+    let content = fetch_bytes_with(client, feed_url, options, &FRESHNESS_CHECK_RETRY).await?;
+    Ok(latest_pub_date(&content, Utc::now(), max_future_skew))
+}
 
-    // Try parsing as Atom
-    if let Ok(feed) = Feed::read_from(&content[..]) {
-        if let Some(latest_entry) = feed.entries().iter()
+/// Whether `content` is an RSS feed: RSS 0.9x/2.0, or RSS 1.0 (RDF), which the daily agent
+/// reads under the same `rss` type whatever prefix its root uses.
+fn is_rss_feed(content: &[u8]) -> bool {
+    Channel::read_from(content).is_ok() || is_rdf_feed(content)
+}
+
+/// Newest item date in an RSS, RSS 1.0 (RDF) or Atom feed, leaving out dates more than `max_future_skew`
+/// after `now`. The daily agent clamps those to the fetch time; here that would make a feed
+/// whose only recent-looking post is dated years ahead pass as fresh.
+fn latest_pub_date(content: &[u8], now: DateTime<Utc>, max_future_skew: Duration) -> Option<DateTime<Utc>> {
+    let dates: Vec<DateTime<Utc>> = if let Some(items) = parse_rdf_items(content) {
+        items.iter().filter_map(|item| item.date.as_deref().and_then(parse_feed_date)).collect()
+    } else if let Ok(channel) = Channel::read_from(content) {
+        // Items the daily agent would drop for want of a page or an audio/video enclosure
+        // don't make a feed fresh
+        let is_episode = |e: &rss::Enclosure| e.mime_type().starts_with("audio/") || e.mime_type().starts_with("video/");
+        channel
+            .items()
+            .iter()
+            .filter(|item| rss_item_url(item).is_some() || item.enclosure().is_some_and(is_episode))
+            .filter_map(rss_item_date)
+            .collect()
+    } else if let Ok(feed) = Feed::read_from(content) {
+        feed.entries()
+            .iter()
             .map(|entry| {
                 entry.published()
                     .map(|d| d.with_timezone(&Utc))
                     .unwrap_or_else(|| entry.updated().with_timezone(&Utc))
             })
-            .max_by_key(|dt| *dt)
-        {
-            return Ok(Some(latest_entry));
-        }
+            .collect()
+    } else {
+        return None;
+    };
+
+    let (future, dates): (Vec<_>, Vec<_>) = dates.into_iter().partition(|date| is_future_dated(*date, now, max_future_skew));
+    if let Some(latest_future) = future.iter().max() {
+        warn!(future_dated = future.len(), latest = %latest_future.format("%Y-%m-%d"), "Ignoring feed items dated in the future");
     }
+    dates.into_iter().max()
+}
+
+/// The relevance judge's answer for a feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Relevance {
+    Relevant,
+    Borderline,
+    Irrelevant,
+}
 
-    Ok(None)
+impl Relevance {
+    /// An unrecognized answer counts as irrelevant.
+    fn from_answer(answer: Option<YesNo>) -> Self {
+        match answer {
+            Some(YesNo::Yes) => Relevance::Relevant,
+            Some(YesNo::Maybe) => Relevance::Borderline,
+            Some(YesNo::No) | None => Relevance::Irrelevant,
+        }
+    }
 }
 
-#[instrument(skip(client, api_key, content_sample), fields(source_name = %name))]
-async fn is_relevant_with_gemini(client: &reqwest::Client, api_key: &str, name: &str, url: &str, content_sample: &str) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+#[instrument(skip(llm, templates, content_sample, cost), fields(source_name = %name))]
+async fn is_relevant_with_gemini(llm: &LlmClient, templates: &PromptTemplates, name: &str, url: &str, content_sample: &str, cost: &mut CostTracker) -> Result<Relevance, Box<dyn std::error::Error + Send + Sync>> {
     let content_context = if content_sample.is_empty() {
         "No content sample available — judge by name and URL only.".to_string()
     } else {
         format!("Content sample:\n{}", content_sample)
     };
-    let prompt = format!(
-        "Blog: '{}' at {}\n\n{}\n\nIs this a technical engineering blog that publishes substantive, deep content relevant to a senior systems engineer (C++/Rust, infrastructure, AI tooling)? Not a news site, not marketing, not beginner tutorials.\n\nRespond ONLY with 'yes' or 'no'.",
-        name, url, content_context
-    );
-
-    let response = call_llm_with_retry(client, LlmProvider::Gemini, api_key, prompt).await?;
-    Ok(response.trim().to_lowercase().starts_with("yes"))
+    let prompt = templates.render("explorer_relevance", &[("name", name), ("url", url), ("content_context", &content_context)])?;
+
+    let response = call_gemini_tracked(llm, prompt, cost).await?;
+    // Models sometimes answer in the page's language ("Sí", "Oui", "是的"); ask once more only
+    // when the normalizer doesn't recognize the answer.
+    let answer = match normalize_yes_no(&response) {
+        Some(answer) => Some(answer),
+        None => llm.clarify_yes_no(&response, cost).await.unwrap_or_else(|e| {
+            warn!(error = %e, answer = %response, "Couldn't clarify the relevance answer");
+            None
+        }),
+    };
+    Ok(Relevance::from_answer(answer))
 }
 
-/// Clean Gemini JSON response by removing markdown code fences
-fn clean_gemini_json(response: &str) -> &str {
-    response.trim()
-        .trim_start_matches("```json")
-        .trim_start_matches("```")
-        .trim_end_matches("```")
-        .trim()
+/// Call Gemini, recording the call's token usage and estimated cost.
+async fn call_gemini_tracked(llm: &LlmClient, prompt: String, cost: &mut CostTracker) -> Result<String, LlmError> {
+    let response = llm.generate(prompt).await?;
+    cost.record(&response);
+    Ok(response.text)
 }
 
 #[cfg(test)]
@@ -409,29 +760,7 @@ mod tests {
     use super::*;
     use chrono::Datelike;
 
-    #[test]
-    fn test_clean_gemini_json_with_fences() {
-        let input = r#"```json
-[{"name": "Test", "url": "https://example.com"}]
-```"#;
-        let expected = r#"[{"name": "Test", "url": "https://example.com"}]"#;
-        assert_eq!(clean_gemini_json(input), expected);
-    }
-
-    #[test]
-    fn test_clean_gemini_json_plain() {
-        let input = r#"[{"name": "Test", "url": "https://example.com"}]"#;
-        assert_eq!(clean_gemini_json(input), input);
-    }
-
-    #[test]
-    fn test_clean_gemini_json_with_extra_whitespace() {
-        let input = r#"  ```json
-[{"name": "Test"}]
-```  "#;
-        let expected = r#"[{"name": "Test"}]"#;
-        assert_eq!(clean_gemini_json(input), expected);
-    }
+    const SKEW: Duration = Duration::hours(DEFAULT_MAX_FUTURE_SKEW_HOURS);
 
     #[test]
     fn test_parse_rss_date() {
@@ -445,6 +774,64 @@ mod tests {
         assert_eq!(dt.day(), 18);
     }
 
+    #[test]
+    fn test_latest_pub_date_reads_dc_and_atom_dates() {
+        let feed = include_str!("../tests/fixtures/rss_without_pubdate.xml");
+        let expected = DateTime::parse_from_rfc3339("2025-06-10T12:30:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(latest_pub_date(feed.as_bytes(), Utc::now(), SKEW), Some(expected));
+        assert_eq!(latest_pub_date(b"<rss version=\"2.0\"><channel><title>t</title><link>l</link><description>d</description></channel></rss>", Utc::now(), SKEW), None);
+    }
+
+    #[test]
+    fn test_latest_pub_date_counts_items_with_permalink_guids_or_episodes() {
+        let feed = |items: &str| format!("<rss version=\"2.0\"><channel><title>t</title><link>l</link><description>d</description>{}</channel></rss>", items);
+        let item = |url: &str, date: &str| format!("<item><title>t</title>{}<pubDate>{}</pubDate></item>", url, date);
+        let older = item("<link>https://news.example.com/p/older</link>", "Mon, 09 Jun 2025 12:00:00 +0000");
+        let permalink = item("<guid>https://news.example.com/p/newer</guid>", "Tue, 10 Jun 2025 12:00:00 +0000");
+        let tag = item("<guid isPermaLink=\"false\">tag:news.example.com,2025:newer</guid>", "Tue, 10 Jun 2025 12:00:00 +0000");
+        let now = DateTime::parse_from_rfc3339("2025-06-11T00:00:00Z").unwrap().with_timezone(&Utc);
+        let date = |text: &str| DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Utc);
+
+        assert_eq!(latest_pub_date(feed(&(older.clone() + &permalink)).as_bytes(), now, SKEW), Some(date("2025-06-10T12:00:00Z")));
+        assert_eq!(latest_pub_date(feed(&(older + &tag)).as_bytes(), now, SKEW), Some(date("2025-06-09T12:00:00Z")));
+        assert_eq!(latest_pub_date(feed(&tag).as_bytes(), now, SKEW), None);
+
+        // A podcast episode without a page still counts through its audio enclosure
+        let episode = item(r#"<enclosure url="https://pod.example.com/ep12.mp3" length="1" type="audio/mpeg"/>"#, "Tue, 10 Jun 2025 12:00:00 +0000");
+        assert_eq!(latest_pub_date(feed(&episode).as_bytes(), now, SKEW), Some(date("2025-06-10T12:00:00Z")));
+        let image = item(r#"<enclosure url="https://pod.example.com/cover.jpg" length="1" type="image/jpeg"/>"#, "Tue, 10 Jun 2025 12:00:00 +0000");
+        assert_eq!(latest_pub_date(feed(&image).as_bytes(), now, SKEW), None);
+    }
+
+    #[test]
+    fn test_rdf_feeds_are_rss_feeds_with_dc_dates() {
+        let feed = include_str!("../tests/fixtures/rdf_journal_club.rdf");
+        assert!(rss::Channel::read_from(feed.as_bytes()).is_err());
+        assert!(is_rss_feed(feed.as_bytes()));
+        let expected = DateTime::parse_from_rfc3339("2025-06-03T16:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(latest_pub_date(feed.as_bytes(), Utc::now(), SKEW), Some(expected));
+
+        assert!(is_rss_feed(include_str!("../tests/fixtures/rss_without_pubdate.xml").as_bytes()));
+        assert!(!is_rss_feed(include_str!("../tests/fixtures/atom_future_dated.xml").as_bytes()));
+        assert!(!is_rss_feed(b"<html><body>Not a feed</body></html>"));
+    }
+
+    #[test]
+    fn test_future_dated_items_do_not_make_a_stale_feed_fresh() {
+        let now = DateTime::parse_from_rfc3339("2025-06-10T12:00:00Z").unwrap().with_timezone(&Utc);
+        let date = |text: &str| DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Utc);
+
+        let rss = include_str!("../tests/fixtures/rss_future_dated_stale.xml");
+        let latest = latest_pub_date(rss.as_bytes(), now, SKEW).unwrap();
+        assert_eq!(latest, date("2024-11-04T08:00:00Z"));
+        assert!(latest < now - Duration::days(FRESHNESS_DAYS));
+
+        // A post a few hours ahead is within the skew and still counts
+        let atom = include_str!("../tests/fixtures/atom_future_dated.xml");
+        assert_eq!(latest_pub_date(atom.as_bytes(), now, SKEW), Some(date("2025-06-10T15:00:00Z")));
+        assert_eq!(latest_pub_date(atom.as_bytes(), now, Duration::zero()), Some(date("2025-06-09T10:00:00Z")));
+    }
+
     #[test]
     fn test_freshness_threshold() {
         let now = Utc::now();
@@ -459,29 +846,30 @@ mod tests {
         assert!(old < three_months_ago);
     }
 
+    #[test]
+    fn test_default_prompts_render() {
+        let templates = PromptTemplates::new(DEFAULT_PROMPTS);
+        let prompt = templates.render("explorer_recommend", &[("current_sources", "{\"Netflix TechBlog\"}")]).unwrap();
+        assert!(prompt.contains("Current sources: {\"Netflix TechBlog\"}"));
+        assert!(prompt.contains(r#"[{"name": "Netflix TechBlog", "url": "https://netflixtechblog.com/feed"}]"#));
+
+        let prompt = templates
+            .render("explorer_relevance", &[("name", "Blog"), ("url", "https://blog.example.com"), ("content_context", "Content sample:\nPosts")])
+            .unwrap();
+        assert!(prompt.starts_with("Blog: 'Blog' at https://blog.example.com\n\nContent sample:\nPosts\n\n"));
+    }
+
     #[test]
     fn test_source_config_equality() {
-        let s1 = SourceConfig {
-            name: "Test".to_string(),
-            source_type: SourceType::Rss,
-            url: "https://example.com/feed".to_string(),
-        };
-        let s2 = SourceConfig {
-            name: "Test".to_string(),
-            source_type: SourceType::Rss,
-            url: "https://example.com/feed".to_string(),
-        };
+        let s1 = SourceConfig::new("Test", SourceType::Rss, "https://example.com/feed");
+        let s2 = SourceConfig::new("Test", SourceType::Rss, "https://example.com/feed");
         assert_eq!(s1, s2);
     }
 
     #[test]
     fn test_source_config_hash_set() {
         let mut sources: HashSet<SourceConfig> = HashSet::new();
-        let s1 = SourceConfig {
-            name: "Test".to_string(),
-            source_type: SourceType::Rss,
-            url: "https://example.com/feed".to_string(),
-        };
+        let s1 = SourceConfig::new("Test", SourceType::Rss, "https://example.com/feed");
         let s2 = s1.clone();
 
         sources.insert(s1);
@@ -490,28 +878,16 @@ mod tests {
     }
 
     #[test]
-    fn test_clean_gemini_json_empty_string() {
-        assert_eq!(clean_gemini_json(""), "");
-    }
-
-    #[test]
-    fn test_clean_gemini_json_only_fences() {
-        assert_eq!(clean_gemini_json("```json\n```"), "");
-    }
-
-    #[test]
-    fn test_clean_gemini_json_generic_code_fence() {
-        let input = "```\n{\"key\": \"value\"}\n```";
-        assert_eq!(clean_gemini_json(input), "{\"key\": \"value\"}");
-    }
-
-    #[test]
-    fn test_clean_gemini_json_nested_content() {
-        let input = r#"```json
-{"sources": [{"name": "Blog", "url": "https://example.com"}]}
-```"#;
-        let expected = r#"{"sources": [{"name": "Blog", "url": "https://example.com"}]}"#;
-        assert_eq!(clean_gemini_json(input), expected);
+    fn test_rewritten_sources_keep_their_headers() {
+        let json = br#"[{"name": "Internal", "type": "rss", "url": "https://eng.example.com/feed", "headers": {"Authorization": "Bearer ${ENG_BLOG_TOKEN}"}}]"#;
+        let parsed: ParsedList<SourceConfig> = parse_json_list("sources.json", json, &SOURCE_SCHEMA_HINT).unwrap();
+        let mut sources: HashSet<SourceConfig> = parsed.entries.into_iter().collect();
+        // A recommendation of the same feed doesn't replace the configured entry
+        assert!(!sources.insert(SourceConfig::new("Internal Blog", SourceType::Rss, "https://eng.example.com/feed")));
+
+        let written: Vec<SourceConfig> = sources.into_iter().collect();
+        let written = serde_json::to_value(&written).unwrap();
+        assert_eq!(written, serde_json::from_slice::<serde_json::Value>(json).unwrap());
     }
 
     #[test]
@@ -530,21 +906,74 @@ mod tests {
 
     #[test]
     fn test_source_config_equality_and_inequality() {
-        let s1 = SourceConfig {
-            name: "Blog".to_string(),
-            source_type: SourceType::Rss,
-            url: "https://example.com/feed".to_string(),
-        };
+        let s1 = SourceConfig::new("Blog", SourceType::Rss, "https://example.com/feed");
         let s1_clone = s1.clone();
         assert_eq!(s1, s1_clone);
 
+        // Sources are keyed on their URL, so edits to other fields don't make a new source
         let different_name = SourceConfig { name: "Other".to_string(), ..s1.clone() };
-        assert_ne!(s1, different_name);
+        assert_eq!(s1, different_name);
 
-        let different_type = SourceConfig { source_type: SourceType::Atom, ..s1.clone() };
-        assert_ne!(s1, different_type);
+        let different_type = SourceConfig { source_type: SourceType::Atom, enabled: false, ..s1.clone() };
+        assert_eq!(s1, different_type);
 
         let different_url = SourceConfig { url: "https://other.com/feed".to_string(), ..s1.clone() };
         assert_ne!(s1, different_url);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_quarantined_sources_are_reviewed_first() {
+        let sources: HashSet<SourceConfig> = ["Alpha", "Dead", "Dying", "Flaky"]
+            .into_iter()
+            .map(|name| SourceConfig::new(name, SourceType::Rss, format!("https://{}.example.com/feed", name.to_lowercase())))
+            .collect();
+        let health = |failures: u32, quarantined: bool| SourceHealth {
+            consecutive_failures: failures,
+            quarantined_since: quarantined.then(|| "2026-05-01".to_string()),
+            ..SourceHealth::default()
+        };
+        let health: SourceHealthMap = [
+            ("https://dead.example.com/feed", health(30, true)),
+            ("https://dying.example.com/feed", health(5, true)),
+            // Failing, but not yet quarantined
+            ("https://flaky.example.com/feed", health(2, false)),
+        ]
+        .into_iter()
+        .map(|(url, h)| (url.to_string(), h))
+        .collect();
+
+        let order: Vec<&str> = quarantined_first(&sources, &health).iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(order, ["Dead", "Dying", "Alpha", "Flaky"]);
+    }
+
+    #[test]
+    fn test_source_errors_refuse_invalid_candidates() {
+        let rules = SourceRules::default();
+        assert_eq!(source_errors(&SourceConfig::new("Blog", SourceType::Rss, "https://example.com/feed"), &rules), None);
+        let errors = source_errors(&SourceConfig::new("", SourceType::Rss, "http://localhost/feed"), &rules).unwrap();
+        assert!(errors.contains("name is empty") && errors.contains("is local"), "{}", errors);
+    }
+
+    #[test]
+    fn test_relevance_from_answer() {
+        for (response, expected) in [
+            ("Yes", Relevance::Relevant),
+            ("  maybe, it mixes news and deep dives", Relevance::Borderline),
+            ("no", Relevance::Irrelevant),
+            ("", Relevance::Irrelevant),
+            ("Sí, es un blog técnico", Relevance::Relevant),
+            ("Oui", Relevance::Relevant),
+            ("Peut-être", Relevance::Borderline),
+            ("是的", Relevance::Relevant),
+            ("Nein", Relevance::Irrelevant),
+        ] {
+            assert_eq!(Relevance::from_answer(normalize_yes_no(response)), expected, "{:?}", response);
+        }
+    }
+
+    #[test]
+    fn test_feed_validation_verdict() {
+        assert_eq!(FeedValidation::Borderline.verdict(), CandidateVerdict::Deferred);
+        assert_eq!(FeedValidation::Invalid.verdict(), CandidateVerdict::Rejected);
+    }
+}
@@ -0,0 +1,336 @@
+//! Opt-in audit trail of LLM calls, for debugging prompt regressions.
+//!
+//! [`LlmOptions::audit`](crate::LlmOptions) (or [`LlmClientBuilder::audit`](crate::LlmClientBuilder))
+//! receives an [`AuditRecord`] after every call: a hash and excerpt of the prompt, an excerpt
+//! of the reply or the error, latency and tokens. Excerpts are capped and have the call's API
+//! keys redacted. [`StoreAuditSink`] writes each record as a JSON object to an [`ObjectStore`]
+//! from a background task, so the store's latency and failures stay off the call's path;
+//! [`audit_sink_from_env`] sets one up when `LLM_AUDIT_PREFIX` is set. Call
+//! [`AuditSink::flush`] before exiting so queued records are written.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use crate::run::run_id;
+use crate::storage::ObjectStore;
+use crate::{redact_secrets, ChatMessage, LlmError, LlmProvider, LlmResponse};
+
+/// Object prefix of audit records, e.g. `audit/`; unset turns auditing off.
+pub const AUDIT_PREFIX_ENV_VAR: &str = "LLM_AUDIT_PREFIX";
+/// Longest prompt or reply excerpt recorded, in characters.
+pub const AUDIT_EXCERPT_CHARS_ENV_VAR: &str = "LLM_AUDIT_EXCERPT_CHARS";
+pub const DEFAULT_AUDIT_EXCERPT_CHARS: usize = 500;
+
+/// One LLM call, as written to the audit trail.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuditRecord {
+    pub timestamp: DateTime<Utc>,
+    pub run_id: String,
+    pub provider: LlmProvider,
+    pub model: String,
+    /// Hex SHA-256 of the whole prompt, so identical prompts can be matched across runs
+    pub prompt_sha256: String,
+    pub prompt_excerpt: String,
+    /// `None` when the call failed
+    pub response_excerpt: Option<String>,
+    /// The error of a failed call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Including retries
+    pub latency_ms: u64,
+    pub tokens: AuditTokens,
+}
+
+/// Token counts of an [`AuditRecord`], when the provider reported them.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AuditTokens {
+    pub prompt: Option<u32>,
+    pub completion: Option<u32>,
+}
+
+impl AuditRecord {
+    /// Record of a call of `model` with `messages` that ended in `result` after `latency`.
+    /// Excerpts keep `excerpt_chars` characters, with `secrets` redacted.
+    pub(crate) fn new(
+        provider: LlmProvider,
+        model: String,
+        messages: &[ChatMessage],
+        result: &Result<LlmResponse, LlmError>,
+        latency: Duration,
+        excerpt_chars: usize,
+        secrets: &[&str],
+    ) -> Self {
+        let prompt = messages.iter().map(|m| m.content.as_str()).collect::<Vec<_>>().join("\n\n");
+        // Redacted before it's cut, so half a key can't slip through
+        let excerpt = |text: &str| redact_secrets(text, secrets).chars().take(excerpt_chars).collect::<String>();
+        let (model, response_excerpt, error, tokens) = match result {
+            Ok(response) => (
+                response.model.clone(),
+                Some(excerpt(&response.text)),
+                None,
+                AuditTokens { prompt: response.prompt_tokens, completion: response.completion_tokens },
+            ),
+            Err(e) => (model, None, Some(redact_secrets(&e.to_string(), secrets)), AuditTokens::default()),
+        };
+        Self {
+            timestamp: Utc::now(),
+            run_id: run_id().to_string(),
+            provider,
+            model,
+            prompt_sha256: format!("{:x}", Sha256::digest(prompt.as_bytes())),
+            prompt_excerpt: excerpt(&prompt),
+            response_excerpt,
+            error,
+            latency_ms: latency.as_millis() as u64,
+            tokens,
+        }
+    }
+}
+
+/// Receives an [`AuditRecord`] after every LLM call. [`AuditSink::record`] runs on the
+/// call's path, so it only queues the record; a sink's own failures never fail the call.
+pub trait AuditSink: fmt::Debug + Send + Sync {
+    /// Longest prompt or reply excerpt to record, in characters.
+    fn excerpt_chars(&self) -> usize {
+        DEFAULT_AUDIT_EXCERPT_CHARS
+    }
+
+    /// Queue `record` without waiting for it to be written.
+    fn record(&self, record: AuditRecord);
+
+    /// Wait until every queued record is written, e.g. before the process exits.
+    fn flush(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async {})
+    }
+}
+
+/// [`AuditSink`] writing each record to `<prefix><date>/<run id>/<sequence>-<provider>.json`
+/// in a store. Records are written in order by a task started with the first one (and again
+/// after a [`AuditSink::flush`]); write failures are logged.
+pub struct StoreAuditSink<S> {
+    store: Arc<S>,
+    prefix: String,
+    excerpt_chars: usize,
+    written: AtomicU64,
+    writer: Mutex<Option<AuditWriter>>,
+}
+
+/// The background task of a [`StoreAuditSink`] and its queue of records with their paths.
+struct AuditWriter {
+    queue: mpsc::UnboundedSender<(String, AuditRecord)>,
+    task: JoinHandle<()>,
+}
+
+impl<S> fmt::Debug for StoreAuditSink<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoreAuditSink").field("prefix", &self.prefix).field("excerpt_chars", &self.excerpt_chars).finish_non_exhaustive()
+    }
+}
+
+impl<S: ObjectStore + 'static> StoreAuditSink<S> {
+    /// Sink writing under `prefix`, which gets a trailing `/` when it has none.
+    pub fn new(store: S, prefix: &str, excerpt_chars: usize) -> Self {
+        let prefix = match prefix.trim().trim_end_matches('/') {
+            "" => String::new(),
+            prefix => format!("{}/", prefix),
+        };
+        Self { store: Arc::new(store), prefix, excerpt_chars, written: AtomicU64::new(0), writer: Mutex::new(None) }
+    }
+
+    /// Object path of the `sequence`th record of this run.
+    pub fn path(&self, record: &AuditRecord, sequence: u64) -> String {
+        format!("{}{}/{}/{:05}-{}.json", self.prefix, record.timestamp.format("%Y-%m-%d"), record.run_id, sequence, record.provider.as_str())
+    }
+
+    /// A task writing queued records to the store until the queue is closed.
+    fn start_writer(&self) -> AuditWriter {
+        let (queue, mut records) = mpsc::unbounded_channel::<(String, AuditRecord)>();
+        let store = self.store.clone();
+        let task = tokio::spawn(async move {
+            while let Some((path, record)) = records.recv().await {
+                let data = match serde_json::to_vec_pretty(&record) {
+                    Ok(data) => data,
+                    Err(e) => {
+                        warn!(error = %e, "Failed to serialize LLM audit record");
+                        continue;
+                    }
+                };
+                if let Err(e) = store.put(&path, data).await {
+                    warn!(path = %path, error = %e, "Failed to write LLM audit record");
+                }
+            }
+        });
+        AuditWriter { queue, task }
+    }
+}
+
+impl<S: ObjectStore + 'static> AuditSink for StoreAuditSink<S> {
+    fn excerpt_chars(&self) -> usize {
+        self.excerpt_chars
+    }
+
+    fn record(&self, record: AuditRecord) {
+        let path = self.path(&record, self.written.fetch_add(1, Ordering::Relaxed));
+        let mut writer = self.writer.lock().unwrap();
+        let writer = writer.get_or_insert_with(|| self.start_writer());
+        if writer.queue.send((path, record)).is_err() {
+            warn!("LLM audit writer stopped, dropping record");
+        }
+    }
+
+    fn flush(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            // Closing the queue lets the writer finish what's in it and stop
+            let Some(AuditWriter { queue, task }) = self.writer.lock().unwrap().take() else { return };
+            drop(queue);
+            if let Err(e) = task.await {
+                warn!(error = %e, "LLM audit writer failed");
+            }
+        })
+    }
+}
+
+/// A [`StoreAuditSink`] over `store` when `LLM_AUDIT_PREFIX` is set, with excerpts of
+/// `LLM_AUDIT_EXCERPT_CHARS` characters ([`DEFAULT_AUDIT_EXCERPT_CHARS`] when unset or invalid).
+pub fn audit_sink_from_env<S: ObjectStore + 'static>(store: S) -> Option<Arc<dyn AuditSink>> {
+    let prefix = std::env::var(AUDIT_PREFIX_ENV_VAR).ok().filter(|p| !p.trim().is_empty())?;
+    let excerpt_chars = match std::env::var(AUDIT_EXCERPT_CHARS_ENV_VAR) {
+        Ok(raw) => raw.trim().parse().unwrap_or_else(|_| {
+            warn!(value = %raw, "Invalid {}, using {}", AUDIT_EXCERPT_CHARS_ENV_VAR, DEFAULT_AUDIT_EXCERPT_CHARS);
+            DEFAULT_AUDIT_EXCERPT_CHARS
+        }),
+        Err(_) => DEFAULT_AUDIT_EXCERPT_CHARS,
+    };
+    Some(Arc::new(StoreAuditSink::new(store, &prefix, excerpt_chars)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStore;
+    use crate::{send_with_policy, LlmOptions, RetryPolicy};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy { max_attempts: Some(1), ..RetryPolicy::FAST_LOCAL }
+    }
+
+    #[tokio::test]
+    async fn test_calls_write_audit_records() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [{ "text": "A reply quoting sk-secret-key back." }],
+                "model": "claude-test",
+                "usage": { "input_tokens": 12, "output_tokens": 7 }
+            })))
+            .mount(&server)
+            .await;
+
+        let store = Arc::new(MemoryStore::default());
+        let sink = Arc::new(StoreAuditSink::new(store.clone(), "audit", 24));
+        let options = LlmOptions { base_url: Some(server.uri()), audit: Some(sink.clone()), ..Default::default() };
+        let prompt = "Summarize this article about caching, please.";
+        send_with_policy(&reqwest::Client::new(), LlmProvider::Claude, "sk-secret-key", &[ChatMessage::user(prompt)], &options, &policy())
+            .await
+            .unwrap();
+        sink.flush().await;
+
+        let paths = store.paths();
+        assert_eq!(paths.len(), 1, "{:?}", paths);
+        assert!(paths[0].starts_with("audit/") && paths[0].ends_with(&format!("/{}/00000-claude.json", run_id())), "{}", paths[0]);
+        let json: serde_json::Value = serde_json::from_slice(&store.object(&paths[0]).unwrap()).unwrap();
+        let mut keys: Vec<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["latency_ms", "model", "prompt_excerpt", "prompt_sha256", "provider", "response_excerpt", "run_id", "timestamp", "tokens"]);
+
+        let record: AuditRecord = serde_json::from_value(json).unwrap();
+        assert_eq!(record.provider, LlmProvider::Claude);
+        assert_eq!(record.model, "claude-test");
+        assert_eq!(record.prompt_sha256, format!("{:x}", Sha256::digest(prompt.as_bytes())));
+        assert_eq!(record.prompt_excerpt, "Summarize this article a");
+        assert_eq!(record.response_excerpt.as_deref(), Some("A reply quoting REDACTED"));
+        assert_eq!(record.tokens, AuditTokens { prompt: Some(12), completion: Some(7) });
+    }
+
+    #[tokio::test]
+    async fn test_failed_calls_record_the_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("bad request"))
+            .mount(&server)
+            .await;
+
+        let store = Arc::new(MemoryStore::default());
+        let sink = Arc::new(StoreAuditSink::new(store.clone(), "audit/", DEFAULT_AUDIT_EXCERPT_CHARS));
+        let options = LlmOptions {
+            base_url: Some(server.uri()),
+            model: Some("gpt-test".to_string()),
+            audit: Some(sink.clone()),
+            ..Default::default()
+        };
+        let result = send_with_policy(&reqwest::Client::new(), LlmProvider::OpenAI, "key", &[ChatMessage::user("hi")], &options, &policy()).await;
+        assert!(result.is_err());
+        sink.flush().await;
+
+        let paths = store.paths();
+        let record: AuditRecord = serde_json::from_slice(&store.object(&paths[0]).unwrap()).unwrap();
+        assert_eq!(record.model, "gpt-test");
+        assert_eq!(record.response_excerpt, None);
+        assert_eq!(record.error.as_deref(), Some("OpenAI API returned HTTP 400: bad request"));
+        assert_eq!(record.tokens, AuditTokens::default());
+    }
+
+    #[tokio::test]
+    async fn test_records_are_written_in_the_background_until_flushed() {
+        let store = Arc::new(MemoryStore::default());
+        let sink = StoreAuditSink::new(store.clone(), "audit", DEFAULT_AUDIT_EXCERPT_CHARS);
+        let record = |provider: LlmProvider| {
+            let result = Err(LlmError::EmptyResponse { provider });
+            AuditRecord::new(provider, "model".to_string(), &[ChatMessage::user("hi")], &result, Duration::ZERO, 10, &[])
+        };
+
+        // Queued without waiting for the store
+        sink.record(record(LlmProvider::Claude));
+        sink.record(record(LlmProvider::Gemini));
+        sink.flush().await;
+        assert_eq!(store.paths().len(), 2);
+
+        // Numbering carries on after a flush, so nothing is overwritten
+        sink.record(record(LlmProvider::OpenAI));
+        sink.flush().await;
+        let paths = store.paths();
+        assert_eq!(paths.len(), 3, "{:?}", paths);
+        assert!(paths.iter().any(|p| p.ends_with("/00002-openai.json")), "{:?}", paths);
+        // Flushing an idle sink returns at once
+        sink.flush().await;
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_sink_from_env() {
+        std::env::remove_var(AUDIT_PREFIX_ENV_VAR);
+        std::env::remove_var(AUDIT_EXCERPT_CHARS_ENV_VAR);
+        assert!(audit_sink_from_env(MemoryStore::default()).is_none());
+        std::env::set_var(AUDIT_PREFIX_ENV_VAR, " ");
+        assert!(audit_sink_from_env(MemoryStore::default()).is_none());
+
+        std::env::set_var(AUDIT_PREFIX_ENV_VAR, "audit/");
+        assert_eq!(audit_sink_from_env(MemoryStore::default()).unwrap().excerpt_chars(), DEFAULT_AUDIT_EXCERPT_CHARS);
+        std::env::set_var(AUDIT_EXCERPT_CHARS_ENV_VAR, "80");
+        assert_eq!(audit_sink_from_env(MemoryStore::default()).unwrap().excerpt_chars(), 80);
+        std::env::remove_var(AUDIT_PREFIX_ENV_VAR);
+        std::env::remove_var(AUDIT_EXCERPT_CHARS_ENV_VAR);
+    }
+}
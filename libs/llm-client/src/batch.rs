@@ -0,0 +1,122 @@
+//! Many independent prompts with bounded concurrency.
+//!
+//! Each prompt is retried on its own, so one failing prompt only fails its own slot.
+
+use std::fmt;
+
+use futures::stream::{self, StreamExt};
+use tracing::{info, instrument, warn};
+
+use crate::retry::RetryPolicy;
+use crate::{send_with_policy, ChatMessage, LlmError, LlmOptions, LlmProvider};
+
+/// A batch prompt that failed after its retries, with its position in the input.
+#[derive(Debug)]
+pub struct BatchFailure {
+    pub index: usize,
+    pub error: LlmError,
+}
+
+impl fmt::Display for BatchFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "prompt {}: {}", self.index, self.error)
+    }
+}
+
+impl std::error::Error for BatchFailure {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Send every prompt with exponential backoff retry, at most `max_concurrency` at a time
+/// (at least 1). Results are in input order. The provider's shared
+/// [`LlmLimiter`](crate::LlmLimiter) still applies on top of `max_concurrency`.
+#[instrument(skip(client, api_key, prompts), fields(provider = %provider.as_str(), prompts = prompts.len()))]
+pub async fn call_llm_batch(
+    client: &reqwest::Client,
+    provider: LlmProvider,
+    api_key: &str,
+    prompts: Vec<String>,
+    max_concurrency: usize,
+) -> Vec<Result<String, BatchFailure>> {
+    let (options, policy) = (LlmOptions::default(), RetryPolicy::llm_from_env());
+    let (options, policy) = (&options, &policy);
+    let results: Vec<Result<String, BatchFailure>> = stream::iter(prompts.into_iter().enumerate())
+        .map(|(index, prompt)| async move {
+            send_with_policy(client, provider, api_key, &[ChatMessage::user(prompt)], options, policy)
+                .await
+                .map(|response| response.text)
+                .map_err(|error| BatchFailure { index, error })
+        })
+        .buffered(max_concurrency.max(1))
+        .collect()
+        .await;
+
+    let failed = results.iter().filter(|r| r.is_err()).count();
+    for failure in results.iter().filter_map(|r| r.as_ref().err()) {
+        warn!(index = failure.index, error = %failure.error, "Batch prompt failed");
+    }
+    info!(succeeded = results.len() - failed, failed = failed, "LLM batch finished");
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Echoes each Claude prompt back, and rejects prompts containing "poison".
+    struct Echo;
+
+    impl wiremock::Respond for Echo {
+        fn respond(&self, request: &wiremock::Request) -> wiremock::ResponseTemplate {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let prompt = body["messages"][0]["content"].as_str().unwrap_or_default().to_string();
+            if prompt.contains("poison") {
+                return wiremock::ResponseTemplate::new(400).set_body_string("bad prompt");
+            }
+            wiremock::ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "content": [{ "text": format!("echo: {}", prompt) }] }))
+                .set_delay(std::time::Duration::from_millis(10))
+        }
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_batch_keeps_order_and_isolates_failures() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(Echo)
+            .expect(6)
+            .mount(&server)
+            .await;
+        std::env::set_var("CLAUDE_BASE_URL", server.uri());
+
+        let prompts: Vec<String> = (0..6).map(|i| if i == 3 { "poison".to_string() } else { format!("prompt {}", i) }).collect();
+        let client = reqwest::Client::new();
+        let results = call_llm_batch(&client, LlmProvider::Claude, "key", prompts, 2).await;
+        std::env::remove_var("CLAUDE_BASE_URL");
+
+        assert_eq!(results.len(), 6);
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Ok(text) => assert_eq!(text, &format!("echo: prompt {}", i)),
+                Err(failure) => {
+                    assert_eq!(failure.index, 3);
+                    assert!(matches!(failure.error, LlmError::Http { status: 400, .. }));
+                    assert!(failure.to_string().starts_with("prompt 3: "));
+                }
+            }
+        }
+        assert!(results[3].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_empty_batch() {
+        let client = reqwest::Client::new();
+        assert!(call_llm_batch(&client, LlmProvider::Gemini, "key", Vec::new(), 4).await.is_empty());
+    }
+}
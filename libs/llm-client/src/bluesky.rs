@@ -0,0 +1,110 @@
+//! Bluesky profile sources: from a handle, DID, `at://` URI or profile URL to the public
+//! `app.bsky.feed.getAuthorFeed` endpoint, which needs no authentication for public posts.
+
+use url::Url;
+
+const AUTHOR_FEED_URL: &str = "https://public.api.bsky.app/xrpc/app.bsky.feed.getAuthorFeed";
+/// Path of the endpoint, for sources that give its URL themselves
+const AUTHOR_FEED_PATH: &str = "/xrpc/app.bsky.feed.getAuthorFeed";
+/// Posts requested, the endpoint's maximum; most aren't link posts
+const AUTHOR_FEED_LIMIT: &str = "100";
+
+/// A DID such as `did:plc:z72i7hdynmk6r22z27h6tvur`.
+fn is_did(actor: &str) -> bool {
+    let mut parts = actor.splitn(3, ':');
+    parts.next() == Some("did")
+        && parts.next().is_some_and(|method| !method.is_empty() && method.chars().all(|c| c.is_ascii_lowercase()))
+        && parts.next().is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || "._:%-".contains(c)))
+}
+
+/// A handle such as `simonwillison.net`: two or more DNS labels.
+fn is_handle(actor: &str) -> bool {
+    let labels: Vec<&str> = actor.split('.').collect();
+    labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty() && !label.starts_with('-') && !label.ends_with('-') && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// The handle (lowercased) or DID a Bluesky source names: `@handle`, `handle`, a DID, an
+/// `at://` URI of the account or any of its records, or a `bsky.app/profile/...` URL.
+pub fn bluesky_actor(source_url: &str) -> Option<String> {
+    let source_url = source_url.trim();
+    let actor = if let Some(rest) = source_url.strip_prefix("at://") {
+        rest.split('/').next().unwrap_or_default()
+    } else if source_url.starts_with("http://") || source_url.starts_with("https://") {
+        let url = Url::parse(source_url).ok()?;
+        let mut segments = url.path_segments()?;
+        if segments.next() != Some("profile") {
+            return None;
+        }
+        // Percent-encoded colons of a DID in the path
+        let actor = segments.next()?;
+        return bluesky_actor(&actor.replace("%3A", ":").replace("%3a", ":"));
+    } else {
+        source_url.strip_prefix('@').unwrap_or(source_url)
+    };
+    if is_did(actor) {
+        Some(actor.to_string())
+    } else {
+        let handle = actor.to_lowercase();
+        is_handle(&handle).then_some(handle)
+    }
+}
+
+/// The `getAuthorFeed` URL of a Bluesky source (see [`bluesky_actor`]), replies left out, or
+/// the source's URL itself when it is already one. `None` when it names no account.
+pub fn bluesky_feed_url(source_url: &str) -> Option<String> {
+    let source_url = source_url.trim();
+    if source_url.contains(AUTHOR_FEED_PATH) {
+        return Some(source_url.to_string());
+    }
+    let actor = bluesky_actor(source_url)?;
+    let params = [("actor", actor.as_str()), ("filter", "posts_no_replies"), ("limit", AUTHOR_FEED_LIMIT)];
+    Url::parse_with_params(AUTHOR_FEED_URL, params).ok().map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bluesky_actor() {
+        for (source_url, expected) in [
+            ("simonwillison.net", Some("simonwillison.net")),
+            (" @Jay.bsky.team ", Some("jay.bsky.team")),
+            ("did:plc:z72i7hdynmk6r22z27h6tvur", Some("did:plc:z72i7hdynmk6r22z27h6tvur")),
+            ("at://did:plc:z72i7hdynmk6r22z27h6tvur", Some("did:plc:z72i7hdynmk6r22z27h6tvur")),
+            ("at://did:plc:z72i7hdynmk6r22z27h6tvur/app.bsky.feed.post/3kgx2jmwzqo2c", Some("did:plc:z72i7hdynmk6r22z27h6tvur")),
+            ("at://pfrazee.com", Some("pfrazee.com")),
+            ("https://bsky.app/profile/danabra.mov", Some("danabra.mov")),
+            ("https://bsky.app/profile/did:plc:fpruhuo22xkm5o7ttr2ktxdo/post/3kgx2jmwzqo2c", Some("did:plc:fpruhuo22xkm5o7ttr2ktxdo")),
+            ("https://bsky.app/profile/did%3Aplc%3Afpruhuo22xkm5o7ttr2ktxdo", Some("did:plc:fpruhuo22xkm5o7ttr2ktxdo")),
+            ("https://bsky.app/search?q=rust", None),
+            ("https://example.com/feed.xml", None),
+            ("localhost", None),
+            ("not a handle.com", None),
+            ("-bad.example.com", None),
+            ("did:plc:", None),
+            ("", None),
+        ] {
+            assert_eq!(bluesky_actor(source_url).as_deref(), expected, "{}", source_url);
+        }
+    }
+
+    #[test]
+    fn test_bluesky_feed_url() {
+        assert_eq!(
+            bluesky_feed_url("@simonwillison.net").as_deref(),
+            Some("https://public.api.bsky.app/xrpc/app.bsky.feed.getAuthorFeed?actor=simonwillison.net&filter=posts_no_replies&limit=100")
+        );
+        assert_eq!(
+            bluesky_feed_url("at://did:plc:z72i7hdynmk6r22z27h6tvur").as_deref(),
+            Some("https://public.api.bsky.app/xrpc/app.bsky.feed.getAuthorFeed?actor=did%3Aplc%3Az72i7hdynmk6r22z27h6tvur&filter=posts_no_replies&limit=100")
+        );
+        // An endpoint URL, e.g. of another AppView, is used as given
+        let own = "https://api.example.social/xrpc/app.bsky.feed.getAuthorFeed?actor=a.example.com";
+        assert_eq!(bluesky_feed_url(own).as_deref(), Some(own));
+        assert_eq!(bluesky_feed_url("https://bsky.app/"), None);
+    }
+}
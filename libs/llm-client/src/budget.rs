@@ -0,0 +1,169 @@
+//! Spending cap for a run's LLM calls, so a runaway loop can't make hundreds of calls.
+//!
+//! A [`BudgetGuard`] shared via `Arc` in [`LlmOptions::budget`](crate::LlmOptions) or
+//! [`LlmClientBuilder::budget`](crate::LlmClientBuilder) is consulted before each call: once
+//! `max_calls` calls were made or their estimated cost reached `max_cost_usd`, further calls
+//! fail with [`LlmError::BudgetExceeded`] without a request. Cost is only known after a reply,
+//! so the call that crosses the cost cap completes; the ones after it are refused.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tracing::warn;
+
+use crate::cost::PriceTable;
+use crate::{LlmError, LlmResponse};
+
+/// Environment variable capping LLM calls per run.
+pub const MAX_CALLS_ENV_VAR: &str = "LLM_MAX_CALLS_PER_RUN";
+
+/// Environment variable capping the estimated LLM cost per run, in USD.
+pub const MAX_COST_ENV_VAR: &str = "LLM_MAX_COST_PER_RUN";
+
+/// Calls let through by a [`BudgetGuard`] and their estimated cost so far.
+#[derive(Serialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct BudgetUsage {
+    pub calls: u32,
+    pub estimated_cost_usd: f64,
+}
+
+/// Call and cost caps shared by every call of a run.
+#[derive(Debug)]
+pub struct BudgetGuard {
+    max_calls: Option<u32>,
+    max_cost_usd: Option<f64>,
+    prices: PriceTable,
+    usage: Mutex<BudgetUsage>,
+}
+
+impl BudgetGuard {
+    /// A guard with either cap or both, pricing calls with [`PriceTable::from_env`].
+    pub fn new(max_calls: Option<u32>, max_cost_usd: Option<f64>) -> Self {
+        Self { max_calls, max_cost_usd, prices: PriceTable::from_env(), usage: Mutex::new(BudgetUsage::default()) }
+    }
+
+    /// The guard configured by `LLM_MAX_CALLS_PER_RUN` and `LLM_MAX_COST_PER_RUN`, or `None`
+    /// when neither is set. Invalid values are ignored with a warning.
+    pub fn from_env() -> Option<Self> {
+        let max_calls = env_limit::<u32>(MAX_CALLS_ENV_VAR);
+        let max_cost_usd = env_limit::<f64>(MAX_COST_ENV_VAR).filter(|cost| *cost >= 0.0);
+        (max_calls.is_some() || max_cost_usd.is_some()).then(|| Self::new(max_calls, max_cost_usd))
+    }
+
+    /// Prices to estimate cost with instead of [`PriceTable::from_env`].
+    pub fn with_prices(mut self, prices: PriceTable) -> Self {
+        self.prices = prices;
+        self
+    }
+
+    pub fn max_calls(&self) -> Option<u32> {
+        self.max_calls
+    }
+
+    pub fn max_cost_usd(&self) -> Option<f64> {
+        self.max_cost_usd
+    }
+
+    pub fn usage(&self) -> BudgetUsage {
+        *self.usage.lock().unwrap()
+    }
+
+    /// Count a call about to start, or refuse it when a cap is reached.
+    pub(crate) fn check(&self) -> Result<(), LlmError> {
+        let mut usage = self.usage.lock().unwrap();
+        let calls_spent = self.max_calls.is_some_and(|max| usage.calls >= max);
+        let cost_spent = self.max_cost_usd.is_some_and(|max| usage.estimated_cost_usd >= max);
+        if calls_spent || cost_spent {
+            return Err(LlmError::BudgetExceeded { calls: usage.calls, estimated_cost_usd: usage.estimated_cost_usd });
+        }
+        usage.calls += 1;
+        Ok(())
+    }
+
+    /// Add the estimated cost of a reply; models without a price add nothing.
+    pub(crate) fn record(&self, response: &LlmResponse) {
+        let prompt = u64::from(response.prompt_tokens.unwrap_or(0));
+        let completion = u64::from(response.completion_tokens.unwrap_or(0));
+        let cost = self.prices.estimate(&response.model, prompt, completion).unwrap_or(0.0);
+        self.usage.lock().unwrap().estimated_cost_usd += cost;
+    }
+}
+
+fn env_limit<T: std::str::FromStr>(var: &str) -> Option<T> {
+    let value = std::env::var(var).ok().filter(|value| !value.trim().is_empty())?;
+    let parsed = value.trim().parse().ok();
+    if parsed.is_none() {
+        warn!(var = var, value = %value, "Ignoring invalid LLM budget");
+    }
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::retry::RetryPolicy;
+    use crate::{send_with_policy, ChatMessage, LlmOptions, LlmProvider};
+    use std::sync::Arc;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn mock_claude(expected_calls: u64) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [{ "text": "ok" }],
+                "model": "priced-model",
+                "usage": { "input_tokens": 1000, "output_tokens": 1000 }
+            })))
+            .expect(expected_calls)
+            .mount(&server)
+            .await;
+        server
+    }
+
+    async fn call(server: &MockServer, budget: &Arc<BudgetGuard>) -> Result<LlmResponse, LlmError> {
+        let options = LlmOptions { base_url: Some(server.uri()), budget: Some(budget.clone()), ..Default::default() };
+        send_with_policy(&reqwest::Client::new(), LlmProvider::Claude, "key", &[ChatMessage::user("Hi")], &options, &RetryPolicy::PATIENT_LLM).await
+    }
+
+    #[tokio::test]
+    async fn test_call_past_max_calls_is_refused_without_a_request() {
+        let server = mock_claude(3).await;
+        let budget = Arc::new(BudgetGuard::new(Some(3), None));
+        for _ in 0..3 {
+            assert_eq!(call(&server, &budget).await.unwrap().text, "ok");
+        }
+        let refused = call(&server, &budget).await;
+        assert!(matches!(refused, Err(LlmError::BudgetExceeded { calls: 3, .. })), "{:?}", refused);
+        assert_eq!(budget.usage().calls, 3);
+        server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn test_cost_cap_refuses_calls_after_it_is_reached() {
+        // $1 per 1000 tokens in and out: each call costs $2
+        let mut prices = PriceTable::default();
+        prices.apply_overrides(r#"{"priced-model": {"input_per_million": 1000.0, "output_per_million": 1000.0}}"#).unwrap();
+        let server = mock_claude(2).await;
+        let budget = Arc::new(BudgetGuard::new(None, Some(3.0)).with_prices(prices));
+        assert!(call(&server, &budget).await.is_ok());
+        assert!(call(&server, &budget).await.is_ok());
+        assert!(matches!(call(&server, &budget).await, Err(LlmError::BudgetExceeded { .. })));
+        assert_eq!(budget.usage(), BudgetUsage { calls: 2, estimated_cost_usd: 4.0 });
+        server.verify().await;
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_from_env() {
+        assert!(BudgetGuard::from_env().is_none());
+        std::env::set_var(MAX_CALLS_ENV_VAR, "50");
+        std::env::set_var(MAX_COST_ENV_VAR, "lots");
+        let guard = BudgetGuard::from_env();
+        std::env::remove_var(MAX_CALLS_ENV_VAR);
+        std::env::remove_var(MAX_COST_ENV_VAR);
+        let guard = guard.unwrap();
+        assert_eq!(guard.max_calls(), Some(50));
+        assert_eq!(guard.max_cost_usd(), None);
+    }
+}
@@ -0,0 +1,177 @@
+//! Record/replay of LLM calls, so prompt changes can be tested end-to-end without API keys.
+//!
+//! With `LLM_CASSETTE_DIR` set, `LLM_CASSETTE_MODE=record` stores every successful call as
+//! `<key>.json` (request and response) in that directory, and `replay` (the default) serves
+//! calls from those files without touching the network. Keys are [`prompt_cache_key`]s over
+//! the provider, the configured model, the options and the messages.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::prompt_cache::prompt_cache_key;
+use crate::{ChatMessage, LlmError, LlmOptions, LlmProvider, LlmResponse};
+
+/// Environment variable naming the cassette directory; unset disables cassettes.
+pub const CASSETTE_DIR_ENV_VAR: &str = "LLM_CASSETTE_DIR";
+/// Environment variable selecting `record` or `replay`.
+pub const CASSETTE_MODE_ENV_VAR: &str = "LLM_CASSETTE_MODE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Call the provider and store each reply.
+    Record,
+    /// Serve stored replies; a call without a cassette fails.
+    Replay,
+}
+
+/// A recorded call.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CassetteEntry {
+    pub provider: String,
+    pub model: String,
+    pub system: Option<String>,
+    pub messages: Vec<ChatMessage>,
+    pub response: LlmResponse,
+}
+
+/// Cassette directory and mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cassette {
+    dir: PathBuf,
+    mode: CassetteMode,
+}
+
+impl Cassette {
+    pub fn new(dir: impl Into<PathBuf>, mode: CassetteMode) -> Self {
+        Self { dir: dir.into(), mode }
+    }
+
+    /// The cassette configured by `LLM_CASSETTE_DIR` and `LLM_CASSETTE_MODE`, if any. An
+    /// unknown mode disables cassettes with a warning rather than calling live by surprise
+    /// in record mode.
+    pub fn from_env() -> Option<Self> {
+        let dir = std::env::var(CASSETTE_DIR_ENV_VAR).ok().filter(|dir| !dir.trim().is_empty())?;
+        let mode = match std::env::var(CASSETTE_MODE_ENV_VAR).unwrap_or_default().trim().to_lowercase().as_str() {
+            "record" => CassetteMode::Record,
+            "replay" | "" => CassetteMode::Replay,
+            other => {
+                warn!(mode = %other, "Unknown {}, expected record or replay; cassettes disabled", CASSETTE_MODE_ENV_VAR);
+                return None;
+            }
+        };
+        Some(Self::new(dir, mode))
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// File of a call.
+    pub fn path(&self, provider: LlmProvider, model: &str, messages: &[ChatMessage], options: &LlmOptions) -> PathBuf {
+        let conversation = serde_json::to_string(messages).unwrap_or_default();
+        self.dir.join(format!("{}.json", prompt_cache_key(provider, model, &conversation, options)))
+    }
+
+    /// The recorded reply at `path`; a missing or unreadable cassette is an error.
+    pub fn replay(&self, path: &Path) -> Result<LlmResponse, LlmError> {
+        let data = std::fs::read(path).map_err(|_| LlmError::MissingCassette { path: path.display().to_string() })?;
+        let entry: CassetteEntry = serde_json::from_slice(&data)?;
+        debug!(path = %path.display(), "Replaying LLM cassette");
+        Ok(entry.response)
+    }
+
+    /// Store a call at `path`. Failures only log: the live reply is still returned.
+    pub fn record(&self, path: &Path, entry: &CassetteEntry) {
+        let result = std::fs::create_dir_all(&self.dir)
+            .and_then(|_| serde_json::to_vec_pretty(entry).map_err(std::io::Error::other))
+            .and_then(|data| std::fs::write(path, data));
+        match result {
+            Ok(()) => debug!(path = %path.display(), "Recorded LLM cassette"),
+            Err(e) => warn!(path = %path.display(), error = %e, "Failed to record LLM cassette"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{call_llm_chat_with_usage, call_llm_with_retry};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cassettes-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_from_env() {
+        assert_eq!(Cassette::from_env(), None);
+        std::env::set_var(CASSETTE_DIR_ENV_VAR, "/tmp/cassettes");
+        let replay = Cassette::from_env();
+        std::env::set_var(CASSETTE_MODE_ENV_VAR, "Record");
+        let record = Cassette::from_env();
+        std::env::set_var(CASSETTE_MODE_ENV_VAR, "rewind");
+        let unknown = Cassette::from_env();
+        std::env::remove_var(CASSETTE_DIR_ENV_VAR);
+        std::env::remove_var(CASSETTE_MODE_ENV_VAR);
+
+        assert_eq!(replay, Some(Cassette::new("/tmp/cassettes", CassetteMode::Replay)));
+        assert_eq!(record.map(|c| c.mode()), Some(CassetteMode::Record));
+        assert_eq!(unknown, None);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_record_then_replay_without_network() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let dir = temp_dir("round-trip");
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [{ "text": "Recorded summary" }],
+                "model": "claude-opus-4-6",
+                "usage": { "input_tokens": 12, "output_tokens": 3 }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        std::env::set_var("CLAUDE_BASE_URL", server.uri());
+        std::env::set_var(CASSETTE_DIR_ENV_VAR, &dir);
+        std::env::set_var(CASSETTE_MODE_ENV_VAR, "record");
+
+        let client = reqwest::Client::new();
+        let recorded = call_llm_with_retry(&client, LlmProvider::Claude, "key", "Summarize this".to_string()).await;
+
+        // Replay with the mock server gone
+        drop(server);
+        std::env::set_var(CASSETTE_MODE_ENV_VAR, "replay");
+        let replayed = call_llm_chat_with_usage(&client, LlmProvider::Claude, "key", &[ChatMessage::user("Summarize this")], &LlmOptions::default()).await;
+        let missing = call_llm_with_retry(&client, LlmProvider::Claude, "key", "Something else".to_string()).await;
+        std::env::remove_var("CLAUDE_BASE_URL");
+        std::env::remove_var(CASSETTE_DIR_ENV_VAR);
+        std::env::remove_var(CASSETTE_MODE_ENV_VAR);
+
+        assert_eq!(recorded.unwrap(), "Recorded summary");
+        let replayed = replayed.unwrap();
+        assert_eq!(replayed.text, "Recorded summary");
+        assert_eq!(replayed.prompt_tokens, Some(12));
+        let err = missing.unwrap_err();
+        assert!(matches!(err.downcast_ref::<LlmError>(), Some(LlmError::MissingCassette { .. })), "{}", err);
+
+        let files: Vec<_> = std::fs::read_dir(&dir).unwrap().map(|e| e.unwrap().path()).collect();
+        assert_eq!(files.len(), 1);
+        let entry: CassetteEntry = serde_json::from_slice(&std::fs::read(&files[0]).unwrap()).unwrap();
+        assert_eq!(entry.provider, "claude");
+        assert_eq!(entry.messages, vec![ChatMessage::user("Summarize this")]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
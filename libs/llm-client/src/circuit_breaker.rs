@@ -0,0 +1,259 @@
+//! Fail fast once a provider keeps refusing calls, e.g. after its API key was revoked.
+//!
+//! After `threshold` consecutive calls to a provider end in a permanent error, the breaker
+//! opens and calls fail immediately with [`LlmError::CircuitOpen`] instead of going through
+//! the retry loop. After the cool-down one trial call is let through (half-open): success
+//! closes the breaker, another permanent failure opens it for a new cool-down. Opt in per
+//! call group with [`LlmOptions::circuit_breaker`](crate::LlmOptions) or
+//! [`LlmClientBuilder::circuit_breaker`](crate::LlmClientBuilder).
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tracing::{info, warn};
+
+use crate::{LlmError, LlmProvider};
+
+/// State of one provider's circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls go through; counts consecutive permanent failures.
+    Closed { failures: u32 },
+    /// Calls fail immediately until `until`.
+    Open { until: Instant },
+    /// The cool-down passed; one trial call is allowed while `trial_in_flight` is false.
+    HalfOpen { trial_in_flight: bool },
+}
+
+/// Per-provider circuit breaker, shared between calls via `Arc`.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cool_down: Duration,
+    /// One state per provider, in [`LlmProvider::ALL`] order.
+    states: Mutex<[CircuitState; LlmProvider::ALL.len()]>,
+}
+
+/// Every circuit closed.
+const ALL_CLOSED: [CircuitState; LlmProvider::ALL.len()] = [CircuitState::Closed { failures: 0 }; LlmProvider::ALL.len()];
+
+fn index(provider: LlmProvider) -> usize {
+    LlmProvider::ALL.iter().position(|p| *p == provider).expect("every provider is listed in LlmProvider::ALL")
+}
+
+impl CircuitBreaker {
+    /// Opens after `threshold` (at least 1) consecutive permanent failures, for `cool_down`.
+    pub fn new(threshold: u32, cool_down: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cool_down,
+            states: Mutex::new(ALL_CLOSED),
+        }
+    }
+
+    /// Current state of `provider`'s circuit.
+    pub fn state(&self, provider: LlmProvider) -> CircuitState {
+        self.states.lock().unwrap()[index(provider)]
+    }
+
+    /// Close every circuit.
+    pub fn reset(&self) {
+        *self.states.lock().unwrap() = ALL_CLOSED;
+    }
+
+    /// Whether a call to `provider` may start, or the error to fail it with.
+    pub(crate) fn check(&self, provider: LlmProvider) -> Result<(), LlmError> {
+        let mut states = self.states.lock().unwrap();
+        let state = &mut states[index(provider)];
+        match *state {
+            CircuitState::Closed { .. } => Ok(()),
+            CircuitState::Open { until } => {
+                let now = Instant::now();
+                if now < until {
+                    return Err(LlmError::CircuitOpen { provider, retry_in: until - now });
+                }
+                info!(provider = %provider.as_str(), "Circuit half-open, allowing a trial call");
+                *state = CircuitState::HalfOpen { trial_in_flight: true };
+                Ok(())
+            }
+            CircuitState::HalfOpen { trial_in_flight: true } => Err(LlmError::CircuitOpen { provider, retry_in: Duration::ZERO }),
+            CircuitState::HalfOpen { trial_in_flight: false } => {
+                *state = CircuitState::HalfOpen { trial_in_flight: true };
+                Ok(())
+            }
+        }
+    }
+
+    /// Record the outcome of a call that [`Self::check`] let through.
+    pub(crate) fn record(&self, provider: LlmProvider, result: Result<(), &LlmError>) {
+        let mut states = self.states.lock().unwrap();
+        let state = &mut states[index(provider)];
+        match result {
+            Ok(()) => {
+                if matches!(state, CircuitState::HalfOpen { .. }) {
+                    info!(provider = %provider.as_str(), "Trial call succeeded, circuit closed");
+                }
+                *state = CircuitState::Closed { failures: 0 };
+            }
+            Err(e) if e.trips_circuit() => {
+                let failures = match *state {
+                    CircuitState::Closed { failures } => failures + 1,
+                    _ => self.threshold,
+                };
+                *state = if failures >= self.threshold {
+                    warn!(provider = %provider.as_str(), failures, cool_down_secs = self.cool_down.as_secs(), error = %e, "Circuit open, failing calls fast");
+                    CircuitState::Open { until: Instant::now() + self.cool_down }
+                } else {
+                    CircuitState::Closed { failures }
+                };
+            }
+            // Says nothing about the provider as a whole; let another trial through
+            Err(_) => {
+                if let CircuitState::HalfOpen { .. } = state {
+                    *state = CircuitState::HalfOpen { trial_in_flight: false };
+                }
+            }
+        }
+    }
+}
+
+impl LlmError {
+    /// A permanent failure of the provider rather than of this prompt: a non-retryable
+    /// HTTP status (e.g. 401 for a revoked key) or a provider error payload.
+    pub fn trips_circuit(&self) -> bool {
+        match self {
+            LlmError::Http { .. } => !self.is_transient(),
+            LlmError::Provider { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::budget::BudgetGuard;
+    use crate::retry::RetryPolicy;
+    use crate::{send_with_policy, ChatMessage, LlmOptions};
+    use std::sync::Arc;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn unauthorized() -> LlmError {
+        LlmError::Http { provider: LlmProvider::Claude, status: 401, body: "invalid x-api-key".to_string() }
+    }
+
+    #[test]
+    fn test_opens_after_consecutive_permanent_failures() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+        let provider = LlmProvider::Claude;
+        for _ in 0..2 {
+            breaker.check(provider).unwrap();
+            breaker.record(provider, Err(&unauthorized()));
+        }
+        assert_eq!(breaker.state(provider), CircuitState::Closed { failures: 2 });
+        // A success in between resets the count; transient and per-prompt errors don't count
+        breaker.record(provider, Ok(()));
+        breaker.record(provider, Err(&LlmError::Http { provider, status: 503, body: String::new() }));
+        breaker.record(provider, Err(&LlmError::EmptyResponse { provider }));
+        assert_eq!(breaker.state(provider), CircuitState::Closed { failures: 0 });
+
+        for _ in 0..3 {
+            breaker.record(provider, Err(&unauthorized()));
+        }
+        assert!(matches!(breaker.state(provider), CircuitState::Open { .. }));
+        assert!(matches!(breaker.check(provider), Err(LlmError::CircuitOpen { provider: LlmProvider::Claude, .. })));
+        // Every other provider has its own circuit
+        for other in LlmProvider::ALL.into_iter().filter(|p| *p != provider) {
+            assert_eq!(breaker.state(other), CircuitState::Closed { failures: 0 });
+        }
+        assert!(breaker.check(LlmProvider::Gemini).is_ok());
+
+        breaker.reset();
+        assert_eq!(breaker.state(provider), CircuitState::Closed { failures: 0 });
+        assert!(breaker.check(provider).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_open_half_open_closed_with_mock_401s() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("invalid x-api-key"))
+            .up_to_n_times(3)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "content": [{ "text": "back" }] })))
+            .mount(&server)
+            .await;
+
+        let breaker = Arc::new(CircuitBreaker::new(2, Duration::from_millis(100)));
+        let options = LlmOptions {
+            base_url: Some(server.uri()),
+            circuit_breaker: Some(breaker.clone()),
+            ..Default::default()
+        };
+        let client = reqwest::Client::new();
+        let policy = RetryPolicy { max_attempts: Some(3), ..RetryPolicy::PATIENT_LLM };
+        let messages = [ChatMessage::user("hi")];
+        let call = || send_with_policy(&client, LlmProvider::Claude, "revoked", &messages, &options, &policy);
+
+        // Two 401s open the circuit
+        assert!(matches!(call().await, Err(LlmError::Http { status: 401, .. })));
+        assert!(matches!(call().await, Err(LlmError::Http { status: 401, .. })));
+        assert!(matches!(breaker.state(LlmProvider::Claude), CircuitState::Open { .. }));
+
+        // Open: fails without a request
+        let err = call().await.unwrap_err();
+        assert!(matches!(err, LlmError::CircuitOpen { .. }));
+        assert!(!err.is_transient());
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+
+        // Half-open trial fails: open again
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert!(matches!(call().await, Err(LlmError::Http { status: 401, .. })));
+        assert!(matches!(breaker.state(LlmProvider::Claude), CircuitState::Open { .. }));
+        assert!(matches!(call().await, Err(LlmError::CircuitOpen { .. })));
+
+        // Half-open trial succeeds: closed
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert_eq!(call().await.unwrap().text, "back");
+        assert_eq!(breaker.state(LlmProvider::Claude), CircuitState::Closed { failures: 0 });
+        assert_eq!(server.received_requests().await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_budget_refusal_releases_the_half_open_trial() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "content": [{ "text": "back" }] })))
+            .mount(&server)
+            .await;
+
+        let breaker = Arc::new(CircuitBreaker::new(1, Duration::from_millis(50)));
+        breaker.record(LlmProvider::Claude, Err(&unauthorized()));
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        let client = reqwest::Client::new();
+        let messages = [ChatMessage::user("hi")];
+        let options = |budget: BudgetGuard| LlmOptions {
+            base_url: Some(server.uri()),
+            circuit_breaker: Some(breaker.clone()),
+            budget: Some(Arc::new(budget)),
+            ..Default::default()
+        };
+
+        // The trial is claimed, then refused by the spent budget
+        let spent = options(BudgetGuard::new(Some(0), None));
+        let refused = send_with_policy(&client, LlmProvider::Claude, "key", &messages, &spent, &RetryPolicy::PATIENT_LLM).await;
+        assert!(matches!(refused, Err(LlmError::BudgetExceeded { .. })), "{:?}", refused);
+        assert_eq!(breaker.state(LlmProvider::Claude), CircuitState::HalfOpen { trial_in_flight: false });
+
+        // With budget again, the next call is the trial and closes the circuit
+        let restored = options(BudgetGuard::new(Some(1), None));
+        let reply = send_with_policy(&client, LlmProvider::Claude, "key", &messages, &restored, &RetryPolicy::PATIENT_LLM).await;
+        assert_eq!(reply.unwrap().text, "back");
+        assert_eq!(breaker.state(LlmProvider::Claude), CircuitState::Closed { failures: 0 });
+    }
+}
@@ -0,0 +1,507 @@
+//! [`LlmClient`]: an HTTP client, provider, API key and call settings bundled together, so
+//! callers stop threading `(client, provider, api_key)` through every call.
+
+use std::fmt;
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+
+use crate::budget::BudgetGuard;
+use crate::audit::AuditSink;
+use crate::circuit_breaker::CircuitBreaker;
+use crate::compare::{judge_responses_with, Verdict};
+use crate::cost::CostTracker;
+use crate::health::{health_check_with, HealthStatus};
+use crate::language::{clarify_yes_no, YesNo};
+use crate::long_text::summarize_long_text_with;
+use crate::metrics::LlmMetrics;
+use crate::models::{list_models_with, validate_model, ModelInfo};
+use crate::retry::RetryPolicy;
+use crate::structured::call_llm_json_with_policy;
+use crate::tools::{ToolReply, ToolSpec};
+use crate::{
+    provider_from_env, resolve_credentials, send_with_policy, ChatMessage, LlmError, LlmOptions, LlmProvider, LlmResponse,
+    MissingCredentials, ParseProviderError,
+};
+
+/// An [`LlmClient`] couldn't be configured.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LlmClientError {
+    /// `LLM_PROVIDER` names no known provider.
+    UnknownProvider(ParseProviderError),
+    /// No API key was given and the provider's key variable isn't set.
+    MissingCredentials(MissingCredentials),
+}
+
+impl fmt::Display for LlmClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LlmClientError::UnknownProvider(e) => e.fmt(f),
+            LlmClientError::MissingCredentials(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for LlmClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LlmClientError::UnknownProvider(e) => Some(e),
+            LlmClientError::MissingCredentials(e) => Some(e),
+        }
+    }
+}
+
+impl From<ParseProviderError> for LlmClientError {
+    fn from(e: ParseProviderError) -> Self {
+        LlmClientError::UnknownProvider(e)
+    }
+}
+
+impl From<MissingCredentials> for LlmClientError {
+    fn from(e: MissingCredentials) -> Self {
+        LlmClientError::MissingCredentials(e)
+    }
+}
+
+/// Calls to one provider with one API key. Cheap to clone: clones share the HTTP client's
+/// connection pool.
+#[derive(Clone)]
+pub struct LlmClient {
+    http: reqwest::Client,
+    provider: LlmProvider,
+    api_key: String,
+    model: Option<String>,
+    base_url: Option<String>,
+    policy: RetryPolicy,
+    metrics: Option<Arc<dyn LlmMetrics>>,
+    audit: Option<Arc<dyn AuditSink>>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    budget: Option<Arc<BudgetGuard>>,
+    extra_headers: Vec<(String, String)>,
+}
+
+impl fmt::Debug for LlmClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LlmClient")
+            .field("provider", &self.provider)
+            .field("model", &self.model())
+            .field("base_url", &self.base_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LlmClient {
+    /// The `LLM_PROVIDER` provider (Gemini when unset) with its API key from the environment.
+    pub fn from_env() -> Result<Self, LlmClientError> {
+        Self::builder(provider_from_env()?).build()
+    }
+
+    /// Settings for a client of `provider`; unset values come from the environment.
+    pub fn builder(provider: LlmProvider) -> LlmClientBuilder {
+        LlmClientBuilder {
+            provider,
+            http: None,
+            api_key: None,
+            model: None,
+            base_url: None,
+            policy: None,
+            metrics: None,
+            audit: None,
+            circuit_breaker: None,
+            budget: None,
+            extra_headers: Vec::new(),
+        }
+    }
+
+    pub fn provider(&self) -> LlmProvider {
+        self.provider
+    }
+
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    /// Model requested: the builder's, else [`LlmProvider::configured_model`].
+    pub fn model(&self) -> String {
+        self.model.clone().unwrap_or_else(|| self.provider.configured_model())
+    }
+
+    pub fn http_client(&self) -> &reqwest::Client {
+        &self.http
+    }
+
+    /// `options` with this client's model, base URL, metrics, audit sink, circuit breaker and budget where it
+    /// sets none, and this client's extra headers before its own.
+    fn options(&self, options: &LlmOptions) -> LlmOptions {
+        let mut options = options.clone();
+        if !self.extra_headers.is_empty() {
+            options.extra_headers = self.extra_headers.iter().cloned().chain(options.extra_headers).collect();
+        }
+        options.model = options.model.or_else(|| self.model.clone());
+        options.base_url = options.base_url.or_else(|| self.base_url.clone());
+        options.metrics = options.metrics.or_else(|| self.metrics.clone());
+        options.audit = options.audit.or_else(|| self.audit.clone());
+        options.circuit_breaker = options.circuit_breaker.or_else(|| self.circuit_breaker.clone());
+        options.budget = options.budget.or_else(|| self.budget.clone());
+        options
+    }
+
+    /// Reply to `prompt` with default options.
+    pub async fn generate(&self, prompt: impl Into<String>) -> Result<LlmResponse, LlmError> {
+        self.generate_with(prompt, &LlmOptions::default()).await
+    }
+
+    /// Reply to `prompt`.
+    pub async fn generate_with(&self, prompt: impl Into<String>, options: &LlmOptions) -> Result<LlmResponse, LlmError> {
+        self.chat_with(&[ChatMessage::user(prompt)], options).await
+    }
+
+    /// Reply to `prompt` as JSON deserialized into `T`; see [`crate::call_llm_json`].
+    pub async fn generate_json<T: DeserializeOwned>(&self, prompt: impl Into<String>, cost: &mut CostTracker) -> Result<T, LlmError> {
+        self.generate_json_with(prompt, &LlmOptions::default(), cost).await
+    }
+
+    /// [`Self::generate_json`] with `options`.
+    pub async fn generate_json_with<T: DeserializeOwned>(
+        &self,
+        prompt: impl Into<String>,
+        options: &LlmOptions,
+        cost: &mut CostTracker,
+    ) -> Result<T, LlmError> {
+        call_llm_json_with_policy(&self.http, self.provider, &self.api_key, prompt.into(), &self.options(options), &self.policy, cost).await
+    }
+
+    /// Summary of a text too long for one prompt; see [`crate::summarize_long_text_with`].
+    pub async fn summarize_long_text(
+        &self,
+        text: &str,
+        target_chunk_tokens: usize,
+        options: &LlmOptions,
+        combine: impl FnOnce(&str) -> String,
+    ) -> Result<LlmResponse, LlmError> {
+        summarize_long_text_with(&self.http, self.provider, &self.api_key, text, target_chunk_tokens, &self.options(options), &self.policy, combine).await
+    }
+
+    /// Which of `responses` to `prompt` is best, judged by this client; see [`crate::judge_responses`].
+    pub async fn judge(&self, prompt: &str, responses: &[(LlmProvider, &str)], options: &LlmOptions, cost: &mut CostTracker) -> Result<Verdict, LlmError> {
+        judge_responses_with(&self.http, (self.provider, &self.api_key), prompt, responses, &self.options(options), &self.policy, cost).await
+    }
+
+    /// `answer` as yes/no/maybe, asking this client to restate it when it isn't recognized;
+    /// see [`crate::clarify_yes_no`].
+    pub async fn clarify_yes_no(&self, answer: &str, cost: &mut CostTracker) -> Result<Option<YesNo>, LlmError> {
+        clarify_yes_no(&self.http, self.provider, &self.api_key, answer, &self.options(&LlmOptions::default()), &self.policy, cost).await
+    }
+
+    /// Reply to `prompt` offering `tools`: a tool invocation, or text when the model chose none.
+    pub async fn generate_with_tools(&self, prompt: impl Into<String>, tools: &[ToolSpec], options: &LlmOptions) -> Result<ToolReply, LlmError> {
+        let options = LlmOptions { tools: tools.to_vec(), ..options.clone() };
+        Ok(self.generate_with(prompt, &options).await?.into())
+    }
+
+    /// Check this client's key and model with one cheap request; see [`crate::health_check`].
+    pub async fn health_check(&self) -> Result<HealthStatus, LlmError> {
+        health_check_with(&self.http, self.provider, &self.api_key, &self.options(&LlmOptions::default())).await
+    }
+
+    /// Models this client's key can use; see [`crate::list_models`].
+    pub async fn list_models(&self) -> Result<Vec<ModelInfo>, LlmError> {
+        list_models_with(&self.http, self.provider, &self.api_key, &self.options(&LlmOptions::default())).await
+    }
+
+    /// Whether [`Self::model`] is in the provider's model list, warning with the closest
+    /// match when it isn't; see [`crate::validate_model`].
+    pub async fn validate_model(&self) -> Result<bool, LlmError> {
+        Ok(validate_model(self.provider, &self.model(), &self.list_models().await?))
+    }
+
+    /// Next turn of a conversation with default options.
+    pub async fn chat(&self, messages: &[ChatMessage]) -> Result<LlmResponse, LlmError> {
+        self.chat_with(messages, &LlmOptions::default()).await
+    }
+
+    /// Next turn of a conversation.
+    pub async fn chat_with(&self, messages: &[ChatMessage], options: &LlmOptions) -> Result<LlmResponse, LlmError> {
+        send_with_policy(&self.http, self.provider, &self.api_key, messages, &self.options(options), &self.policy).await
+    }
+}
+
+/// Explicit settings for an [`LlmClient`]; see [`LlmClient::builder`].
+#[derive(Debug, Clone)]
+pub struct LlmClientBuilder {
+    provider: LlmProvider,
+    http: Option<reqwest::Client>,
+    api_key: Option<String>,
+    model: Option<String>,
+    base_url: Option<String>,
+    policy: Option<RetryPolicy>,
+    metrics: Option<Arc<dyn LlmMetrics>>,
+    audit: Option<Arc<dyn AuditSink>>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    budget: Option<Arc<BudgetGuard>>,
+    extra_headers: Vec<(String, String)>,
+}
+
+impl LlmClientBuilder {
+    /// HTTP client to share; a default client otherwise.
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    /// API key; [`resolve_credentials`] otherwise.
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Model; the provider's model variable or default otherwise.
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// API base URL, e.g. a gateway or a mock server; the provider's otherwise.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Retry policy; [`RetryPolicy::llm_from_env`] otherwise.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Receiver of this client's call events; none otherwise.
+    pub fn metrics(mut self, metrics: Arc<dyn LlmMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Receiver of a record of each of this client's calls; none otherwise.
+    pub fn audit(mut self, audit: Arc<dyn AuditSink>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Breaker failing this client's calls fast while the provider keeps failing; none otherwise.
+    pub fn circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    /// Caps shared with the run's other clients, refusing calls once reached; none otherwise.
+    pub fn budget(mut self, budget: Arc<BudgetGuard>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Header added to every request of this client, e.g. `anthropic-beta`; checked at call time.
+    pub fn extra_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra_headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// The client. A blank API key counts as missing.
+    pub fn build(self) -> Result<LlmClient, LlmClientError> {
+        let api_key = match self.api_key.filter(|key| !key.trim().is_empty()) {
+            Some(key) => key,
+            None => resolve_credentials(self.provider)?,
+        };
+        Ok(LlmClient {
+            http: self.http.unwrap_or_default(),
+            provider: self.provider,
+            api_key,
+            model: self.model,
+            base_url: self.base_url,
+            policy: self.policy.unwrap_or_else(RetryPolicy::llm_from_env),
+            metrics: self.metrics,
+            audit: self.audit,
+            circuit_breaker: self.circuit_breaker,
+            budget: self.budget,
+            extra_headers: self.extra_headers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::fast_policy;
+    use crate::{get_api_key_env_var, PROVIDER_ENV_VAR};
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    #[serial_test::serial]
+    fn test_from_env_reads_provider_and_key() {
+        std::env::set_var(PROVIDER_ENV_VAR, "Claude");
+        std::env::set_var(get_api_key_env_var(LlmProvider::Claude), "claude-key");
+        let client = LlmClient::from_env();
+        std::env::remove_var(PROVIDER_ENV_VAR);
+        std::env::remove_var(get_api_key_env_var(LlmProvider::Claude));
+
+        let client = client.unwrap();
+        assert_eq!(client.provider(), LlmProvider::Claude);
+        assert_eq!(client.api_key(), "claude-key");
+        // The key stays out of debug output
+        assert!(!format!("{:?}", client).contains("claude-key"));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_from_env_bad_provider() {
+        std::env::set_var(PROVIDER_ENV_VAR, "mistral");
+        let err = LlmClient::from_env().unwrap_err();
+        std::env::remove_var(PROVIDER_ENV_VAR);
+
+        assert_eq!(err, LlmClientError::UnknownProvider(ParseProviderError("mistral".to_string())));
+        assert!(err.to_string().contains("unknown LLM provider 'mistral'"), "{}", err);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_missing_key() {
+        std::env::remove_var(PROVIDER_ENV_VAR);
+        let env_var = get_api_key_env_var(LlmProvider::Gemini);
+        std::env::remove_var(env_var);
+        let err = LlmClient::from_env().unwrap_err();
+        assert_eq!(err, LlmClientError::MissingCredentials(MissingCredentials { provider: LlmProvider::Gemini, env_var }));
+        assert!(err.to_string().contains(env_var), "{}", err);
+
+        // A blank explicit key doesn't hide the missing one
+        assert!(matches!(LlmClient::builder(LlmProvider::Gemini).api_key("  ").build(), Err(LlmClientError::MissingCredentials(_))));
+        assert_eq!(LlmClient::builder(LlmProvider::Gemini).api_key("explicit").build().unwrap().api_key(), "explicit");
+    }
+
+    #[tokio::test]
+    async fn test_builder_model_and_base_url() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(header("x-api-key", "test-key"))
+            .and(body_partial_json(serde_json::json!({ "model": "claude-test" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [{ "text": "{\"index\": 2}" }],
+                "model": "claude-test",
+                "usage": { "input_tokens": 5, "output_tokens": 2 }
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+        let client = LlmClient::builder(LlmProvider::Claude)
+            .api_key("test-key")
+            .model("claude-test")
+            .base_url(format!("{}/v1", server.uri()))
+            .retry_policy(fast_policy(2))
+            .build()
+            .unwrap();
+        assert_eq!(client.model(), "claude-test");
+
+        let response = client.generate("Pick one").await.unwrap();
+        assert_eq!(response.text, "{\"index\": 2}");
+        assert_eq!(response.model, "claude-test");
+
+        #[derive(serde::Deserialize)]
+        struct Pick {
+            index: usize,
+        }
+        let mut cost = CostTracker::default();
+        let pick: Pick = client.generate_json("Pick one", &mut cost).await.unwrap();
+        assert_eq!(pick.index, 2);
+        assert_eq!(cost.usage.calls, 1);
+    }
+
+    fn ok_reply(provider: LlmProvider) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(match provider {
+            LlmProvider::Gemini => serde_json::json!({ "candidates": [{ "content": { "parts": [{ "text": "ok" }] } }] }),
+            LlmProvider::OpenAI => serde_json::json!({ "choices": [{ "message": { "content": "ok" } }] }),
+            LlmProvider::Claude => serde_json::json!({ "content": [{ "text": "ok" }] }),
+            LlmProvider::Echo => unreachable!("echo sends no request"),
+        })
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_extra_headers_reach_every_provider() {
+        let server = MockServer::start().await;
+        for provider in LlmProvider::REMOTE {
+            let route = match provider {
+                LlmProvider::Gemini => "/v1beta/models/test-model:generateContent",
+                LlmProvider::OpenAI => "/chat/completions",
+                LlmProvider::Claude => "/messages",
+                LlmProvider::Echo => unreachable!("echo sends no request"),
+            };
+            let mut mock = Mock::given(method("POST"))
+                .and(path(route))
+                .and(header("x-team", "platform"))
+                .and(header("x-request-tag", "daily"));
+            if provider == LlmProvider::Claude {
+                mock = mock.and(header("anthropic-version", crate::DEFAULT_ANTHROPIC_API_VERSION));
+            }
+            mock.respond_with(ok_reply(provider))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        std::env::remove_var(crate::ANTHROPIC_API_VERSION_ENV_VAR);
+
+        for provider in LlmProvider::REMOTE {
+            let client = LlmClient::builder(provider)
+                .api_key("key")
+                .model("test-model")
+                .base_url(server.uri())
+                .extra_header("x-team", "platform")
+                .build()
+                .unwrap();
+            let options = LlmOptions { extra_headers: vec![("x-request-tag".to_string(), "daily".to_string())], ..Default::default() };
+            assert_eq!(client.generate_with("hi", &options).await.unwrap().text, "ok", "{:?}", provider);
+        }
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_anthropic_version_and_beta_headers() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/messages"))
+            .and(header("anthropic-version", "2024-10-22"))
+            .and(header("anthropic-beta", "prompt-caching-2024-07-31"))
+            .respond_with(ok_reply(LlmProvider::Claude))
+            .expect(1)
+            .mount(&server)
+            .await;
+        std::env::set_var(crate::ANTHROPIC_API_VERSION_ENV_VAR, "2024-10-22");
+        let client = LlmClient::builder(LlmProvider::Claude)
+            .api_key("key")
+            .base_url(server.uri())
+            .extra_header("anthropic-beta", "prompt-caching-2024-07-31")
+            .build()
+            .unwrap();
+        let reply = client.generate("hi").await;
+        std::env::remove_var(crate::ANTHROPIC_API_VERSION_ENV_VAR);
+        assert_eq!(reply.unwrap().text, "ok");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_header_fails_before_sending() {
+        let server = MockServer::start().await;
+        let client = LlmClient::builder(LlmProvider::OpenAI)
+            .api_key("key")
+            .base_url(server.uri())
+            .retry_policy(fast_policy(2))
+            .build()
+            .unwrap();
+
+        let bad_name = LlmOptions { extra_headers: vec![("x bad".to_string(), "v".to_string())], ..Default::default() };
+        let err = client.generate_with("hi", &bad_name).await.unwrap_err();
+        assert!(matches!(&err, LlmError::InvalidHeader { name, .. } if name == "x bad"), "{:?}", err);
+        assert!(!err.is_transient());
+        assert!(err.to_string().contains("x bad"), "{}", err);
+
+        let bad_value = LlmOptions { extra_headers: vec![("x-tag".to_string(), "line\nbreak".to_string())], ..Default::default() };
+        assert!(matches!(client.generate_with("hi", &bad_value).await, Err(LlmError::InvalidHeader { .. })));
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+//! A/B comparison of providers: the same prompt to several providers at once, and an
+//! optional blind judge asking one provider which reply is better.
+
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument, warn};
+
+use crate::cost::CostTracker;
+use crate::retry::RetryPolicy;
+use crate::structured::call_llm_json_with_policy;
+use crate::{send_with_policy, ChatMessage, LlmError, LlmOptions, LlmProvider, LlmResponse};
+
+const JUDGE_PROMPT: &str = "Several assistants answered the same request. Judge which response best fulfils it: \
+accuracy, insight, clarity and following the request's instructions. Ignore length for its own sake.\n\n\
+Request:\n<<<\n{prompt}\n>>>\n\n{responses}\n\n\
+Pick the best response and reply ONLY with JSON giving its index and a one-sentence reason: \
+{\"index\": <number>, \"reason\": \"...\"}";
+
+/// The judge's pick among compared responses.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Verdict {
+    pub winner: LlmProvider,
+    pub reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JudgeReply {
+    index: usize,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// `prompt` sent to each of `providers` (with its API key) concurrently, with default options.
+/// Results are in the order of `providers`; one failing doesn't affect the others.
+pub async fn call_llm_compare(
+    client: &reqwest::Client,
+    providers: &[(LlmProvider, String)],
+    prompt: impl Into<String>,
+) -> Vec<(LlmProvider, Result<String, LlmError>)> {
+    call_llm_compare_with(client, providers, prompt, &LlmOptions::default(), &RetryPolicy::llm_from_env())
+        .await
+        .into_iter()
+        .map(|(provider, result)| (provider, result.map(|response| response.text)))
+        .collect()
+}
+
+/// [`call_llm_compare`] with `options` and `policy`, returning the full responses.
+#[instrument(skip(client, providers, prompt, options, policy), fields(providers = providers.len()))]
+pub async fn call_llm_compare_with(
+    client: &reqwest::Client,
+    providers: &[(LlmProvider, String)],
+    prompt: impl Into<String>,
+    options: &LlmOptions,
+    policy: &RetryPolicy,
+) -> Vec<(LlmProvider, Result<LlmResponse, LlmError>)> {
+    let messages = [ChatMessage::user(prompt)];
+    let calls = providers.iter().map(|(provider, api_key)| {
+        let messages = &messages;
+        async move { (*provider, send_with_policy(client, *provider, api_key, messages, options, policy).await) }
+    });
+    let results = join_all(calls).await;
+    for (provider, result) in &results {
+        if let Err(e) = result {
+            warn!(provider = %provider.as_str(), error = %e, "Compared provider failed");
+        }
+    }
+    results
+}
+
+/// Prompt asking a judge to pick the best of `responses` to `prompt`. Responses are shown by
+/// index only, so the judge doesn't know which provider wrote which.
+pub fn judge_prompt(prompt: &str, responses: &[(LlmProvider, &str)]) -> String {
+    let responses = responses
+        .iter()
+        .enumerate()
+        .map(|(i, (_, text))| format!("Response {}:\n<<<\n{}\n>>>", i, text.trim()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    JUDGE_PROMPT.replace("{prompt}", prompt.trim()).replace("{responses}", &responses)
+}
+
+/// Ask `judge` (a provider and its API key) which of `responses` to `prompt` is best. The
+/// judge's calls are recorded in `cost`. A pick outside `responses` is a
+/// [`LlmError::Provider`].
+#[instrument(skip(client, judge, prompt, responses, options, cost), fields(judge = %judge.0.as_str(), responses = responses.len()))]
+pub async fn judge_responses(
+    client: &reqwest::Client,
+    judge: (LlmProvider, &str),
+    prompt: &str,
+    responses: &[(LlmProvider, &str)],
+    options: &LlmOptions,
+    cost: &mut CostTracker,
+) -> Result<Verdict, LlmError> {
+    judge_responses_with(client, judge, prompt, responses, options, &RetryPolicy::llm_from_env(), cost).await
+}
+
+/// [`judge_responses`] retrying transient failures under `policy`.
+pub async fn judge_responses_with(
+    client: &reqwest::Client,
+    (provider, api_key): (LlmProvider, &str),
+    prompt: &str,
+    responses: &[(LlmProvider, &str)],
+    options: &LlmOptions,
+    policy: &RetryPolicy,
+    cost: &mut CostTracker,
+) -> Result<Verdict, LlmError> {
+    let reply: JudgeReply = call_llm_json_with_policy(client, provider, api_key, judge_prompt(prompt, responses), options, policy, cost).await?;
+    let Some((winner, _)) = responses.get(reply.index) else {
+        return Err(LlmError::Provider { provider, message: format!("judge picked response {} of {}", reply.index, responses.len()) });
+    };
+    info!(winner = %winner.as_str(), reason = ?reply.reason, "Judge picked a response");
+    Ok(Verdict { winner: *winner, reason: reply.reason })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::claude_reply;
+    use wiremock::matchers::{body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_compare_fans_out_and_judges() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/v1beta/models/gemini-test:generateContent"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "candidates": [{ "content": { "parts": [{ "text": "Gemini's brief" }] }, "finishReason": "STOP" }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST")).and(path("/messages")).and(body_string_contains("Judge which response"))
+            .respond_with(claude_reply(r#"{"index": 1, "reason": "More concrete."}"#))
+            .expect(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(claude_reply("Claude's brief"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST")).and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("bad key"))
+            .mount(&server)
+            .await;
+        std::env::set_var("GEMINI_MODEL", "gemini-test");
+        for provider in LlmProvider::REMOTE {
+            std::env::set_var(provider.base_url_env_var(), server.uri());
+        }
+
+        let client = reqwest::Client::new();
+        let providers = [(LlmProvider::Gemini, "g".to_string()), (LlmProvider::Claude, "c".to_string()), (LlmProvider::OpenAI, "o".to_string())];
+        let results = call_llm_compare(&client, &providers, "Write a brief").await;
+        let judged = {
+            let responses: Vec<(LlmProvider, &str)> = results.iter().filter_map(|(p, r)| Some((*p, r.as_deref().ok()?))).collect();
+            judge_responses(&client, (LlmProvider::Claude, "c"), "Write a brief", &responses, &LlmOptions::default(), &mut CostTracker::default()).await
+        };
+        std::env::remove_var("GEMINI_MODEL");
+        for provider in LlmProvider::REMOTE {
+            std::env::remove_var(provider.base_url_env_var());
+        }
+
+        assert_eq!(results.iter().map(|(p, _)| *p).collect::<Vec<_>>(), vec![LlmProvider::Gemini, LlmProvider::Claude, LlmProvider::OpenAI]);
+        assert_eq!(results[0].1.as_deref().unwrap(), "Gemini's brief");
+        assert_eq!(results[1].1.as_deref().unwrap(), "Claude's brief");
+        assert!(matches!(results[2].1, Err(LlmError::Http { status: 401, .. })));
+        assert_eq!(judged.unwrap(), Verdict { winner: LlmProvider::Claude, reason: Some("More concrete.".to_string()) });
+    }
+
+    #[test]
+    fn test_judge_prompt_hides_providers() {
+        let prompt = judge_prompt("Summarize", &[(LlmProvider::Gemini, "first"), (LlmProvider::Claude, " second\n")]);
+        assert!(prompt.contains("Response 0:\n<<<\nfirst\n>>>\n\nResponse 1:\n<<<\nsecond\n>>>"), "{}", prompt);
+        assert!(!prompt.to_lowercase().contains("gemini") && !prompt.to_lowercase().contains("claude"));
+    }
+
+    #[tokio::test]
+    async fn test_pick_out_of_range_is_an_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(claude_reply(r#"{"index": 5}"#))
+            .mount(&server)
+            .await;
+        let options = LlmOptions { base_url: Some(server.uri()), ..Default::default() };
+        let responses = [(LlmProvider::Gemini, "a"), (LlmProvider::Claude, "b")];
+        let err = judge_responses(&reqwest::Client::new(), (LlmProvider::Claude, "c"), "p", &responses, &options, &mut CostTracker::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("judge picked response 5 of 2"), "{}", err);
+    }
+}
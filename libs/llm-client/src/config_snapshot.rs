@@ -0,0 +1,313 @@
+//! Effective-config snapshots, persisted at the end of each run and diffed at the start of
+//! the next so that changed providers, models or defaults show up in the logs and run report.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::storage::{ObjectStore, StorageError};
+
+/// Version of this library, recorded so default-constant changes can be attributed to upgrades.
+pub const LIB_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Object path of the effective-config snapshot for an agent.
+pub fn snapshot_path(agent: &str) -> String {
+    format!("runs/{}/effective_config.json", agent)
+}
+
+/// Where a resolved config value came from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigSource {
+    /// Read from an environment variable.
+    Env,
+    /// Compiled-in default constant.
+    Default,
+    /// Computed from other values (e.g. which providers have keys).
+    Derived,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConfigValue {
+    pub value: String,
+    pub source: ConfigSource,
+}
+
+/// Why a value differs from the previous run.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftCause {
+    /// An environment variable was set, changed or removed.
+    Env,
+    /// A compiled-in default changed along with a crate version bump.
+    Upgrade,
+    /// A compiled-in default changed without a version bump.
+    Default,
+    /// A derived value changed (e.g. a provider key appeared).
+    Derived,
+    /// The key did not exist in the previous snapshot.
+    Added,
+    /// The key no longer exists.
+    Removed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConfigDrift {
+    pub key: String,
+    pub previous: Option<String>,
+    pub current: Option<String>,
+    pub cause: DriftCause,
+}
+
+/// Resolved configuration of one agent run. Secrets are stored only as short hashes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EffectiveConfig {
+    pub agent: String,
+    pub crate_version: String,
+    pub lib_version: String,
+    pub captured_at: DateTime<Utc>,
+    pub values: BTreeMap<String, ConfigValue>,
+}
+
+impl EffectiveConfig {
+    pub fn new(agent: &str, crate_version: &str) -> Self {
+        Self {
+            agent: agent.to_string(),
+            crate_version: crate_version.to_string(),
+            lib_version: LIB_VERSION.to_string(),
+            captured_at: Utc::now(),
+            values: BTreeMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, key: &str, value: impl Into<String>, source: ConfigSource) {
+        self.values.insert(key.to_string(), ConfigValue { value: value.into(), source });
+    }
+
+    /// Resolve `env_var` (falling back to `default`), record it under `key` and return it.
+    pub fn env_or_default(&mut self, key: &str, env_var: &str, default: &str) -> String {
+        match std::env::var(env_var) {
+            Ok(value) => {
+                self.record(key, value.clone(), ConfigSource::Env);
+                value
+            }
+            Err(_) => {
+                self.record(key, default, ConfigSource::Default);
+                default.to_string()
+            }
+        }
+    }
+
+    /// Record a secret as a truncated SHA-256 so rotations are visible without leaking it.
+    pub fn record_secret(&mut self, key: &str, secret: &str, source: ConfigSource) {
+        self.record(key, hash_secret(secret), source);
+    }
+
+    fn version_changed(&self, previous: &EffectiveConfig) -> bool {
+        self.crate_version != previous.crate_version || self.lib_version != previous.lib_version
+    }
+
+    /// List values that differ from `previous`, classified by cause.
+    pub fn diff(&self, previous: &EffectiveConfig) -> Vec<ConfigDrift> {
+        let version_changed = self.version_changed(previous);
+        let mut drift = Vec::new();
+
+        for (key, current) in &self.values {
+            match previous.values.get(key) {
+                Some(prev) if prev.value == current.value => {}
+                Some(prev) => {
+                    let cause = if prev.source == ConfigSource::Env || current.source == ConfigSource::Env {
+                        DriftCause::Env
+                    } else if prev.source == ConfigSource::Derived || current.source == ConfigSource::Derived {
+                        DriftCause::Derived
+                    } else if version_changed {
+                        DriftCause::Upgrade
+                    } else {
+                        DriftCause::Default
+                    };
+                    drift.push(ConfigDrift {
+                        key: key.clone(),
+                        previous: Some(prev.value.clone()),
+                        current: Some(current.value.clone()),
+                        cause,
+                    });
+                }
+                None => drift.push(ConfigDrift {
+                    key: key.clone(),
+                    previous: None,
+                    current: Some(current.value.clone()),
+                    cause: DriftCause::Added,
+                }),
+            }
+        }
+
+        for (key, prev) in &previous.values {
+            if !self.values.contains_key(key) {
+                drift.push(ConfigDrift {
+                    key: key.clone(),
+                    previous: Some(prev.value.clone()),
+                    current: None,
+                    cause: DriftCause::Removed,
+                });
+            }
+        }
+
+        drift
+    }
+}
+
+/// Short, non-reversible fingerprint of a secret value.
+pub fn hash_secret(secret: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(secret.as_bytes()));
+    format!("sha256:{}", &digest[..12])
+}
+
+/// Load the previous snapshot for `config.agent`, diff it against `config` and log any drift.
+/// Returns an empty list on the first run or when the previous snapshot is unreadable.
+pub async fn check_config_drift<S: ObjectStore>(store: &S, config: &EffectiveConfig) -> Vec<ConfigDrift> {
+    let path = snapshot_path(&config.agent);
+    let previous = match store.get(&path).await {
+        Ok(Some(data)) => match serde_json::from_slice::<EffectiveConfig>(&data) {
+            Ok(previous) => previous,
+            Err(e) => {
+                warn!(path = %path, error = %e, "Previous config snapshot is unreadable, skipping drift check");
+                return Vec::new();
+            }
+        },
+        Ok(None) => {
+            info!(path = %path, "No previous config snapshot, skipping drift check");
+            return Vec::new();
+        }
+        Err(e) => {
+            warn!(path = %path, error = %e, "Failed to load previous config snapshot");
+            return Vec::new();
+        }
+    };
+
+    let drift = config.diff(&previous);
+    for d in &drift {
+        warn!(
+            key = %d.key,
+            previous = d.previous.as_deref().unwrap_or("-"),
+            current = d.current.as_deref().unwrap_or("-"),
+            cause = ?d.cause,
+            previous_version = %previous.crate_version,
+            "Config drift since previous run"
+        );
+    }
+    drift
+}
+
+/// Persist `config` as the snapshot the next run will diff against.
+pub async fn save_config_snapshot<S: ObjectStore>(store: &S, config: &EffectiveConfig) -> Result<(), StorageError> {
+    let data = serde_json::to_vec_pretty(config).map_err(|e| StorageError::new(None, e.to_string()))?;
+    store.put(&snapshot_path(&config.agent), data).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStore;
+
+    fn config(version: &str, values: &[(&str, &str, ConfigSource)]) -> EffectiveConfig {
+        let mut cfg = EffectiveConfig::new("daily-agent", version);
+        for (k, v, s) in values {
+            cfg.record(k, *v, *s);
+        }
+        cfg
+    }
+
+    #[test]
+    fn test_diff_identical_is_empty() {
+        let a = config("0.1.0", &[("model", "m1", ConfigSource::Default)]);
+        let b = config("0.1.0", &[("model", "m1", ConfigSource::Default)]);
+        assert!(a.diff(&b).is_empty());
+    }
+
+    #[test]
+    fn test_diff_env_change() {
+        let prev = config("0.1.0", &[("bucket", "a", ConfigSource::Default)]);
+        let cur = config("0.1.0", &[("bucket", "b", ConfigSource::Env)]);
+        let drift = cur.diff(&prev);
+        assert_eq!(drift.len(), 1);
+        assert_eq!(drift[0].cause, DriftCause::Env);
+        assert_eq!(drift[0].previous.as_deref(), Some("a"));
+        assert_eq!(drift[0].current.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_diff_default_change_with_version_bump_is_upgrade() {
+        let prev = config("0.1.0", &[("model", "m1", ConfigSource::Default)]);
+        let cur = config("0.2.0", &[("model", "m2", ConfigSource::Default)]);
+        assert_eq!(cur.diff(&prev)[0].cause, DriftCause::Upgrade);
+    }
+
+    #[test]
+    fn test_diff_default_change_without_version_bump() {
+        let prev = config("0.1.0", &[("model", "m1", ConfigSource::Default)]);
+        let cur = config("0.1.0", &[("model", "m2", ConfigSource::Default)]);
+        assert_eq!(cur.diff(&prev)[0].cause, DriftCause::Default);
+    }
+
+    #[test]
+    fn test_diff_added_and_removed() {
+        let prev = config("0.1.0", &[("old", "x", ConfigSource::Env)]);
+        let cur = config("0.1.0", &[("new", "y", ConfigSource::Env)]);
+        let drift = cur.diff(&prev);
+        assert_eq!(drift.len(), 2);
+        assert!(drift.iter().any(|d| d.key == "new" && d.cause == DriftCause::Added));
+        assert!(drift.iter().any(|d| d.key == "old" && d.cause == DriftCause::Removed));
+    }
+
+    #[test]
+    fn test_secret_is_hashed_not_stored() {
+        let mut cfg = EffectiveConfig::new("daily-agent", "0.1.0");
+        cfg.record_secret("gemini_api_key", "super-secret-key-123", ConfigSource::Env);
+        let json = serde_json::to_string(&cfg).unwrap();
+        assert!(!json.contains("super-secret-key-123"));
+        assert!(json.contains("sha256:"));
+        assert_eq!(cfg.values["gemini_api_key"].value, hash_secret("super-secret-key-123"));
+    }
+
+    #[test]
+    fn test_secret_rotation_is_detected() {
+        let mut prev = EffectiveConfig::new("daily-agent", "0.1.0");
+        prev.record_secret("key", "old", ConfigSource::Env);
+        let mut cur = EffectiveConfig::new("daily-agent", "0.1.0");
+        cur.record_secret("key", "new", ConfigSource::Env);
+        let drift = cur.diff(&prev);
+        assert_eq!(drift.len(), 1);
+        assert!(!drift[0].current.as_deref().unwrap().contains("new"));
+    }
+
+    #[tokio::test]
+    async fn test_check_config_drift_first_run() {
+        let store = MemoryStore::new();
+        let cfg = config("0.1.0", &[("model", "m1", ConfigSource::Default)]);
+        assert!(check_config_drift(&store, &cfg).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_check_config_drift_against_saved_snapshot() {
+        let store = MemoryStore::new();
+        let prev = config("0.1.0", &[("model", "m1", ConfigSource::Default)]);
+        save_config_snapshot(&store, &prev).await.unwrap();
+        assert!(store.object("runs/daily-agent/effective_config.json").is_some());
+
+        let cur = config("0.2.0", &[("model", "m2", ConfigSource::Default)]);
+        let drift = check_config_drift(&store, &cur).await;
+        assert_eq!(drift.len(), 1);
+        assert_eq!(drift[0].cause, DriftCause::Upgrade);
+    }
+
+    #[tokio::test]
+    async fn test_check_config_drift_corrupt_snapshot() {
+        let store = MemoryStore::new();
+        store.put("runs/daily-agent/effective_config.json", b"not json".to_vec()).await.unwrap();
+        let cfg = config("0.1.0", &[]);
+        assert!(check_config_drift(&store, &cfg).await.is_empty());
+    }
+}
@@ -0,0 +1,217 @@
+//! Estimated LLM spend from reported token usage.
+//!
+//! Prices are USD per million tokens, matched on the longest model-name prefix so dated or
+//! preview variants (`claude-opus-4-6-20260301`) share their family's price. The built-in
+//! table can be overridden or extended with `LLM_PRICES`, a JSON object of
+//! `{"model": {"input_per_million": 1.25, "output_per_million": 10.0}}`.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::{LlmResponse, TokenUsage};
+
+/// Environment variable with price overrides (JSON).
+pub const PRICES_ENV_VAR: &str = "LLM_PRICES";
+
+/// Built-in list prices, USD per million tokens: (model prefix, input, output).
+const DEFAULT_PRICES: [(&str, f64, f64); 4] = [
+    ("gemini-3.1-pro", 2.00, 12.00),
+    ("gemini-2.0-flash", 0.10, 0.40),
+    ("gpt-5.2", 1.75, 14.00),
+    ("claude-opus-4-6", 5.00, 25.00),
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+/// Prices keyed by model name prefix.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PriceTable(BTreeMap<String, ModelPrice>);
+
+impl Default for PriceTable {
+    /// The built-in prices, without environment overrides.
+    fn default() -> Self {
+        Self(
+            DEFAULT_PRICES
+                .iter()
+                .map(|(model, input, output)| {
+                    (model.to_string(), ModelPrice { input_per_million: *input, output_per_million: *output })
+                })
+                .collect(),
+        )
+    }
+}
+
+impl PriceTable {
+    /// Built-in prices with `LLM_PRICES` applied. An unparseable override is ignored with a warning.
+    pub fn from_env() -> Self {
+        let mut table = Self::default();
+        if let Ok(json) = std::env::var(PRICES_ENV_VAR) {
+            if let Err(e) = table.apply_overrides(&json) {
+                warn!(var = PRICES_ENV_VAR, error = %e, "Ignoring invalid price overrides");
+            }
+        }
+        table
+    }
+
+    /// Add or replace prices from a JSON object of model prefix to [`ModelPrice`].
+    pub fn apply_overrides(&mut self, json: &str) -> Result<(), serde_json::Error> {
+        let overrides: BTreeMap<String, ModelPrice> = serde_json::from_str(json)?;
+        self.0.extend(overrides);
+        Ok(())
+    }
+
+    /// Price of `model`: exact match first, else the longest prefix.
+    pub fn price(&self, model: &str) -> Option<ModelPrice> {
+        self.0.get(model).copied().or_else(|| {
+            self.0
+                .iter()
+                .filter(|(prefix, _)| model.starts_with(prefix.as_str()))
+                .max_by_key(|(prefix, _)| prefix.len())
+                .map(|(_, price)| *price)
+        })
+    }
+
+    /// Estimated cost in USD, or `None` for a model without a price.
+    pub fn estimate(&self, model: &str, prompt_tokens: u64, completion_tokens: u64) -> Option<f64> {
+        let price = self.price(model)?;
+        Some((prompt_tokens as f64 * price.input_per_million + completion_tokens as f64 * price.output_per_million) / 1_000_000.0)
+    }
+}
+
+/// Estimated cost in USD of one call with the built-in prices and `LLM_PRICES` overrides.
+/// `None` for an unknown model.
+pub fn estimate_cost(model: &str, prompt_tokens: u64, completion_tokens: u64) -> Option<f64> {
+    PriceTable::from_env().estimate(model, prompt_tokens, completion_tokens)
+}
+
+/// Token usage and estimated cost accumulated over a run's LLM calls.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CostTracker {
+    #[serde(flatten)]
+    pub usage: TokenUsage,
+    pub estimated_cost_usd: f64,
+    /// Calls to models without a price; their cost is missing from the estimate.
+    #[serde(default)]
+    pub unpriced_calls: u32,
+    #[serde(skip)]
+    prices: PriceTable,
+}
+
+impl CostTracker {
+    pub fn new(prices: PriceTable) -> Self {
+        Self { prices, ..Default::default() }
+    }
+
+    pub fn record(&mut self, response: &LlmResponse) {
+        self.usage.record(response);
+        let prompt = u64::from(response.prompt_tokens.unwrap_or(0));
+        let completion = u64::from(response.completion_tokens.unwrap_or(0));
+        match self.prices.estimate(&response.model, prompt, completion) {
+            Some(cost) => self.estimated_cost_usd += cost,
+            None => {
+                debug!(model = %response.model, "No price for model, cost not estimated");
+                self.unpriced_calls += 1;
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.usage.is_empty()
+    }
+
+    /// Log the token usage and estimated cost, as agents do at the end of a run.
+    pub fn log_summary(&self) {
+        info!(
+            calls = self.usage.calls,
+            prompt_tokens = self.usage.prompt_tokens,
+            completion_tokens = self.usage.completion_tokens,
+            total_tokens = self.usage.total_tokens(),
+            unreported_calls = self.usage.unreported_calls,
+            estimated_cost_usd = format!("{:.4}", self.estimated_cost_usd),
+            unpriced_calls = self.unpriced_calls,
+            "LLM usage and estimated cost"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(model: &str, prompt: u32, completion: u32) -> LlmResponse {
+        LlmResponse {
+            text: String::new(),
+            prompt_tokens: Some(prompt),
+            completion_tokens: Some(completion),
+            model: model.to_string(),
+            truncated: false,
+            continuations: 0,
+            alternatives: Vec::new(),
+            grounding: None,
+            tool_call: None,
+            thoughts: None,
+        }
+    }
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn test_estimate_known_and_prefixed_models() {
+        let prices = PriceTable::default();
+        // 1M input at $5 + 100k output at $25
+        assert!(approx(prices.estimate("claude-opus-4-6", 1_000_000, 100_000).unwrap(), 7.5));
+        assert_eq!(prices.estimate("claude-opus-4-6-20260301", 1_000_000, 0), Some(5.0));
+        assert_eq!(prices.estimate("gemini-3.1-pro-preview", 0, 1_000_000), Some(12.0));
+    }
+
+    #[test]
+    fn test_unknown_model_has_no_estimate() {
+        let prices = PriceTable::default();
+        assert_eq!(prices.estimate("llama-4-local", 1000, 1000), None);
+        assert_eq!(prices.estimate("", 1000, 1000), None);
+    }
+
+    #[test]
+    fn test_overrides_replace_and_extend() {
+        let mut prices = PriceTable::default();
+        prices.apply_overrides(r#"{"gpt-5.2": {"input_per_million": 1.0, "output_per_million": 2.0},
+            "llama-4": {"input_per_million": 0.2, "output_per_million": 0.2}}"#).unwrap();
+        assert_eq!(prices.estimate("gpt-5.2-2025-12-11", 1_000_000, 1_000_000), Some(3.0));
+        assert!(prices.estimate("llama-4-local", 1_000_000, 0).is_some());
+        // Longest prefix wins
+        prices.apply_overrides(r#"{"gpt-5.2-2025": {"input_per_million": 9.0, "output_per_million": 0.0}}"#).unwrap();
+        assert_eq!(prices.estimate("gpt-5.2-2025-12-11", 1_000_000, 0), Some(9.0));
+
+        assert!(prices.apply_overrides("not json").is_err());
+    }
+
+    #[test]
+    fn test_tracker_accumulates_across_calls() {
+        let mut tracker = CostTracker::new(PriceTable::default());
+        tracker.record(&response("claude-opus-4-6", 10_000, 1_000)); // 0.05 + 0.025
+        tracker.record(&response("gemini-3.1-pro-preview", 20_000, 2_000)); // 0.04 + 0.024
+        tracker.record(&response("mystery-model", 5_000, 500));
+        assert_eq!(tracker.usage.calls, 3);
+        assert_eq!(tracker.usage.total_tokens(), 38_500);
+        assert!(approx(tracker.estimated_cost_usd, 0.139));
+        assert_eq!(tracker.unpriced_calls, 1);
+    }
+
+    #[test]
+    fn test_tracker_serializes_flat() {
+        let mut tracker = CostTracker::default();
+        tracker.record(&response("claude-opus-4-6", 1, 1));
+        let json = serde_json::to_value(&tracker).unwrap();
+        assert_eq!(json["calls"], 1);
+        assert!(json["estimated_cost_usd"].is_number());
+        assert!(json.get("prices").is_none());
+    }
+}
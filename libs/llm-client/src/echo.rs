@@ -0,0 +1,108 @@
+//! Offline provider for running the agents end-to-end without LLM calls.
+//!
+//! `LLM_PROVIDER=echo` selects [`LlmProvider::Echo`](crate::LlmProvider::Echo), which answers
+//! in-process and never sends a request. The reply is `ECHO_RESPONSE` when set; otherwise a
+//! prompt asking for an index (the article pickers) gets `{"index": 0}`, which parses both as a
+//! pick and as a bare integer, and anything else a lorem-ipsum summary. No API key is needed.
+
+use tracing::debug;
+
+use crate::tokens::estimate_tokens;
+use crate::{ChatMessage, LlmOptions, LlmProvider, LlmResponse, Role};
+
+/// Environment variable with a fixed reply for every echo call.
+pub const ECHO_RESPONSE_ENV_VAR: &str = "ECHO_RESPONSE";
+
+/// API key used for echo calls when `ECHO_API_KEY` is unset; nothing checks it.
+pub const ECHO_API_KEY: &str = "echo";
+
+/// Reply to index-selection prompts.
+const INDEX_REPLY: &str = r#"{"index": 0}"#;
+
+const LOREM_SUMMARY: &str = "**Lorem ipsum dolor sit amet**, consectetur adipiscing elit. Sed do eiusmod tempor \
+incididunt ut labore et dolore magna aliqua.\n\n\
+- **Ut enim ad minim veniam**: quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat.\n\
+- **Duis aute irure dolor**: in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur.";
+
+/// The echo reply to `messages`, with token counts estimated from the texts.
+pub(crate) fn reply(messages: &[ChatMessage], options: &LlmOptions) -> LlmResponse {
+    let prompt = messages.iter().rev().find(|m| m.role == Role::User).map_or("", |m| m.content.as_str());
+    let text = std::env::var(ECHO_RESPONSE_ENV_VAR)
+        .ok()
+        .filter(|text| !text.is_empty())
+        .unwrap_or_else(|| if asks_for_index(prompt) { INDEX_REPLY } else { LOREM_SUMMARY }.to_string());
+    debug!(prompt_len = prompt.len(), reply_len = text.len(), "Echo provider replying without a request");
+    let prompt_tokens = messages.iter().map(|m| estimate_tokens(&m.content)).sum::<usize>()
+        + options.system.as_deref().map_or(0, estimate_tokens);
+    LlmResponse {
+        prompt_tokens: Some(prompt_tokens as u32),
+        completion_tokens: Some(estimate_tokens(&text) as u32),
+        model: options.model_for(LlmProvider::Echo),
+        text,
+        truncated: false,
+        continuations: 0,
+        alternatives: Vec::new(),
+        grounding: None,
+        tool_call: None,
+        thoughts: None,
+    }
+}
+
+/// The prompt asks for the index of a chosen item.
+fn asks_for_index(prompt: &str) -> bool {
+    let prompt = prompt.to_lowercase();
+    prompt.contains("index") && ["select", "choose", "pick"].iter().any(|verb| prompt.contains(verb))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{call_llm_with_retry, extract_first_integer, parse_llm_json, LlmClient};
+
+    /// A client whose every request fails: any echo call that touched HTTP would error.
+    fn offline_client() -> reqwest::Client {
+        reqwest::Client::builder()
+            .proxy(reqwest::Proxy::all("http://127.0.0.1:1").unwrap())
+            .timeout(std::time::Duration::from_millis(200))
+            .build()
+            .unwrap()
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Pick {
+        index: usize,
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_echo_answers_without_http() {
+        let client = offline_client();
+        assert!(client.get("http://example.com").send().await.is_err(), "the proxy must be unreachable");
+
+        let pick = "Select the SINGLE best article.\n\n0. First\n1. Second\n\nReply ONLY with JSON giving the index of the chosen article.";
+        let text = call_llm_with_retry(&client, LlmProvider::Echo, ECHO_API_KEY, pick).await.unwrap();
+        assert_eq!(parse_llm_json::<Pick>(&text).unwrap().index, 0);
+        assert_eq!(extract_first_integer(&text), Some(0));
+
+        let summary = call_llm_with_retry(&client, LlmProvider::Echo, "", "Summarize this article: ...").await.unwrap();
+        assert!(summary.starts_with("**Lorem ipsum"), "{}", summary);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_echo_response_env_var_and_client() {
+        std::env::set_var(ECHO_RESPONSE_ENV_VAR, "canned");
+        let llm = LlmClient::builder(LlmProvider::Echo).http_client(offline_client()).build();
+        let text = llm.as_ref().unwrap().generate("Select the best, give its index").await.map(|r| r.text);
+        std::env::remove_var(ECHO_RESPONSE_ENV_VAR);
+        assert_eq!(text.unwrap(), "canned");
+        assert_eq!(llm.unwrap().model(), crate::ECHO_MODEL);
+    }
+
+    #[test]
+    fn test_index_prompts_are_recognized() {
+        assert!(asks_for_index("Pick one and reply with its INDEX"));
+        assert!(!asks_for_index("Summarize this article"));
+        assert!(!asks_for_index("Explain how a database index works"));
+    }
+}
@@ -0,0 +1,185 @@
+//! Tolerant parsing of feed item dates.
+//!
+//! Feeds nominally use RFC 2822 (RSS `pubDate`) or RFC 3339 (Atom, `dc:date`), but generators
+//! drop the seconds, spell the zone as `GMT`/`UTC` after an ISO timestamp, or give only a day.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use tracing::warn;
+
+/// Environment variable setting how many hours ahead of now a feed date may be before it's
+/// treated as bogus
+pub const FEED_MAX_FUTURE_SKEW_HOURS_ENV_VAR: &str = "FEED_MAX_FUTURE_SKEW_HOURS";
+/// Allowed skew when `FEED_MAX_FUTURE_SKEW_HOURS` is unset or invalid: enough for a publisher
+/// whose clock or zone is off, not for a post dated next year
+pub const DEFAULT_MAX_FUTURE_SKEW_HOURS: i64 = 6;
+
+/// Zone-less layouts, read as UTC once a trailing `GMT`/`UTC`/`Z` is removed.
+const NAIVE_FORMATS: [&str; 9] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+    "%a, %d %b %Y %H:%M:%S",
+    "%a, %d %b %Y %H:%M",
+    "%d %b %Y %H:%M:%S",
+    "%d %b %Y %H:%M",
+    // ThoughtWorks: "Tue Nov 18 00:00:00 UTC 2025"
+    "%a %b %d %H:%M:%S UTC %Y",
+];
+
+/// Layouts with a numeric offset that RFC 2822/3339 parsing rejects.
+const OFFSET_FORMATS: [&str; 4] = ["%Y-%m-%dT%H:%M%:z", "%Y-%m-%dT%H:%M%z", "%Y-%m-%d %H:%M:%S %z", "%a, %d %b %Y %H:%M %z"];
+
+/// A feed date in any of the forms seen in the wild: RFC 2822 (with or without seconds, any
+/// zone name chrono knows), RFC 3339 (with or without seconds), ISO timestamps followed by
+/// `GMT`/`UTC`, and bare `YYYY-MM-DD` days (midnight UTC). `None` if nothing fits.
+pub fn parse_feed_date(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc2822(text).or_else(|_| DateTime::parse_from_rfc3339(text)) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Some(dt) = OFFSET_FORMATS.iter().find_map(|format| DateTime::parse_from_str(text, format).ok()) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    let naive = [" GMT", " UTC", "Z"].iter().find_map(|zone| text.strip_suffix(zone)).unwrap_or(text).trim_end();
+    if let Some(dt) = NAIVE_FORMATS.iter().find_map(|format| NaiveDateTime::parse_from_str(naive, format).ok()) {
+        return Some(dt.and_utc());
+    }
+    NaiveDate::parse_from_str(naive, "%Y-%m-%d").ok().and_then(|day| day.and_hms_opt(0, 0, 0)).map(|dt| dt.and_utc())
+}
+
+/// The first of an item's candidate dates (e.g. `pubDate`, then `dc:date`, then
+/// `atom:updated`) that [`parse_feed_date`] understands.
+pub fn first_feed_date<'a>(candidates: impl IntoIterator<Item = &'a str>) -> Option<DateTime<Utc>> {
+    candidates.into_iter().find_map(parse_feed_date)
+}
+
+/// When an RSS item was published: its `pubDate`, else `dc:date`, else `atom:updated`, the
+/// latter two being all some WordPress and custom generators emit.
+pub fn rss_item_date(item: &rss::Item) -> Option<DateTime<Utc>> {
+    let dc_dates = item.dublin_core_ext().map(|dc| dc.dates()).unwrap_or_default();
+    let atom_updated = item
+        .extensions()
+        .get("atom")
+        .and_then(|atom| atom.get("updated"))
+        .map(|values| values.as_slice())
+        .unwrap_or_default();
+    first_feed_date(
+        item.pub_date()
+            .into_iter()
+            .chain(dc_dates.iter().map(String::as_str))
+            .chain(atom_updated.iter().filter_map(|value| value.value())),
+    )
+}
+
+/// `FEED_MAX_FUTURE_SKEW_HOURS` as a duration; unset or invalid is
+/// [`DEFAULT_MAX_FUTURE_SKEW_HOURS`].
+pub fn max_future_skew_from_env() -> Duration {
+    let hours = match std::env::var(FEED_MAX_FUTURE_SKEW_HOURS_ENV_VAR) {
+        Ok(raw) => raw.trim().parse().ok().filter(|&hours: &i64| hours >= 0).unwrap_or_else(|| {
+            warn!(value = %raw, "Invalid {}, using {}", FEED_MAX_FUTURE_SKEW_HOURS_ENV_VAR, DEFAULT_MAX_FUTURE_SKEW_HOURS);
+            DEFAULT_MAX_FUTURE_SKEW_HOURS
+        }),
+        Err(_) => DEFAULT_MAX_FUTURE_SKEW_HOURS,
+    };
+    Duration::hours(hours)
+}
+
+/// Whether `date` is more than `max_skew` after `now`: a misconfigured feed rather than a post
+/// from a publisher slightly ahead of us. Such a date would always look like the newest post.
+pub fn is_future_dated(date: DateTime<Utc>, now: DateTime<Utc>, max_skew: Duration) -> bool {
+    date > now + max_skew
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOON: &str = "2025-06-10T12:30:00Z";
+
+    #[test]
+    fn test_parse_feed_date_formats() {
+        let noon = DateTime::parse_from_rfc3339(NOON).unwrap().with_timezone(&Utc);
+        let noon_with_seconds = noon + chrono::Duration::seconds(45);
+        for (text, expected) in [
+            // RFC 2822, as in pubDate
+            ("Tue, 10 Jun 2025 12:30:00 +0000", noon),
+            ("Tue, 10 Jun 2025 12:30:00 GMT", noon),
+            ("Tue, 10 Jun 2025 05:30:00 PDT", noon),
+            ("Tue, 10 Jun 2025 12:30 GMT", noon),
+            ("10 Jun 2025 12:30:45 +0000", noon_with_seconds),
+            // RFC 3339, as in dc:date and atom:updated
+            ("2025-06-10T12:30:00Z", noon),
+            ("2025-06-10T14:30:00+02:00", noon),
+            ("2025-06-10T12:30:45.000Z", noon_with_seconds),
+            ("2025-06-10T14:30+02:00", noon),
+            ("2025-06-10T12:30Z", noon),
+            // ISO with a zone name, or none at all
+            ("2025-06-10 12:30:00 GMT", noon),
+            ("2025-06-10T12:30:00 UTC", noon),
+            ("2025-06-10 12:30:00", noon),
+            ("2025-06-10 14:30:00 +0200", noon),
+            ("  Tue, 10 Jun 2025 12:30:00 +0000\n", noon),
+        ] {
+            assert_eq!(parse_feed_date(text), Some(expected), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_feed_date_days_and_rejects() {
+        let midnight = NaiveDate::from_ymd_opt(2025, 11, 18).unwrap().and_hms_opt(0, 0, 0).unwrap().and_utc();
+        assert_eq!(parse_feed_date("2025-11-18"), Some(midnight));
+        assert_eq!(parse_feed_date("Tue Nov 18 00:00:00 UTC 2025"), Some(midnight));
+        for text in ["", "   ", "not a date", "2025-13-45", "yesterday"] {
+            assert_eq!(parse_feed_date(text), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_first_feed_date_skips_unparseable_candidates() {
+        let expected = DateTime::parse_from_rfc3339(NOON).unwrap().with_timezone(&Utc);
+        assert_eq!(first_feed_date(["sometime in June", NOON, "2020-01-01"]), Some(expected));
+        assert_eq!(first_feed_date(["", "n/a"]), None);
+        assert_eq!(first_feed_date([]), None);
+    }
+
+    #[test]
+    fn test_rss_item_date_falls_back_to_dc_date() {
+        let feed = r#"<rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/"><channel><title>Blog</title>
+            <item><title>Both</title><pubDate>Tue, 10 Jun 2025 12:30:00 GMT</pubDate><dc:date>2020-01-01</dc:date></item>
+            <item><title>Dublin Core</title><dc:date>2025-06-10T12:30:00Z</dc:date></item>
+            <item><title>Undated</title></item>
+            </channel></rss>"#;
+        let channel = rss::Channel::read_from(feed.as_bytes()).unwrap();
+        let noon = DateTime::parse_from_rfc3339(NOON).unwrap().with_timezone(&Utc);
+        let dates: Vec<Option<DateTime<Utc>>> = channel.items().iter().map(rss_item_date).collect();
+        assert_eq!(dates, [Some(noon), Some(noon), None]);
+    }
+
+    #[test]
+    fn test_is_future_dated() {
+        let now = DateTime::parse_from_rfc3339(NOON).unwrap().with_timezone(&Utc);
+        let skew = Duration::hours(DEFAULT_MAX_FUTURE_SKEW_HOURS);
+        assert!(!is_future_dated(now - Duration::days(3), now, skew));
+        assert!(!is_future_dated(now + Duration::hours(6), now, skew));
+        assert!(is_future_dated(now + Duration::hours(6) + Duration::seconds(1), now, skew));
+        assert!(is_future_dated(parse_feed_date("Mon, 06 Jan 2031 09:00:00 GMT").unwrap(), now, skew));
+        assert!(is_future_dated(now + Duration::seconds(1), now, Duration::zero()));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_max_future_skew_from_env() {
+        for (value, expected) in [(None, 6), (Some("12"), 12), (Some(" 0 "), 0), (Some("-3"), 6), (Some("soon"), 6)] {
+            match value {
+                Some(v) => std::env::set_var(FEED_MAX_FUTURE_SKEW_HOURS_ENV_VAR, v),
+                None => std::env::remove_var(FEED_MAX_FUTURE_SKEW_HOURS_ENV_VAR),
+            }
+            assert_eq!(max_future_skew_from_env(), Duration::hours(expected), "{:?}", value);
+        }
+        std::env::remove_var(FEED_MAX_FUTURE_SKEW_HOURS_ENV_VAR);
+    }
+}
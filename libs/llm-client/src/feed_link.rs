@@ -0,0 +1,49 @@
+//! The page an RSS item points at, for items that omit `<link>` and give only a `<guid>`, as
+//! some podcast and newsletter platforms do.
+
+use url::Url;
+
+/// An item's `<link>`, else its `<guid>` when that is an `http(s)` URL and either flagged
+/// `isPermaLink` (the RSS default when the attribute is absent) or shaped like a post URL,
+/// with a path below the site root. `tag:` and `urn:` GUIDs are never used.
+pub fn feed_item_url(link: Option<&str>, guid: Option<&str>, guid_is_permalink: bool) -> Option<String> {
+    if let Some(link) = link.map(str::trim).filter(|link| !link.is_empty()) {
+        return Some(link.to_string());
+    }
+    let guid = guid?.trim();
+    let url = Url::parse(guid).ok().filter(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some())?;
+    let looks_like_post = !url.path().trim_matches('/').is_empty();
+    (guid_is_permalink || looks_like_post).then(|| guid.to_string())
+}
+
+/// An RSS item's page: its `<link>`, else its `<guid>` as [`feed_item_url`] accepts it.
+pub fn rss_item_url(item: &rss::Item) -> Option<String> {
+    feed_item_url(item.link(), item.guid().map(|guid| guid.value()), item.guid().is_some_and(|guid| guid.is_permalink()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feed_item_url() {
+        for (link, guid, permalink, expected) in [
+            // The link wins over any GUID
+            (Some("https://blog.example.com/post"), Some("https://blog.example.com/?p=1"), true, Some("https://blog.example.com/post")),
+            (Some(" https://blog.example.com/post "), None, false, Some("https://blog.example.com/post")),
+            (None, Some("https://pod.example.com/episodes/42"), true, Some("https://pod.example.com/episodes/42")),
+            (Some("  "), Some("https://pod.example.com/episodes/42"), true, Some("https://pod.example.com/episodes/42")),
+            // Unflagged GUIDs count when they look like a post's URL
+            (None, Some("https://news.example.com/p/issue-12"), false, Some("https://news.example.com/p/issue-12")),
+            (None, Some("https://news.example.com/"), false, None),
+            (None, Some("https://news.example.com/"), true, Some("https://news.example.com/")),
+            (None, Some("tag:news.example.com,2025:issue-12"), true, None),
+            (None, Some("urn:uuid:6f1c2e0a-8c1b-4f7e-9d3a-2b5c7e9f0a11"), false, None),
+            (None, Some("ftp://files.example.com/ep.mp3"), true, None),
+            (None, Some("1b2c3d4e"), true, None),
+            (None, None, true, None),
+        ] {
+            assert_eq!(feed_item_url(link, guid, permalink).as_deref(), expected, "{:?} {:?} {}", link, guid, permalink);
+        }
+    }
+}
@@ -0,0 +1,323 @@
+//! HTTP GET with the shared retry policy, for feeds and other plain fetches.
+
+use std::fmt;
+use std::time::Duration;
+
+use base64::Engine;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+
+use crate::retry::{classify_message, classify_reqwest, classify_status, retry_async, Classification, RetryPolicy, Transience};
+use crate::SourceConfig;
+
+/// A failed HTTP fetch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchError {
+    pub url: String,
+    /// HTTP status when the server answered with a non-success code.
+    pub status: Option<u16>,
+    pub message: String,
+    /// [`classify_reqwest`] of the underlying reqwest error, which the message loses.
+    pub network: Option<Classification>,
+}
+
+impl FetchError {
+    fn from_reqwest(url: &str, err: reqwest::Error) -> Self {
+        Self { url: url.to_string(), status: err.status().map(|s| s.as_u16()), message: err.to_string(), network: Some(classify_reqwest(&err)) }
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            Some(status) => write!(f, "fetching {} returned {}: {}", self.url, status, self.message),
+            None => write!(f, "fetching {} failed: {}", self.url, self.message),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+impl Transience for FetchError {
+    fn classify(&self) -> Classification {
+        match (self.status, self.network) {
+            (Some(status), _) => classify_status(status),
+            (None, Some(classification)) => classification,
+            (None, None) => classify_message(&self.message),
+        }
+    }
+}
+
+/// Per-request settings of [`fetch_bytes_with`]; the default sends a plain GET.
+#[derive(Debug, Clone, Default)]
+pub struct FetchOptions {
+    /// Sent with every attempt, e.g. a source's [`SourceConfig::request_headers`].
+    pub headers: HeaderMap,
+    /// Whole-request timeout replacing the client's.
+    pub timeout: Option<Duration>,
+    /// Larger bodies fail, without reading more than this.
+    pub max_bytes: Option<usize>,
+}
+
+/// GET `url` and return the body, retrying transient failures under `policy`.
+/// Non-success statuses are errors.
+pub async fn fetch_bytes(client: &reqwest::Client, url: &str, policy: &RetryPolicy) -> Result<Vec<u8>, FetchError> {
+    fetch_bytes_with(client, url, &FetchOptions::default(), policy).await
+}
+
+/// [`fetch_bytes`] with headers, a timeout and a size cap from `options`. A body over the cap
+/// is a permanent error, raised as soon as its `Content-Length` or the bytes read so far pass it.
+pub async fn fetch_bytes_with(client: &reqwest::Client, url: &str, options: &FetchOptions, policy: &RetryPolicy) -> Result<Vec<u8>, FetchError> {
+    let too_large = |max: usize| FetchError {
+        url: url.to_string(),
+        status: None,
+        message: format!("response is larger than {} bytes", max),
+        network: Some(Classification::Permanent),
+    };
+    retry_async(policy, FetchError::classify, || async {
+        let mut request = client.get(url).headers(options.headers.clone());
+        if let Some(timeout) = options.timeout {
+            request = request.timeout(timeout);
+        }
+        let mut res = request.send().await.map_err(|e| FetchError::from_reqwest(url, e))?;
+        let status = res.status();
+        if !status.is_success() {
+            let reason = status.canonical_reason().unwrap_or("unexpected status").to_string();
+            return Err(FetchError { url: url.to_string(), status: Some(status.as_u16()), message: reason, network: None });
+        }
+        if let (Some(max), Some(length)) = (options.max_bytes, res.content_length()) {
+            if length > max as u64 {
+                return Err(too_large(max));
+            }
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = res.chunk().await.map_err(|e| FetchError::from_reqwest(url, e))? {
+            if options.max_bytes.is_some_and(|max| body.len() + chunk.len() > max) {
+                return Err(too_large(options.max_bytes.unwrap_or_default()));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }).await
+}
+
+impl SourceConfig {
+    /// The source's `headers` ready to send, with each `${VAR}` replaced by that environment
+    /// variable, plus the `Authorization` header of its `basic_auth`. Fails, without a
+    /// request, on an unset variable or an invalid name or value.
+    pub fn request_headers(&self) -> Result<HeaderMap, FetchError> {
+        let invalid = |message: String| FetchError { url: self.url.clone(), status: None, message, network: None };
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let value = expand_env_vars(value, |var| std::env::var(var).ok())
+                .map_err(|reason| invalid(format!("header '{}': {}", name, reason)))?;
+            let header_name = HeaderName::from_bytes(name.trim().as_bytes())
+                .map_err(|_| invalid(format!("header '{}' is not a valid header name", name)))?;
+            let header_value = HeaderValue::from_str(value.trim())
+                .map_err(|_| invalid(format!("header '{}' has characters not allowed in a header", name)))?;
+            headers.insert(header_name, header_value);
+        }
+        if let Some(auth) = &self.basic_auth {
+            let expand = |field: &str, value: &str| {
+                expand_env_vars(value, |var| std::env::var(var).ok()).map_err(|reason| invalid(format!("basic_auth {}: {}", field, reason)))
+            };
+            let credentials = format!("{}:{}", expand("username", &auth.username)?, expand("password", &auth.password)?);
+            let encoded = base64::engine::general_purpose::STANDARD.encode(credentials);
+            let mut value = HeaderValue::from_str(&format!("Basic {}", encoded)).map_err(|_| invalid("basic_auth cannot be encoded".to_string()))?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+        Ok(headers)
+    }
+}
+
+/// `text` with every `${VAR}` replaced by `lookup(VAR)`. An unknown variable or an unclosed
+/// `${` is an error; any other `$` is kept as is.
+pub(crate) fn expand_env_vars(text: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find('}').ok_or_else(|| "unclosed '${' in value".to_string())?;
+        let var = after[..end].trim();
+        if var.is_empty() {
+            return Err("empty '${}' in value".to_string());
+        }
+        out.push_str(&lookup(var).ok_or_else(|| format!("environment variable {} is not set", var))?);
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    use crate::{BasicAuth, SourceType};
+
+    #[test]
+    fn test_fetch_error_classification() {
+        let err = |status: Option<u16>, message: &str| FetchError { url: "https://x".to_string(), status, message: message.to_string(), network: None };
+        assert_eq!(err(Some(502), "Bad Gateway").classify(), Classification::Transient);
+        assert_eq!(err(Some(404), "Not Found").classify(), Classification::Permanent);
+        assert_eq!(err(None, "error sending request: connection refused").classify(), Classification::Transient);
+        assert_eq!(err(None, "invalid URL").classify(), Classification::Permanent);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bytes_retries_up_to_max_attempts() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET")).and(path("/feed.xml"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let err = fetch_bytes(&client, &format!("{}/feed.xml", server.uri()), &RetryPolicy::FAST_LOCAL).await.unwrap_err();
+        assert_eq!(err.status, Some(503));
+    }
+
+    #[tokio::test]
+    async fn test_connection_refused_is_classified_by_error_kind() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/feed.xml", listener.local_addr().unwrap());
+        drop(listener);
+
+        let client = reqwest::Client::new();
+        let err = fetch_bytes(&client, &url, &RetryPolicy::FAST_LOCAL).await.unwrap_err();
+        assert_eq!(err.status, None);
+        assert_eq!(err.network, Some(Classification::Transient));
+        assert_eq!(err.classify(), Classification::Transient);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bytes_does_not_retry_not_found() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET")).and(path("/gone.xml"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let err = fetch_bytes(&client, &format!("{}/gone.xml", server.uri()), &RetryPolicy::FAST_LOCAL).await.unwrap_err();
+        assert_eq!(err.status, Some(404));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bytes_returns_body() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET")).and(path("/ok"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("hello"))
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let body = fetch_bytes(&client, &format!("{}/ok", server.uri()), &RetryPolicy::FAST_LOCAL).await.unwrap();
+        assert_eq!(body, b"hello");
+    }
+
+    #[test]
+    fn test_expand_env_vars() {
+        let lookup = |var: &str| (var == "TOKEN").then(|| "s3cret".to_string());
+        for (text, expected) in [
+            ("Bearer ${TOKEN}", Ok("Bearer s3cret".to_string())),
+            ("${ TOKEN }:${TOKEN}", Ok("s3cret:s3cret".to_string())),
+            ("costs $5", Ok("costs $5".to_string())),
+            ("Bearer ${MISSING}", Err("environment variable MISSING is not set".to_string())),
+            ("Bearer ${TOKEN", Err("unclosed '${' in value".to_string())),
+            ("${}", Err("empty '${}' in value".to_string())),
+        ] {
+            assert_eq!(expand_env_vars(text, lookup), expected, "{}", text);
+        }
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_source_headers_are_sent_with_interpolated_values() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET")).and(path("/private.xml"))
+            .and(header("authorization", "Bearer t0ken"))
+            .and(header("referer", "https://example.com/"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<rss/>"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut source = SourceConfig::new("Internal", SourceType::Rss, format!("{}/private.xml", server.uri()));
+        source.headers.insert("Authorization".to_string(), "Bearer ${TEST_FEED_TOKEN}".to_string());
+        source.headers.insert("Referer".to_string(), "https://example.com/".to_string());
+
+        let err = source.request_headers().unwrap_err();
+        assert!(err.to_string().contains("TEST_FEED_TOKEN is not set"), "{}", err);
+
+        std::env::set_var("TEST_FEED_TOKEN", "t0ken");
+        let headers = source.request_headers();
+        std::env::remove_var("TEST_FEED_TOKEN");
+        let options = FetchOptions { headers: headers.unwrap(), ..Default::default() };
+        let body = fetch_bytes_with(&reqwest::Client::new(), &source.url, &options, &RetryPolicy::FAST_LOCAL).await.unwrap();
+        assert_eq!(body, b"<rss/>");
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_basic_auth_is_sent_with_the_password_from_the_environment() {
+        let server = MockServer::start().await;
+        // "reader:pa55" in base64
+        Mock::given(method("GET")).and(path("/private.xml"))
+            .and(header("authorization", "Basic cmVhZGVyOnBhNTU="))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<rss/>"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut source = SourceConfig::new("Internal", SourceType::Rss, format!("{}/private.xml", server.uri()));
+        source.basic_auth = Some(BasicAuth { username: "reader".to_string(), password: "${TEST_FEED_PASSWORD}".to_string() });
+
+        let err = source.request_headers().unwrap_err();
+        assert!(err.to_string().contains("basic_auth password: environment variable TEST_FEED_PASSWORD is not set"), "{}", err);
+
+        std::env::set_var("TEST_FEED_PASSWORD", "pa55");
+        let headers = source.request_headers();
+        std::env::remove_var("TEST_FEED_PASSWORD");
+        let headers = headers.unwrap();
+        assert!(headers[AUTHORIZATION].is_sensitive());
+        let options = FetchOptions { headers, ..Default::default() };
+        let body = fetch_bytes_with(&reqwest::Client::new(), &source.url, &options, &RetryPolicy::FAST_LOCAL).await.unwrap();
+        assert_eq!(body, b"<rss/>");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bytes_with_size_cap_and_timeout() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET")).and(path("/huge.xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("x".repeat(64 * 1024)))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET")).and(path("/slow.xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<rss/>").set_delay(Duration::from_millis(300)))
+            .mount(&server)
+            .await;
+        let client = reqwest::Client::new();
+
+        // Over the cap: a permanent error, not retried
+        let capped = FetchOptions { max_bytes: Some(16 * 1024), ..Default::default() };
+        let err = fetch_bytes_with(&client, &format!("{}/huge.xml", server.uri()), &capped, &RetryPolicy::FAST_LOCAL).await.unwrap_err();
+        assert!(err.message.contains("larger than 16384 bytes"), "{}", err);
+        assert_eq!(err.classify(), Classification::Permanent);
+        let roomy = FetchOptions { max_bytes: Some(64 * 1024), ..Default::default() };
+        assert_eq!(fetch_bytes_with(&client, &format!("{}/huge.xml", server.uri()), &roomy, &RetryPolicy::FAST_LOCAL).await.unwrap().len(), 64 * 1024);
+
+        let url = format!("{}/slow.xml", server.uri());
+        let hasty = FetchOptions { timeout: Some(Duration::from_millis(50)), ..Default::default() };
+        let no_retry = RetryPolicy { max_attempts: Some(1), ..RetryPolicy::FAST_LOCAL };
+        let err = fetch_bytes_with(&client, &url, &hasty, &no_retry).await.unwrap_err();
+        assert_eq!(err.classify(), Classification::Transient, "{}", err);
+        let patient = FetchOptions { timeout: Some(Duration::from_secs(5)), ..Default::default() };
+        assert_eq!(fetch_bytes_with(&client, &url, &patient, &no_retry).await.unwrap(), b"<rss/>");
+    }
+}
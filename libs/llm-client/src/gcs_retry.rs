@@ -0,0 +1,79 @@
+//! GCS object calls with retries, for code that talks to the bucket directly rather than
+//! through [`crate::GcsStore`].
+//!
+//! Every call retries under [`RetryPolicy::gcs_from_env`]: 429s, 5xx and dropped connections
+//! are transient, anything else (403, 404, bad requests) fails at once. See
+//! [`StorageError`]'s [`crate::Transience`] impl.
+
+use gcloud_storage::client::Client;
+use gcloud_storage::http::objects::delete::DeleteObjectRequest;
+use gcloud_storage::http::objects::download::Range;
+use gcloud_storage::http::objects::get::GetObjectRequest;
+use gcloud_storage::http::objects::upload::{Media, UploadObjectRequest, UploadType};
+use gcloud_storage::http::Error;
+
+use crate::retry::{retry_async, RetryPolicy, Transience};
+use crate::storage::StorageError;
+
+fn status_of(err: &Error) -> Option<u16> {
+    match err {
+        Error::Response(resp) => Some(resp.code),
+        Error::HttpClient(e) | Error::RawResponse(e, _) => e.status().map(|s| s.as_u16()),
+        _ => None,
+    }
+}
+
+impl From<Error> for StorageError {
+    fn from(err: Error) -> Self {
+        StorageError::new(status_of(&err), err.to_string())
+    }
+}
+
+/// Download `object` from `bucket`. A missing object is an error for which
+/// [`StorageError::is_not_found`] holds.
+pub async fn download_object(client: &Client, bucket: &str, object: &str) -> Result<Vec<u8>, StorageError> {
+    download_object_with(client, bucket, object, &RetryPolicy::gcs_from_env()).await
+}
+
+/// [`download_object`] retrying under `policy`.
+pub async fn download_object_with(client: &Client, bucket: &str, object: &str, policy: &RetryPolicy) -> Result<Vec<u8>, StorageError> {
+    retry_async(policy, StorageError::classify, || async {
+        let request = GetObjectRequest { bucket: bucket.to_string(), object: object.to_string(), ..Default::default() };
+        Ok(client.download_object(&request, &Range::default()).await?)
+    })
+    .await
+}
+
+/// Create or overwrite `object` in `bucket`.
+pub async fn upload_object(client: &Client, bucket: &str, object: &str, data: Vec<u8>) -> Result<(), StorageError> {
+    upload_object_with(client, bucket, object, data, &RetryPolicy::gcs_from_env()).await
+}
+
+/// [`upload_object`] retrying under `policy`.
+pub async fn upload_object_with(client: &Client, bucket: &str, object: &str, data: Vec<u8>, policy: &RetryPolicy) -> Result<(), StorageError> {
+    retry_async(policy, StorageError::classify, || async {
+        client
+            .upload_object(
+                &UploadObjectRequest { bucket: bucket.to_string(), ..Default::default() },
+                data.clone(),
+                &UploadType::Simple(Media::new(object.to_string())),
+            )
+            .await?;
+        Ok(())
+    })
+    .await
+}
+
+/// Delete `object` from `bucket`.
+pub async fn delete_object(client: &Client, bucket: &str, object: &str) -> Result<(), StorageError> {
+    delete_object_with(client, bucket, object, &RetryPolicy::gcs_from_env()).await
+}
+
+/// [`delete_object`] retrying under `policy`.
+pub async fn delete_object_with(client: &Client, bucket: &str, object: &str, policy: &RetryPolicy) -> Result<(), StorageError> {
+    retry_async(policy, StorageError::classify, || async {
+        let request = DeleteObjectRequest { bucket: bucket.to_string(), object: object.to_string(), ..Default::default() };
+        Ok(client.delete_object(&request).await?)
+    })
+    .await
+}
@@ -0,0 +1,234 @@
+//! Cheap authenticated request per provider, to find a bad key or model before a long run
+//! rather than after it.
+//!
+//! Gemini and OpenAI get a `GET` of the configured model's metadata; Claude, which has no
+//! such endpoint for every key, a one-token message. While replaying cassettes no request
+//! is sent and every provider counts as healthy, as does the offline echo provider.
+
+use std::fmt;
+use std::time::Duration;
+
+use tracing::{debug, instrument, warn};
+
+use crate::cassette::{Cassette, CassetteMode};
+use crate::key_pool::KeyPool;
+use crate::{anthropic_api_version, with_headers, ChatMessage, ClaudeRequest, LlmError, LlmOptions, LlmProvider};
+
+/// Time allowed for a health check when [`LlmOptions::timeout`] isn't set.
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Outcome of a [`health_check`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HealthStatus {
+    /// The key is accepted and the model exists.
+    Ok,
+    /// HTTP 401 or 403, or Gemini's 400 for an invalid key.
+    InvalidCredentials,
+    /// HTTP 404 for the configured model.
+    ModelNotFound { model: String },
+    /// No answer, or a server error.
+    Unreachable { reason: String },
+}
+
+impl HealthStatus {
+    pub fn is_ok(&self) -> bool {
+        *self == HealthStatus::Ok
+    }
+}
+
+impl fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthStatus::Ok => f.write_str("ok"),
+            HealthStatus::InvalidCredentials => f.write_str("API key rejected"),
+            HealthStatus::ModelNotFound { model } => write!(f, "model '{}' not found", model),
+            HealthStatus::Unreachable { reason } => write!(f, "unreachable: {}", reason),
+        }
+    }
+}
+
+/// Check `api_key` (each key of a comma-separated list) and the configured model of
+/// `provider`. One attempt per key, no retries.
+pub async fn health_check(client: &reqwest::Client, provider: LlmProvider, api_key: &str) -> Result<HealthStatus, LlmError> {
+    health_check_with(client, provider, api_key, &LlmOptions::default()).await
+}
+
+/// [`health_check`] of the model, base URL, timeout and extra headers in `options`.
+/// Statuses that say nothing about the key or model (e.g. 429) are returned as errors.
+#[instrument(skip(client, api_key, options), fields(provider = %provider.as_str()))]
+pub async fn health_check_with(
+    client: &reqwest::Client,
+    provider: LlmProvider,
+    api_key: &str,
+    options: &LlmOptions,
+) -> Result<HealthStatus, LlmError> {
+    if Cassette::from_env().is_some_and(|cassette| cassette.mode() == CassetteMode::Replay) {
+        debug!("Replaying cassettes; skipping health check");
+        return Ok(HealthStatus::Ok);
+    }
+    // Every key of a key list, since rotation may reach any of them
+    let pool = KeyPool::shared(api_key);
+    for (key_index, key) in pool.keys().iter().enumerate() {
+        let status = check_key(client, provider, key, options).await?;
+        if !status.is_ok() {
+            if pool.len() > 1 {
+                warn!(key_index, status = %status, "API key failed its health check");
+            }
+            return Ok(status);
+        }
+    }
+    Ok(HealthStatus::Ok)
+}
+
+async fn check_key(client: &reqwest::Client, provider: LlmProvider, api_key: &str, options: &LlmOptions) -> Result<HealthStatus, LlmError> {
+    let model = options.model_for(provider);
+    let request = match provider {
+        // Answers in-process, so there's nothing to reach
+        LlmProvider::Echo => return Ok(HealthStatus::Ok),
+        LlmProvider::Gemini => client
+            .get(options.endpoint(provider, &format!("v1beta/models/{}", model)))
+            .header("x-goog-api-key", api_key),
+        LlmProvider::OpenAI => client
+            .get(options.endpoint(provider, &format!("models/{}", model)))
+            .header("Authorization", format!("Bearer {}", api_key)),
+        LlmProvider::Claude => {
+            let ping = LlmOptions { max_output_tokens: Some(1), ..Default::default() };
+            let request = client
+                .post(options.endpoint(provider, "messages"))
+                .header("x-api-key", api_key)
+                .json(&ClaudeRequest::new(model.clone(), &[ChatMessage::user("ping")], &ping));
+            with_headers(request, &[("anthropic-version".to_string(), anthropic_api_version())])?
+        }
+    };
+    let request = with_headers(request, &options.extra_headers)?.timeout(options.timeout.unwrap_or(HEALTH_CHECK_TIMEOUT));
+
+    let res = match request.send().await {
+        Ok(res) => res,
+        Err(e) => return Ok(HealthStatus::Unreachable { reason: LlmError::Network(e).redact(&[api_key]).to_string() }),
+    };
+    let status = res.status().as_u16();
+    debug!(status, model = %model, "Health check response");
+    match status {
+        200..=299 => Ok(HealthStatus::Ok),
+        401 | 403 => Ok(HealthStatus::InvalidCredentials),
+        404 => Ok(HealthStatus::ModelNotFound { model }),
+        429 => Err(LlmError::RateLimited { provider, retry_after: None }),
+        500..=599 => Ok(HealthStatus::Unreachable { reason: format!("HTTP {}", status) }),
+        _ => {
+            let body = res.text().await.unwrap_or_default();
+            // Gemini rejects a bad key with 400 rather than 401
+            if provider == LlmProvider::Gemini && body.contains("API_KEY_INVALID") {
+                return Ok(HealthStatus::InvalidCredentials);
+            }
+            Err(LlmError::Http { provider, status, body }.redact(&[api_key]))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn options(server: &MockServer) -> LlmOptions {
+        LlmOptions { base_url: Some(server.uri()), model: Some("test-model".to_string()), ..Default::default() }
+    }
+
+    async fn check(provider: LlmProvider, status: u16, body: &str) -> Result<HealthStatus, LlmError> {
+        let server = MockServer::start().await;
+        let (http_method, route) = match provider {
+            LlmProvider::Gemini => ("GET", "/v1beta/models/test-model"),
+            LlmProvider::OpenAI => ("GET", "/models/test-model"),
+            LlmProvider::Claude => ("POST", "/messages"),
+            LlmProvider::Echo => unreachable!("echo sends no request"),
+        };
+        Mock::given(method(http_method)).and(path(route))
+            .respond_with(ResponseTemplate::new(status).set_body_string(body))
+            .expect(1)
+            .mount(&server)
+            .await;
+        health_check_with(&reqwest::Client::new(), provider, "key", &options(&server)).await
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_each_provider_reports_ok_bad_key_and_missing_model() {
+        for provider in LlmProvider::REMOTE {
+            assert_eq!(check(provider, 200, "{}").await.unwrap(), HealthStatus::Ok, "{:?}", provider);
+            assert_eq!(check(provider, 401, "").await.unwrap(), HealthStatus::InvalidCredentials, "{:?}", provider);
+            assert_eq!(check(provider, 403, "").await.unwrap(), HealthStatus::InvalidCredentials, "{:?}", provider);
+            assert_eq!(
+                check(provider, 404, "").await.unwrap(),
+                HealthStatus::ModelNotFound { model: "test-model".to_string() },
+                "{:?}",
+                provider
+            );
+        }
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_gemini_invalid_key_400() {
+        let body = r#"{"error":{"code":400,"status":"INVALID_ARGUMENT","details":[{"reason":"API_KEY_INVALID"}]}}"#;
+        assert_eq!(check(LlmProvider::Gemini, 400, body).await.unwrap(), HealthStatus::InvalidCredentials);
+        // Any other 400 is an error, not a verdict on the key
+        assert!(matches!(check(LlmProvider::Gemini, 400, "bad request").await, Err(LlmError::Http { status: 400, .. })));
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_replaying_cassettes_sends_nothing() {
+        std::env::set_var(crate::CASSETTE_DIR_ENV_VAR, "unused-cassettes");
+        let dead = LlmOptions { base_url: Some("http://127.0.0.1:1".to_string()), ..Default::default() };
+        let status = health_check_with(&reqwest::Client::new(), LlmProvider::Gemini, "key", &dead).await;
+        std::env::remove_var(crate::CASSETTE_DIR_ENV_VAR);
+        assert_eq!(status.unwrap(), HealthStatus::Ok);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_every_key_of_a_list_is_checked() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET")).and(path("/models/test-model")).and(header("Authorization", "Bearer good"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET")).and(path("/models/test-model")).and(header("Authorization", "Bearer revoked"))
+            .respond_with(ResponseTemplate::new(401))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let status = health_check_with(&reqwest::Client::new(), LlmProvider::OpenAI, "good,revoked", &options(&server)).await;
+        assert_eq!(status.unwrap(), HealthStatus::InvalidCredentials);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_server_errors_and_dead_hosts_are_unreachable() {
+        assert!(matches!(check(LlmProvider::OpenAI, 503, "").await, Ok(HealthStatus::Unreachable { .. })));
+
+        let dead = LlmOptions { base_url: Some("http://127.0.0.1:1".to_string()), ..Default::default() };
+        let status = health_check_with(&reqwest::Client::new(), LlmProvider::Claude, "key", &dead).await.unwrap();
+        assert!(matches!(status, HealthStatus::Unreachable { .. }), "{:?}", status);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_claude_check_is_a_one_token_authenticated_message() {
+        use wiremock::matchers::body_partial_json;
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .and(header("x-api-key", "key"))
+            .and(header("anthropic-version", anthropic_api_version().as_str()))
+            .and(body_partial_json(serde_json::json!({ "model": "test-model", "max_tokens": 1 })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "content": [{ "text": "pong" }] })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let status = health_check_with(&reqwest::Client::new(), LlmProvider::Claude, "key", &options(&server)).await;
+        assert_eq!(status.unwrap(), HealthStatus::Ok);
+    }
+}
@@ -0,0 +1,219 @@
+//! Per-host spacing of feed requests, so sources that share a host (several subreddits,
+//! several GitHub repos, a run of Hacker News items) don't reach it in a burst and draw 429s.
+//!
+//! Each host has a token bucket holding one token: a request takes it and the next one waits
+//! until it has refilled. The refill time is the default interval from
+//! `FEED_REQUESTS_PER_SECOND`, the host's own rate for APIs built for many small reads (the
+//! Hacker News API), or a source's own longer delay.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use tokio::time::Instant;
+use tracing::{debug, warn};
+use url::Url;
+
+/// Environment variable setting how many requests a second may start against any one host
+pub const FEED_REQUESTS_PER_SECOND_ENV_VAR: &str = "FEED_REQUESTS_PER_SECOND";
+/// Requests a second per host when `FEED_REQUESTS_PER_SECOND` is unset or invalid
+pub const DEFAULT_FEED_REQUESTS_PER_SECOND: f64 = 5.0;
+/// Host of the Hacker News API, which serves a story list as one request per item
+pub const HACKER_NEWS_API_HOST: &str = "hacker-news.firebaseio.com";
+/// Requests a second to [`HACKER_NEWS_API_HOST`]: a few dozen items shouldn't take seconds
+pub const HACKER_NEWS_API_REQUESTS_PER_SECOND: f64 = 50.0;
+
+/// Spaces out the starts of requests to the same host; shared by every fetch of a run.
+#[derive(Debug)]
+pub struct HostLimiter {
+    interval: Option<Duration>,
+    /// Hosts spaced out by their own interval instead of `interval`
+    host_intervals: HashMap<String, Option<Duration>>,
+    /// When the latest request to each host was let through
+    last_start: Mutex<HashMap<String, Instant>>,
+}
+
+impl HostLimiter {
+    /// At most `requests_per_second` request starts a second per host; zero, negative or
+    /// non-finite rates space requests only by the delays callers ask for.
+    pub fn new(requests_per_second: f64) -> Self {
+        Self { interval: interval_of(requests_per_second), host_intervals: HashMap::new(), last_start: Mutex::new(HashMap::new()) }
+    }
+
+    /// This limiter with `host` (as in `host` or `host:port`) held to `requests_per_second`
+    /// instead of the default rate.
+    pub fn with_host_rate(mut self, host: &str, requests_per_second: f64) -> Self {
+        self.host_intervals.insert(host.to_ascii_lowercase(), interval_of(requests_per_second));
+        self
+    }
+
+    /// The rate from `FEED_REQUESTS_PER_SECOND`, else [`DEFAULT_FEED_REQUESTS_PER_SECOND`];
+    /// unless that's unlimited, the Hacker News API gets [`HACKER_NEWS_API_REQUESTS_PER_SECOND`].
+    pub fn from_env() -> Self {
+        let rate = match std::env::var(FEED_REQUESTS_PER_SECOND_ENV_VAR) {
+            Ok(value) => value.trim().parse::<f64>().ok().filter(|rate| rate.is_finite() && *rate >= 0.0).unwrap_or_else(|| {
+                warn!(var = FEED_REQUESTS_PER_SECOND_ENV_VAR, value = %value, "Ignoring invalid request rate");
+                DEFAULT_FEED_REQUESTS_PER_SECOND
+            }),
+            Err(_) => DEFAULT_FEED_REQUESTS_PER_SECOND,
+        };
+        let limiter = Self::new(rate);
+        // Never stricter than no limit at all
+        if limiter.interval.is_none() {
+            return limiter;
+        }
+        limiter.with_host_rate(HACKER_NEWS_API_HOST, HACKER_NEWS_API_REQUESTS_PER_SECOND)
+    }
+
+    /// The process-wide limiter, created from the environment on first use.
+    pub fn shared() -> Arc<HostLimiter> {
+        static LIMITER: OnceLock<Arc<HostLimiter>> = OnceLock::new();
+        LIMITER.get_or_init(|| Arc::new(HostLimiter::from_env())).clone()
+    }
+
+    /// Gap between request starts to one host, `None` when only callers' delays apply.
+    pub fn interval(&self) -> Option<Duration> {
+        self.interval
+    }
+
+    /// Wait until `url`'s host is due: the host's interval (the default unless set with
+    /// [`Self::with_host_rate`]), or `min_delay` when longer, after the latest request let
+    /// through to it. Hosts are told apart by port too, and URLs without a host go at once.
+    pub async fn wait(&self, url: &str, min_delay: Option<Duration>) {
+        let Some(host) = host_key(url) else { return };
+        let interval = self.host_intervals.get(&host).copied().unwrap_or(self.interval);
+        let gap = interval.into_iter().chain(min_delay).max().unwrap_or_default();
+        let now = Instant::now();
+        let start = {
+            let mut last_start = self.last_start.lock().unwrap_or_else(|e| e.into_inner());
+            let start = match last_start.get(&host) {
+                Some(last) if *last + gap > now => *last + gap,
+                _ => now,
+            };
+            last_start.insert(host.clone(), start);
+            start
+        };
+        if start > now {
+            debug!(host = %host, wait_ms = (start - now).as_millis() as u64, "Spacing out requests to host");
+            tokio::time::sleep_until(start).await;
+        }
+    }
+}
+
+impl Default for HostLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_FEED_REQUESTS_PER_SECOND)
+    }
+}
+
+/// Gap between request starts at `requests_per_second`; `None` for zero, negative or
+/// non-finite rates.
+fn interval_of(requests_per_second: f64) -> Option<Duration> {
+    (requests_per_second.is_finite() && requests_per_second > 0.0)
+        .then(|| Duration::from_secs_f64(1.0 / requests_per_second))
+        .filter(|interval| !interval.is_zero())
+}
+
+/// `host` or `host:port` of `url`, lowercased.
+fn host_key(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let host = url.host_str()?.to_ascii_lowercase();
+    Some(match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[serial_test::serial]
+    fn test_from_env() {
+        let default_interval = Duration::from_secs_f64(1.0 / DEFAULT_FEED_REQUESTS_PER_SECOND);
+        assert_eq!(HostLimiter::from_env().interval(), Some(default_interval));
+
+        for (value, expected) in [("2", Some(Duration::from_millis(500))), ("0", None), ("fast", Some(default_interval)), ("-1", Some(default_interval))] {
+            std::env::set_var(FEED_REQUESTS_PER_SECOND_ENV_VAR, value);
+            let limiter = HostLimiter::from_env();
+            std::env::remove_var(FEED_REQUESTS_PER_SECOND_ENV_VAR);
+            assert_eq!(limiter.interval(), expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_host_key() {
+        assert_eq!(host_key("https://Old.Reddit.com/r/rust/.rss").as_deref(), Some("old.reddit.com"));
+        assert_eq!(host_key("https://old.reddit.com:443/r/golang/.rss").as_deref(), Some("old.reddit.com"));
+        assert_eq!(host_key("http://127.0.0.1:8080/feed").as_deref(), Some("127.0.0.1:8080"));
+        assert_eq!(host_key("not a url"), None);
+    }
+
+    /// Start offsets of `urls` fetched concurrently through `limiter`, in the order given.
+    async fn start_offsets(limiter: Arc<HostLimiter>, urls: &[(&'static str, Option<Duration>)]) -> Vec<Duration> {
+        let started = Instant::now();
+        let tasks: Vec<_> = urls
+            .iter()
+            .map(|&(url, delay)| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move {
+                    limiter.wait(url, delay).await;
+                    started.elapsed()
+                })
+            })
+            .collect();
+        let mut offsets = Vec::new();
+        for task in tasks {
+            offsets.push(task.await.unwrap());
+        }
+        offsets
+    }
+
+    #[tokio::test]
+    async fn test_requests_to_one_host_are_spaced_out() {
+        let limiter = Arc::new(HostLimiter::new(10.0));
+        let urls = [("https://a.example.com/1", None), ("https://a.example.com/2", None), ("https://a.example.com/3", None), ("https://b.example.com/1", None)];
+
+        let mut offsets = start_offsets(limiter, &urls).await;
+        // Other hosts aren't held up
+        assert!(offsets.pop().unwrap() < Duration::from_millis(50));
+        offsets.sort();
+        // The first goes at once, the next two 100ms apart
+        for (i, offset) in offsets.iter().enumerate() {
+            assert!(*offset >= Duration::from_millis(100) * i as u32, "{:?}", offsets);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_source_delay_overrides_a_shorter_interval() {
+        let limiter = Arc::new(HostLimiter::new(0.0));
+        let delay = Some(Duration::from_millis(250));
+        let urls = [("https://github.com/a/releases.atom", delay), ("https://github.com/b/releases.atom", delay)];
+
+        let mut offsets = start_offsets(limiter.clone(), &urls).await;
+        offsets.sort();
+        assert!(offsets[1] - offsets[0] >= Duration::from_millis(250), "{:?}", offsets);
+
+        // Without a rate or delay requests go at once
+        let offsets = start_offsets(limiter, &[("https://github.com/c", None), ("https://github.com/d", None)]).await;
+        assert!(offsets.iter().all(|offset| *offset < Duration::from_millis(50)), "{:?}", offsets);
+    }
+
+    #[tokio::test]
+    async fn test_host_rate_overrides_the_default() {
+        let limiter = Arc::new(HostLimiter::new(2.0).with_host_rate(HACKER_NEWS_API_HOST, HACKER_NEWS_API_REQUESTS_PER_SECOND));
+        let items = [
+            ("https://hacker-news.firebaseio.com/v0/item/1.json", None),
+            ("https://hacker-news.firebaseio.com/v0/item/2.json", None),
+            ("https://hacker-news.firebaseio.com/v0/item/3.json", None),
+        ];
+        let offsets = start_offsets(limiter.clone(), &items).await;
+        // 20ms apart rather than the default 500ms
+        assert!(offsets.iter().all(|offset| *offset < Duration::from_millis(200)), "{:?}", offsets);
+
+        let mut offsets = start_offsets(limiter, &[("https://news.example.com/a", None), ("https://news.example.com/b", None)]).await;
+        offsets.sort();
+        assert!(offsets[1] >= Duration::from_millis(500), "{:?}", offsets);
+    }
+}
@@ -0,0 +1,193 @@
+//! Shared settings for the agents' HTTP clients: timeouts, User-Agent and proxy.
+//!
+//! Some blogs reject reqwest's default User-Agent, so every client identifies itself as
+//! [`DEFAULT_USER_AGENT`] unless `HTTP_USER_AGENT` says otherwise.
+
+use std::time::Duration;
+
+use tracing::warn;
+use url::Url;
+
+use crate::run::http_client_builder;
+
+pub const HTTP_TIMEOUT_ENV_VAR: &str = "HTTP_TIMEOUT_SECS";
+pub const HTTPS_PROXY_ENV_VAR: &str = "HTTPS_PROXY";
+pub const HTTP_USER_AGENT_ENV_VAR: &str = "HTTP_USER_AGENT";
+
+pub const DEFAULT_USER_AGENT: &str = concat!("eng-pulse-agent/", env!("CARGO_PKG_VERSION"), " (+https://github.com/tsvet01/eng-pulse)");
+pub const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Settings for [`build_http_client`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpClientConfig {
+    /// Whole-request timeout
+    pub timeout: Duration,
+    pub connect_timeout: Duration,
+    pub user_agent: String,
+    /// Proxy for HTTPS requests, skipping the hosts in `NO_PROXY`; without one, reqwest still
+    /// honours the usual proxy variables
+    pub proxy: Option<Url>,
+    /// Skip TLS certificate checks; only for testing against self-signed servers
+    pub accept_invalid_certs: bool,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_HTTP_TIMEOUT,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            proxy: None,
+            accept_invalid_certs: false,
+        }
+    }
+}
+
+impl HttpClientConfig {
+    /// Defaults with a `default_timeout`, overridden by `HTTP_TIMEOUT_SECS`, `HTTPS_PROXY`
+    /// (or `https_proxy`) and `HTTP_USER_AGENT`. Invalid values are logged and ignored.
+    pub fn from_env(default_timeout: Duration) -> Self {
+        let timeout = match std::env::var(HTTP_TIMEOUT_ENV_VAR) {
+            Ok(raw) => match raw.trim().parse::<u64>() {
+                Ok(secs) if secs > 0 => Duration::from_secs(secs),
+                _ => {
+                    warn!(value = %raw, "Invalid {}, using {}s", HTTP_TIMEOUT_ENV_VAR, default_timeout.as_secs());
+                    default_timeout
+                }
+            },
+            Err(_) => default_timeout,
+        };
+        let proxy = std::env::var(HTTPS_PROXY_ENV_VAR)
+            .or_else(|_| std::env::var(HTTPS_PROXY_ENV_VAR.to_lowercase()))
+            .ok()
+            .filter(|raw| !raw.trim().is_empty())
+            .and_then(|raw| match Url::parse(raw.trim()) {
+                Ok(url) => Some(url),
+                Err(e) => {
+                    warn!(error = %e, "Invalid {}, connecting directly", HTTPS_PROXY_ENV_VAR);
+                    None
+                }
+            });
+        let user_agent = std::env::var(HTTP_USER_AGENT_ENV_VAR)
+            .ok()
+            .map(|ua| ua.trim().to_string())
+            .filter(|ua| !ua.is_empty())
+            .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string());
+        Self { timeout, proxy, user_agent, ..Self::default() }
+    }
+}
+
+/// A client with `config` whose requests also carry the run ID; see [`http_client_builder`].
+pub fn build_http_client(config: HttpClientConfig) -> Result<reqwest::Client, reqwest::Error> {
+    let mut builder = http_client_builder()
+        .timeout(config.timeout)
+        .connect_timeout(config.connect_timeout)
+        .user_agent(config.user_agent);
+    if let Some(proxy) = config.proxy {
+        builder = builder.proxy(reqwest::Proxy::https(proxy)?.no_proxy(reqwest::NoProxy::from_env()));
+    }
+    if config.accept_invalid_certs {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run_id, REQUEST_ID_HEADER};
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn clear_env() {
+        for var in [HTTP_TIMEOUT_ENV_VAR, HTTPS_PROXY_ENV_VAR, "https_proxy", HTTP_USER_AGENT_ENV_VAR, "NO_PROXY", "no_proxy"] {
+            std::env::remove_var(var);
+        }
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_from_env() {
+        clear_env();
+        assert_eq!(HttpClientConfig::from_env(Duration::from_secs(60)), HttpClientConfig { timeout: Duration::from_secs(60), ..Default::default() });
+
+        std::env::set_var(HTTP_TIMEOUT_ENV_VAR, " 5 ");
+        std::env::set_var(HTTPS_PROXY_ENV_VAR, "http://proxy.internal:3128");
+        std::env::set_var(HTTP_USER_AGENT_ENV_VAR, "custom-agent/2.0");
+        let config = HttpClientConfig::from_env(Duration::from_secs(60));
+        assert_eq!(config.timeout, Duration::from_secs(5));
+        assert_eq!(config.proxy.as_ref().map(Url::as_str), Some("http://proxy.internal:3128/"));
+        assert_eq!(config.user_agent, "custom-agent/2.0");
+
+        for (timeout, proxy) in [("0", "not a url"), ("soon", " ")] {
+            std::env::set_var(HTTP_TIMEOUT_ENV_VAR, timeout);
+            std::env::set_var(HTTPS_PROXY_ENV_VAR, proxy);
+            std::env::set_var(HTTP_USER_AGENT_ENV_VAR, "");
+            let config = HttpClientConfig::from_env(Duration::from_secs(60));
+            assert_eq!(config, HttpClientConfig { timeout: Duration::from_secs(60), ..Default::default() }, "{} {:?}", timeout, proxy);
+        }
+
+        std::env::remove_var(HTTPS_PROXY_ENV_VAR);
+        std::env::set_var("https_proxy", "http://lower.internal:8080");
+        assert_eq!(HttpClientConfig::from_env(DEFAULT_HTTP_TIMEOUT).proxy.map(String::from), Some("http://lower.internal:8080/".to_string()));
+        clear_env();
+    }
+
+    #[tokio::test]
+    async fn test_requests_carry_the_user_agent() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET")).and(path("/feed.xml"))
+            .and(header("user-agent", DEFAULT_USER_AGENT))
+            .and(header(REQUEST_ID_HEADER, run_id()))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET")).and(path("/feed.xml"))
+            .and(header("user-agent", "custom-agent/2.0"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/feed.xml", server.uri());
+        let client = build_http_client(HttpClientConfig::default()).unwrap();
+        assert_eq!(client.get(&url).send().await.unwrap().status(), 200);
+        let client = build_http_client(HttpClientConfig { user_agent: "custom-agent/2.0".to_string(), ..Default::default() }).unwrap();
+        assert_eq!(client.get(&url).send().await.unwrap().status(), 200);
+        assert!(DEFAULT_USER_AGENT.starts_with("eng-pulse-agent/"));
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_proxy_is_only_used_for_https() {
+        clear_env();
+        let proxy = MockServer::start().await;
+        let server = MockServer::start().await;
+        Mock::given(method("GET")).and(path("/feed.xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("direct"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // Plain HTTP goes direct
+        let config = HttpClientConfig { proxy: Some(Url::parse(&proxy.uri()).unwrap()), ..Default::default() };
+        let client = build_http_client(config.clone()).unwrap();
+        let body = client.get(format!("{}/feed.xml", server.uri())).send().await.unwrap().text().await.unwrap();
+        assert_eq!(body, "direct");
+
+        // HTTPS is tunnelled through the proxy, unless the host is in NO_PROXY
+        let client = build_http_client(config.clone()).unwrap();
+        assert!(client.get("https://feeds.example.invalid/feed.xml").send().await.is_err());
+        let connects = proxy.received_requests().await.unwrap();
+        assert_eq!(connects.len(), 1);
+        assert_eq!(connects[0].method.to_string(), "CONNECT");
+
+        std::env::set_var("NO_PROXY", "feeds.example.invalid");
+        let client = build_http_client(config).unwrap();
+        std::env::remove_var("NO_PROXY");
+        assert!(client.get("https://feeds.example.invalid/feed.xml").send().await.is_err());
+        assert_eq!(proxy.received_requests().await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,227 @@
+//! Image input for multimodal calls, e.g. the diagrams of an article whose text alone
+//! loses the main content.
+//!
+//! Images travel with a user turn ([`ChatMessage::images`](crate::ChatMessage)) as base64
+//! and become Gemini `inlineData` parts, Claude `image` blocks or OpenAI `image_url` parts
+//! with a data URL, placed before the turn's text.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::retry::RetryPolicy;
+use crate::{send_with_policy, ChatMessage, LlmOptions, LlmProvider};
+
+/// An image attached to a message.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ImageInput {
+    /// e.g. `image/png`
+    pub mime_type: String,
+    /// Standard base64 of the image bytes.
+    pub data: String,
+}
+
+impl ImageInput {
+    pub fn new(mime_type: impl Into<String>, bytes: &[u8]) -> Self {
+        Self { mime_type: mime_type.into(), data: base64::engine::general_purpose::STANDARD.encode(bytes) }
+    }
+
+    fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.mime_type, self.data)
+    }
+}
+
+/// Send `prompt` with `images` (mime type and bytes each), with exponential backoff retry.
+#[instrument(skip(client, api_key, prompt, images), fields(provider = %provider.as_str(), prompt_len = tracing::field::Empty, images = images.len()))]
+pub async fn call_llm_with_images(
+    client: &reqwest::Client,
+    provider: LlmProvider,
+    api_key: &str,
+    prompt: impl Into<String>,
+    images: Vec<(String, Vec<u8>)>,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let prompt = prompt.into();
+    tracing::Span::current().record("prompt_len", prompt.len());
+    let images = images.into_iter().map(|(mime_type, bytes)| ImageInput::new(mime_type, &bytes)).collect();
+    let messages = [ChatMessage::user_with_images(prompt, images)];
+    let response = send_with_policy(client, provider, api_key, &messages, &LlmOptions::default(), &RetryPolicy::llm_from_env()).await?;
+    Ok(response.text)
+}
+
+// --- Provider wire formats ---
+
+/// Gemini `inlineData`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiBlob {
+    pub mime_type: String,
+    pub data: String,
+}
+
+impl From<&ImageInput> for GeminiBlob {
+    fn from(image: &ImageInput) -> Self {
+        Self { mime_type: image.mime_type.clone(), data: image.data.clone() }
+    }
+}
+
+/// OpenAI message content: a string, or parts when there are images.
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+pub(crate) enum OpenAIContent {
+    Text(String),
+    Parts(Vec<OpenAIContentPart>),
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum OpenAIContentPart {
+    Text { text: String },
+    ImageUrl { image_url: OpenAIImageUrl },
+}
+
+#[derive(Serialize, Debug)]
+pub(crate) struct OpenAIImageUrl {
+    url: String,
+}
+
+impl From<&ChatMessage> for OpenAIContent {
+    fn from(message: &ChatMessage) -> Self {
+        if message.images.is_empty() {
+            return OpenAIContent::Text(message.content.clone());
+        }
+        let mut parts: Vec<_> = message.images
+            .iter()
+            .map(|image| OpenAIContentPart::ImageUrl { image_url: OpenAIImageUrl { url: image.data_url() } })
+            .collect();
+        parts.push(OpenAIContentPart::Text { text: message.content.clone() });
+        OpenAIContent::Parts(parts)
+    }
+}
+
+/// Claude message content: a string, or blocks when there are images.
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+pub(crate) enum ClaudeContent {
+    Text(String),
+    Blocks(Vec<ClaudeInputBlock>),
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ClaudeInputBlock {
+    Text { text: String },
+    Image { source: ClaudeImageSource },
+}
+
+#[derive(Serialize, Debug)]
+pub(crate) struct ClaudeImageSource {
+    r#type: &'static str,
+    media_type: String,
+    data: String,
+}
+
+impl From<&ChatMessage> for ClaudeContent {
+    fn from(message: &ChatMessage) -> Self {
+        if message.images.is_empty() {
+            return ClaudeContent::Text(message.content.clone());
+        }
+        let mut blocks: Vec<_> = message.images
+            .iter()
+            .map(|image| ClaudeInputBlock::Image {
+                source: ClaudeImageSource { r#type: "base64", media_type: image.mime_type.clone(), data: image.data.clone() },
+            })
+            .collect();
+        blocks.push(ClaudeInputBlock::Text { text: message.content.clone() });
+        ClaudeContent::Blocks(blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClaudeRequest, GeminiRequest, OpenAIRequest};
+    use serde_json::json;
+
+    // "PNG" with its first byte; encodes as "iVBORw=="
+    const BYTES: &[u8] = &[0x89, b'P', b'N', b'G'];
+
+    fn message() -> ChatMessage {
+        ChatMessage::user_with_images("Describe the diagram", vec![ImageInput::new("image/png", BYTES)])
+    }
+
+    #[test]
+    fn test_base64_encoding() {
+        let image = ImageInput::new("image/png", BYTES);
+        assert_eq!(image.data, "iVBORw==");
+        assert_eq!(image.data_url(), "data:image/png;base64,iVBORw==");
+        assert_eq!(ImageInput::new("image/jpeg", b"").data, "");
+    }
+
+    #[test]
+    fn test_gemini_inline_data() {
+        let json = serde_json::to_value(GeminiRequest::new(&[message()], &LlmOptions::default())).unwrap();
+        assert_eq!(json["contents"][0]["parts"], json!([
+            { "inlineData": { "mimeType": "image/png", "data": "iVBORw==" } },
+            { "text": "Describe the diagram" }
+        ]));
+    }
+
+    #[test]
+    fn test_claude_image_blocks() {
+        let json = serde_json::to_value(ClaudeRequest::new("claude".to_string(), &[message()], &LlmOptions::default())).unwrap();
+        assert_eq!(json["messages"][0]["content"], json!([
+            { "type": "image", "source": { "type": "base64", "media_type": "image/png", "data": "iVBORw==" } },
+            { "type": "text", "text": "Describe the diagram" }
+        ]));
+        // Text-only turns keep the plain string
+        let json = serde_json::to_value(ClaudeRequest::new("claude".to_string(), &[ChatMessage::user("hi")], &LlmOptions::default())).unwrap();
+        assert_eq!(json["messages"][0]["content"], "hi");
+    }
+
+    #[test]
+    fn test_openai_image_url_parts() {
+        let options = LlmOptions { system: Some("Be brief".to_string()), ..Default::default() };
+        let json = serde_json::to_value(OpenAIRequest::new("gpt-4o".to_string(), &[message()], &options)).unwrap();
+        assert_eq!(json["messages"][0]["content"], "Be brief");
+        assert_eq!(json["messages"][1]["content"], json!([
+            { "type": "image_url", "image_url": { "url": "data:image/png;base64,iVBORw==" } },
+            { "type": "text", "text": "Describe the diagram" }
+        ]));
+    }
+
+    #[test]
+    fn test_images_survive_a_cassette_round_trip() {
+        let json = serde_json::to_value(message()).unwrap();
+        assert_eq!(json["images"], json!([{ "mime_type": "image/png", "data": "iVBORw==" }]));
+        assert_eq!(serde_json::from_value::<ChatMessage>(json).unwrap(), message());
+        // Text-only messages serialize as before, so existing cassette keys still match
+        assert!(serde_json::to_value(ChatMessage::user("hi")).unwrap().get("images").is_none());
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_call_llm_with_images() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .and(body_partial_json(json!({
+                "messages": [{ "content": [{ "type": "image", "source": { "data": "iVBORw==" } }] }]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "content": [{ "type": "text", "text": "A pipeline diagram" }] })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        std::env::set_var("CLAUDE_BASE_URL", server.uri());
+        let reply = call_llm_with_images(
+            &reqwest::Client::new(),
+            LlmProvider::Claude,
+            "key",
+            "Describe the diagram".to_string(),
+            vec![("image/png".to_string(), BYTES.to_vec())],
+        ).await;
+        std::env::remove_var("CLAUDE_BASE_URL");
+        assert_eq!(reply.unwrap(), "A pipeline diagram");
+    }
+}
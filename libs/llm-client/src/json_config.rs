@@ -0,0 +1,307 @@
+//! Parsing of JSON config/state objects (sources.json, manifest.json, user_candidates.json)
+//! with readable errors and salvage of partially invalid lists.
+
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tracing::warn;
+
+/// How many characters of the offending content to include in error messages.
+const EXCERPT_CHARS: usize = 200;
+
+/// Expected shape of one list entry, used to build hints for common mistakes.
+#[derive(Debug, Clone, Copy)]
+pub struct SchemaHint {
+    pub entry_name: &'static str,
+    pub required_fields: &'static [&'static str],
+    pub example: &'static str,
+}
+
+/// Schema hint for `config/sources.json` and `config/user_candidates.json`.
+pub const SOURCE_SCHEMA_HINT: SchemaHint = SchemaHint {
+    entry_name: "source",
+    required_fields: &["name", "type", "url"],
+    example: r#"{"name": "Netflix TechBlog", "type": "rss", "url": "https://netflixtechblog.com/feed"}"#,
+};
+
+/// A list entry that failed to deserialize and was skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedEntry {
+    pub index: usize,
+    pub error: String,
+}
+
+/// Result of a lenient list parse: the valid entries plus the ones that were dropped.
+#[derive(Debug, Clone)]
+pub struct ParsedList<T> {
+    pub entries: Vec<T>,
+    pub rejected: Vec<RejectedEntry>,
+}
+
+impl<T> ParsedList<T> {
+    pub fn rejected_indices(&self) -> Vec<usize> {
+        self.rejected.iter().map(|r| r.index).collect()
+    }
+
+    /// Log a warning listing the rejected entries, if any.
+    pub fn warn_rejected(&self, object_path: &str) {
+        if self.rejected.is_empty() {
+            return;
+        }
+        warn!(
+            object = %object_path,
+            rejected = ?self.rejected_indices(),
+            kept = self.entries.len(),
+            "Skipping invalid entries"
+        );
+        for entry in &self.rejected {
+            warn!(object = %object_path, index = entry.index, error = %entry.error, "Rejected entry");
+        }
+    }
+}
+
+/// Deserialization failure with enough context to fix the object by hand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigParseError {
+    pub object_path: String,
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+    pub excerpt: String,
+    pub hint: Option<String>,
+}
+
+impl fmt::Display for ConfigParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to parse {}: {}", self.object_path, self.message)?;
+        if self.line > 0 && !self.message.contains(" at line ") {
+            write!(f, " at line {} column {}", self.line, self.column)?;
+        }
+        if let Some(hint) = &self.hint {
+            write!(f, "\n  hint: {}", hint)?;
+        }
+        write!(f, "\n  content starts with: {}", self.excerpt)
+    }
+}
+
+impl std::error::Error for ConfigParseError {}
+
+fn excerpt(data: &[u8]) -> String {
+    let text = String::from_utf8_lossy(data);
+    let mut out: String = text.chars().take(EXCERPT_CHARS).collect();
+    if text.chars().count() > EXCERPT_CHARS {
+        out.push_str("...");
+    }
+    out
+}
+
+fn parse_error(object_path: &str, data: &[u8], err: &serde_json::Error, hint: Option<String>) -> ConfigParseError {
+    ConfigParseError {
+        object_path: object_path.to_string(),
+        message: err.to_string(),
+        line: err.line(),
+        column: err.column(),
+        excerpt: excerpt(data),
+        hint,
+    }
+}
+
+/// Explain the common wrong-shape mistakes for a value that should be a list of entries.
+fn diagnose_shape(value: &Value, schema: &SchemaHint) -> Option<String> {
+    let expected = format!(
+        "expected a JSON array of {} objects, e.g. [{}]",
+        schema.entry_name, schema.example
+    );
+    match value {
+        Value::Object(map) => {
+            if let Some((key, _)) = map.iter().find(|(_, v)| v.is_array()) {
+                return Some(format!(
+                    "found an object wrapping an array under \"{}\"; {}",
+                    key, expected
+                ));
+            }
+            let keys: Vec<&str> = map.keys().take(3).map(|k| k.as_str()).collect();
+            Some(format!(
+                "found an object (keys: {}) instead of an array; if entries are keyed by name, move each into the list with a \"name\" field. {}",
+                keys.join(", "),
+                expected
+            ))
+        }
+        Value::Array(items) => {
+            if items.iter().any(|v| v.is_string()) {
+                return Some(format!(
+                    "found strings in the array; each {} must be an object with fields {}. {}",
+                    schema.entry_name,
+                    schema.required_fields.join(", "),
+                    expected
+                ));
+            }
+            for (i, item) in items.iter().enumerate() {
+                if let Value::Object(map) = item {
+                    let missing: Vec<&str> = schema.required_fields.iter()
+                        .copied()
+                        .filter(|f| !map.contains_key(*f))
+                        .collect();
+                    if !missing.is_empty() {
+                        return Some(format!(
+                            "entry {} is missing required field(s) {}; example entry: {}",
+                            i,
+                            missing.iter().map(|f| format!("\"{}\"", f)).collect::<Vec<_>>().join(", "),
+                            schema.example
+                        ));
+                    }
+                }
+            }
+            None
+        }
+        _ => Some(expected),
+    }
+}
+
+/// Strictly parse a JSON document, reporting path, position, an excerpt and a shape hint on failure.
+pub fn parse_json_strict<T: DeserializeOwned>(
+    object_path: &str,
+    data: &[u8],
+    schema: Option<&SchemaHint>,
+) -> Result<T, ConfigParseError> {
+    serde_json::from_slice::<T>(data).map_err(|err| {
+        let hint = schema.and_then(|schema| {
+            serde_json::from_slice::<Value>(data).ok().and_then(|v| diagnose_shape(&v, schema))
+        });
+        parse_error(object_path, data, &err, hint)
+    })
+}
+
+/// Parse a JSON array of entries, salvaging the valid ones when only some entries are malformed.
+/// Fails only when the document isn't an array or no entry is usable.
+pub fn parse_json_list<T: DeserializeOwned>(
+    object_path: &str,
+    data: &[u8],
+    schema: &SchemaHint,
+) -> Result<ParsedList<T>, ConfigParseError> {
+    let strict_err = match serde_json::from_slice::<Vec<T>>(data) {
+        Ok(entries) => return Ok(ParsedList { entries, rejected: Vec::new() }),
+        Err(e) => e,
+    };
+
+    let value: Value = match serde_json::from_slice(data) {
+        Ok(value) => value,
+        Err(_) => return Err(parse_error(object_path, data, &strict_err, None)),
+    };
+
+    let Value::Array(items) = &value else {
+        return Err(parse_error(object_path, data, &strict_err, diagnose_shape(&value, schema)));
+    };
+
+    let mut entries = Vec::new();
+    let mut rejected = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        match serde_json::from_value::<T>(item.clone()) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => rejected.push(RejectedEntry { index, error: e.to_string() }),
+        }
+    }
+
+    if entries.is_empty() && !rejected.is_empty() {
+        return Err(parse_error(object_path, data, &strict_err, diagnose_shape(&value, schema)));
+    }
+
+    Ok(ParsedList { entries, rejected })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SourceConfig;
+
+    const PATH: &str = "gs://bucket/config/sources.json";
+
+    #[test]
+    fn test_parse_list_valid() {
+        let data = br#"[{"name": "A", "type": "rss", "url": "https://a.com/feed"}]"#;
+        let parsed: ParsedList<SourceConfig> = parse_json_list(PATH, data, &SOURCE_SCHEMA_HINT).unwrap();
+        assert_eq!(parsed.entries.len(), 1);
+        assert!(parsed.rejected.is_empty());
+    }
+
+    #[test]
+    fn test_parse_list_object_instead_of_array() {
+        let data = br#"{"Netflix": {"type": "rss", "url": "https://netflixtechblog.com/feed"}}"#;
+        let err = parse_json_list::<SourceConfig>(PATH, data, &SOURCE_SCHEMA_HINT).unwrap_err();
+        assert_eq!(err.object_path, PATH);
+        assert_eq!(err.line, 1);
+        let hint = err.hint.clone().unwrap();
+        assert!(hint.contains("instead of an array"));
+        assert!(hint.contains("Netflix"));
+        let msg = err.to_string();
+        assert!(msg.contains(PATH));
+        assert!(msg.contains("line 1"));
+        assert!(msg.contains("content starts with: {\"Netflix\""));
+    }
+
+    #[test]
+    fn test_parse_list_wrapper_object() {
+        let data = br#"{"sources": [{"name": "A", "type": "rss", "url": "https://a.com"}]}"#;
+        let err = parse_json_list::<SourceConfig>(PATH, data, &SOURCE_SCHEMA_HINT).unwrap_err();
+        assert!(err.hint.unwrap().contains("wrapping an array under \"sources\""));
+    }
+
+    #[test]
+    fn test_parse_list_array_of_strings() {
+        let data = br#"["https://a.com/feed", "https://b.com/feed"]"#;
+        let err = parse_json_list::<SourceConfig>(PATH, data, &SOURCE_SCHEMA_HINT).unwrap_err();
+        let hint = err.hint.unwrap();
+        assert!(hint.contains("found strings"));
+        assert!(hint.contains("name, type, url"));
+    }
+
+    #[test]
+    fn test_parse_list_missing_type_field() {
+        let data = br#"[{"name": "A", "url": "https://a.com/feed"}]"#;
+        let err = parse_json_list::<SourceConfig>(PATH, data, &SOURCE_SCHEMA_HINT).unwrap_err();
+        let hint = err.hint.unwrap();
+        assert!(hint.contains("entry 0 is missing required field(s) \"type\""));
+        assert!(hint.contains("\"type\": \"rss\""));
+    }
+
+    #[test]
+    fn test_parse_list_salvages_valid_entries() {
+        let data = br#"[
+            {"name": "A", "type": "rss", "url": "https://a.com/feed"},
+            {"name": "B", "url": "https://b.com/feed"},
+            "https://c.com/feed",
+            {"name": "D", "type": "atom", "url": "https://d.com/atom.xml"}
+        ]"#;
+        let parsed: ParsedList<SourceConfig> = parse_json_list(PATH, data, &SOURCE_SCHEMA_HINT).unwrap();
+        assert_eq!(parsed.entries.len(), 2);
+        assert_eq!(parsed.entries[0].name, "A");
+        assert_eq!(parsed.entries[1].name, "D");
+        assert_eq!(parsed.rejected_indices(), vec![1, 2]);
+        assert!(parsed.rejected[0].error.contains("type"));
+    }
+
+    #[test]
+    fn test_parse_list_syntax_error_has_position() {
+        let data = b"[\n  {\"name\": \"A\",\n  }\n]";
+        let err = parse_json_list::<SourceConfig>(PATH, data, &SOURCE_SCHEMA_HINT).unwrap_err();
+        assert_eq!(err.line, 3);
+        assert!(err.hint.is_none());
+    }
+
+    #[test]
+    fn test_parse_list_excerpt_is_truncated() {
+        let long = format!("{{\"x\": \"{}\"}}", "a".repeat(500));
+        let err = parse_json_list::<SourceConfig>(PATH, long.as_bytes(), &SOURCE_SCHEMA_HINT).unwrap_err();
+        assert_eq!(err.excerpt.chars().count(), EXCERPT_CHARS + 3);
+    }
+
+    #[test]
+    fn test_parse_strict_reports_hint() {
+        let data = br#"{"a": 1}"#;
+        let err = parse_json_strict::<Vec<SourceConfig>>(PATH, data, Some(&SOURCE_SCHEMA_HINT)).unwrap_err();
+        assert!(err.hint.unwrap().contains("instead of an array"));
+        let err = parse_json_strict::<Vec<SourceConfig>>(PATH, data, None).unwrap_err();
+        assert!(err.hint.is_none());
+    }
+}
@@ -0,0 +1,105 @@
+//! Several API keys of one provider, e.g. from different projects with separate quotas.
+//!
+//! An API key given as a comma-separated list (`GEMINI_API_KEY=key-a,key-b`) becomes a
+//! [`KeyPool`]. Calls use the pool's current key; a rate-limited attempt (HTTP 429, which
+//! includes exhausted quotas) moves the pool to the next key for the retry. The pool is shared
+//! by every call with the same key list, so later calls in the run start from the last key
+//! that worked. Logs name keys by index only.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use tracing::warn;
+
+use crate::LlmProvider;
+
+/// API keys used in turn, starting from the last one that wasn't rate limited.
+#[derive(Debug)]
+pub struct KeyPool {
+    keys: Vec<String>,
+    current: AtomicUsize,
+}
+
+impl KeyPool {
+    /// The keys of a comma-separated list; blank entries are dropped. A list without any key
+    /// keeps the value as its only key, so a missing key fails at the provider as before.
+    pub fn new(api_keys: &str) -> Self {
+        let mut keys: Vec<String> = api_keys.split(',').map(str::trim).filter(|k| !k.is_empty()).map(String::from).collect();
+        if keys.is_empty() {
+            keys.push(api_keys.to_string());
+        }
+        Self { keys, current: AtomicUsize::new(0) }
+    }
+
+    /// The process-wide pool of a key list, created on first use.
+    pub fn shared(api_keys: &str) -> Arc<KeyPool> {
+        static POOLS: OnceLock<Mutex<HashMap<String, Arc<KeyPool>>>> = OnceLock::new();
+        let mut pools = POOLS.get_or_init(Default::default).lock().unwrap();
+        pools.entry(api_keys.to_string()).or_insert_with(|| Arc::new(KeyPool::new(api_keys))).clone()
+    }
+
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Index of the key calls start with.
+    pub fn current(&self) -> usize {
+        self.current.load(Ordering::Relaxed)
+    }
+
+    /// Move past key `index` after it was rate limited, unless another call already did.
+    /// Returns the key index to use next.
+    pub fn rotate_from(&self, provider: LlmProvider, index: usize) -> usize {
+        if self.keys.len() < 2 {
+            return index;
+        }
+        let next = (index + 1) % self.keys.len();
+        match self.current.compare_exchange(index, next, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => {
+                warn!(provider = %provider.as_str(), key_index = index, next_key_index = next, keys = self.keys.len(), "API key rate limited, rotating to the next key");
+                next
+            }
+            Err(current) => current,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key_list() {
+        assert_eq!(KeyPool::new("key-a").keys(), ["key-a"]);
+        assert_eq!(KeyPool::new(" key-a , key-b,, ").keys(), ["key-a", "key-b"]);
+        assert_eq!(KeyPool::new("").keys(), [""]);
+    }
+
+    #[test]
+    fn test_rotation_wraps_and_ignores_stale_indexes() {
+        let pool = KeyPool::new("a,b,c");
+        assert_eq!(pool.rotate_from(LlmProvider::Gemini, 0), 1);
+        // Another call saw key 0 fail too; the pool already moved on
+        assert_eq!(pool.rotate_from(LlmProvider::Gemini, 0), 1);
+        assert_eq!(pool.rotate_from(LlmProvider::Gemini, 1), 2);
+        assert_eq!(pool.rotate_from(LlmProvider::Gemini, 2), 0);
+        assert_eq!(KeyPool::new("only").rotate_from(LlmProvider::Gemini, 0), 0);
+    }
+
+    #[test]
+    fn test_shared_pool_per_key_list() {
+        let pool = KeyPool::shared("shared-a,shared-b");
+        pool.rotate_from(LlmProvider::Gemini, 0);
+        assert_eq!(KeyPool::shared("shared-a,shared-b").current(), 1);
+        assert_eq!(KeyPool::shared("shared-a").current(), 0);
+    }
+}
@@ -0,0 +1,298 @@
+//! The language of LLM replies: yes/no answers in any language, and a heuristic for replies
+//! that didn't come back in English.
+//!
+//! Models sometimes answer in the language of the content they were shown ("sí", "да") or
+//! wrap the answer in a sentence. [`normalize_yes_no`] reads the leading word against a
+//! multilingual whitelist; [`clarify_yes_no`] asks the model to restate an answer the
+//! whitelist doesn't cover as a single word.
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+
+use crate::cost::CostTracker;
+use crate::retry::RetryPolicy;
+use crate::{send_with_policy, ChatMessage, LlmError, LlmOptions, LlmProvider};
+
+/// A yes/no answer, or a hedged one.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum YesNo {
+    Yes,
+    No,
+    Maybe,
+}
+
+// Checked before the others, so "not sure" isn't read as "not"
+const MAYBE: &[&str] = &[
+    "maybe", "perhaps", "possibly", "partially", "partly", "borderline", "unsure", "not sure", "unclear", "somewhat",
+    "quizás", "quizas", "quizá", "tal vez", "peut-être", "peut etre", "vielleicht", "talvez", "forse", "misschien",
+    "может быть", "возможно", "może", "たぶん", "也许", "可能", "아마",
+];
+
+const YES: &[&str] = &[
+    "yes", "y", "yeah", "yep", "yup", "sure", "definitely", "absolutely", "certainly", "correct", "affirmative", "relevant",
+    "sí", "si", "oui", "ja", "jawohl", "sim", "tak", "da", "ano", "igen", "kyllä", "evet", "да", "так", "ναι", "כן", "نعم",
+    "はい", "是的", "是", "对", "對", "네", "예",
+];
+
+const NO: &[&str] = &[
+    "no", "n", "nope", "nah", "not", "negative", "irrelevant",
+    "non", "nein", "não", "nao", "nie", "nee", "nej", "ne", "nem", "ei", "hayır", "нет", "ні", "не", "όχι", "לא", "لا",
+    "いいえ", "不是", "不", "否", "아니요", "아니오", "아니",
+];
+
+/// Labels a model may put before its answer, e.g. "Answer: yes".
+const LABELS: &[&str] = &["answer:", "response:", "reply:", "verdict:", "final answer:"];
+
+/// The answer `response` leads with, from a multilingual whitelist: "Yes.", "yes, this is
+/// relevant", "**Oui**", "да" and "是的" are all [`YesNo::Yes`]. `None` when the leading
+/// word isn't on it; see [`clarify_yes_no`].
+pub fn normalize_yes_no(response: &str) -> Option<YesNo> {
+    let mut answer = response.trim().to_lowercase();
+    loop {
+        let stripped = answer.trim_start_matches(|c: char| !c.is_alphanumeric()).to_string();
+        let unlabelled = LABELS.iter().find_map(|label| stripped.strip_prefix(label)).map(str::to_string);
+        let done = unlabelled.is_none();
+        answer = unlabelled.unwrap_or(stripped);
+        if done {
+            break;
+        }
+    }
+    [(MAYBE, YesNo::Maybe), (YES, YesNo::Yes), (NO, YesNo::No)]
+        .into_iter()
+        .find(|(words, _)| words.iter().any(|word| leads_with(&answer, word)))
+        .map(|(_, answer)| answer)
+}
+
+/// `text` starts with `word` followed by a non-alphanumeric character or the end. Han and
+/// kana words are matched as prefixes, since those scripts don't separate words.
+fn leads_with(text: &str, word: &str) -> bool {
+    let Some(rest) = text.strip_prefix(word) else {
+        return false;
+    };
+    let unspaced = word.chars().all(|c| matches!(script(c), Some(Script::Han | Script::Kana)));
+    unspaced || rest.chars().next().is_none_or(|c| !c.is_alphanumeric())
+}
+
+const CLARIFY_PROMPT: &str = "Someone was asked a yes-or-no question and answered as below, possibly in another language. \
+Does the answer mean yes, no or maybe? Reply with exactly one English word: yes, no or maybe.\n\nAnswer:\n{answer}";
+
+/// [`normalize_yes_no`], asking `provider` to restate an unrecognized `answer` as one English
+/// word when the whitelist doesn't cover it. `None` when even the restated answer isn't
+/// recognized. The extra call is recorded in `cost`.
+#[instrument(skip(client, api_key, answer, options, policy, cost), fields(provider = %provider.as_str()))]
+pub async fn clarify_yes_no(
+    client: &reqwest::Client,
+    provider: LlmProvider,
+    api_key: &str,
+    answer: &str,
+    options: &LlmOptions,
+    policy: &RetryPolicy,
+    cost: &mut CostTracker,
+) -> Result<Option<YesNo>, LlmError> {
+    if let Some(normalized) = normalize_yes_no(answer) {
+        return Ok(Some(normalized));
+    }
+    debug!(answer = %answer, "Unrecognized yes/no answer, asking for a one-word restatement");
+    let prompt = CLARIFY_PROMPT.replace("{answer}", answer.trim());
+    let options = LlmOptions { temperature: Some(0.0), max_output_tokens: Some(8), ..options.clone() };
+    let response = send_with_policy(client, provider, api_key, &[ChatMessage::user(prompt)], &options, policy).await?;
+    cost.record(&response);
+    Ok(normalize_yes_no(&response.text))
+}
+
+/// Fewest words of running text [`detect_language`] decides on.
+const MIN_DETECT_WORDS: usize = 8;
+
+/// Common short words of each language detected in Latin script. Words shared by several of
+/// these (like "a" or "de") are left out.
+const STOPWORDS: &[(&str, &[&str])] = &[
+    ("en", &["the", "and", "of", "to", "is", "in", "that", "it", "for", "with", "this", "are", "on", "as", "be", "by", "you", "not", "or", "from", "how", "what", "which", "can"]),
+    ("es", &["el", "la", "los", "las", "y", "que", "en", "es", "por", "para", "con", "una", "del", "se", "lo", "como", "más", "pero", "su", "al"]),
+    ("fr", &["le", "la", "les", "et", "des", "est", "que", "une", "du", "pour", "dans", "qui", "pas", "sur", "au", "avec", "ce", "sont", "il", "nous"]),
+    ("de", &["der", "die", "und", "das", "ist", "nicht", "mit", "den", "ein", "eine", "zu", "auf", "für", "sich", "dem", "auch", "es", "von", "wird", "wir"]),
+    ("pt", &["o", "os", "as", "e", "que", "em", "um", "uma", "para", "com", "não", "do", "da", "dos", "se", "mais", "por", "mas", "ao", "é"]),
+    ("it", &["il", "lo", "gli", "e", "che", "di", "un", "una", "per", "con", "non", "sono", "del", "della", "nel", "è", "anche", "come", "ma", "si"]),
+    ("nl", &["het", "een", "en", "van", "is", "dat", "niet", "met", "voor", "op", "zijn", "ook", "maar", "bij", "wordt", "naar", "worden", "wat", "deze", "hoe"]),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Hangul,
+    Kana,
+    Han,
+}
+
+const SCRIPTS: [Script; 9] = [
+    Script::Latin,
+    Script::Cyrillic,
+    Script::Greek,
+    Script::Hebrew,
+    Script::Arabic,
+    Script::Devanagari,
+    Script::Hangul,
+    Script::Kana,
+    Script::Han,
+];
+
+fn script(c: char) -> Option<Script> {
+    Some(match c {
+        'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' => Script::Latin,
+        '\u{0370}'..='\u{03FF}' => Script::Greek,
+        '\u{0400}'..='\u{04FF}' => Script::Cyrillic,
+        '\u{0590}'..='\u{05FF}' => Script::Hebrew,
+        '\u{0600}'..='\u{06FF}' => Script::Arabic,
+        '\u{0900}'..='\u{097F}' => Script::Devanagari,
+        '\u{3040}'..='\u{30FF}' => Script::Kana,
+        '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' => Script::Han,
+        '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' => Script::Hangul,
+        _ => return None,
+    })
+}
+
+/// Best guess at the language of `text` as an ISO 639-1 code, e.g. `"en"` or `"ru"`. Text
+/// mostly in a non-Latin script is named by the script (Japanese when there is any kana);
+/// Latin-script text by its most frequent common words in English, Spanish, French, German,
+/// Portuguese, Italian or Dutch. Code blocks and URLs count like any other text, so a heuristic
+/// for prose. `None` when there's too little text, or no common words, to tell.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let mut counts = [0usize; SCRIPTS.len()];
+    for script in text.chars().filter_map(script) {
+        counts[script as usize] += 1;
+    }
+    let letters: usize = counts.iter().sum();
+    let top = SCRIPTS.into_iter().max_by_key(|s| counts[*s as usize])?;
+    if letters == 0 {
+        return None;
+    }
+    if top != Script::Latin && counts[top as usize] * 2 >= letters {
+        return Some(match top {
+            Script::Cyrillic => "ru",
+            Script::Greek => "el",
+            Script::Hebrew => "he",
+            Script::Arabic => "ar",
+            Script::Devanagari => "hi",
+            Script::Hangul => "ko",
+            Script::Kana => "ja",
+            Script::Han if counts[Script::Kana as usize] > 0 => "ja",
+            Script::Han | Script::Latin => "zh",
+        });
+    }
+
+    let words: Vec<String> = text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).map(str::to_lowercase).collect();
+    if words.len() < MIN_DETECT_WORDS {
+        return None;
+    }
+    STOPWORDS
+        .iter()
+        .map(|(lang, stopwords)| (*lang, words.iter().filter(|w| stopwords.contains(&w.as_str())).count()))
+        .filter(|(_, hits)| *hits > 0)
+        // Ties go to the language listed first, English
+        .fold(None, |best: Option<(&str, usize)>, (lang, hits)| match best {
+            Some((_, top)) if top >= hits => best,
+            _ => Some((lang, hits)),
+        })
+        .map(|(lang, _)| lang)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_normalize_yes_no() {
+        for (response, expected) in [
+            ("yes", Some(YesNo::Yes)),
+            ("Yes.", Some(YesNo::Yes)),
+            ("  YES\n", Some(YesNo::Yes)),
+            ("yes, this is relevant", Some(YesNo::Yes)),
+            ("Yes — it publishes in-depth engineering posts.", Some(YesNo::Yes)),
+            ("**Yes**", Some(YesNo::Yes)),
+            ("\"yes\"", Some(YesNo::Yes)),
+            ("Answer: Yes", Some(YesNo::Yes)),
+            ("Relevant: deep technical content.", Some(YesNo::Yes)),
+            ("sí", Some(YesNo::Yes)),
+            ("Sí, es relevante.", Some(YesNo::Yes)),
+            ("oui", Some(YesNo::Yes)),
+            ("Ja, sehr relevant", Some(YesNo::Yes)),
+            ("да", Some(YesNo::Yes)),
+            ("Да, это технический блог.", Some(YesNo::Yes)),
+            ("是的", Some(YesNo::Yes)),
+            ("はい、関連しています", Some(YesNo::Yes)),
+            ("no", Some(YesNo::No)),
+            ("No.", Some(YesNo::No)),
+            ("No, it's a marketing site.", Some(YesNo::No)),
+            ("Not relevant.", Some(YesNo::No)),
+            ("Nein", Some(YesNo::No)),
+            ("нет", Some(YesNo::No)),
+            ("Non, ce n'est pas pertinent", Some(YesNo::No)),
+            ("不是", Some(YesNo::No)),
+            ("Irrelevant", Some(YesNo::No)),
+            ("maybe", Some(YesNo::Maybe)),
+            ("  maybe, it mixes news and deep dives", Some(YesNo::Maybe)),
+            ("Not sure - mostly product announcements.", Some(YesNo::Maybe)),
+            ("Peut-être", Some(YesNo::Maybe)),
+            ("Tal vez", Some(YesNo::Maybe)),
+            ("", None),
+            ("The blog covers distributed systems in depth.", None),
+            ("I think so", None),
+            ("yesterday's post was great", None),
+            ("nobody reads it", None),
+            ("sino que", None),
+        ] {
+            assert_eq!(normalize_yes_no(response), expected, "{:?}", response);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_clarify_asks_only_for_unrecognized_answers() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/messages")).and(body_string_contains("covers distributed systems"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [{ "text": "Yes" }], "usage": { "input_tokens": 40, "output_tokens": 1 }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = reqwest::Client::new();
+        let options = LlmOptions { base_url: Some(server.uri()), ..Default::default() };
+        let mut cost = CostTracker::default();
+
+        assert_eq!(clarify_yes_no(&client, LlmProvider::Claude, "key", "Sí.", &options, &RetryPolicy::FAST_LOCAL, &mut cost).await.unwrap(), Some(YesNo::Yes));
+        assert_eq!(cost.usage.calls, 0);
+        let answer = "The blog covers distributed systems in depth.";
+        assert_eq!(clarify_yes_no(&client, LlmProvider::Claude, "key", answer, &options, &RetryPolicy::FAST_LOCAL, &mut cost).await.unwrap(), Some(YesNo::Yes));
+        assert_eq!(cost.usage.calls, 1);
+    }
+
+    #[test]
+    fn test_detect_language() {
+        for (text, expected) in [
+            ("This article explains how the new scheduler reduces tail latency for services with bursty traffic.", Some("en")),
+            ("## Key Points\n- The team moved **all** builds to a remote cache.\n- Build times fell by half, which is what matters for this kind of change.", Some("en")),
+            ("Este artículo explica cómo el nuevo planificador reduce la latencia de cola para los servicios con tráfico irregular.", Some("es")),
+            ("Cet article explique comment le nouveau planificateur réduit la latence pour les services dont le trafic est irrégulier.", Some("fr")),
+            ("Dieser Artikel erklärt, wie der neue Scheduler die Latenz für Dienste mit unregelmäßigem Verkehr reduziert und warum das nicht trivial ist.", Some("de")),
+            ("Este artigo explica como o novo agendador reduz a latência para os serviços com tráfego irregular, e não é trivial.", Some("pt")),
+            ("Questo articolo spiega come il nuovo scheduler riduce la latenza per i servizi con traffico irregolare, e non è banale.", Some("it")),
+            ("Dit artikel legt uit hoe de nieuwe scheduler de latentie voor diensten met onregelmatig verkeer verlaagt, en waarom dat niet triviaal is.", Some("nl")),
+            ("В этой статье объясняется, как новый планировщик снижает задержки для сервисов с неравномерным трафиком.", Some("ru")),
+            ("本文解释了新的调度器如何降低突发流量服务的尾部延迟。", Some("zh")),
+            ("この記事では、新しいスケジューラがテールレイテンシをどのように削減するかを説明します。", Some("ja")),
+            ("이 글은 새로운 스케줄러가 지연 시간을 줄이는 방법을 설명합니다.", Some("ko")),
+            ("Too short.", None),
+            ("", None),
+            ("12345 67890 !!! ???", None),
+        ] {
+            assert_eq!(detect_language(text), expected, "{:?}", text);
+        }
+    }
+}
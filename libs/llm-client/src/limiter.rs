@@ -0,0 +1,212 @@
+//! Per-provider limits on LLM requests: a cap on requests in flight and an optional
+//! minimum delay between request starts.
+//!
+//! Every provider call waits for a permit, including retry attempts. By default each
+//! provider has a process-wide limiter configured from `<PROVIDER>_MAX_CONCURRENCY` and
+//! `<PROVIDER>_MIN_INTERVAL_MS`; [`LlmOptions::limiter`](crate::LlmOptions) replaces it
+//! for a group of calls.
+
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+use crate::LlmProvider;
+
+/// Requests in flight per provider when `<PROVIDER>_MAX_CONCURRENCY` is unset.
+pub const DEFAULT_MAX_CONCURRENCY: usize = 8;
+
+/// Limits concurrent requests and spaces out their starts.
+#[derive(Debug)]
+pub struct LlmLimiter {
+    semaphore: Arc<Semaphore>,
+    max_concurrency: usize,
+    min_interval: Option<Duration>,
+    /// Earliest start time of the next request
+    next_start: Mutex<Instant>,
+}
+
+/// Held while a request is in flight; dropping it frees the slot.
+#[derive(Debug)]
+pub struct LlmPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+impl LlmLimiter {
+    /// At most `max_concurrency` requests in flight (at least 1), starting at least
+    /// `min_interval` apart.
+    pub fn new(max_concurrency: usize, min_interval: Option<Duration>) -> Self {
+        let max_concurrency = max_concurrency.max(1);
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_concurrency)),
+            max_concurrency,
+            min_interval: min_interval.filter(|d| !d.is_zero()),
+            next_start: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Limits for `provider` from `<PROVIDER>_MAX_CONCURRENCY` and `<PROVIDER>_MIN_INTERVAL_MS`.
+    /// Unset or invalid values fall back to [`DEFAULT_MAX_CONCURRENCY`] and no interval.
+    pub fn from_env(provider: LlmProvider) -> Self {
+        let max_concurrency = env_number(provider.max_concurrency_env_var()).unwrap_or(DEFAULT_MAX_CONCURRENCY as u64);
+        let min_interval = env_number(provider.min_interval_env_var()).map(Duration::from_millis);
+        Self::new(max_concurrency as usize, min_interval)
+    }
+
+    /// The process-wide limiter of `provider`, created from the environment on first use.
+    pub fn shared(provider: LlmProvider) -> Arc<LlmLimiter> {
+        static LIMITERS: OnceLock<[Arc<LlmLimiter>; 4]> = OnceLock::new();
+        let limiters = LIMITERS.get_or_init(|| {
+            LlmProvider::ALL.map(|p| Arc::new(LlmLimiter::from_env(p)))
+        });
+        match provider {
+            LlmProvider::Gemini => limiters[0].clone(),
+            LlmProvider::OpenAI => limiters[1].clone(),
+            LlmProvider::Claude => limiters[2].clone(),
+            LlmProvider::Echo => limiters[3].clone(),
+        }
+    }
+
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    pub fn min_interval(&self) -> Option<Duration> {
+        self.min_interval
+    }
+
+    /// Wait for a free slot, then for the minimum interval since the previous start.
+    pub async fn acquire(&self) -> LlmPermit {
+        let permit = self.semaphore.clone().acquire_owned().await.expect("limiter semaphore is never closed");
+        if let Some(interval) = self.min_interval {
+            let mut next_start = self.next_start.lock().await;
+            let now = Instant::now();
+            if *next_start > now {
+                debug!(wait_ms = (*next_start - now).as_millis() as u64, "Spacing out LLM requests");
+                tokio::time::sleep_until(*next_start).await;
+            }
+            *next_start = Instant::now() + interval;
+        }
+        LlmPermit { _permit: permit }
+    }
+}
+
+fn env_number(var: &str) -> Option<u64> {
+    let value = std::env::var(var).ok()?;
+    match value.trim().parse() {
+        Ok(n) => Some(n),
+        Err(e) => {
+            warn!(var = var, value = %value, error = %e, "Ignoring invalid LLM limit");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[serial_test::serial]
+    fn test_from_env() {
+        let limiter = LlmLimiter::from_env(LlmProvider::Claude);
+        assert_eq!(limiter.max_concurrency(), DEFAULT_MAX_CONCURRENCY);
+        assert_eq!(limiter.min_interval(), None);
+
+        std::env::set_var("GEMINI_MAX_CONCURRENCY", "2");
+        std::env::set_var("GEMINI_MIN_INTERVAL_MS", "250");
+        let limiter = LlmLimiter::from_env(LlmProvider::Gemini);
+        std::env::set_var("GEMINI_MAX_CONCURRENCY", "lots");
+        let invalid = LlmLimiter::from_env(LlmProvider::Gemini);
+        std::env::remove_var("GEMINI_MAX_CONCURRENCY");
+        std::env::remove_var("GEMINI_MIN_INTERVAL_MS");
+
+        assert_eq!(limiter.max_concurrency(), 2);
+        assert_eq!(limiter.min_interval(), Some(Duration::from_millis(250)));
+        assert_eq!(invalid.max_concurrency(), DEFAULT_MAX_CONCURRENCY);
+    }
+
+    #[test]
+    fn test_zero_limits_are_clamped() {
+        let limiter = LlmLimiter::new(0, Some(Duration::ZERO));
+        assert_eq!(limiter.max_concurrency(), 1);
+        assert_eq!(limiter.min_interval(), None);
+    }
+
+    #[tokio::test]
+    async fn test_min_interval_spaces_out_starts() {
+        let limiter = Arc::new(LlmLimiter::new(10, Some(Duration::from_millis(40))));
+        let started = Instant::now();
+        let tasks: Vec<_> = (0..3)
+            .map(|_| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move { limiter.acquire().await })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        // The first starts immediately, the next two 40ms apart
+        assert!(started.elapsed() >= Duration::from_millis(80));
+    }
+
+    /// Records when each request arrives and answers after a fixed delay.
+    struct DelayedRecorder {
+        arrivals: Arc<std::sync::Mutex<Vec<std::time::Instant>>>,
+        delay: Duration,
+    }
+
+    impl wiremock::Respond for DelayedRecorder {
+        fn respond(&self, _request: &wiremock::Request) -> wiremock::ResponseTemplate {
+            self.arrivals.lock().unwrap().push(std::time::Instant::now());
+            wiremock::ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "content": [{ "text": "ok" }] }))
+                .set_delay(self.delay)
+        }
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_concurrent_calls_respect_limit() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer};
+
+        const LIMIT: usize = 3;
+        let delay = Duration::from_millis(150);
+        let arrivals = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(DelayedRecorder { arrivals: arrivals.clone(), delay })
+            .expect(10)
+            .mount(&server)
+            .await;
+        std::env::set_var("CLAUDE_BASE_URL", server.uri());
+
+        let client = reqwest::Client::new();
+        let options = crate::LlmOptions { limiter: Some(Arc::new(LlmLimiter::new(LIMIT, None))), ..Default::default() };
+        let calls: Vec<_> = (0..10)
+            .map(|i| {
+                let (client, options) = (client.clone(), options.clone());
+                tokio::spawn(async move { crate::call_llm(&client, LlmProvider::Claude, "key", format!("call {}", i), &options).await.ok() })
+            })
+            .collect();
+        let mut replies = Vec::new();
+        for call in calls {
+            replies.push(call.await.unwrap());
+        }
+        std::env::remove_var("CLAUDE_BASE_URL");
+        assert!(replies.iter().all(|r| r.as_deref() == Some("ok")));
+
+        // A request only starts once an earlier one has been answered, so no window shorter
+        // than the response delay sees more than LIMIT arrivals
+        let mut arrivals = arrivals.lock().unwrap().clone();
+        arrivals.sort();
+        let window = delay - Duration::from_millis(30);
+        for (i, start) in arrivals.iter().enumerate() {
+            let in_flight = arrivals[i..].iter().take_while(|t| t.duration_since(*start) < window).count();
+            assert!(in_flight <= LIMIT, "{} requests in flight", in_flight);
+        }
+    }
+}
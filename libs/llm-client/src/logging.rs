@@ -0,0 +1,235 @@
+//! Shared logging setup: output format, destination and per-module verbosity.
+//!
+//! The filter is built from layered directive sets, later layers winning per target:
+//! built-in defaults, then the binary's own overrides, then `LOG_OVERRIDES`, then `RUST_LOG`.
+//! `LOG_FORMAT` picks the rendering and `LOG_FILE` sends the lines to a file instead of stdout.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use tracing::{info, Level};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::{fmt, EnvFilter};
+
+/// Built-in directives: info globally, quiet HTTP client internals.
+pub const DEFAULT_DIRECTIVES: [(&str, Level); 4] = [
+    ("", Level::INFO),
+    ("hyper", Level::WARN),
+    ("reqwest", Level::WARN),
+    ("h2", Level::WARN),
+];
+
+/// How log lines are rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// One JSON object per line, for Cloud Logging.
+    Json,
+    /// Human-readable lines for local development.
+    Pretty,
+    /// Human-readable lines with span fields folded in, one line per event.
+    Compact,
+}
+
+impl LogFormat {
+    /// Format from the environment; see [`resolve_log_config`].
+    pub fn detect() -> Self {
+        resolve_log_config(&std::env::vars().collect()).format
+    }
+}
+
+/// Where and how log lines are written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogConfig {
+    pub format: LogFormat,
+    /// File the lines are appended to instead of stdout.
+    pub file: Option<PathBuf>,
+}
+
+/// Log config from environment variables: `LOG_FORMAT=json|pretty|compact` when set (unknown
+/// values are ignored), otherwise JSON whenever `RUST_LOG` is set (as it is in the deployed
+/// jobs) and pretty output locally; `LOG_FILE` names the log file.
+pub fn resolve_log_config(env: &HashMap<String, String>) -> LogConfig {
+    let format = match env.get("LOG_FORMAT").map(|v| v.trim().to_lowercase()).as_deref() {
+        Some("json") => LogFormat::Json,
+        Some("pretty") => LogFormat::Pretty,
+        Some("compact") => LogFormat::Compact,
+        _ if env.contains_key("RUST_LOG") => LogFormat::Json,
+        _ => LogFormat::Pretty,
+    };
+    let file = env.get("LOG_FILE").map(|path| path.trim()).filter(|path| !path.is_empty()).map(PathBuf::from);
+    LogConfig { format, file }
+}
+
+/// Split a comma-separated directive list into `(target, directive)` pairs.
+/// A bare level (`debug`) has an empty target and sets the global default.
+fn parse_directives(list: &str) -> Vec<(String, String)> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(|d| match d.rsplit_once('=') {
+            Some((target, _)) => (target.trim().to_string(), d.to_string()),
+            None if d.parse::<Level>().is_ok() || d.eq_ignore_ascii_case("off") => (String::new(), d.to_string()),
+            // A bare target (`my_crate`) enables it at every level
+            None => (d.to_string(), d.to_string()),
+        })
+        .collect()
+}
+
+fn directive(target: &str, level: Level) -> String {
+    let level = level.as_str().to_lowercase();
+    if target.is_empty() {
+        level
+    } else {
+        format!("{}={}", target, level)
+    }
+}
+
+/// Merge directive layers into one filter string. Later layers win for the same target.
+pub fn merge_directives(layers: &[&str]) -> String {
+    let mut merged: BTreeMap<String, String> = BTreeMap::new();
+    for layer in layers {
+        for (target, directive) in parse_directives(layer) {
+            merged.insert(target, directive);
+        }
+    }
+    merged.into_values().collect::<Vec<_>>().join(",")
+}
+
+/// Effective filter for the given binary overrides, `LOG_OVERRIDES` and `RUST_LOG` values.
+pub fn effective_filter(filter_overrides: &[(&str, Level)], log_overrides: Option<&str>, rust_log: Option<&str>) -> String {
+    let render = |directives: &[(&str, Level)]| {
+        directives.iter().map(|(t, l)| directive(t, *l)).collect::<Vec<_>>().join(",")
+    };
+    let defaults = render(&DEFAULT_DIRECTIVES);
+    let binary = render(filter_overrides);
+    merge_directives(&[&defaults, &binary, log_overrides.unwrap_or(""), rust_log.unwrap_or("")])
+}
+
+/// Initialize structured logging with the built-in directive defaults.
+/// Keep the returned guard until exit; see [`init_logging_with`].
+#[must_use = "dropping the guard stops writing to LOG_FILE"]
+pub fn init_logging() -> Option<WorkerGuard> {
+    init_logging_with(&[])
+}
+
+/// Initialize logging with binary-specific filter defaults, e.g. `[("my_agent::fetcher", Level::DEBUG)]`.
+/// `LOG_OVERRIDES` and `RUST_LOG` still take precedence over these.
+///
+/// With `LOG_FILE` set, lines go to that file through a background writer, and the returned
+/// guard flushes it when dropped: hold it in `main`. A file that can't be opened falls back
+/// to stdout.
+#[must_use = "dropping the guard stops writing to LOG_FILE"]
+pub fn init_logging_with(filter_overrides: &[(&str, Level)]) -> Option<WorkerGuard> {
+    let config = resolve_log_config(&std::env::vars().collect());
+    let format = config.format;
+    let log_overrides = std::env::var("LOG_OVERRIDES").ok();
+    let rust_log = std::env::var("RUST_LOG").ok();
+    let filter = effective_filter(filter_overrides, log_overrides.as_deref(), rust_log.as_deref());
+
+    let env_filter = EnvFilter::try_new(&filter).unwrap_or_else(|e| {
+        eprintln!("Invalid log filter '{}': {}; falling back to info", filter, e);
+        EnvFilter::new("info")
+    });
+
+    let file = config.file.as_deref().and_then(|path| match file_appender(path) {
+        Ok(appender) => Some((path, tracing_appender::non_blocking(appender))),
+        Err(e) => {
+            eprintln!("Can't log to {}: {}; logging to stdout", path.display(), e);
+            None
+        }
+    });
+    let (file, writer, guard) = match file {
+        Some((path, (writer, guard))) => (Some(path), BoxMakeWriter::new(writer), Some(guard)),
+        None => (None, BoxMakeWriter::new(std::io::stdout), None),
+    };
+    let builder = fmt().with_env_filter(env_filter).with_writer(writer).with_ansi(file.is_none());
+
+    let initialized = match format {
+        LogFormat::Json => builder
+            .json()
+            .with_target(true)
+            .with_thread_ids(false)
+            .with_file(true)
+            .with_line_number(true)
+            .try_init(),
+        LogFormat::Pretty => builder.with_target(false).try_init(),
+        LogFormat::Compact => builder.compact().with_target(false).try_init(),
+    };
+    if initialized.is_ok() {
+        info!(filter = %filter, format = ?format, file = ?file, "Logging initialized");
+    }
+    guard
+}
+
+/// Appender for `path`, without rotation.
+fn file_appender(path: &Path) -> Result<RollingFileAppender, tracing_appender::rolling::InitError> {
+    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    RollingFileAppender::builder().rotation(Rotation::NEVER).filename_prefix(name).build(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_only() {
+        assert_eq!(effective_filter(&[], None, None), "info,h2=warn,hyper=warn,reqwest=warn");
+    }
+
+    #[test]
+    fn test_rust_log_wins_over_overrides_for_same_target() {
+        let filter = effective_filter(&[("app::fetcher", Level::DEBUG)], Some("hyper=info"), Some("hyper=trace,app::fetcher=warn"));
+        assert_eq!(filter, "info,app::fetcher=warn,h2=warn,hyper=trace,reqwest=warn");
+    }
+
+    #[test]
+    fn test_target_only_in_defaults_survives() {
+        let filter = effective_filter(&[], Some("app=debug"), Some("debug"));
+        // Global level replaced by RUST_LOG; HTTP internals stay quiet from the defaults
+        assert_eq!(filter, "debug,app=debug,h2=warn,hyper=warn,reqwest=warn");
+    }
+
+    #[test]
+    fn test_target_only_in_rust_log_is_added() {
+        let filter = effective_filter(&[], None, Some("tower=error"));
+        assert_eq!(filter, "info,h2=warn,hyper=warn,reqwest=warn,tower=error");
+    }
+
+    #[test]
+    fn test_log_overrides_beat_binary_defaults() {
+        let filter = effective_filter(&[("app::fetcher", Level::DEBUG)], Some("app::fetcher=info, reqwest=error"), None);
+        assert_eq!(filter, "info,app::fetcher=info,h2=warn,hyper=warn,reqwest=error");
+    }
+
+    fn env(vars: &[(&str, &str)]) -> HashMap<String, String> {
+        vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_log_format_selection() {
+        assert_eq!(resolve_log_config(&env(&[])).format, LogFormat::Pretty);
+        assert_eq!(resolve_log_config(&env(&[("RUST_LOG", "info")])).format, LogFormat::Json);
+        // An explicit format beats the RUST_LOG heuristic
+        assert_eq!(resolve_log_config(&env(&[("RUST_LOG", "debug"), ("LOG_FORMAT", "pretty")])).format, LogFormat::Pretty);
+        assert_eq!(resolve_log_config(&env(&[("LOG_FORMAT", " Compact ")])).format, LogFormat::Compact);
+        assert_eq!(resolve_log_config(&env(&[("LOG_FORMAT", "json")])).format, LogFormat::Json);
+        assert_eq!(resolve_log_config(&env(&[("LOG_FORMAT", "xml")])).format, LogFormat::Pretty);
+        assert_eq!(resolve_log_config(&env(&[("LOG_FORMAT", "xml"), ("RUST_LOG", "info")])).format, LogFormat::Json);
+    }
+
+    #[test]
+    fn test_log_file() {
+        assert_eq!(resolve_log_config(&env(&[])).file, None);
+        assert_eq!(resolve_log_config(&env(&[("LOG_FILE", " ")])).file, None);
+        assert_eq!(resolve_log_config(&env(&[("LOG_FILE", "logs/agent.log")])).file, Some(PathBuf::from("logs/agent.log")));
+    }
+
+    #[test]
+    fn test_filter_is_valid_env_filter() {
+        let filter = effective_filter(&[("app::fetcher", Level::DEBUG)], Some("off"), Some("app[span]=trace"));
+        assert!(EnvFilter::try_new(&filter).is_ok(), "{}", filter);
+    }
+}
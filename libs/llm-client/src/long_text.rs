@@ -0,0 +1,255 @@
+//! Map-reduce summaries of texts too long for one prompt.
+//!
+//! [`split_into_chunks`] packs paragraphs into chunks of about `target_chunk_tokens`, each
+//! starting with the last paragraphs of the one before so a point spanning the cut is seen
+//! whole. Every chunk is summarized on its own, then a combining prompt over the partial
+//! summaries, in order, gives the summary of the whole text.
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+use tracing::{info, instrument};
+
+use crate::retry::RetryPolicy;
+use crate::tokens::{estimate_tokens, truncate_to_tokens};
+use crate::{add_tokens, send_with_policy, ChatMessage, LlmError, LlmOptions, LlmProvider, LlmResponse};
+
+/// Chunks summarized at once.
+const CHUNK_CONCURRENCY: usize = 4;
+
+/// Share of a chunk, as a divisor of the target, repeated from the previous chunk.
+const OVERLAP_DIVISOR: usize = 8;
+
+const CHUNK_PROMPT: &str = "This is part {part} of {parts} of a long article. Summarize this part in detail: its key \
+points, facts, numbers and conclusions, so the summaries of all parts can be combined into one. Its opening may \
+repeat the end of the previous part. Reply with the summary only.\n\n{text}";
+
+const COMBINE_PROMPT: &str = "Below are summaries of the consecutive parts of one long article, in order. Combine them \
+into one summary of the whole article, keeping its conclusion.\n\n{text}";
+
+/// The default combining prompt over `partials`, the joined partial summaries.
+pub fn combine_prompt(partials: &str) -> String {
+    COMBINE_PROMPT.replace("{text}", partials)
+}
+
+/// `text` split on blank lines into chunks of at most about `target_chunk_tokens` each.
+/// After the first, a chunk starts with the trailing paragraphs of the previous one that fit
+/// in an eighth of the target. Paragraphs longer than the target are cut at word boundaries.
+pub fn split_into_chunks(text: &str, target_chunk_tokens: usize) -> Vec<String> {
+    let target = target_chunk_tokens.max(1);
+    let overlap = target / OVERLAP_DIVISOR;
+    let paragraphs: Vec<&str> = text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()).flat_map(|p| split_paragraph(p, target)).collect();
+
+    let mut chunks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    // Paragraphs of `current` carried over from the previous chunk
+    let mut carried = 0;
+    for paragraph in paragraphs {
+        let tokens = estimate_tokens(paragraph);
+        if current.len() > carried && tokens_of(&current) + tokens > target {
+            chunks.push(current.join("\n\n"));
+            current = tail_within(&current, overlap);
+            // Keep the carried paragraphs only when the new one still fits after them
+            if tokens_of(&current) + tokens > target {
+                current.clear();
+            }
+            carried = current.len();
+        }
+        current.push(paragraph);
+    }
+    if current.len() > carried {
+        chunks.push(current.join("\n\n"));
+    }
+    chunks
+}
+
+/// `paragraph` in pieces of at most `target` tokens.
+fn split_paragraph(mut paragraph: &str, target: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    while estimate_tokens(paragraph) > target {
+        let piece = truncate_to_tokens(paragraph, target);
+        // A target too small for a single character sends out the first word whole
+        let piece = if piece.is_empty() { paragraph.split_whitespace().next().unwrap_or(paragraph) } else { piece };
+        pieces.push(piece);
+        paragraph = paragraph[piece.len()..].trim_start();
+    }
+    if !paragraph.is_empty() {
+        pieces.push(paragraph);
+    }
+    pieces
+}
+
+fn tokens_of(paragraphs: &[&str]) -> usize {
+    paragraphs.iter().map(|p| estimate_tokens(p)).sum()
+}
+
+/// The longest run of trailing `paragraphs` estimated at no more than `max_tokens`.
+fn tail_within<'a>(paragraphs: &[&'a str], max_tokens: usize) -> Vec<&'a str> {
+    let mut total = 0;
+    let start = paragraphs
+        .iter()
+        .rposition(|p| {
+            total += estimate_tokens(p);
+            total > max_tokens
+        })
+        .map_or(0, |i| i + 1);
+    paragraphs[start..].to_vec()
+}
+
+/// Summary of `text` with default options and [`combine_prompt`]; see [`summarize_long_text_with`].
+pub async fn summarize_long_text(
+    client: &reqwest::Client,
+    provider: LlmProvider,
+    api_key: &str,
+    text: &str,
+    target_chunk_tokens: usize,
+) -> Result<LlmResponse, LlmError> {
+    summarize_long_text_with(client, provider, api_key, text, target_chunk_tokens, &LlmOptions::default(), &RetryPolicy::llm_from_env(), combine_prompt).await
+}
+
+/// Summary of `text`, chunked by [`split_into_chunks`]. Each chunk is summarized with
+/// `options`, then `combine` turns the partial summaries, joined in order under `Part i of n`
+/// headings, into the final prompt. A text that fits in one chunk is passed to `combine`
+/// as it is, in a single call. Token counts cover every call; the first failure fails it.
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip(client, api_key, text, options, policy, combine), fields(provider = %provider.as_str(), text_tokens = estimate_tokens(text)))]
+pub async fn summarize_long_text_with(
+    client: &reqwest::Client,
+    provider: LlmProvider,
+    api_key: &str,
+    text: &str,
+    target_chunk_tokens: usize,
+    options: &LlmOptions,
+    policy: &RetryPolicy,
+    combine: impl FnOnce(&str) -> String,
+) -> Result<LlmResponse, LlmError> {
+    let chunks = split_into_chunks(text, target_chunk_tokens);
+    if chunks.len() <= 1 {
+        return send_with_policy(client, provider, api_key, &[ChatMessage::user(combine(text))], options, policy).await;
+    }
+
+    let parts = chunks.len();
+    info!(parts, target_chunk_tokens, "Summarizing long text in parts");
+    let partials: Vec<LlmResponse> = stream::iter(chunks.iter().enumerate())
+        .map(|(i, chunk)| {
+            let prompt = CHUNK_PROMPT.replace("{part}", &(i + 1).to_string()).replace("{parts}", &parts.to_string()).replace("{text}", chunk);
+            async move { send_with_policy(client, provider, api_key, &[ChatMessage::user(prompt)], options, policy).await }
+        })
+        .buffered(CHUNK_CONCURRENCY)
+        .try_collect()
+        .await?;
+
+    let joined = partials
+        .iter()
+        .enumerate()
+        .map(|(i, partial)| format!("Part {} of {}:\n{}", i + 1, parts, partial.text.trim()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let mut response = send_with_policy(client, provider, api_key, &[ChatMessage::user(combine(&joined))], options, policy).await?;
+    for partial in &partials {
+        response.prompt_tokens = add_tokens(response.prompt_tokens, partial.prompt_tokens);
+        response.completion_tokens = add_tokens(response.completion_tokens, partial.completion_tokens);
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+    /// `n` numbered paragraphs of 4 estimated tokens each.
+    fn paragraphs(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("p{:02} alpha beta", i)).collect()
+    }
+
+    #[test]
+    fn test_chunks_break_on_paragraphs_and_overlap() {
+        let paragraphs = paragraphs(20);
+        let text = paragraphs.join("\n\n");
+        let chunks = split_into_chunks(&text, 32);
+        assert_eq!(chunks.len(), 3, "{:?}", chunks);
+        for chunk in &chunks {
+            assert!(estimate_tokens(chunk) <= 32, "{}", chunk);
+            assert!(chunk.split("\n\n").all(|p| paragraphs.iter().any(|q| q == p)), "cut inside a paragraph: {}", chunk);
+        }
+        for pair in chunks.windows(2) {
+            let last = pair[0].rsplit("\n\n").next().unwrap();
+            assert!(pair[1].starts_with(last), "no overlap between {:?} and {:?}", pair[0], pair[1]);
+        }
+        // Every paragraph is in a chunk, in order
+        let firsts: Vec<&str> = chunks.iter().flat_map(|c| c.split("\n\n")).map(|p| &p[..3]).collect();
+        let mut seen = firsts.clone();
+        seen.dedup();
+        assert_eq!(seen, (0..20).map(|i| format!("p{:02}", i)).collect::<Vec<_>>());
+        assert!(chunks.last().unwrap().ends_with(paragraphs.last().unwrap().as_str()));
+    }
+
+    #[test]
+    fn test_short_text_and_long_paragraphs() {
+        assert_eq!(split_into_chunks("One paragraph.\n\nAnother.", 100), vec!["One paragraph.\n\nAnother."]);
+        assert!(split_into_chunks("  \n\n ", 100).is_empty());
+
+        let long = "word ".repeat(300);
+        let chunks = split_into_chunks(&long, 50);
+        assert!(chunks.len() >= 8, "{}", chunks.len());
+        assert!(chunks.iter().all(|c| estimate_tokens(c) <= 50 && !c.is_empty()));
+        assert_eq!(chunks.iter().map(|c| c.split_whitespace().count()).sum::<usize>(), 300);
+    }
+
+    /// Replies to part prompts with `summary of <first paragraph tag>`, and to the
+    /// combining prompt with the prompt itself.
+    struct Summarizer;
+
+    impl wiremock::Respond for Summarizer {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let prompt = body["messages"][0]["content"].as_str().unwrap_or_default();
+            let text = match prompt.split_once("Reply with the summary only.\n\n") {
+                Some((_, chunk)) => format!("summary of {}", &chunk[..3]),
+                None => prompt.to_string(),
+            };
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [{ "text": text }],
+                "usage": { "input_tokens": 10, "output_tokens": 2 }
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_partials_are_combined_in_order() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/messages")).respond_with(Summarizer).mount(&server).await;
+        let options = LlmOptions { base_url: Some(server.uri()), ..Default::default() };
+        let policy = RetryPolicy { max_attempts: Some(1), ..RetryPolicy::PATIENT_LLM };
+
+        let text = paragraphs(20).join("\n\n");
+        let parts = split_into_chunks(&text, 32);
+        let combine = |partials: &str| format!("COMBINE\n{}", partials);
+        let response = summarize_long_text_with(&reqwest::Client::new(), LlmProvider::Claude, "key", &text, 32, &options, &policy, combine)
+            .await
+            .unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), parts.len() + 1);
+        let expected: Vec<String> = parts
+            .iter()
+            .enumerate()
+            .map(|(i, part)| format!("Part {} of {}:\nsummary of {}", i + 1, parts.len(), &part[..3]))
+            .collect();
+        assert_eq!(response.text, format!("COMBINE\n{}", expected.join("\n\n")));
+        assert_eq!(response.prompt_tokens, Some(10 * requests.len() as u32));
+        assert_eq!(response.completion_tokens, Some(2 * requests.len() as u32));
+    }
+
+    #[tokio::test]
+    async fn test_text_of_one_chunk_is_a_single_call() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/messages")).respond_with(Summarizer).expect(1).mount(&server).await;
+        let options = LlmOptions { base_url: Some(server.uri()), ..Default::default() };
+
+        let response = summarize_long_text_with(&reqwest::Client::new(), LlmProvider::Claude, "key", "Short.", 100, &options, &RetryPolicy::PATIENT_LLM, combine_prompt)
+            .await
+            .unwrap();
+        assert_eq!(response.text, combine_prompt("Short."));
+    }
+}
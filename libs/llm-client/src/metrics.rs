@@ -0,0 +1,208 @@
+//! Hooks for counting LLM calls, retries and latency without a metrics crate.
+//!
+//! [`LlmOptions::metrics`](crate::LlmOptions) (or [`LlmClientBuilder::metrics`](crate::LlmClientBuilder))
+//! receives an event for every call: one request, then a retry per extra attempt, then
+//! either a success with the call's latency (retries included) or a failure with the
+//! final error's [`LlmError::kind`]. [`InMemoryMetrics`] accumulates them for a run.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::{LlmError, LlmProvider};
+
+/// Receives LLM call events. Every hook defaults to doing nothing.
+pub trait LlmMetrics: fmt::Debug + Send + Sync {
+    /// A call started.
+    fn on_request(&self, _provider: LlmProvider) {}
+    /// The call is being retried; `attempt` is the attempt about to start (2 for the first retry).
+    fn on_retry(&self, _provider: LlmProvider, _attempt: u32) {}
+    /// The call succeeded after `latency`, using `tokens` (prompt plus completion) when reported.
+    fn on_success(&self, _provider: LlmProvider, _latency: Duration, _tokens: Option<u32>) {}
+    /// The call failed for good with an error of this [`LlmError::kind`].
+    fn on_failure(&self, _provider: LlmProvider, _error_kind: &'static str) {}
+}
+
+/// Counters of [`LlmMetrics`] events, shareable across tasks.
+#[derive(Debug, Default)]
+pub struct InMemoryMetrics {
+    requests: AtomicU64,
+    retries: AtomicU64,
+    successes: AtomicU64,
+    failures: AtomicU64,
+    tokens: AtomicU64,
+    latencies_ms: Mutex<Vec<u64>>,
+    failure_kinds: Mutex<BTreeMap<&'static str, u64>>,
+}
+
+/// Totals of an [`InMemoryMetrics`] at one point in time.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    pub calls: u64,
+    pub retries: u64,
+    pub successes: u64,
+    pub failures: u64,
+    /// Tokens reported by successful calls.
+    pub tokens: u64,
+    /// Median latency of successful calls; `None` before the first success.
+    pub p50_latency_ms: Option<u64>,
+    pub p95_latency_ms: Option<u64>,
+    /// Failures by [`LlmError::kind`].
+    pub failure_kinds: BTreeMap<String, u64>,
+}
+
+impl InMemoryMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let mut latencies = self.latencies_ms.lock().unwrap().clone();
+        latencies.sort_unstable();
+        MetricsSnapshot {
+            calls: self.requests.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            successes: self.successes.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            tokens: self.tokens.load(Ordering::Relaxed),
+            p50_latency_ms: percentile(&latencies, 50),
+            p95_latency_ms: percentile(&latencies, 95),
+            failure_kinds: self.failure_kinds.lock().unwrap().iter().map(|(kind, n)| (kind.to_string(), *n)).collect(),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[u64], pct: usize) -> Option<u64> {
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+impl LlmMetrics for InMemoryMetrics {
+    fn on_request(&self, _provider: LlmProvider) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_retry(&self, _provider: LlmProvider, _attempt: u32) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_success(&self, _provider: LlmProvider, latency: Duration, tokens: Option<u32>) {
+        self.successes.fetch_add(1, Ordering::Relaxed);
+        self.tokens.fetch_add(tokens.unwrap_or(0) as u64, Ordering::Relaxed);
+        self.latencies_ms.lock().unwrap().push(latency.as_millis() as u64);
+    }
+
+    fn on_failure(&self, _provider: LlmProvider, error_kind: &'static str) {
+        self.failures.fetch_add(1, Ordering::Relaxed);
+        *self.failure_kinds.lock().unwrap().entry(error_kind).or_default() += 1;
+    }
+}
+
+impl LlmError {
+    /// Short name of the variant, e.g. `rate_limited`, for metrics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LlmError::Http { .. } => "http",
+            LlmError::Network(_) => "network",
+            LlmError::RateLimited { .. } => "rate_limited",
+            LlmError::Parse(_) => "parse",
+            LlmError::Provider { .. } => "provider",
+            LlmError::EmptyResponse { .. } => "empty_response",
+            LlmError::Blocked { .. } => "blocked",
+            LlmError::Refused { .. } => "refused",
+            LlmError::MissingCassette { .. } => "missing_cassette",
+            LlmError::CircuitOpen { .. } => "circuit_open",
+            LlmError::InvalidHeader { .. } => "invalid_header",
+            LlmError::BudgetExceeded { .. } => "budget_exceeded",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{claude_reply, fast_policy};
+    use crate::{send_with_policy, ChatMessage, LlmOptions};
+    use std::sync::Arc;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_percentile() {
+        assert_eq!(percentile(&[], 50), None);
+        assert_eq!(percentile(&[7], 95), Some(7));
+        let values: Vec<u64> = (1..=20).collect();
+        assert_eq!(percentile(&values, 50), Some(10));
+        assert_eq!(percentile(&values, 95), Some(19));
+    }
+
+    #[tokio::test]
+    async fn test_success_retry_and_failure_are_counted() {
+        let server = MockServer::start().await;
+        // First call: one 503, then success; second call: 401, which isn't retried
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(claude_reply("ok"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("bad key"))
+            .mount(&server)
+            .await;
+
+        let metrics = Arc::new(InMemoryMetrics::new());
+        let options = LlmOptions {
+            base_url: Some(server.uri()),
+            metrics: Some(metrics.clone()),
+            ..Default::default()
+        };
+        let client = reqwest::Client::new();
+        let messages = [ChatMessage::user("Hi")];
+        let ok = send_with_policy(&client, LlmProvider::Claude, "key", &messages, &options, &fast_policy(3)).await;
+        assert_eq!(ok.unwrap().text, "ok");
+        let failed = send_with_policy(&client, LlmProvider::Claude, "key", &messages, &options, &fast_policy(3)).await;
+        assert!(matches!(failed, Err(LlmError::Http { status: 401, .. })));
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.calls, 2);
+        assert_eq!(snapshot.retries, 1);
+        assert_eq!(snapshot.successes, 1);
+        assert_eq!(snapshot.failures, 1);
+        assert_eq!(snapshot.tokens, 15);
+        assert!(snapshot.p50_latency_ms.is_some());
+        assert_eq!(snapshot.failure_kinds, BTreeMap::from([("http".to_string(), 1)]));
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_retries_count_as_one_failure() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(ResponseTemplate::new(429))
+            .expect(3)
+            .mount(&server)
+            .await;
+        let metrics = Arc::new(InMemoryMetrics::new());
+        let options = LlmOptions {
+            base_url: Some(server.uri()),
+            metrics: Some(metrics.clone()),
+            ..Default::default()
+        };
+        let result = send_with_policy(&reqwest::Client::new(), LlmProvider::Claude, "key", &[ChatMessage::user("Hi")], &options, &fast_policy(3)).await;
+        assert!(matches!(result, Err(LlmError::RateLimited { .. })));
+
+        let snapshot = metrics.snapshot();
+        assert_eq!((snapshot.calls, snapshot.retries, snapshot.successes, snapshot.failures), (1, 2, 0, 1));
+        assert_eq!(snapshot.p50_latency_ms, None);
+        assert_eq!(snapshot.failure_kinds, BTreeMap::from([("rate_limited".to_string(), 1)]));
+    }
+}
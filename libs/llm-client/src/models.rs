@@ -0,0 +1,323 @@
+//! Models a provider currently serves, to catch a stale default or a typo in `<PROVIDER>_MODEL`.
+//!
+//! Each provider's list format is normalized to [`ModelInfo`]. Gemini names models
+//! `models/<id>`; the prefix is dropped so ids match what `GEMINI_MODEL` holds.
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument, warn};
+
+use crate::key_pool::KeyPool;
+use crate::{anthropic_api_version, parse_response, with_headers, LlmError, LlmOptions, LlmProvider};
+
+/// Pages fetched at most; each page holds up to 1000 models.
+const MAX_MODEL_PAGES: usize = 10;
+
+/// A model offered by a provider.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ModelInfo {
+    /// The name to request, e.g. `gemini-2.0-flash`.
+    pub id: String,
+    /// Human-readable name; the id when the provider has none.
+    pub display_name: String,
+    /// Input token limit, when the provider reports it (only Gemini does).
+    pub context_window: Option<u32>,
+}
+
+// --- Provider list formats ---
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GeminiModelList {
+    #[serde(default)]
+    models: Vec<GeminiModel>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GeminiModel {
+    name: String,
+    display_name: Option<String>,
+    input_token_limit: Option<u32>,
+}
+
+impl From<GeminiModel> for ModelInfo {
+    fn from(model: GeminiModel) -> Self {
+        let id = model.name.strip_prefix("models/").unwrap_or(&model.name).to_string();
+        ModelInfo { display_name: model.display_name.unwrap_or_else(|| id.clone()), id, context_window: model.input_token_limit }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct OpenAIModelList {
+    #[serde(default)]
+    data: Vec<OpenAIModel>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OpenAIModel {
+    id: String,
+}
+
+impl From<OpenAIModel> for ModelInfo {
+    fn from(model: OpenAIModel) -> Self {
+        ModelInfo { display_name: model.id.clone(), id: model.id, context_window: None }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct ClaudeModelList {
+    #[serde(default)]
+    data: Vec<ClaudeModel>,
+    #[serde(default)]
+    has_more: bool,
+    last_id: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ClaudeModel {
+    id: String,
+    display_name: Option<String>,
+}
+
+impl From<ClaudeModel> for ModelInfo {
+    fn from(model: ClaudeModel) -> Self {
+        ModelInfo { display_name: model.display_name.unwrap_or_else(|| model.id.clone()), id: model.id, context_window: None }
+    }
+}
+
+// --- Listing ---
+
+/// Models `api_key` can use with `provider`.
+pub async fn list_models(client: &reqwest::Client, provider: LlmProvider, api_key: &str) -> Result<Vec<ModelInfo>, LlmError> {
+    list_models_with(client, provider, api_key, &LlmOptions::default()).await
+}
+
+/// [`list_models`] under the base URL, timeout and extra headers in `options`.
+#[instrument(skip(client, api_key, options), fields(provider = %provider.as_str()))]
+pub async fn list_models_with(
+    client: &reqwest::Client,
+    provider: LlmProvider,
+    api_key: &str,
+    options: &LlmOptions,
+) -> Result<Vec<ModelInfo>, LlmError> {
+    // The key calls currently use, of a key list
+    let pool = KeyPool::shared(api_key);
+    let api_key = pool.keys()[pool.current()].as_str();
+    let mut models = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..MAX_MODEL_PAGES {
+        let next = match provider {
+            // Answers with whatever model is configured
+            LlmProvider::Echo => {
+                models.push(ModelInfo { id: options.model_for(provider), display_name: "Echo".to_string(), context_window: None });
+                None
+            }
+            LlmProvider::Gemini => {
+                let mut path = "v1beta/models?pageSize=1000".to_string();
+                if let Some(token) = &cursor {
+                    path.push_str(&format!("&pageToken={}", encode(token)));
+                }
+                let request = client.get(options.endpoint(provider, &path)).header("x-goog-api-key", api_key);
+                let page: GeminiModelList = fetch_page(provider, request, api_key, options).await?;
+                models.extend(page.models.into_iter().map(ModelInfo::from));
+                page.next_page_token.filter(|token| !token.is_empty())
+            }
+            LlmProvider::OpenAI => {
+                let request = client.get(options.endpoint(provider, "models")).header("Authorization", format!("Bearer {}", api_key));
+                let page: OpenAIModelList = fetch_page(provider, request, api_key, options).await?;
+                models.extend(page.data.into_iter().map(ModelInfo::from));
+                None
+            }
+            LlmProvider::Claude => {
+                let mut path = "models?limit=1000".to_string();
+                if let Some(after) = &cursor {
+                    path.push_str(&format!("&after_id={}", encode(after)));
+                }
+                let request = client.get(options.endpoint(provider, &path)).header("x-api-key", api_key);
+                let request = with_headers(request, &[("anthropic-version".to_string(), anthropic_api_version())])?;
+                let page: ClaudeModelList = fetch_page(provider, request, api_key, options).await?;
+                models.extend(page.data.into_iter().map(ModelInfo::from));
+                page.last_id.filter(|_| page.has_more)
+            }
+        };
+        match next {
+            Some(next) => cursor = Some(next),
+            None => return Ok(models),
+        }
+    }
+    warn!(pages = MAX_MODEL_PAGES, models = models.len(), "Model list has more pages; stopping");
+    Ok(models)
+}
+
+async fn fetch_page<T: serde::de::DeserializeOwned>(
+    provider: LlmProvider,
+    request: reqwest::RequestBuilder,
+    api_key: &str,
+    options: &LlmOptions,
+) -> Result<T, LlmError> {
+    let mut request = with_headers(request, &options.extra_headers)?;
+    if let Some(timeout) = options.timeout {
+        request = request.timeout(timeout);
+    }
+    let res = request.send().await.map_err(|e| LlmError::Network(e).redact(&[api_key]))?;
+    parse_response(provider, res).await.map_err(|e| e.redact(&[api_key]))
+}
+
+fn encode(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+// --- Validation ---
+
+/// Whether `model` is among `models`. When it isn't, logs a warning naming the closest
+/// available id.
+pub fn validate_model(provider: LlmProvider, model: &str, models: &[ModelInfo]) -> bool {
+    if models.iter().any(|m| m.id == model) {
+        info!(provider = %provider.as_str(), model = %model, "Configured model is available");
+        return true;
+    }
+    match closest_model(model, models) {
+        Some(closest) => warn!(
+            provider = %provider.as_str(),
+            model = %model,
+            closest = %closest.id,
+            "Configured model is not in the provider's model list; set {} to an available model",
+            crate::get_model_env_var(provider)
+        ),
+        None => warn!(provider = %provider.as_str(), model = %model, "Configured model is not in the provider's (empty) model list"),
+    }
+    false
+}
+
+/// The model whose id is the fewest single-character edits from `model`.
+pub fn closest_model<'a>(model: &str, models: &'a [ModelInfo]) -> Option<&'a ModelInfo> {
+    models.iter().min_by_key(|m| edit_distance(model, &m.id))
+}
+
+/// Levenshtein distance between `a` and `b`, by character.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn model(id: &str) -> ModelInfo {
+        ModelInfo { id: id.to_string(), display_name: id.to_string(), context_window: None }
+    }
+
+    #[test]
+    fn test_gemini_list_format() {
+        let page: GeminiModelList = serde_json::from_value(json!({
+            "models": [
+                {
+                    "name": "models/gemini-2.0-flash",
+                    "displayName": "Gemini 2.0 Flash",
+                    "inputTokenLimit": 1048576,
+                    "supportedGenerationMethods": ["generateContent", "countTokens"]
+                },
+                { "name": "models/embedding-001" }
+            ],
+            "nextPageToken": "abc"
+        })).unwrap();
+        assert_eq!(page.next_page_token.as_deref(), Some("abc"));
+        let models: Vec<ModelInfo> = page.models.into_iter().map(ModelInfo::from).collect();
+        assert_eq!(models, vec![
+            ModelInfo { id: "gemini-2.0-flash".to_string(), display_name: "Gemini 2.0 Flash".to_string(), context_window: Some(1_048_576) },
+            model("embedding-001"),
+        ]);
+    }
+
+    #[test]
+    fn test_openai_list_format() {
+        let page: OpenAIModelList = serde_json::from_value(json!({
+            "object": "list",
+            "data": [{ "id": "gpt-4o", "object": "model", "created": 1715367049, "owned_by": "system" }]
+        })).unwrap();
+        let models: Vec<ModelInfo> = page.data.into_iter().map(ModelInfo::from).collect();
+        assert_eq!(models, vec![model("gpt-4o")]);
+    }
+
+    #[test]
+    fn test_claude_list_format() {
+        let page: ClaudeModelList = serde_json::from_value(json!({
+            "data": [{
+                "type": "model",
+                "id": "claude-opus-4-6",
+                "display_name": "Claude Opus 4.6",
+                "created_at": "2026-02-05T00:00:00Z"
+            }],
+            "has_more": false,
+            "first_id": "claude-opus-4-6",
+            "last_id": "claude-opus-4-6"
+        })).unwrap();
+        assert!(!page.has_more);
+        let models: Vec<ModelInfo> = page.data.into_iter().map(ModelInfo::from).collect();
+        assert_eq!(models[0].display_name, "Claude Opus 4.6");
+        assert_eq!(models[0].context_window, None);
+    }
+
+    #[test]
+    fn test_closest_model_and_validation() {
+        let models = [model("gemini-2.0-flash"), model("gemini-2.5-pro"), model("gemini-3.1-pro")];
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(closest_model("gemini-3.1-pro-preview", &models).unwrap().id, "gemini-3.1-pro");
+        assert_eq!(closest_model("gemini-2.0-flsh", &models).unwrap().id, "gemini-2.0-flash");
+        assert_eq!(closest_model("anything", &[]), None);
+
+        assert!(validate_model(LlmProvider::Gemini, "gemini-2.5-pro", &models));
+        assert!(!validate_model(LlmProvider::Gemini, "gemini-3.1-pro-preview", &models));
+    }
+
+    #[tokio::test]
+    async fn test_pages_are_followed() {
+        use wiremock::matchers::{header, method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET")).and(path("/models")).and(query_param("after_id", "claude-a"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{ "id": "claude-b" }], "has_more": false, "last_id": "claude-b"
+            })))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET")).and(path("/models")).and(header("x-api-key", "key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{ "id": "claude-a", "display_name": "Claude A" }], "has_more": true, "last_id": "claude-a"
+            })))
+            .mount(&server)
+            .await;
+        let options = LlmOptions { base_url: Some(server.uri()), ..Default::default() };
+        let models = list_models_with(&reqwest::Client::new(), LlmProvider::Claude, "key", &options).await.unwrap();
+        assert_eq!(models.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(), ["claude-a", "claude-b"]);
+    }
+
+    #[tokio::test]
+    async fn test_listing_errors_are_llm_errors() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET")).and(path("/v1beta/models"))
+            .respond_with(ResponseTemplate::new(403).set_body_string("denied"))
+            .mount(&server)
+            .await;
+        let options = LlmOptions { base_url: Some(server.uri()), ..Default::default() };
+        let result = list_models_with(&reqwest::Client::new(), LlmProvider::Gemini, "key", &options).await;
+        assert!(matches!(result, Err(LlmError::Http { status: 403, .. })));
+    }
+}
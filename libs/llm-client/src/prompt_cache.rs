@@ -0,0 +1,242 @@
+//! Opt-in response cache for LLM calls, keyed on everything that shapes the reply.
+//!
+//! Keys are SHA-256 hashes of the provider, the configured model, the options and the
+//! prompt, so switching models (e.g. `GEMINI_MODEL`) or options never returns a stale
+//! answer. Only successful replies are cached.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+
+use crate::{call_llm, LlmOptions, LlmProvider, ResponseFormat};
+
+/// Environment variable naming the directory of a [`FilePromptCache`].
+pub const CACHE_DIR_ENV_VAR: &str = "LLM_CACHE_DIR";
+
+/// Stored LLM replies by cache key (see [`prompt_cache_key`]).
+pub trait PromptCache: Send + Sync {
+    fn get(&self, key: &str) -> Option<String>;
+    fn put(&self, key: &str, response: &str);
+}
+
+/// Process-local cache, e.g. for repeated calls within one run or tests.
+#[derive(Debug, Default)]
+pub struct MemoryPromptCache {
+    entries: Mutex<HashMap<String, String>>,
+}
+
+impl MemoryPromptCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl PromptCache for MemoryPromptCache {
+    fn get(&self, key: &str) -> Option<String> {
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    fn put(&self, key: &str, response: &str) {
+        self.entries.lock().unwrap().insert(key.to_string(), response.to_string());
+    }
+}
+
+/// Cache persisted as one file per key in a directory, so it survives across runs.
+/// I/O failures only log: a broken cache means a cache miss, not a failed call.
+#[derive(Debug, Clone)]
+pub struct FilePromptCache {
+    dir: PathBuf,
+}
+
+impl FilePromptCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The cache in `LLM_CACHE_DIR`, or `None` when caching isn't enabled.
+    pub fn from_env() -> Option<Self> {
+        std::env::var(CACHE_DIR_ENV_VAR).ok().filter(|dir| !dir.trim().is_empty()).map(Self::new)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl PromptCache for FilePromptCache {
+    fn get(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(self.dir.join(key)).ok()
+    }
+
+    fn put(&self, key: &str, response: &str) {
+        // Write then rename, so a concurrent reader never sees a partial file
+        let path = self.dir.join(key);
+        let tmp = self.dir.join(format!("{}.tmp", key));
+        let result = std::fs::create_dir_all(&self.dir)
+            .and_then(|_| std::fs::write(&tmp, response))
+            .and_then(|_| std::fs::rename(&tmp, &path));
+        if let Err(e) = result {
+            warn!(path = %path.display(), error = %e, "Failed to write prompt cache entry");
+        }
+    }
+}
+
+/// Cache key of a call: hex SHA-256 over provider, model, options and prompt.
+pub fn prompt_cache_key(provider: LlmProvider, model: &str, prompt: &str, options: &LlmOptions) -> String {
+    let response_format = match &options.response_format {
+        ResponseFormat::Text => serde_json::Value::Null,
+        ResponseFormat::Json(schema) => serde_json::json!({ "json": schema }),
+    };
+    let mut canonical = serde_json::json!({
+        "provider": provider.as_str(),
+        "model": model,
+        "system": options.system,
+        "temperature": options.temperature,
+        "max_output_tokens": options.max_output_tokens,
+        "top_p": options.top_p,
+        "stop_sequences": options.stop_sequences,
+        "response_format": response_format,
+        "prompt": prompt,
+    });
+    // Only when set, so keys of existing calls are unchanged
+    if let Some(n) = options.candidate_count.filter(|n| *n > 1) {
+        canonical["candidate_count"] = n.into();
+    }
+    if options.google_search {
+        canonical["google_search"] = true.into();
+    }
+    if !options.tools.is_empty() {
+        canonical["tools"] = serde_json::to_value(&options.tools).unwrap_or_default();
+    }
+    if let Some(budget) = options.thinking_budget_tokens {
+        canonical["thinking_budget_tokens"] = budget.into();
+    }
+    if options.include_thoughts {
+        canonical["include_thoughts"] = true.into();
+    }
+    format!("{:x}", Sha256::digest(canonical.to_string().as_bytes()))
+}
+
+/// [`call_llm`] through `cache`: a cached reply is returned without calling the provider,
+/// and a fresh reply is stored.
+pub async fn call_llm_cached(
+    cache: &dyn PromptCache,
+    client: &reqwest::Client,
+    provider: LlmProvider,
+    api_key: &str,
+    prompt: impl Into<String>,
+    options: &LlmOptions,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let prompt = prompt.into();
+    let key = prompt_cache_key(provider, &options.model_for(provider), &prompt, options);
+    if let Some(response) = cache.get(&key) {
+        debug!(provider = %provider.as_str(), key = %&key[..12], "Prompt cache hit");
+        return Ok(response);
+    }
+    let response = call_llm(client, provider, api_key, prompt, options).await?;
+    cache.put(&key, &response);
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("prompt-cache-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_key_covers_provider_model_options_and_prompt() {
+        let options = LlmOptions::default();
+        let key = prompt_cache_key(LlmProvider::Gemini, "gemini-2.0-flash", "Is this relevant?", &options);
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, prompt_cache_key(LlmProvider::Gemini, "gemini-2.0-flash", "Is this relevant?", &options));
+
+        assert_ne!(key, prompt_cache_key(LlmProvider::OpenAI, "gemini-2.0-flash", "Is this relevant?", &options));
+        assert_ne!(key, prompt_cache_key(LlmProvider::Gemini, "gemini-3.1-pro", "Is this relevant?", &options));
+        assert_ne!(key, prompt_cache_key(LlmProvider::Gemini, "gemini-2.0-flash", "Is this relevant? ", &options));
+        let json = LlmOptions { response_format: ResponseFormat::Json(None), ..Default::default() };
+        assert_ne!(key, prompt_cache_key(LlmProvider::Gemini, "gemini-2.0-flash", "Is this relevant?", &json));
+        let thinking = LlmOptions { thinking_budget_tokens: Some(1024), ..Default::default() };
+        let thinking_key = prompt_cache_key(LlmProvider::Gemini, "gemini-2.0-flash", "Is this relevant?", &thinking);
+        assert_ne!(key, thinking_key);
+        let with_thoughts = LlmOptions { include_thoughts: true, ..thinking };
+        assert_ne!(thinking_key, prompt_cache_key(LlmProvider::Gemini, "gemini-2.0-flash", "Is this relevant?", &with_thoughts));
+    }
+
+    #[test]
+    fn test_file_cache_round_trip() {
+        let dir = temp_cache_dir("round-trip");
+        let cache = FilePromptCache::new(&dir);
+        assert_eq!(cache.get("abc"), None);
+        cache.put("abc", "yes");
+        assert_eq!(cache.get("abc"), Some("yes".to_string()));
+        // Persisted for a new instance, with no temp file left behind
+        assert_eq!(FilePromptCache::new(&dir).get("abc"), Some("yes".to_string()));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    async fn mock_gemini(expected_calls: u64) -> wiremock::MockServer {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/v1beta/models/cache-model:generateContent"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "candidates": [{ "content": { "parts": [{ "text": "yes" }] } }]
+            })))
+            .expect(expected_calls)
+            .mount(&server)
+            .await;
+        std::env::set_var("GEMINI_BASE_URL", server.uri());
+        std::env::set_var("GEMINI_MODEL", "cache-model");
+        server
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_second_identical_call_is_served_from_cache() {
+        let _server = mock_gemini(1).await;
+        let client = reqwest::Client::new();
+        let cache = MemoryPromptCache::new();
+        let options = LlmOptions::default();
+        let first = call_llm_cached(&cache, &client, LlmProvider::Gemini, "key", "Relevant?".to_string(), &options).await;
+        let second = call_llm_cached(&cache, &client, LlmProvider::Gemini, "key", "Relevant?".to_string(), &options).await;
+        std::env::remove_var("GEMINI_BASE_URL");
+        std::env::remove_var("GEMINI_MODEL");
+        assert_eq!(first.unwrap(), "yes");
+        assert_eq!(second.unwrap(), "yes");
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_file_cache_survives_across_instances() {
+        let dir = temp_cache_dir("calls");
+        let _server = mock_gemini(1).await;
+        let client = reqwest::Client::new();
+        let options = LlmOptions::default();
+        let first = call_llm_cached(&FilePromptCache::new(&dir), &client, LlmProvider::Gemini, "key", "Relevant?".to_string(), &options).await;
+        let second = call_llm_cached(&FilePromptCache::new(&dir), &client, LlmProvider::Gemini, "key", "Relevant?".to_string(), &options).await;
+        std::env::remove_var("GEMINI_BASE_URL");
+        std::env::remove_var("GEMINI_MODEL");
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(first.unwrap(), "yes");
+        assert_eq!(second.unwrap(), "yes");
+    }
+}
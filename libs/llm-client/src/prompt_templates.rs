@@ -0,0 +1,268 @@
+//! Named prompt templates with `{placeholder}` substitution, tunable without a redeploy.
+//!
+//! Each binary compiles in its templates as defaults; `config/prompts.json` in the bucket
+//! (a JSON object of template name to text) overrides any of them. `{{` and `}}` are
+//! literal braces, as in `format!`.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use tracing::{info, warn};
+
+use crate::json_config::parse_json_strict;
+use crate::storage::ObjectStore;
+
+/// Bucket object holding template overrides.
+pub const PROMPTS_OBJECT: &str = "config/prompts.json";
+
+/// A template that can't be rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptError {
+    /// No template is registered under this name.
+    UnknownTemplate(String),
+    /// The template uses a placeholder no value was given for.
+    UnknownPlaceholder { template: String, placeholder: String },
+    /// A `{` or `}` that is neither a placeholder nor escaped.
+    Malformed { template: String, message: String },
+}
+
+impl fmt::Display for PromptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PromptError::UnknownTemplate(name) => write!(f, "unknown prompt template '{}'", name),
+            PromptError::UnknownPlaceholder { template, placeholder } => {
+                write!(f, "prompt template '{}' uses unknown placeholder {{{}}}", template, placeholder)
+            }
+            PromptError::Malformed { template, message } => write!(f, "malformed prompt template '{}': {}", template, message),
+        }
+    }
+}
+
+impl std::error::Error for PromptError {}
+
+/// A piece of a parsed template.
+#[derive(Debug, PartialEq)]
+enum Segment<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Split a template into text and placeholders, unescaping `{{` and `}}`.
+fn parse_template(template: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        if i > 0 {
+            segments.push(Segment::Text(&rest[..i]));
+        }
+        let brace = &rest[i..i + 1];
+        if rest[i + 1..].starts_with(brace) {
+            segments.push(Segment::Text(brace));
+            rest = &rest[i + 2..];
+            continue;
+        }
+        if brace == "}" {
+            return Err(format!("unmatched '}}' at byte {}", template.len() - rest.len() + i));
+        }
+        let name_len = rest[i + 1..].find('}').ok_or_else(|| format!("unclosed '{{' at byte {}", template.len() - rest.len() + i))?;
+        let name = &rest[i + 1..i + 1 + name_len];
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("invalid placeholder {{{}}}; write literal braces as {{{{ and }}}}", name));
+        }
+        segments.push(Segment::Placeholder(name));
+        rest = &rest[i + 2 + name_len..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    Ok(segments)
+}
+
+/// Placeholder names used by a template.
+fn placeholders(template: &str) -> Result<Vec<&str>, String> {
+    Ok(parse_template(template)?
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Placeholder(name) => Some(name),
+            Segment::Text(_) => None,
+        })
+        .collect())
+}
+
+/// Prompt templates by name: the compiled-in defaults, with any valid overrides applied.
+#[derive(Debug, Clone, Default)]
+pub struct PromptTemplates {
+    templates: HashMap<String, String>,
+    overridden: Vec<String>,
+}
+
+impl PromptTemplates {
+    /// Only the compiled-in `(name, template)` defaults.
+    pub fn new(defaults: &[(&str, &str)]) -> Self {
+        let templates = defaults.iter().map(|(name, text)| (name.to_string(), text.to_string())).collect();
+        Self { templates, overridden: Vec::new() }
+    }
+
+    /// Replace defaults with `overrides`. An override for an unknown name, with unbalanced
+    /// braces, or using a placeholder its default doesn't provide is skipped with a warning,
+    /// so a bad edit never breaks a run.
+    pub fn with_overrides(mut self, overrides: HashMap<String, String>) -> Self {
+        for (name, text) in overrides {
+            let Some(default) = self.templates.get(&name) else {
+                warn!(template = %name, "Ignoring override for unknown prompt template");
+                continue;
+            };
+            let allowed = placeholders(default).unwrap_or_default();
+            let used = match placeholders(&text) {
+                Ok(used) => used,
+                Err(e) => {
+                    warn!(template = %name, error = %e, "Ignoring malformed prompt override");
+                    continue;
+                }
+            };
+            if let Some(unknown) = used.iter().find(|p| !allowed.contains(p)) {
+                warn!(template = %name, placeholder = %unknown, allowed = ?allowed, "Ignoring prompt override with unknown placeholder");
+                continue;
+            }
+            self.templates.insert(name.clone(), text);
+            self.overridden.push(name);
+        }
+        self.overridden.sort();
+        self
+    }
+
+    /// Defaults with the overrides in [`PROMPTS_OBJECT`]. A missing, unreadable or malformed
+    /// object leaves the defaults in place.
+    pub async fn load<S: ObjectStore>(store: &S, defaults: &[(&str, &str)]) -> Self {
+        let templates = Self::new(defaults);
+        let overrides = match store.get(PROMPTS_OBJECT).await {
+            Ok(Some(data)) => match parse_json_strict::<HashMap<String, String>>(PROMPTS_OBJECT, &data, None) {
+                Ok(overrides) => overrides,
+                Err(e) => {
+                    warn!(error = %e, "Invalid prompt templates, using built-in prompts");
+                    return templates;
+                }
+            },
+            Ok(None) => return templates,
+            Err(e) => {
+                warn!(error = %e, "Failed to load prompt templates, using built-in prompts");
+                return templates;
+            }
+        };
+        let templates = templates.with_overrides(overrides);
+        if !templates.overridden.is_empty() {
+            info!(templates = ?templates.overridden, "Using prompt overrides from {}", PROMPTS_OBJECT);
+        }
+        templates
+    }
+
+    /// Names of templates replaced by an override, sorted.
+    pub fn overridden(&self) -> &[String] {
+        &self.overridden
+    }
+
+    /// Render template `name`, replacing each `{placeholder}` with its value from `vars`.
+    /// Values are inserted verbatim; extra values are ignored.
+    pub fn render(&self, name: &str, vars: &[(&str, &str)]) -> Result<String, PromptError> {
+        let template = self.templates.get(name).ok_or_else(|| PromptError::UnknownTemplate(name.to_string()))?;
+        let segments = parse_template(template)
+            .map_err(|message| PromptError::Malformed { template: name.to_string(), message })?;
+        let mut prompt = String::with_capacity(template.len());
+        for segment in segments {
+            match segment {
+                Segment::Text(text) => prompt.push_str(text),
+                Segment::Placeholder(placeholder) => match vars.iter().find(|(key, _)| *key == placeholder) {
+                    Some((_, value)) => prompt.push_str(value),
+                    None => {
+                        return Err(PromptError::UnknownPlaceholder {
+                            template: name.to_string(),
+                            placeholder: placeholder.to_string(),
+                        })
+                    }
+                },
+            }
+        }
+        Ok(prompt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStore;
+
+    const DEFAULTS: &[(&str, &str)] = &[
+        ("daily_summary", "Summarize '{title}' from {source}:\n{content}"),
+        ("daily_selection", "Pick one:\n{articles}\nReply with {{\"index\": 3}}."),
+    ];
+
+    #[test]
+    fn test_render_substitutes_placeholders() {
+        let templates = PromptTemplates::new(DEFAULTS);
+        let prompt = templates
+            .render("daily_summary", &[("title", "Rust Perf"), ("source", "HN"), ("content", "Body {with braces}")])
+            .unwrap();
+        assert_eq!(prompt, "Summarize 'Rust Perf' from HN:\nBody {with braces}");
+
+        // Escaped braces are literal; extra values are ignored
+        let prompt = templates.render("daily_selection", &[("articles", "0. A"), ("unused", "x")]).unwrap();
+        assert_eq!(prompt, "Pick one:\n0. A\nReply with {\"index\": 3}.");
+    }
+
+    #[test]
+    fn test_render_errors() {
+        let templates = PromptTemplates::new(DEFAULTS);
+        assert_eq!(
+            templates.render("daily_summary", &[("title", "T"), ("source", "S")]),
+            Err(PromptError::UnknownPlaceholder { template: "daily_summary".to_string(), placeholder: "content".to_string() })
+        );
+        assert_eq!(templates.render("nope", &[]), Err(PromptError::UnknownTemplate("nope".to_string())));
+
+        for broken in ["Reply with {\"index\": 3}", "unclosed {title", "stray } brace", "{}"] {
+            let templates = PromptTemplates::new(&[("t", broken)]);
+            assert!(matches!(templates.render("t", &[("title", "T")]), Err(PromptError::Malformed { .. })), "{}", broken);
+        }
+    }
+
+    #[test]
+    fn test_overrides_replace_only_valid_templates() {
+        let overrides = HashMap::from([
+            ("daily_summary".to_string(), "TL;DR of {title}: {content}".to_string()),
+            ("daily_selection".to_string(), "Pick from {articles} by {deadline}".to_string()),
+            ("explorer_relevance".to_string(), "Relevant?".to_string()),
+        ]);
+        let templates = PromptTemplates::new(DEFAULTS).with_overrides(overrides);
+        assert_eq!(templates.overridden(), ["daily_summary"]);
+        assert_eq!(
+            templates.render("daily_summary", &[("title", "T"), ("source", "S"), ("content", "C")]).unwrap(),
+            "TL;DR of T: C"
+        );
+        // The override with an unknown placeholder was rejected in favour of the default
+        assert!(templates.render("daily_selection", &[("articles", "0. A")]).unwrap().starts_with("Pick one:"));
+        assert_eq!(templates.render("explorer_relevance", &[]), Err(PromptError::UnknownTemplate("explorer_relevance".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_load_from_store() {
+        let store = MemoryStore::new();
+        let missing = PromptTemplates::load(&store, DEFAULTS).await;
+        assert!(missing.overridden().is_empty());
+
+        store.put(PROMPTS_OBJECT, br#"{"daily_selection": "Choose:\n{articles}"}"#.to_vec()).await.unwrap();
+        let loaded = PromptTemplates::load(&store, DEFAULTS).await;
+        assert_eq!(loaded.render("daily_selection", &[("articles", "0. A")]).unwrap(), "Choose:\n0. A");
+        // Templates without an override keep their default
+        assert!(loaded.render("daily_summary", &[("title", "T"), ("source", "S"), ("content", "C")]).unwrap().starts_with("Summarize"));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_config_uses_defaults() {
+        for data in [&b"{not json"[..], br#"["daily_summary"]"#, br#"{"daily_summary": 3}"#] {
+            let store = MemoryStore::new();
+            store.put(PROMPTS_OBJECT, data.to_vec()).await.unwrap();
+            let templates = PromptTemplates::load(&store, DEFAULTS).await;
+            assert!(templates.overridden().is_empty());
+            assert!(templates.render("daily_selection", &[("articles", "0. A")]).unwrap().starts_with("Pick one:"));
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! Per-source provenance (`config/source_provenance.json`): when and how each source was added,
+//! plus onboarding counters for its first daily runs. daily-agent increments the counters,
+//! explorer-agent turns them into a keep / watch / candidate-for-removal verdict.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{update_json, ObjectStore, StorageError};
+
+/// Object path of the provenance map.
+pub const PROVENANCE_PATH: &str = "config/source_provenance.json";
+
+/// Number of daily runs a newly added source is tracked for before it gets a verdict.
+pub const ONBOARDING_WINDOW_RUNS: u32 = 7;
+
+/// Provenance records keyed by source name (the `source` field of fetched articles).
+pub type ProvenanceMap = BTreeMap<String, SourceProvenance>;
+
+/// Who added a source.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceOrigin {
+    /// Submitted through `config/user_candidates.json`.
+    User,
+    /// Recommended by the explorer's LLM discovery.
+    Explorer,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingVerdict {
+    Keep,
+    Watch,
+    CandidateForRemoval,
+}
+
+/// Counters accumulated over the onboarding window.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct OnboardingStats {
+    /// Daily runs counted so far.
+    pub runs: u32,
+    /// Articles the source contributed to the candidate pool.
+    pub articles: u32,
+    /// Times one of its articles made the shortlist.
+    pub shortlisted: u32,
+    /// Times one of its articles was the daily pick.
+    pub selected: u32,
+    /// Date of the last counted run, so a re-run on the same day isn't counted twice.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run_date: Option<String>,
+}
+
+/// What a source contributed to a single daily run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunContribution {
+    pub articles: u32,
+    pub shortlisted: u32,
+    pub selected: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SourceProvenance {
+    pub url: String,
+    /// Date (YYYY-MM-DD) the source was added.
+    pub added_at: String,
+    pub origin: SourceOrigin,
+    #[serde(default)]
+    pub onboarding: OnboardingStats,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<OnboardingVerdict>,
+    /// Queued for re-validation by the explorer instead of being removed outright.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub revalidate: bool,
+}
+
+impl SourceProvenance {
+    pub fn new(url: &str, added_at: &str, origin: SourceOrigin) -> Self {
+        Self {
+            url: url.to_string(),
+            added_at: added_at.to_string(),
+            origin,
+            onboarding: OnboardingStats::default(),
+            verdict: None,
+            revalidate: false,
+        }
+    }
+
+    /// Whether the source is still inside its onboarding window.
+    pub fn in_window(&self) -> bool {
+        self.verdict.is_none() && self.onboarding.runs < ONBOARDING_WINDOW_RUNS
+    }
+
+    /// Count one daily run. Returns false when the window is closed, the run predates the
+    /// addition, or this date was already counted.
+    pub fn record_run(&mut self, date: &str, contribution: &RunContribution) -> bool {
+        if !self.in_window()
+            || date < self.added_at.as_str()
+            || self.onboarding.last_run_date.as_deref() == Some(date)
+        {
+            return false;
+        }
+        let stats = &mut self.onboarding;
+        stats.runs += 1;
+        stats.articles += contribution.articles;
+        stats.shortlisted += contribution.shortlisted;
+        stats.selected += contribution.selected;
+        stats.last_run_date = Some(date.to_string());
+        true
+    }
+}
+
+/// Thresholds for classifying a source once its onboarding window closes.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OnboardingThresholds {
+    /// Picks needed for "keep".
+    pub keep_min_selected: u32,
+    /// Articles contributed needed for "watch".
+    pub watch_min_articles: u32,
+    /// Shortlist appearances needed for "watch".
+    pub watch_min_shortlisted: u32,
+}
+
+impl Default for OnboardingThresholds {
+    fn default() -> Self {
+        Self { keep_min_selected: 1, watch_min_articles: 3, watch_min_shortlisted: 1 }
+    }
+}
+
+impl OnboardingThresholds {
+    pub fn classify(&self, stats: &OnboardingStats) -> OnboardingVerdict {
+        if stats.selected >= self.keep_min_selected {
+            OnboardingVerdict::Keep
+        } else if stats.shortlisted >= self.watch_min_shortlisted || stats.articles >= self.watch_min_articles {
+            OnboardingVerdict::Watch
+        } else {
+            OnboardingVerdict::CandidateForRemoval
+        }
+    }
+}
+
+/// Verdict for one source whose onboarding window closed, as written to the run report.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct OnboardingOutcome {
+    pub name: String,
+    pub verdict: OnboardingVerdict,
+    pub stats: OnboardingStats,
+}
+
+/// Count a daily run for every source still in its window. Sources absent from
+/// `contributions` still use up a run, with zero counts. Returns how many were updated.
+pub fn record_daily_run(
+    provenance: &mut ProvenanceMap,
+    date: &str,
+    contributions: &HashMap<String, RunContribution>,
+) -> usize {
+    let empty = RunContribution::default();
+    let mut updated = 0;
+    for (name, record) in provenance.iter_mut() {
+        if record.record_run(date, contributions.get(name).unwrap_or(&empty)) {
+            updated += 1;
+        }
+    }
+    updated
+}
+
+/// Assign verdicts to sources whose window has closed. Candidates for removal are queued
+/// for re-validation.
+pub fn close_onboarding_windows(
+    provenance: &mut ProvenanceMap,
+    thresholds: &OnboardingThresholds,
+) -> Vec<OnboardingOutcome> {
+    let mut outcomes = Vec::new();
+    for (name, record) in provenance.iter_mut() {
+        if record.verdict.is_some() || record.onboarding.runs < ONBOARDING_WINDOW_RUNS {
+            continue;
+        }
+        let verdict = thresholds.classify(&record.onboarding);
+        record.verdict = Some(verdict);
+        record.revalidate = verdict == OnboardingVerdict::CandidateForRemoval;
+        outcomes.push(OnboardingOutcome { name: name.clone(), verdict, stats: record.onboarding.clone() });
+    }
+    outcomes
+}
+
+/// Load the provenance map, treating a missing object as empty.
+pub async fn load_provenance<S: ObjectStore>(store: &S) -> Result<ProvenanceMap, StorageError> {
+    match store.get(PROVENANCE_PATH).await? {
+        Some(data) => serde_json::from_slice(&data)
+            .map_err(|e| StorageError::new(None, format!("failed to parse {}: {}", PROVENANCE_PATH, e))),
+        None => Ok(ProvenanceMap::new()),
+    }
+}
+
+/// Read-modify-write the provenance map.
+pub async fn update_provenance<S, F>(store: &S, update: F) -> Result<ProvenanceMap, StorageError>
+where
+    S: ObjectStore,
+    F: FnOnce(&mut ProvenanceMap),
+{
+    update_json(store, PROVENANCE_PATH, update).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStore;
+
+    fn contribution(articles: u32, shortlisted: u32, selected: u32) -> RunContribution {
+        RunContribution { articles, shortlisted, selected }
+    }
+
+    fn map_with(name: &str, added_at: &str) -> ProvenanceMap {
+        let mut map = ProvenanceMap::new();
+        map.insert(name.to_string(), SourceProvenance::new("https://a.com/feed", added_at, SourceOrigin::Explorer));
+        map
+    }
+
+    #[test]
+    fn test_record_daily_run_increments_counters() {
+        let mut map = map_with("A", "2026-04-01");
+        map.insert("B".to_string(), SourceProvenance::new("https://b.com/feed", "2026-04-01", SourceOrigin::User));
+        let contributions = HashMap::from([("A".to_string(), contribution(4, 1, 1))]);
+
+        assert_eq!(record_daily_run(&mut map, "2026-04-02", &contributions), 2);
+        let a = &map["A"].onboarding;
+        assert_eq!((a.runs, a.articles, a.shortlisted, a.selected), (1, 4, 1, 1));
+        let b = &map["B"].onboarding;
+        assert_eq!((b.runs, b.articles), (1, 0));
+    }
+
+    #[test]
+    fn test_record_run_same_day_counted_once() {
+        let mut map = map_with("A", "2026-04-01");
+        let contributions = HashMap::from([("A".to_string(), contribution(2, 0, 0))]);
+        record_daily_run(&mut map, "2026-04-02", &contributions);
+        assert_eq!(record_daily_run(&mut map, "2026-04-02", &contributions), 0);
+        assert_eq!(map["A"].onboarding.articles, 2);
+    }
+
+    #[test]
+    fn test_record_run_before_addition_ignored() {
+        let mut map = map_with("A", "2026-04-05");
+        assert_eq!(record_daily_run(&mut map, "2026-04-04", &HashMap::new()), 0);
+        assert_eq!(map["A"].onboarding.runs, 0);
+    }
+
+    #[test]
+    fn test_window_expires_after_seven_runs() {
+        let mut map = map_with("A", "2026-04-01");
+        let contributions = HashMap::from([("A".to_string(), contribution(1, 0, 0))]);
+        for day in 1..=9 {
+            record_daily_run(&mut map, &format!("2026-04-{:02}", day), &contributions);
+        }
+        assert_eq!(map["A"].onboarding.runs, ONBOARDING_WINDOW_RUNS);
+        assert_eq!(map["A"].onboarding.articles, ONBOARDING_WINDOW_RUNS);
+        assert!(!map["A"].in_window());
+    }
+
+    #[test]
+    fn test_close_windows_only_after_expiry() {
+        let mut map = map_with("A", "2026-04-01");
+        map.get_mut("A").unwrap().onboarding.runs = ONBOARDING_WINDOW_RUNS - 1;
+        assert!(close_onboarding_windows(&mut map, &OnboardingThresholds::default()).is_empty());
+
+        map.get_mut("A").unwrap().onboarding.runs = ONBOARDING_WINDOW_RUNS;
+        let outcomes = close_onboarding_windows(&mut map, &OnboardingThresholds::default());
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].verdict, OnboardingVerdict::CandidateForRemoval);
+        assert!(map["A"].revalidate);
+
+        // Verdict is only issued once
+        assert!(close_onboarding_windows(&mut map, &OnboardingThresholds::default()).is_empty());
+    }
+
+    #[test]
+    fn test_threshold_classification() {
+        let thresholds = OnboardingThresholds::default();
+        let stats = |articles, shortlisted, selected| OnboardingStats { runs: 7, articles, shortlisted, selected, last_run_date: None };
+        assert_eq!(thresholds.classify(&stats(10, 2, 1)), OnboardingVerdict::Keep);
+        assert_eq!(thresholds.classify(&stats(1, 1, 0)), OnboardingVerdict::Watch);
+        assert_eq!(thresholds.classify(&stats(3, 0, 0)), OnboardingVerdict::Watch);
+        assert_eq!(thresholds.classify(&stats(2, 0, 0)), OnboardingVerdict::CandidateForRemoval);
+
+        let strict = OnboardingThresholds { keep_min_selected: 2, watch_min_articles: 10, watch_min_shortlisted: 3 };
+        assert_eq!(strict.classify(&stats(10, 2, 1)), OnboardingVerdict::Watch);
+        assert_eq!(strict.classify(&stats(9, 2, 1)), OnboardingVerdict::CandidateForRemoval);
+    }
+
+    #[tokio::test]
+    async fn test_update_provenance_roundtrip() {
+        let store = MemoryStore::new();
+        assert!(load_provenance(&store).await.unwrap().is_empty());
+        update_provenance(&store, |map| {
+            map.insert("A".to_string(), SourceProvenance::new("https://a.com/feed", "2026-04-01", SourceOrigin::User));
+        }).await.unwrap();
+        let contributions = HashMap::from([("A".to_string(), contribution(3, 1, 0))]);
+        update_provenance(&store, |map| {
+            record_daily_run(map, "2026-04-02", &contributions);
+        }).await.unwrap();
+
+        let map = load_provenance(&store).await.unwrap();
+        assert_eq!(map["A"].onboarding.articles, 3);
+        assert_eq!(map["A"].origin, SourceOrigin::User);
+    }
+}
@@ -0,0 +1,231 @@
+//! RSS 1.0 and 0.90 feeds, whose root is an RDF document rather than `<rss>`.
+//!
+//! The `rss` crate only reads them when the root is spelled `rdf:RDF` and the items follow a
+//! `<channel>`; Slashdot-style and academic feeds bind the RDF namespace to another prefix or
+//! leave the channel out and fail to parse. Here elements are matched by namespace, so any
+//! prefix works, and only what an item carries in the RSS 1.0 core, Dublin Core and content
+//! modules is read.
+
+use quick_xml::events::Event;
+use quick_xml::name::{Namespace, ResolveResult};
+use quick_xml::NsReader;
+
+const RDF_NS: &[u8] = b"http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+/// RSS 1.0 core, and RSS 0.90, whose items have the same shape
+const RSS_NAMESPACES: [&[u8]; 2] = [b"http://purl.org/rss/1.0/", b"http://my.netscape.com/rdf/simple/0.9/"];
+const DC_NS: &[u8] = b"http://purl.org/dc/elements/1.1/";
+const CONTENT_NS: &[u8] = b"http://purl.org/rss/1.0/modules/content/";
+
+/// One `<item>` of an RDF feed; text fields are trimmed and left out when empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RdfItem {
+    pub title: Option<String>,
+    pub link: Option<String>,
+    pub description: Option<String>,
+    /// `content:encoded`
+    pub content: Option<String>,
+    /// `dc:date`, as written; see [`crate::parse_feed_date`]
+    pub date: Option<String>,
+    /// `dc:creator`
+    pub creators: Vec<String>,
+    /// `dc:subject`
+    pub subjects: Vec<String>,
+}
+
+/// The item children read, by namespace and local name.
+#[derive(Debug, Clone, Copy)]
+enum Field {
+    Title,
+    Link,
+    Description,
+    Content,
+    Date,
+    Creator,
+    Subject,
+}
+
+impl Field {
+    fn of(namespace: Option<&[u8]>, local: &[u8]) -> Option<Self> {
+        match (namespace, local) {
+            (Some(ns), b"title") if RSS_NAMESPACES.contains(&ns) => Some(Field::Title),
+            (Some(ns), b"link") if RSS_NAMESPACES.contains(&ns) => Some(Field::Link),
+            (Some(ns), b"description") if RSS_NAMESPACES.contains(&ns) => Some(Field::Description),
+            (Some(CONTENT_NS), b"encoded") => Some(Field::Content),
+            (Some(DC_NS), b"date") => Some(Field::Date),
+            (Some(DC_NS), b"creator") => Some(Field::Creator),
+            (Some(DC_NS), b"subject") => Some(Field::Subject),
+            _ => None,
+        }
+    }
+
+    fn set(self, item: &mut RdfItem, text: String) {
+        match self {
+            Field::Title => item.title = Some(text),
+            Field::Link => item.link = Some(text),
+            Field::Description => item.description = Some(text),
+            Field::Content => item.content = Some(text),
+            Field::Date => item.date = Some(text),
+            Field::Creator => item.creators.push(text),
+            Field::Subject => item.subjects.push(text),
+        }
+    }
+}
+
+fn bound(resolved: ResolveResult) -> Option<Vec<u8>> {
+    match resolved {
+        ResolveResult::Bound(Namespace(ns)) => Some(ns.to_vec()),
+        _ => None,
+    }
+}
+
+/// Whether `content` is an XML document whose root element is `RDF` in the RDF namespace,
+/// whatever its prefix.
+pub fn is_rdf_feed(content: &[u8]) -> bool {
+    let mut reader = NsReader::from_reader(content);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_resolved_event_into(&mut buf) {
+            Ok((ns, Event::Start(e) | Event::Empty(e))) => {
+                return bound(ns).as_deref() == Some(RDF_NS) && e.local_name().as_ref() == b"RDF";
+            }
+            Ok((_, Event::Eof)) | Err(_) => return false,
+            Ok(_) => buf.clear(),
+        }
+    }
+}
+
+/// The items of an RDF feed, in document order, wherever they sit under the root. `None`
+/// when `content` isn't one (see [`is_rdf_feed`]) or isn't well-formed XML.
+pub fn parse_rdf_items(content: &[u8]) -> Option<Vec<RdfItem>> {
+    if !is_rdf_feed(content) {
+        return None;
+    }
+    let mut reader = NsReader::from_reader(content);
+    let mut buf = Vec::new();
+    let mut items = Vec::new();
+    let mut depth = 0usize;
+    // The item being read and its depth, then the field being read and its text so far
+    let mut item: Option<(RdfItem, usize)> = None;
+    let mut field: Option<(Field, String)> = None;
+    loop {
+        let (ns, event) = reader.read_resolved_event_into(&mut buf).ok()?;
+        let ns = bound(ns);
+        match event {
+            Event::Start(e) => {
+                depth += 1;
+                let local = e.local_name();
+                match &item {
+                    None if local.as_ref() == b"item" && ns.as_deref().is_some_and(|ns| RSS_NAMESPACES.contains(&ns)) => {
+                        item = Some((RdfItem::default(), depth));
+                    }
+                    Some((_, item_depth)) if depth == item_depth + 1 => {
+                        field = Field::of(ns.as_deref(), local.as_ref()).map(|f| (f, String::new()));
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(e) => {
+                if let Some((_, text)) = &mut field {
+                    match e.unescape() {
+                        Ok(unescaped) => text.push_str(&unescaped),
+                        Err(_) => text.push_str(&String::from_utf8_lossy(&e)),
+                    }
+                }
+            }
+            Event::CData(e) => {
+                if let Some((_, text)) = &mut field {
+                    text.push_str(&e.decode().ok()?);
+                }
+            }
+            Event::End(_) => {
+                match &mut item {
+                    Some((current, item_depth)) if depth == *item_depth + 1 => {
+                        if let Some((f, text)) = field.take() {
+                            let text = text.trim();
+                            if !text.is_empty() {
+                                f.set(current, text.to_string());
+                            }
+                        }
+                    }
+                    Some((_, item_depth)) if depth == *item_depth => {
+                        items.extend(item.take().map(|(current, _)| current));
+                    }
+                    _ => {}
+                }
+                depth = depth.checked_sub(1)?;
+            }
+            Event::Eof if depth == 0 => break,
+            Event::Eof => return None,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Some(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLASHDOT: &str = include_str!("../tests/fixtures/rdf_slashdot.rdf");
+
+    #[test]
+    fn test_slashdot_feed() {
+        assert!(is_rdf_feed(SLASHDOT.as_bytes()));
+        let items = parse_rdf_items(SLASHDOT.as_bytes()).unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0], RdfItem {
+            title: Some("Rust Compiler Gets a New Borrow Checker".to_string()),
+            link: Some("https://developers.slashdot.org/story/25/06/10/1432215/rust-compiler-gets-a-new-borrow-checker?utm_source=rss1.0mainlinkanon&utm_medium=feed".to_string()),
+            description: Some("An anonymous reader shares a report: the Rust team has shipped <b>Polonius</b> on nightly...".to_string()),
+            content: None,
+            date: Some("2025-06-10T14:40:00+00:00".to_string()),
+            creators: vec!["BeauHD".to_string()],
+            subjects: vec!["programming".to_string()],
+        });
+        let titles: Vec<Option<&str>> = items.iter().map(|i| i.title.as_deref()).collect();
+        assert_eq!(titles, [
+            Some("Rust Compiler Gets a New Borrow Checker"),
+            Some("Linux 6.16 Released With Faster Ext4 & Better Scheduling"),
+            Some("Ask Slashdot: How Do You Review Large PRs?"),
+        ]);
+        assert_eq!(items[2].content.as_deref(), Some("<p>A reader asks how teams review <em>large</em> pull requests.</p>"));
+    }
+
+    #[test]
+    fn test_any_prefix_and_no_channel() {
+        let feed = r#"<?xml version="1.0"?>
+            <RDF:RDF xmlns:RDF="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:rss="http://purl.org/rss/1.0/"
+                     xmlns:dc="http://purl.org/dc/elements/1.1/">
+              <rss:item RDF:about="https://lab.example.edu/notes/1">
+                <rss:title>Lab note</rss:title>
+                <rss:link>https://lab.example.edu/notes/1</rss:link>
+                <dc:date>2025-06-10</dc:date>
+                <dc:creator>A. Author</dc:creator>
+                <dc:creator>B. Author</dc:creator>
+              </rss:item>
+              <rss:item><rss:title>  </rss:title><dc:rights>CC BY</dc:rights></rss:item>
+            </RDF:RDF>"#;
+        let items = parse_rdf_items(feed.as_bytes()).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].date.as_deref(), Some("2025-06-10"));
+        assert_eq!(items[0].creators, ["A. Author", "B. Author"]);
+        assert_eq!(items[1], RdfItem::default());
+    }
+
+    #[test]
+    fn test_other_documents_are_not_rdf_feeds() {
+        for content in [
+            r#"<rss version="2.0"><channel><title>t</title></channel></rss>"#,
+            r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>t</title></feed>"#,
+            // Right local name, wrong namespace
+            r#"<RDF xmlns="http://example.com/not-rdf"><item/></RDF>"#,
+            "<html><body>Not a feed</body></html>",
+            "",
+        ] {
+            assert!(!is_rdf_feed(content.as_bytes()), "{}", content);
+            assert_eq!(parse_rdf_items(content.as_bytes()), None, "{}", content);
+        }
+        assert_eq!(parse_rdf_items(br#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"><item>"#), None);
+    }
+}
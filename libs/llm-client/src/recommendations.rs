@@ -0,0 +1,282 @@
+//! Lenient parsing of the explorer's source recommendations.
+//!
+//! Models asked for `[{"name": ..., "url": ...}]` often return something close: a single
+//! object, a `{"recommendations": [...]}` wrapper, trailing commas, `"Name"`/`"URL"` keys, or
+//! a reply cut off mid-array. [`parse_recommendations`] recovers what it can from all of them.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tracing::{debug, info, warn};
+
+use crate::structured::json_candidates;
+
+/// Keys accepted (case-insensitively) for a recommendation's name.
+const NAME_KEYS: &[&str] = &["name", "title", "source", "blog", "source_name"];
+/// Keys accepted (case-insensitively) for a recommendation's feed URL.
+const URL_KEYS: &[&str] = &["url", "feed", "feed_url", "feedurl", "rss", "rss_url", "link", "href"];
+
+/// A source suggested by the model.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Recommendation {
+    pub name: String,
+    pub url: String,
+}
+
+/// How [`parse_recommendations`] got its result, in the order the paths are tried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecommendationParse {
+    /// A JSON array of `{"name", "url"}` objects, possibly inside a code fence or prose
+    Strict,
+    /// An array once trailing commas are dropped and keys matched loosely
+    Lenient,
+    /// An array under some key of a wrapper object, e.g. `{"recommendations": [...]}`
+    Wrapper,
+    /// A single recommendation object
+    SingleObject,
+    /// Name and URL fields picked out of text that isn't valid JSON, e.g. a truncated reply
+    Salvage,
+    /// Nothing recognizable
+    Failed,
+}
+
+impl RecommendationParse {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecommendationParse::Strict => "strict",
+            RecommendationParse::Lenient => "lenient",
+            RecommendationParse::Wrapper => "wrapper",
+            RecommendationParse::SingleObject => "single_object",
+            RecommendationParse::Salvage => "salvage",
+            RecommendationParse::Failed => "failed",
+        }
+    }
+}
+
+/// The recommendations in a model's reply, however it was shaped; empty when nothing could be
+/// recovered. Logs which parse path succeeded.
+pub fn parse_recommendations(text: &str) -> Vec<Recommendation> {
+    let (recommendations, path) = parse_recommendations_with_path(text);
+    match path {
+        RecommendationParse::Strict => debug!(count = recommendations.len(), "Parsed recommendations"),
+        RecommendationParse::Failed => warn!(raw_response = %text, "No recommendations could be parsed"),
+        _ => info!(path = path.as_str(), count = recommendations.len(), "Parsed malformed recommendations"),
+    }
+    recommendations
+}
+
+/// [`parse_recommendations`] without the logging, also returning the path that succeeded.
+pub fn parse_recommendations_with_path(text: &str) -> (Vec<Recommendation>, RecommendationParse) {
+    let cleaned = strip_trailing_commas(text);
+    let had_trailing_commas = cleaned != text;
+    let parsed = json_candidates(&cleaned).find_map(|block| {
+        let value: Value = serde_json::from_str(block).ok()?;
+        let (recommendations, path) = match &value {
+            Value::Array(_) if !had_trailing_commas => match serde_json::from_value::<Vec<Recommendation>>(value.clone()) {
+                Ok(recommendations) => (recommendations, RecommendationParse::Strict),
+                Err(_) => (from_items(value.as_array()?), RecommendationParse::Lenient),
+            },
+            Value::Array(items) => (from_items(items), RecommendationParse::Lenient),
+            Value::Object(object) => match object.values().find_map(Value::as_array) {
+                Some(items) => (from_items(items), RecommendationParse::Wrapper),
+                None => (vec![from_object(object)?], RecommendationParse::SingleObject),
+            },
+            _ => return None,
+        };
+        (!recommendations.is_empty()).then_some((recommendations, path))
+    });
+    // A reply cut off mid-array still has complete objects inside it, which parse as single
+    // recommendations; salvaging the fields keeps the rest.
+    let salvaged = salvage(text);
+    match parsed {
+        Some((recommendations, path)) if recommendations.len() >= salvaged.len() => (recommendations, path),
+        _ if salvaged.is_empty() => (salvaged, RecommendationParse::Failed),
+        _ => (salvaged, RecommendationParse::Salvage),
+    }
+}
+
+fn from_items(items: &[Value]) -> Vec<Recommendation> {
+    items.iter().filter_map(Value::as_object).filter_map(from_object).collect()
+}
+
+fn from_object(object: &Map<String, Value>) -> Option<Recommendation> {
+    let field = |keys: &[&str]| {
+        object
+            .iter()
+            .find(|(key, _)| is_key(key, keys))
+            .and_then(|(_, value)| value.as_str())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    Some(Recommendation { name: field(NAME_KEYS)?, url: field(URL_KEYS)? })
+}
+
+fn is_key(key: &str, keys: &[&str]) -> bool {
+    let key = key.trim().to_lowercase().replace(['-', ' '], "_");
+    keys.contains(&key.as_str())
+}
+
+/// `text` without commas directly before a closing `]` or `}`, ignoring those inside strings.
+fn strip_trailing_commas(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let (mut in_string, mut escaped) = (false, false);
+    let mut pending_comma: Option<usize> = None;
+    for c in text.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            out.push(c);
+            continue;
+        }
+        match c {
+            ']' | '}' => {
+                if let Some(at) = pending_comma.take() {
+                    out.remove(at);
+                }
+            }
+            c if c.is_whitespace() => {}
+            ',' => {
+                pending_comma = Some(out.len());
+                out.push(c);
+                continue;
+            }
+            '"' => in_string = true,
+            _ => {}
+        }
+        if !c.is_whitespace() {
+            pending_comma = None;
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// The JSON string starting at the opening quote at the start of `text`, and the rest of the
+/// text after it. `None` if the string is never closed.
+fn read_string(text: &str) -> Option<(String, &str)> {
+    let body = text.strip_prefix('"')?;
+    let mut escaped = false;
+    for (i, c) in body.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => {
+                let raw = &body[..i];
+                let value = serde_json::from_str(&format!("\"{}\"", raw)).unwrap_or_else(|_| raw.to_string());
+                return Some((value, &body[i + 1..]));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Every `"key": "value"` pair in `text`, in order, whether or not the text around them is
+/// valid JSON.
+fn string_fields(text: &str) -> Vec<(String, String)> {
+    let mut fields = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('"') {
+        let Some((key, after_key)) = read_string(&rest[start..]) else {
+            break;
+        };
+        rest = after_key;
+        let Some(after_colon) = rest.trim_start().strip_prefix(':') else {
+            continue;
+        };
+        let value_start = after_colon.trim_start();
+        if !value_start.starts_with('"') {
+            continue;
+        }
+        let Some((value, after_value)) = read_string(value_start) else {
+            break;
+        };
+        fields.push((key, value));
+        rest = after_value;
+    }
+    fields
+}
+
+/// Recommendations from name and URL fields in reading order: each name is paired with the
+/// next URL before another name, in either order within an entry.
+fn salvage(text: &str) -> Vec<Recommendation> {
+    let mut recommendations = Vec::new();
+    let (mut name, mut url): (Option<String>, Option<String>) = (None, None);
+    for (key, value) in string_fields(text) {
+        let value = value.trim().to_string();
+        if value.is_empty() {
+            continue;
+        }
+        if is_key(&key, NAME_KEYS) {
+            name = Some(value);
+        } else if is_key(&key, URL_KEYS) {
+            url = Some(value);
+        } else {
+            continue;
+        }
+        if let (Some(n), Some(u)) = (&name, &url) {
+            recommendations.push(Recommendation { name: n.clone(), url: u.clone() });
+            (name, url) = (None, None);
+        }
+    }
+    recommendations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rec(name: &str, url: &str) -> Recommendation {
+        Recommendation { name: name.to_string(), url: url.to_string() }
+    }
+
+    macro_rules! fixture {
+        ($name:literal) => {
+            ($name, include_str!(concat!("../tests/fixtures/recommendations/", $name)))
+        };
+    }
+
+    #[test]
+    fn test_captured_responses() {
+        let two = vec![
+            rec("Cloudflare Blog", "https://blog.cloudflare.com/rss/"),
+            rec("Julia Evans", "https://jvns.ca/atom.xml"),
+        ];
+        let one = vec![rec("Cloudflare Blog", "https://blog.cloudflare.com/rss/")];
+        for ((name, text), (expected, path)) in [
+            (fixture!("01-fenced-array.txt"), (two.clone(), RecommendationParse::Strict)),
+            (fixture!("02-prose-around-array.txt"), (two.clone(), RecommendationParse::Strict)),
+            (fixture!("03-trailing-commas.txt"), (two.clone(), RecommendationParse::Lenient)),
+            (fixture!("04-capitalized-keys.txt"), (two.clone(), RecommendationParse::Lenient)),
+            (fixture!("05-wrapper-object.txt"), (two.clone(), RecommendationParse::Wrapper)),
+            (fixture!("06-wrapper-trailing-comma.txt"), (two.clone(), RecommendationParse::Wrapper)),
+            (fixture!("07-single-object.txt"), (one.clone(), RecommendationParse::SingleObject)),
+            (fixture!("08-truncated.txt"), (two.clone(), RecommendationParse::Salvage)),
+            (fixture!("09-missing-comma.txt"), (one.clone(), RecommendationParse::Salvage)),
+            (fixture!("10-no-json.txt"), (vec![], RecommendationParse::Failed)),
+        ] {
+            assert_eq!(parse_recommendations_with_path(text), (expected, path), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_strip_trailing_commas() {
+        for (input, expected) in [
+            (r#"[1, 2, ]"#, r#"[1, 2 ]"#),
+            ("{\"a\": 1,\n}", "{\"a\": 1\n}"),
+            (r#"["a,]", "b",]"#, r#"["a,]", "b"]"#),
+            (r#"[1, 2]"#, r#"[1, 2]"#),
+        ] {
+            assert_eq!(strip_trailing_commas(input), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_entries_without_a_name_or_url_are_dropped() {
+        let text = r#"[{"name": "A", "url": "https://a.example.com/feed"}, {"name": "B"}, {"url": " "}]"#;
+        assert_eq!(parse_recommendations(text), vec![rec("A", "https://a.example.com/feed")]);
+    }
+}
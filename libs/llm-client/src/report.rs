@@ -0,0 +1,114 @@
+//! Per-run report persisted to `runs/{agent}/{date}.json`.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config_snapshot::ConfigDrift;
+use crate::storage::{ObjectStore, StorageError};
+use crate::cost::CostTracker;
+
+/// Object path of the run report for an agent and date.
+pub fn report_path(agent: &str, date: &str) -> String {
+    format!("runs/{}/{}.json", agent, date)
+}
+
+/// Summary of one agent run. Agents add their own named sections.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunReport {
+    pub agent: String,
+    pub date: String,
+    pub started_at: DateTime<Utc>,
+    /// [`run_id`](crate::run_id) of the process that wrote the report.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Resolved config values that changed since the previous run.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub config_drift: Vec<ConfigDrift>,
+    /// Tokens used and estimated cost of the run's LLM calls, recorded by the agent.
+    #[serde(default, skip_serializing_if = "CostTracker::is_empty")]
+    pub llm_usage: CostTracker,
+    #[serde(flatten)]
+    pub sections: BTreeMap<String, serde_json::Value>,
+}
+
+impl RunReport {
+    pub fn new(agent: &str, date: &str) -> Self {
+        Self {
+            agent: agent.to_string(),
+            date: date.to_string(),
+            started_at: Utc::now(),
+            run_id: Some(crate::run_id().to_string()),
+            finished_at: None,
+            error: None,
+            config_drift: Vec::new(),
+            llm_usage: CostTracker::default(),
+            sections: BTreeMap::new(),
+        }
+    }
+
+    /// Add or replace a named section. Values that fail to serialize are dropped.
+    pub fn set_section<T: Serialize>(&mut self, name: &str, value: &T) {
+        if let Ok(value) = serde_json::to_value(value) {
+            self.sections.insert(name.to_string(), value);
+        }
+    }
+
+    pub fn path(&self) -> String {
+        report_path(&self.agent, &self.date)
+    }
+
+    /// Mark the run finished, recording the error if it failed.
+    pub fn finish(&mut self, error: Option<String>) {
+        self.finished_at = Some(Utc::now());
+        self.error = error;
+    }
+
+    pub async fn save<S: ObjectStore>(&self, store: &S) -> Result<(), StorageError> {
+        let data = serde_json::to_vec_pretty(self).map_err(|e| StorageError::new(None, e.to_string()))?;
+        store.put(&self.path(), data).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_snapshot::DriftCause;
+    use crate::storage::MemoryStore;
+
+    #[tokio::test]
+    async fn test_report_roundtrip_with_sections() {
+        let store = MemoryStore::new();
+        let mut report = RunReport::new("daily-agent", "2026-03-20");
+        report.config_drift.push(ConfigDrift {
+            key: "model".to_string(),
+            previous: Some("a".to_string()),
+            current: Some("b".to_string()),
+            cause: DriftCause::Upgrade,
+        });
+        report.set_section("articles", &serde_json::json!({"total": 12}));
+        report.finish(None);
+        report.save(&store).await.unwrap();
+
+        let data = store.object("runs/daily-agent/2026-03-20.json").unwrap();
+        let parsed: RunReport = serde_json::from_slice(&data).unwrap();
+        assert_eq!(parsed, report);
+        let raw: serde_json::Value = serde_json::from_slice(&data).unwrap();
+        assert_eq!(raw["articles"]["total"], 12);
+        assert_eq!(raw["config_drift"][0]["cause"], "upgrade");
+        assert_eq!(raw["run_id"], crate::run_id());
+    }
+
+    #[test]
+    fn test_report_omits_empty_drift() {
+        let report = RunReport::new("explorer-agent", "2026-03-20");
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(!json.contains("config_drift"));
+        assert!(!json.contains("error"));
+    }
+}
@@ -0,0 +1,471 @@
+//! Shared retry policy for LLM calls, HTTP fetches and object storage.
+//!
+//! Every layer retries through [`retry_async`] with one of the [`RetryPolicy`] presets and
+//! an error classifier, usually [`Transience::classify`]. Delays are jittered: each wait is
+//! drawn uniformly from the bottom `jitter` fraction of the exponential backoff cap up to the
+//! cap itself, so `1.0` (full jitter, the presets' default) spreads retries from zero.
+
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use tracing::{debug, error, info, warn};
+
+use crate::storage::StorageError;
+
+/// Whether a failed attempt is worth retrying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Classification {
+    Transient,
+    Permanent,
+}
+
+impl fmt::Display for Classification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Classification::Transient => "transient",
+            Classification::Permanent => "permanent",
+        })
+    }
+}
+
+/// Errors that know whether retrying them can help.
+pub trait Transience {
+    fn classify(&self) -> Classification;
+}
+
+/// Classify an HTTP status: timeouts, rate limits and server errors are transient.
+pub fn classify_status(status: u16) -> Classification {
+    match status {
+        408 | 429 | 500..=599 => Classification::Transient,
+        _ => Classification::Permanent,
+    }
+}
+
+/// Classify a reqwest failure by its kind: timeouts, refused or dropped connections and
+/// bodies cut off mid-read are transient, as is an error status that is. Bodies that arrive
+/// but can't be decoded, invalid requests and redirect loops are permanent.
+pub fn classify_reqwest(err: &reqwest::Error) -> Classification {
+    if let Some(status) = err.status() {
+        return classify_status(status.as_u16());
+    }
+    if err.is_timeout() || err.is_connect() || err.is_request() || err.is_body() {
+        Classification::Transient
+    } else {
+        Classification::Permanent
+    }
+}
+
+/// Classify an error message by the patterns transient network/provider failures use.
+/// Only for errors that arrive as text; prefer [`classify_reqwest`] or [`classify_status`].
+pub fn classify_message(message: &str) -> Classification {
+    const TRANSIENT_PATTERNS: [&str; 11] = [
+        "timeout",
+        "connection",
+        "rate limit",
+        "408", // Request Timeout
+        "429", // Too Many Requests
+        "500",
+        "502",
+        "503",
+        "504",
+        "temporarily",
+        "overloaded",
+    ];
+
+    let lower = message.to_lowercase();
+    if TRANSIENT_PATTERNS.iter().any(|p| lower.contains(p)) {
+        Classification::Transient
+    } else {
+        Classification::Permanent
+    }
+}
+
+impl Transience for StorageError {
+    fn classify(&self) -> Classification {
+        match self.status {
+            Some(status) => classify_status(status),
+            None => classify_message(&self.message),
+        }
+    }
+}
+
+/// Prefix of the environment variables tuning the LLM retry policy, e.g. `LLM_RETRY_MAX_ATTEMPTS`.
+pub const LLM_RETRY_ENV_PREFIX: &str = "LLM_RETRY";
+
+/// Prefix of the environment variables tuning the GCS retry policy, e.g. `GCS_RETRY_MAX_ATTEMPTS`.
+pub const GCS_RETRY_ENV_PREFIX: &str = "GCS_RETRY";
+
+/// Prefix of the environment variables tuning the feed retry policy, e.g. `FEED_RETRY_MAX_ATTEMPTS`.
+pub const FEED_RETRY_ENV_PREFIX: &str = "FEED_RETRY";
+
+/// Backoff schedule and retry budget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Name reported in retry logs.
+    pub name: &'static str,
+    /// Backoff cap before the first retry.
+    pub initial_delay: Duration,
+    /// Growth of the backoff cap per attempt.
+    pub multiplier: f64,
+    /// Upper bound on the backoff cap.
+    pub max_delay: Duration,
+    /// Total attempts including the first; `None` means limited by `max_elapsed` only.
+    pub max_attempts: Option<u32>,
+    /// Stop retrying once the next wait would end past this budget.
+    pub max_elapsed: Option<Duration>,
+    /// Randomized fraction of each wait, in `[0, 1]`: waits are uniform in
+    /// `[cap * (1 - jitter), cap]`, so 0 waits exactly the cap.
+    pub jitter: f64,
+}
+
+impl RetryPolicy {
+    /// Cheap one-off fetches such as newsletter issue pages: up to 3 attempts within 5s.
+    pub const FAST_LOCAL: RetryPolicy = RetryPolicy {
+        name: "fast-local",
+        initial_delay: Duration::from_millis(50),
+        multiplier: 2.0,
+        max_delay: Duration::from_secs(1),
+        max_attempts: Some(3),
+        max_elapsed: Some(Duration::from_secs(5)),
+        jitter: 1.0,
+    };
+
+    /// Daily source fetches: a few attempts over a few seconds, enough to ride out a restarting
+    /// server or a dropped connection without holding up the run for one dead feed.
+    pub const FEED_FETCH: RetryPolicy = RetryPolicy {
+        name: "feed-fetch",
+        initial_delay: Duration::from_secs(1),
+        multiplier: 2.0,
+        max_delay: Duration::from_secs(4),
+        max_attempts: Some(3),
+        max_elapsed: Some(Duration::from_secs(10)),
+        jitter: 0.5,
+    };
+
+    /// Third-party HTTP APIs.
+    pub const STANDARD_API: RetryPolicy = RetryPolicy {
+        name: "standard-api",
+        initial_delay: Duration::from_millis(500),
+        multiplier: 2.0,
+        max_delay: Duration::from_secs(10),
+        max_attempts: Some(5),
+        max_elapsed: Some(Duration::from_secs(30)),
+        jitter: 1.0,
+    };
+
+    /// LLM providers: slow and often overloaded, so keep trying for up to two minutes.
+    pub const PATIENT_LLM: RetryPolicy = RetryPolicy {
+        name: "patient-llm",
+        initial_delay: Duration::from_millis(500),
+        multiplier: 1.5,
+        max_delay: Duration::from_secs(60),
+        max_attempts: None,
+        max_elapsed: Some(Duration::from_secs(120)),
+        jitter: 1.0,
+    };
+
+    /// [`Self::PATIENT_LLM`] with `LLM_RETRY_*` overrides. Used by the LLM calls that don't
+    /// take a policy.
+    pub fn llm_from_env() -> RetryPolicy {
+        Self::PATIENT_LLM.with_env_overrides(LLM_RETRY_ENV_PREFIX)
+    }
+
+    /// [`Self::STANDARD_API`] with `GCS_RETRY_*` overrides. Used for every bucket operation.
+    pub fn gcs_from_env() -> RetryPolicy {
+        Self::STANDARD_API.with_env_overrides(GCS_RETRY_ENV_PREFIX)
+    }
+
+    /// [`Self::FEED_FETCH`] with `FEED_RETRY_*` overrides. Shared by every source type.
+    pub fn feed_from_env() -> RetryPolicy {
+        Self::FEED_FETCH.with_env_overrides(FEED_RETRY_ENV_PREFIX)
+    }
+
+    /// This policy with fields overridden from `<prefix>_MAX_ELAPSED_SECS`,
+    /// `<prefix>_INITIAL_INTERVAL_MS` (or `<prefix>_INITIAL_MS`), `<prefix>_MAX_INTERVAL_MS`,
+    /// `<prefix>_MULTIPLIER`, `<prefix>_JITTER` (0 to 1) and `<prefix>_MAX_ATTEMPTS` (0 removes
+    /// the attempt cap). Invalid values are ignored.
+    pub fn with_env_overrides(mut self, prefix: &str) -> RetryPolicy {
+        if let Some(secs) = env_override::<u64>(prefix, "MAX_ELAPSED_SECS").filter(|&s| s > 0) {
+            self.max_elapsed = Some(Duration::from_secs(secs));
+        }
+        if let Some(ms) = env_override(prefix, "INITIAL_INTERVAL_MS").or_else(|| env_override(prefix, "INITIAL_MS")) {
+            self.initial_delay = Duration::from_millis(ms);
+        }
+        if let Some(ms) = env_override(prefix, "MAX_INTERVAL_MS") {
+            self.max_delay = Duration::from_millis(ms);
+        }
+        if let Some(multiplier) = env_override::<f64>(prefix, "MULTIPLIER").filter(|&m| m >= 1.0) {
+            self.multiplier = multiplier;
+        }
+        if let Some(jitter) = env_override::<f64>(prefix, "JITTER").filter(|j| (0.0..=1.0).contains(j)) {
+            self.jitter = jitter;
+        }
+        if let Some(attempts) = env_override::<u32>(prefix, "MAX_ATTEMPTS") {
+            self.max_attempts = (attempts > 0).then_some(attempts);
+        }
+        if self.max_attempts.is_none() && self.max_elapsed.is_none() {
+            warn!(prefix = prefix, "Retry policy has neither an attempt nor a time limit; capping at {} attempts", DEFAULT_MAX_ATTEMPTS);
+            self.max_attempts = Some(DEFAULT_MAX_ATTEMPTS);
+        }
+        self
+    }
+
+    /// Backoff cap after failed attempt number `attempt` (1-based).
+    pub fn delay_cap(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(64) as i32;
+        let cap = self.initial_delay.as_secs_f64() * self.multiplier.powi(exponent);
+        Duration::from_secs_f64(cap.min(self.max_delay.as_secs_f64()))
+    }
+
+    /// Wait after failed attempt number `attempt`: uniform in
+    /// `[delay_cap * (1 - jitter), delay_cap]`.
+    pub fn jittered_delay<R: Rng>(&self, attempt: u32, rng: &mut R) -> Duration {
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        self.delay_cap(attempt).mul_f64(1.0 - jitter * rng.random::<f64>())
+    }
+}
+
+/// Attempt cap for a policy whose overrides would otherwise leave it unbounded.
+const DEFAULT_MAX_ATTEMPTS: u32 = 10;
+
+fn env_override<T: FromStr>(prefix: &str, name: &str) -> Option<T>
+where
+    T::Err: fmt::Display,
+{
+    let var = format!("{}_{}", prefix, name);
+    let value = std::env::var(&var).ok()?;
+    match value.trim().parse() {
+        Ok(parsed) => Some(parsed),
+        Err(e) => {
+            warn!(var = %var, value = %value, error = %e, "Ignoring invalid retry setting");
+            None
+        }
+    }
+}
+
+/// Run `op` until it succeeds, fails permanently, or the policy's budget runs out.
+/// Returns the last error when giving up. Every event carries the attempt number and the
+/// milliseconds elapsed since the first attempt started.
+pub async fn retry_async<T, E, C, F, Fut>(policy: &RetryPolicy, classify: C, mut op: F) -> Result<T, E>
+where
+    E: fmt::Display,
+    C: Fn(&E) -> Classification,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let started = Instant::now();
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let err = match op().await {
+            Ok(value) => {
+                let elapsed_ms = started.elapsed().as_millis() as u64;
+                if attempt > 1 {
+                    info!(policy = policy.name, attempt, elapsed_ms, "Succeeded after retrying");
+                } else {
+                    debug!(policy = policy.name, attempt, elapsed_ms, "Succeeded");
+                }
+                return Ok(value);
+            }
+            Err(err) => err,
+        };
+
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        let classification = classify(&err);
+        if classification == Classification::Permanent {
+            error!(policy = policy.name, attempt, elapsed_ms, classification = %classification, error = %err, "Permanent error, not retrying");
+            return Err(err);
+        }
+        if policy.max_attempts.is_some_and(|max| attempt >= max) {
+            error!(policy = policy.name, attempt, elapsed_ms, classification = %classification, error = %err, "Retry attempts exhausted");
+            return Err(err);
+        }
+        let delay = policy.jittered_delay(attempt, &mut rand::rng());
+        if policy.max_elapsed.is_some_and(|max| started.elapsed() + delay > max) {
+            error!(policy = policy.name, attempt, elapsed_ms, classification = %classification, error = %err, "Retry time budget exhausted");
+            return Err(err);
+        }
+        warn!(
+            policy = policy.name,
+            attempt,
+            elapsed_ms,
+            next_backoff_ms = delay.as_millis() as u64,
+            classification = %classification,
+            error = %err,
+            "Transient error, retrying"
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const INSTANT: RetryPolicy = RetryPolicy {
+        name: "test",
+        initial_delay: Duration::from_millis(1),
+        multiplier: 2.0,
+        max_delay: Duration::from_millis(2),
+        max_attempts: Some(4),
+        max_elapsed: None,
+        jitter: 1.0,
+    };
+
+    #[test]
+    fn test_jitter_stays_within_cap() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for policy in [RetryPolicy::FAST_LOCAL, RetryPolicy::STANDARD_API, RetryPolicy::PATIENT_LLM] {
+            for attempt in 1..=20 {
+                let cap = policy.delay_cap(attempt);
+                assert!(cap <= policy.max_delay);
+                for _ in 0..50 {
+                    assert!(policy.jittered_delay(attempt, &mut rng) <= cap);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_jitter_fraction_bounds_the_wait() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let fixed = RetryPolicy { jitter: 0.0, ..RetryPolicy::PATIENT_LLM };
+        assert_eq!(fixed.jittered_delay(2, &mut rng), Duration::from_millis(750));
+        let partial = RetryPolicy { jitter: 0.2, ..RetryPolicy::PATIENT_LLM };
+        for _ in 0..50 {
+            let delay = partial.jittered_delay(1, &mut rng);
+            assert!(delay >= Duration::from_millis(400) && delay <= Duration::from_millis(500), "{:?}", delay);
+        }
+    }
+
+    #[test]
+    fn test_delay_cap_grows_then_saturates() {
+        let policy = RetryPolicy::PATIENT_LLM;
+        assert_eq!(policy.delay_cap(1), Duration::from_millis(500));
+        assert_eq!(policy.delay_cap(2), Duration::from_millis(750));
+        assert_eq!(policy.delay_cap(40), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_storage_error_classification() {
+        assert_eq!(StorageError::new(Some(503), "unavailable").classify(), Classification::Transient);
+        assert_eq!(StorageError::new(Some(429), "slow down").classify(), Classification::Transient);
+        assert_eq!(StorageError::new(Some(403), "forbidden").classify(), Classification::Permanent);
+        assert_eq!(StorageError::new(Some(404), "No such object: bucket/manifest.json").classify(), Classification::Permanent);
+        assert_eq!(StorageError::new(Some(500), "backendError").classify(), Classification::Transient);
+        assert_eq!(StorageError::new(None, "connection reset").classify(), Classification::Transient);
+        assert_eq!(StorageError::new(None, "error sending request: connection reset by peer").classify(), Classification::Transient);
+        assert_eq!(StorageError::new(None, "failed to parse x.json").classify(), Classification::Permanent);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_env_overrides() {
+        // Unset variables keep the preset
+        assert_eq!(RetryPolicy::PATIENT_LLM.with_env_overrides("TEST_RETRY"), RetryPolicy::PATIENT_LLM);
+
+        let vars = [
+            ("TEST_RETRY_MAX_ELAPSED_SECS", "20"),
+            ("TEST_RETRY_INITIAL_INTERVAL_MS", "100"),
+            ("TEST_RETRY_MAX_INTERVAL_MS", "2000"),
+            ("TEST_RETRY_MULTIPLIER", "3"),
+            ("TEST_RETRY_JITTER", "0.25"),
+            ("TEST_RETRY_MAX_ATTEMPTS", "4"),
+        ];
+        for (var, value) in vars {
+            std::env::set_var(var, value);
+        }
+        let policy = RetryPolicy::PATIENT_LLM.with_env_overrides("TEST_RETRY");
+        std::env::set_var("TEST_RETRY_MULTIPLIER", "fast");
+        std::env::set_var("TEST_RETRY_JITTER", "1.5");
+        std::env::remove_var("TEST_RETRY_INITIAL_INTERVAL_MS");
+        std::env::set_var("TEST_RETRY_INITIAL_MS", "300");
+        std::env::set_var("TEST_RETRY_MAX_ATTEMPTS", "0");
+        let uncapped = RetryPolicy::PATIENT_LLM.with_env_overrides("TEST_RETRY");
+        for (var, _) in vars {
+            std::env::remove_var(var);
+        }
+        std::env::remove_var("TEST_RETRY_INITIAL_MS");
+
+        assert_eq!(policy.max_elapsed, Some(Duration::from_secs(20)));
+        assert_eq!(policy.initial_delay, Duration::from_millis(100));
+        assert_eq!(policy.max_delay, Duration::from_secs(2));
+        assert_eq!(policy.multiplier, 3.0);
+        assert_eq!(policy.jitter, 0.25);
+        assert_eq!(policy.max_attempts, Some(4));
+        assert_eq!(policy.name, "patient-llm");
+
+        // Invalid values are ignored; 0 attempts removes the cap
+        assert_eq!(uncapped.multiplier, RetryPolicy::PATIENT_LLM.multiplier);
+        assert_eq!(uncapped.jitter, RetryPolicy::PATIENT_LLM.jitter);
+        assert_eq!(uncapped.max_attempts, None);
+        // The short alias of the initial interval
+        assert_eq!(uncapped.initial_delay, Duration::from_millis(300));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_feed_policy_env_overrides() {
+        assert_eq!(RetryPolicy::feed_from_env(), RetryPolicy::FEED_FETCH);
+        std::env::set_var("FEED_RETRY_MAX_ATTEMPTS", "2");
+        std::env::set_var("FEED_RETRY_INITIAL_MS", "200");
+        let policy = RetryPolicy::feed_from_env();
+        std::env::remove_var("FEED_RETRY_MAX_ATTEMPTS");
+        std::env::remove_var("FEED_RETRY_INITIAL_MS");
+        assert_eq!(policy.max_attempts, Some(2));
+        assert_eq!(policy.initial_delay, Duration::from_millis(200));
+        assert_eq!(policy.max_elapsed, RetryPolicy::FEED_FETCH.max_elapsed);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_env_overrides_never_leave_policy_unbounded() {
+        let unbounded = RetryPolicy { max_attempts: Some(3), max_elapsed: None, ..RetryPolicy::PATIENT_LLM };
+        std::env::set_var("TEST_RETRY_MAX_ATTEMPTS", "0");
+        let policy = unbounded.with_env_overrides("TEST_RETRY");
+        std::env::remove_var("TEST_RETRY_MAX_ATTEMPTS");
+        assert_eq!(policy.max_attempts, Some(DEFAULT_MAX_ATTEMPTS));
+    }
+
+    #[tokio::test]
+    async fn test_retry_stops_at_max_attempts() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), StorageError> = retry_async(&INSTANT, StorageError::classify, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(StorageError::new(Some(503), "unavailable"))
+        }).await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_retry_does_not_repeat_permanent_errors() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), StorageError> = retry_async(&INSTANT, StorageError::classify, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(StorageError::new(Some(404), "missing"))
+        }).await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_returns_first_success() {
+        let calls = AtomicU32::new(0);
+        let result = retry_async(&INSTANT, StorageError::classify, || async {
+            if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(StorageError::new(None, "timeout"))
+            } else {
+                Ok("done")
+            }
+        }).await;
+        assert_eq!(result, Ok("done"));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,56 @@
+//! Identity of the current agent run, for correlating our logs with provider-side dashboards.
+//!
+//! [`run_id`] is a UUID generated once per process. Every LLM request carries it as an
+//! `x-request-id` header, and clients from [`http_client_builder`] send it on feed fetches too.
+
+use std::sync::OnceLock;
+
+use reqwest::header::{HeaderMap, HeaderValue};
+
+/// Header carrying [`run_id`] on outgoing requests.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// This run's ID, the same for every call in the process.
+pub fn run_id() -> &'static str {
+    static RUN_ID: OnceLock<String> = OnceLock::new();
+    RUN_ID.get_or_init(|| uuid::Uuid::new_v4().to_string())
+}
+
+/// A reqwest client builder whose requests carry [`REQUEST_ID_HEADER`]; add the timeout and
+/// other settings before building.
+pub fn http_client_builder() -> reqwest::ClientBuilder {
+    let mut headers = HeaderMap::new();
+    headers.insert(REQUEST_ID_HEADER, HeaderValue::from_static(run_id()));
+    reqwest::Client::builder().default_headers(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch_bytes;
+    use crate::RetryPolicy;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_run_id_is_a_stable_uuid() {
+        assert_eq!(run_id(), run_id());
+        assert!(uuid::Uuid::parse_str(run_id()).is_ok(), "{}", run_id());
+    }
+
+    #[tokio::test]
+    async fn test_feed_fetches_carry_the_run_id() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET")).and(path("/feed.xml"))
+            .and(header(REQUEST_ID_HEADER, run_id()))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<rss/>"))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = http_client_builder().build().unwrap();
+        let url = format!("{}/feed.xml", server.uri());
+        fetch_bytes(&client, &url, &RetryPolicy::FAST_LOCAL).await.unwrap();
+        fetch_bytes(&client, &url, &RetryPolicy::FAST_LOCAL).await.unwrap();
+    }
+}
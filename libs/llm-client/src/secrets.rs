@@ -0,0 +1,253 @@
+//! API keys from Google Secret Manager, for deployments that keep keys out of plain env vars.
+//!
+//! [`resolve_api_key`] reads `<KEY VAR>_SECRET` (e.g. `GEMINI_API_KEY_SECRET`) holding a secret
+//! resource name, `projects/<p>/secrets/<s>` (latest version) or `.../versions/<v>`, and
+//! fetches it through the Secret Manager REST API with application default credentials, the
+//! same ones the agents use for GCS. Without that variable the plain key variable is used, as
+//! [`resolve_credentials`] does. Fetched secrets are cached for the process.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+
+use base64::Engine;
+use serde::Deserialize;
+use tracing::{debug, info};
+
+use crate::{get_api_key_env_var, join_url, resolve_credentials, LlmProvider, MissingCredentials};
+
+/// Secret Manager API root.
+pub const DEFAULT_SECRET_MANAGER_BASE_URL: &str = "https://secretmanager.googleapis.com/v1";
+
+/// Environment variable overriding [`DEFAULT_SECRET_MANAGER_BASE_URL`], e.g. for a mock server.
+pub const SECRET_MANAGER_BASE_URL_ENV_VAR: &str = "SECRET_MANAGER_BASE_URL";
+
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+
+/// An API key couldn't be resolved.
+#[derive(Debug)]
+pub enum SecretError {
+    /// Neither the secret variable nor the key variable is set.
+    MissingCredentials(MissingCredentials),
+    /// The secret or version doesn't exist (HTTP 404).
+    NotFound { name: String },
+    /// The credentials may not access the secret (HTTP 401 or 403).
+    PermissionDenied { name: String },
+    /// No application default credentials, or no token from them.
+    Auth(String),
+    /// Any other non-success status.
+    Http { status: u16, body: String },
+    /// The request couldn't be sent or the response read.
+    Network(reqwest::Error),
+    /// The response isn't a secret payload of UTF-8 text.
+    InvalidPayload(String),
+}
+
+impl fmt::Display for SecretError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecretError::MissingCredentials(e) => e.fmt(f),
+            SecretError::NotFound { name } => write!(f, "secret not found: {}", name),
+            SecretError::PermissionDenied { name } => {
+                write!(f, "permission denied for secret {} (grant roles/secretmanager.secretAccessor)", name)
+            }
+            SecretError::Auth(e) => write!(f, "no Google Cloud credentials for Secret Manager: {}", e),
+            SecretError::Http { status, body } => write!(f, "Secret Manager HTTP {}: {}", status, body),
+            SecretError::Network(e) => write!(f, "Secret Manager network error: {}", e),
+            SecretError::InvalidPayload(e) => write!(f, "invalid secret payload: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SecretError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SecretError::MissingCredentials(e) => Some(e),
+            SecretError::Network(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<MissingCredentials> for SecretError {
+    fn from(e: MissingCredentials) -> Self {
+        SecretError::MissingCredentials(e)
+    }
+}
+
+/// Environment variable naming the Secret Manager secret of `provider`'s API key.
+pub fn secret_env_var(provider: LlmProvider) -> &'static str {
+    match provider {
+        LlmProvider::Gemini => "GEMINI_API_KEY_SECRET",
+        LlmProvider::OpenAI => "OPENAI_API_KEY_SECRET",
+        LlmProvider::Claude => "ANTHROPIC_API_KEY_SECRET",
+        LlmProvider::Echo => "ECHO_API_KEY_SECRET",
+    }
+}
+
+/// API key of `provider`: the secret named by [`secret_env_var`] when set, else the key
+/// variable ([`get_api_key_env_var`]).
+pub async fn resolve_api_key(provider: LlmProvider) -> Result<String, SecretError> {
+    resolve_api_key_with(provider, &reqwest::Client::new(), adc_token).await
+}
+
+/// [`resolve_api_key`] with an HTTP client and a source of `Authorization` header values.
+pub async fn resolve_api_key_with<F, Fut>(provider: LlmProvider, client: &reqwest::Client, token: F) -> Result<String, SecretError>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<String, SecretError>>,
+{
+    let Some(name) = std::env::var(secret_env_var(provider)).ok().filter(|name| !name.trim().is_empty()) else {
+        return Ok(resolve_credentials(provider)?);
+    };
+    let name = version_name(name.trim());
+    if let Some(key) = cache().lock().unwrap().get(&name) {
+        debug!(provider = %provider.as_str(), secret = %name, "Using cached secret");
+        return Ok(key.clone());
+    }
+    let key = access_secret(client, &name, &token().await?).await?;
+    info!(provider = %provider.as_str(), secret = %name, "Loaded {} from Secret Manager", get_api_key_env_var(provider));
+    cache().lock().unwrap().insert(name, key.clone());
+    Ok(key)
+}
+
+fn cache() -> &'static Mutex<HashMap<String, String>> {
+    static SECRETS: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    SECRETS.get_or_init(Default::default)
+}
+
+/// `name` with `/versions/latest` appended when it names no version.
+fn version_name(name: &str) -> String {
+    if name.contains("/versions/") {
+        name.to_string()
+    } else {
+        format!("{}/versions/latest", name.trim_end_matches('/'))
+    }
+}
+
+/// `Authorization` header value from application default credentials.
+async fn adc_token() -> Result<String, SecretError> {
+    use gcloud_auth::project::Config;
+    use gcloud_auth::token::DefaultTokenSourceProvider;
+    use token_source::TokenSourceProvider;
+
+    let provider = DefaultTokenSourceProvider::new(Config::default().with_scopes(&[CLOUD_PLATFORM_SCOPE]))
+        .await
+        .map_err(|e| SecretError::Auth(e.to_string()))?;
+    provider.token_source().token().await.map_err(|e| SecretError::Auth(e.to_string()))
+}
+
+#[derive(Deserialize)]
+struct AccessResponse {
+    payload: SecretPayload,
+}
+
+#[derive(Deserialize)]
+struct SecretPayload {
+    data: String,
+}
+
+/// Value of secret version `name` (`projects/<p>/secrets/<s>/versions/<v>`), with whitespace
+/// around it trimmed.
+async fn access_secret(client: &reqwest::Client, name: &str, authorization: &str) -> Result<String, SecretError> {
+    let base_url = std::env::var(SECRET_MANAGER_BASE_URL_ENV_VAR)
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_SECRET_MANAGER_BASE_URL.to_string());
+    let res = client
+        .get(join_url(&base_url, &format!("{}:access", name)))
+        .header("Authorization", authorization)
+        .send()
+        .await
+        .map_err(SecretError::Network)?;
+    match res.status().as_u16() {
+        200..=299 => {}
+        404 => return Err(SecretError::NotFound { name: name.to_string() }),
+        401 | 403 => return Err(SecretError::PermissionDenied { name: name.to_string() }),
+        status => return Err(SecretError::Http { status, body: res.text().await.unwrap_or_default() }),
+    }
+    let response: AccessResponse = res.json().await.map_err(|e| SecretError::InvalidPayload(e.to_string()))?;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(response.payload.data)
+        .map_err(|e| SecretError::InvalidPayload(e.to_string()))?;
+    let value = String::from_utf8(bytes).map_err(|e| SecretError::InvalidPayload(e.to_string()))?;
+    Ok(value.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const SECRET: &str = "projects/p/secrets/gemini-key";
+
+    async fn fake_token() -> Result<String, SecretError> {
+        Ok("Bearer test-token".to_string())
+    }
+
+    async fn secret_manager(status: u16, body: serde_json::Value) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET")).and(path(format!("/{}/versions/latest:access", SECRET)))
+            .and(header("Authorization", "Bearer test-token"))
+            .respond_with(ResponseTemplate::new(status).set_body_json(body))
+            .expect(1)
+            .mount(&server)
+            .await;
+        std::env::set_var(SECRET_MANAGER_BASE_URL_ENV_VAR, server.uri());
+        server
+    }
+
+    async fn resolve(secret: &str) -> Result<String, SecretError> {
+        std::env::set_var(secret_env_var(LlmProvider::Gemini), secret);
+        let result = resolve_api_key_with(LlmProvider::Gemini, &reqwest::Client::new(), fake_token).await;
+        std::env::remove_var(secret_env_var(LlmProvider::Gemini));
+        std::env::remove_var(SECRET_MANAGER_BASE_URL_ENV_VAR);
+        result
+    }
+
+    #[test]
+    fn test_version_name() {
+        assert_eq!(version_name(SECRET), "projects/p/secrets/gemini-key/versions/latest");
+        assert_eq!(version_name("projects/p/secrets/s/versions/3"), "projects/p/secrets/s/versions/3");
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_secret_is_fetched_once_and_cached() {
+        cache().lock().unwrap().clear();
+        // "secret-key\n", base64
+        let server = secret_manager(200, serde_json::json!({ "name": SECRET, "payload": { "data": "c2VjcmV0LWtleQo=" } })).await;
+        assert_eq!(resolve(SECRET).await.unwrap(), "secret-key");
+        assert_eq!(resolve(SECRET).await.unwrap(), "secret-key");
+        server.verify().await;
+        cache().lock().unwrap().clear();
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_not_found_and_permission_denied_are_distinct() {
+        cache().lock().unwrap().clear();
+        let _server = secret_manager(404, serde_json::json!({ "error": { "status": "NOT_FOUND" } })).await;
+        let err = resolve(SECRET).await.unwrap_err();
+        assert!(matches!(err, SecretError::NotFound { .. }), "{:?}", err);
+        assert!(err.to_string().contains("secret not found"));
+
+        let _server = secret_manager(403, serde_json::json!({ "error": { "status": "PERMISSION_DENIED" } })).await;
+        let err = resolve(SECRET).await.unwrap_err();
+        assert!(matches!(err, SecretError::PermissionDenied { .. }), "{:?}", err);
+        assert!(err.to_string().contains("permission denied"));
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_plain_env_var_without_a_secret() {
+        std::env::set_var("GEMINI_API_KEY", "plain-key");
+        let key = resolve_api_key_with(LlmProvider::Gemini, &reqwest::Client::new(), fake_token).await;
+        std::env::remove_var("GEMINI_API_KEY");
+        assert_eq!(key.unwrap(), "plain-key");
+        let missing = resolve_api_key_with(LlmProvider::Gemini, &reqwest::Client::new(), fake_token).await;
+        assert!(matches!(missing, Err(SecretError::MissingCredentials(_))));
+    }
+}
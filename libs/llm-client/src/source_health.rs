@@ -0,0 +1,311 @@
+//! Per-source fetch health (`state/source_health.json`): consecutive failed runs of each
+//! source, keyed by URL. daily-agent stops fetching a source after
+//! [`QuarantinePolicy::after_failures`] failed runs in a row and only probes it every
+//! [`QuarantinePolicy::probe_every`] runs until it recovers; explorer-agent reviews
+//! quarantined sources first.
+//!
+//! A dead feed would otherwise be retried, and logged as failing, every day until the
+//! explorer gets round to pruning it.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::state::{load_or_default, save_state, schema, unversioned, Migration, StateError};
+use crate::storage::{ObjectStore, StorageError};
+
+/// Object path of the health map.
+pub const SOURCE_HEALTH_OBJECT: &str = "state/source_health.json";
+/// Schema migrations of `state/source_health.json`; see [`crate::state`].
+const SOURCE_HEALTH_MIGRATIONS: [Migration; schema::SOURCE_HEALTH as usize] = [unversioned];
+
+/// Environment variable setting [`QuarantinePolicy::after_failures`].
+pub const QUARANTINE_AFTER_FAILURES_ENV_VAR: &str = "SOURCE_QUARANTINE_AFTER_FAILURES";
+/// Environment variable setting [`QuarantinePolicy::probe_every`].
+pub const QUARANTINE_PROBE_EVERY_ENV_VAR: &str = "SOURCE_QUARANTINE_PROBE_EVERY";
+pub const DEFAULT_QUARANTINE_AFTER_FAILURES: u32 = 5;
+pub const DEFAULT_QUARANTINE_PROBE_EVERY: u32 = 7;
+
+/// When a failing source is quarantined and how often it is probed once it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuarantinePolicy {
+    /// Failed runs in a row that quarantine a source.
+    pub after_failures: u32,
+    /// A quarantined source is fetched once every this many runs; the others skip it.
+    pub probe_every: u32,
+}
+
+impl Default for QuarantinePolicy {
+    fn default() -> Self {
+        Self { after_failures: DEFAULT_QUARANTINE_AFTER_FAILURES, probe_every: DEFAULT_QUARANTINE_PROBE_EVERY }
+    }
+}
+
+impl QuarantinePolicy {
+    /// Defaults, overridden by `SOURCE_QUARANTINE_AFTER_FAILURES` and
+    /// `SOURCE_QUARANTINE_PROBE_EVERY`; values that aren't positive integers are ignored with a
+    /// warning.
+    pub fn from_env() -> Self {
+        let read = |name: &str, default: u32| match std::env::var(name) {
+            Ok(raw) => raw.trim().parse().ok().filter(|&runs: &u32| runs > 0).unwrap_or_else(|| {
+                warn!(value = %raw, "Invalid {}, using {}", name, default);
+                default
+            }),
+            Err(_) => default,
+        };
+        Self {
+            after_failures: read(QUARANTINE_AFTER_FAILURES_ENV_VAR, DEFAULT_QUARANTINE_AFTER_FAILURES),
+            probe_every: read(QUARANTINE_PROBE_EVERY_ENV_VAR, DEFAULT_QUARANTINE_PROBE_EVERY),
+        }
+    }
+}
+
+/// What a run does with a source, see [`SourceHealth::decide`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchDecision {
+    Fetch,
+    /// Quarantined, but due for its attempt at recovering
+    Probe,
+    /// Quarantined
+    Skip,
+}
+
+/// Fetch record of one source.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceHealth {
+    /// Source name when last fetched, for reading the file
+    pub name: String,
+    /// Runs in a row whose fetch failed
+    pub consecutive_failures: u32,
+    /// Runs that skipped the source since it was last fetched
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub runs_skipped: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Day of the last successful fetch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<String>,
+    /// Day the source was quarantined; `None` while it is fetched every run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quarantined_since: Option<String>,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+impl SourceHealth {
+    pub fn is_quarantined(&self) -> bool {
+        self.quarantined_since.is_some()
+    }
+
+    /// Fetch unless quarantined; a quarantined source is probed on every
+    /// `policy.probe_every`th run and skipped on the others.
+    pub fn decide(&self, policy: &QuarantinePolicy) -> FetchDecision {
+        match self.is_quarantined() {
+            false => FetchDecision::Fetch,
+            true if self.runs_skipped + 1 >= policy.probe_every => FetchDecision::Probe,
+            true => FetchDecision::Skip,
+        }
+    }
+
+    /// Count a run that skipped the source.
+    pub fn record_skip(&mut self) {
+        self.runs_skipped += 1;
+    }
+
+    /// A successful fetch on `date` clears the failures and any quarantine.
+    pub fn record_success(&mut self, date: &str) {
+        self.consecutive_failures = 0;
+        self.runs_skipped = 0;
+        self.last_error = None;
+        self.last_success = Some(date.to_string());
+        self.quarantined_since = None;
+    }
+
+    /// Count a failed fetch on `date`. Returns whether it put the source in quarantine.
+    pub fn record_failure(&mut self, date: &str, error: &str, policy: &QuarantinePolicy) -> bool {
+        self.consecutive_failures += 1;
+        self.runs_skipped = 0;
+        self.last_error = Some(error.to_string());
+        let quarantine = !self.is_quarantined() && self.consecutive_failures >= policy.after_failures;
+        if quarantine {
+            self.quarantined_since = Some(date.to_string());
+        }
+        quarantine
+    }
+}
+
+/// Health records keyed by source URL.
+pub type SourceHealthMap = BTreeMap<String, SourceHealth>;
+
+/// What a run does with the source at `url`; sources without a record are fetched.
+pub fn fetch_decision(health: &SourceHealthMap, url: &str, policy: &QuarantinePolicy) -> FetchDecision {
+    health.get(url.trim()).map_or(FetchDecision::Fetch, |h| h.decide(policy))
+}
+
+/// Drop records of sources no longer listed; returns how many.
+pub fn prune_unlisted<'a>(health: &mut SourceHealthMap, listed_urls: impl IntoIterator<Item = &'a str>) -> usize {
+    let listed: HashSet<&str> = listed_urls.into_iter().map(str::trim).collect();
+    let before = health.len();
+    health.retain(|url, _| listed.contains(url.as_str()));
+    before - health.len()
+}
+
+/// Load the health map; missing or corrupt is empty.
+pub async fn load_source_health<S: ObjectStore>(store: &S) -> Result<SourceHealthMap, StateError> {
+    load_or_default(store, SOURCE_HEALTH_OBJECT, &SOURCE_HEALTH_MIGRATIONS).await
+}
+
+/// Write the health map back.
+pub async fn save_source_health<S: ObjectStore>(store: &S, health: &SourceHealthMap) -> Result<(), StorageError> {
+    save_state(store, SOURCE_HEALTH_OBJECT, health, schema::SOURCE_HEALTH).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// An [`ObjectStore`] of files under a temporary directory.
+    struct FileStore {
+        root: PathBuf,
+    }
+
+    impl FileStore {
+        fn new(name: &str) -> Self {
+            let root = std::env::temp_dir().join(format!("source-health-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&root);
+            Self { root }
+        }
+    }
+
+    impl Drop for FileStore {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+    }
+
+    impl ObjectStore for FileStore {
+        async fn get(&self, path: &str) -> Result<Option<Vec<u8>>, StorageError> {
+            match std::fs::read(self.root.join(path)) {
+                Ok(data) => Ok(Some(data)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(StorageError::new(None, e.to_string())),
+            }
+        }
+
+        async fn put(&self, path: &str, data: Vec<u8>) -> Result<(), StorageError> {
+            let file = self.root.join(path);
+            let write = std::fs::create_dir_all(file.parent().unwrap()).and_then(|_| std::fs::write(&file, data));
+            write.map_err(|e| StorageError::new(None, e.to_string()))
+        }
+    }
+
+    const POLICY: QuarantinePolicy = QuarantinePolicy { after_failures: 3, probe_every: 4 };
+    const FEED: &str = "https://dead.example.com/feed";
+
+    /// One daily run against the stored map, the way daily-agent does it: decide, fetch unless
+    /// skipped (`fails` says how that goes), record, save. Returns the decision.
+    async fn run(store: &FileStore, day: u32, fails: bool) -> FetchDecision {
+        let mut health = load_source_health(store).await.unwrap();
+        let decision = fetch_decision(&health, FEED, &POLICY);
+        let date = format!("2026-05-{:02}", day);
+        let entry = health.entry(FEED.to_string()).or_default();
+        entry.name = "Dead Blog".to_string();
+        match decision {
+            FetchDecision::Skip => entry.record_skip(),
+            _ if fails => {
+                entry.record_failure(&date, "HTTP 404", &POLICY);
+            }
+            _ => entry.record_success(&date),
+        }
+        save_source_health(store, &health).await.unwrap();
+        decision
+    }
+
+    #[tokio::test]
+    async fn test_quarantine_probe_and_recovery_across_runs() {
+        use FetchDecision::*;
+        let store = FileStore::new("transitions");
+        // (fetch fails, expected decision, failures after, quarantined after)
+        let runs = [
+            (true, Fetch, 1, false),
+            // A success in between starts the count over
+            (false, Fetch, 0, false),
+            (true, Fetch, 1, false),
+            (true, Fetch, 2, false),
+            (true, Fetch, 3, true),
+            // Skipped three runs, probed on the fourth
+            (true, Skip, 3, true),
+            (true, Skip, 3, true),
+            (true, Skip, 3, true),
+            (true, Probe, 4, true),
+            // The failed probe starts the wait over
+            (false, Skip, 4, true),
+            (false, Skip, 4, true),
+            (false, Skip, 4, true),
+            // A successful probe lifts the quarantine
+            (false, Probe, 0, false),
+            (true, Fetch, 1, false),
+        ];
+        for (day, (fails, expected, failures, quarantined)) in runs.into_iter().enumerate() {
+            let day = day as u32 + 1;
+            assert_eq!(run(&store, day, fails).await, expected, "day {}", day);
+            let health = load_source_health(&store).await.unwrap();
+            assert_eq!(health[FEED].consecutive_failures, failures, "day {}", day);
+            assert_eq!(health[FEED].is_quarantined(), quarantined, "day {}", day);
+        }
+
+        let health = load_source_health(&store).await.unwrap();
+        assert_eq!(health[FEED].last_success.as_deref(), Some("2026-05-13"));
+        assert_eq!(health[FEED].last_error.as_deref(), Some("HTTP 404"));
+        let raw: serde_json::Value = serde_json::from_slice(&store.get(SOURCE_HEALTH_OBJECT).await.unwrap().unwrap()).unwrap();
+        assert_eq!(raw["schema_version"], schema::SOURCE_HEALTH);
+        assert_eq!(raw["data"][FEED]["name"], "Dead Blog");
+    }
+
+    #[test]
+    fn test_quarantined_since_is_the_day_it_started() {
+        let mut health = SourceHealth::default();
+        assert!(!health.record_failure("2026-05-01", "timeout", &POLICY));
+        assert!(!health.record_failure("2026-05-02", "timeout", &POLICY));
+        assert!(health.record_failure("2026-05-03", "timeout", &POLICY));
+        assert!(!health.record_failure("2026-05-07", "timeout", &POLICY));
+        assert_eq!(health.quarantined_since.as_deref(), Some("2026-05-03"));
+
+        // Probing every run never skips
+        let always = QuarantinePolicy { probe_every: 1, ..POLICY };
+        assert_eq!(health.decide(&always), FetchDecision::Probe);
+    }
+
+    #[test]
+    fn test_prune_unlisted() {
+        let mut health: SourceHealthMap =
+            ["https://a.example.com/feed", "https://gone.example.com/feed"].into_iter().map(|url| (url.to_string(), SourceHealth::default())).collect();
+        assert_eq!(prune_unlisted(&mut health, [" https://a.example.com/feed ", "https://new.example.com/feed"]), 1);
+        assert_eq!(health.keys().collect::<Vec<_>>(), ["https://a.example.com/feed"]);
+        assert_eq!(fetch_decision(&health, "https://new.example.com/feed", &POLICY), FetchDecision::Fetch);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_quarantine_policy_from_env() {
+        for (after, every, expected) in [
+            (None, None, QuarantinePolicy::default()),
+            (Some("2"), Some(" 10 "), QuarantinePolicy { after_failures: 2, probe_every: 10 }),
+            (Some("0"), Some("weekly"), QuarantinePolicy::default()),
+        ] {
+            for (name, value) in [(QUARANTINE_AFTER_FAILURES_ENV_VAR, after), (QUARANTINE_PROBE_EVERY_ENV_VAR, every)] {
+                match value {
+                    Some(v) => std::env::set_var(name, v),
+                    None => std::env::remove_var(name),
+                }
+            }
+            assert_eq!(QuarantinePolicy::from_env(), expected, "{:?} {:?}", after, every);
+        }
+        std::env::remove_var(QUARANTINE_AFTER_FAILURES_ENV_VAR);
+        std::env::remove_var(QUARANTINE_PROBE_EVERY_ENV_VAR);
+    }
+}
@@ -0,0 +1,260 @@
+//! Versioned state files: `{schema_version, updated_at, data}` wrappers with ordered
+//! migrations, so changing a state file's shape doesn't crash the agents or silently
+//! discard old state.
+//!
+//! `migrations[i]` upgrades version `i` to `i + 1`, so a file's current version is the
+//! number of migrations. Version 0 is a bare, unwrapped value written before versioning.
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::storage::{ObjectStore, StorageError};
+
+/// Current schema version of each versioned state file.
+pub mod schema {
+    /// `stats/extraction_health.json` (daily-agent)
+    pub const EXTRACTION_HEALTH: u32 = 1;
+    /// `runs/explorer-agent/candidate_memory.json` (explorer-agent)
+    pub const CANDIDATE_MEMORY: u32 = 1;
+    /// `state/seen_urls.json` (daily-agent)
+    pub const SEEN_URLS: u32 = 1;
+    /// `state/source_health.json` (daily-agent, read by explorer-agent)
+    pub const SOURCE_HEALTH: u32 = 1;
+}
+
+/// Upgrades a state value by one schema version.
+pub type Migration = fn(Value) -> Result<Value, String>;
+
+/// Migration from version 0: the data predates the wrapper and keeps its shape.
+pub fn unversioned(value: Value) -> Result<Value, String> {
+    Ok(value)
+}
+
+/// On-disk wrapper of a versioned state file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VersionedState<T> {
+    pub schema_version: u32,
+    pub updated_at: DateTime<Utc>,
+    pub data: T,
+}
+
+/// Why a state file couldn't be loaded. Corrupt content isn't an error: it falls back to
+/// the default with a warning.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StateError {
+    Storage(StorageError),
+    /// Written by a newer agent; left untouched rather than downgraded.
+    NewerVersion { path: String, found: u32, supported: u32 },
+    Migration { path: String, from: u32, message: String },
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::Storage(e) => write!(f, "{}", e),
+            StateError::NewerVersion { path, found, supported } => write!(
+                f,
+                "{} has schema version {}, newer than supported version {}; leaving it untouched",
+                path, found, supported
+            ),
+            StateError::Migration { path, from, message } => {
+                write!(f, "failed to migrate {} from schema version {}: {}", path, from, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
+
+impl From<StorageError> for StateError {
+    fn from(e: StorageError) -> Self {
+        StateError::Storage(e)
+    }
+}
+
+/// Stored version and data of a raw state value.
+fn split_versioned(value: Value) -> (u32, Value) {
+    if let Value::Object(map) = &value {
+        if let (Some(version), Some(data)) = (map.get("schema_version").and_then(Value::as_u64), map.get("data")) {
+            return (version as u32, data.clone());
+        }
+    }
+    (0, value)
+}
+
+/// Load a versioned state file, migrating it forward and saving it back when it was older.
+/// A missing file, unparseable JSON or data that doesn't match `T` yields `T::default()`.
+pub async fn load_or_default<S, T>(store: &S, path: &str, migrations: &[Migration]) -> Result<T, StateError>
+where
+    S: ObjectStore,
+    T: Serialize + DeserializeOwned + Default,
+{
+    let current = migrations.len() as u32;
+    let Some(bytes) = store.get(path).await? else {
+        return Ok(T::default());
+    };
+    let raw: Value = match serde_json::from_slice(&bytes) {
+        Ok(raw) => raw,
+        Err(e) => {
+            warn!(path = %path, error = %e, "Corrupt state file, starting from default");
+            return Ok(T::default());
+        }
+    };
+
+    let (stored, mut data) = split_versioned(raw);
+    if stored > current {
+        return Err(StateError::NewerVersion { path: path.to_string(), found: stored, supported: current });
+    }
+    for (version, migrate) in migrations.iter().enumerate().skip(stored as usize) {
+        data = migrate(data).map_err(|message| StateError::Migration { path: path.to_string(), from: version as u32, message })?;
+    }
+
+    let value: T = match serde_json::from_value(data) {
+        Ok(value) => value,
+        Err(e) => {
+            warn!(path = %path, error = %e, "State file doesn't match its schema, starting from default");
+            return Ok(T::default());
+        }
+    };
+    if stored < current {
+        info!(path = %path, from = stored, to = current, "Migrated state file");
+        if let Err(e) = save_state(store, path, &value, current).await {
+            warn!(path = %path, error = %e, "Failed to save migrated state file");
+        }
+    }
+    Ok(value)
+}
+
+/// Write `data` wrapped at `schema_version`.
+pub async fn save_state<S, T>(store: &S, path: &str, data: &T, schema_version: u32) -> Result<(), StorageError>
+where
+    S: ObjectStore,
+    T: Serialize,
+{
+    let state = VersionedState { schema_version, updated_at: Utc::now(), data };
+    let bytes = serde_json::to_vec_pretty(&state).map_err(|e| StorageError::new(None, e.to_string()))?;
+    store.put(path, bytes).await
+}
+
+/// Read-modify-write a versioned state file at the current version. Returns the updated value.
+pub async fn update_state<S, T, F>(store: &S, path: &str, migrations: &[Migration], update: F) -> Result<T, StateError>
+where
+    S: ObjectStore,
+    T: Serialize + DeserializeOwned + Default,
+    F: FnOnce(&mut T),
+{
+    let mut value: T = load_or_default(store, path, migrations).await?;
+    update(&mut value);
+    save_state(store, path, &value, migrations.len() as u32).await?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStore;
+    use std::collections::BTreeMap;
+
+    const PATH: &str = "stats/test.json";
+
+    /// v2 renamed `count` to `runs`.
+    #[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+    struct Counters {
+        runs: BTreeMap<String, u32>,
+    }
+
+    fn rename_count(mut value: Value) -> Result<Value, String> {
+        let map = value.as_object_mut().ok_or("expected an object")?;
+        let count = map.remove("count").unwrap_or_default();
+        map.insert("runs".to_string(), count);
+        Ok(value)
+    }
+
+    const MIGRATIONS: [Migration; 2] = [unversioned, rename_count];
+
+    async fn stored(store: &MemoryStore) -> Value {
+        serde_json::from_slice(&store.object(PATH).unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_forward_migration_from_unversioned() {
+        let store = MemoryStore::new();
+        store.put(PATH, br#"{"count": {"a": 3}}"#.to_vec()).await.unwrap();
+
+        let value: Counters = load_or_default(&store, PATH, &MIGRATIONS).await.unwrap();
+        assert_eq!(value.runs["a"], 3);
+
+        // Saved back at the current version
+        let saved = stored(&store).await;
+        assert_eq!(saved["schema_version"], 2);
+        assert_eq!(saved["data"]["runs"]["a"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_forward_migration_from_versioned() {
+        let store = MemoryStore::new();
+        store.put(PATH, br#"{"schema_version": 1, "updated_at": "2026-04-01T00:00:00Z", "data": {"count": {"b": 1}}}"#.to_vec()).await.unwrap();
+        let value: Counters = load_or_default(&store, PATH, &MIGRATIONS).await.unwrap();
+        assert_eq!(value.runs["b"], 1);
+        assert_eq!(stored(&store).await["schema_version"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_same_version_passthrough() {
+        let store = MemoryStore::new();
+        let original = br#"{"schema_version": 2, "updated_at": "2026-04-01T00:00:00Z", "data": {"runs": {"c": 7}}}"#.to_vec();
+        store.put(PATH, original.clone()).await.unwrap();
+
+        let value: Counters = load_or_default(&store, PATH, &MIGRATIONS).await.unwrap();
+        assert_eq!(value.runs["c"], 7);
+        // Not rewritten
+        assert_eq!(store.object(PATH), Some(original));
+    }
+
+    #[tokio::test]
+    async fn test_newer_version_is_refused_and_untouched() {
+        let store = MemoryStore::new();
+        let original = br#"{"schema_version": 3, "updated_at": "2026-04-01T00:00:00Z", "data": {"totals": 1}}"#.to_vec();
+        store.put(PATH, original.clone()).await.unwrap();
+
+        let err = load_or_default::<_, Counters>(&store, PATH, &MIGRATIONS).await.unwrap_err();
+        assert_eq!(err, StateError::NewerVersion { path: PATH.to_string(), found: 3, supported: 2 });
+        assert!(update_state(&store, PATH, &MIGRATIONS, |c: &mut Counters| { c.runs.insert("x".to_string(), 1); }).await.is_err());
+        assert_eq!(store.object(PATH), Some(original));
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_content_falls_back_to_default() {
+        let store = MemoryStore::new();
+        store.put(PATH, b"{not json".to_vec()).await.unwrap();
+        let value: Counters = load_or_default(&store, PATH, &MIGRATIONS).await.unwrap();
+        assert_eq!(value, Counters::default());
+
+        store.put(PATH, br#"{"schema_version": 2, "updated_at": "2026-04-01T00:00:00Z", "data": [1, 2]}"#.to_vec()).await.unwrap();
+        let value: Counters = load_or_default(&store, PATH, &MIGRATIONS).await.unwrap();
+        assert_eq!(value, Counters::default());
+    }
+
+    #[tokio::test]
+    async fn test_failed_migration_is_an_error() {
+        let store = MemoryStore::new();
+        store.put(PATH, b"[1, 2]".to_vec()).await.unwrap();
+        let err = load_or_default::<_, Counters>(&store, PATH, &MIGRATIONS).await.unwrap_err();
+        assert!(matches!(err, StateError::Migration { from: 1, .. }));
+        assert_eq!(store.object(PATH), Some(b"[1, 2]".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_update_state_writes_current_version() {
+        let store = MemoryStore::new();
+        update_state(&store, PATH, &MIGRATIONS, |c: &mut Counters| { c.runs.insert("d".to_string(), 1); }).await.unwrap();
+        let saved = stored(&store).await;
+        assert_eq!(saved["schema_version"], 2);
+        assert_eq!(saved["data"]["runs"]["d"], 1);
+    }
+}
@@ -0,0 +1,218 @@
+//! Minimal object-storage abstraction so shared state helpers can be used with GCS in
+//! production and an in-memory store in tests.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::Mutex;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Error returned by an [`ObjectStore`] operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageError {
+    /// HTTP status reported by the backend, when there was one.
+    pub status: Option<u16>,
+    pub message: String,
+}
+
+impl StorageError {
+    pub fn new(status: Option<u16>, message: impl Into<String>) -> Self {
+        Self { status, message: message.into() }
+    }
+
+    /// The object doesn't exist: a 404, or GCS's "No such object" message without a status.
+    pub fn is_not_found(&self) -> bool {
+        self.status == Some(404) || self.message.contains("No such object")
+    }
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            Some(status) => write!(f, "storage error ({}): {}", status, self.message),
+            None => write!(f, "storage error: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+/// A bucket-like key/value store of byte blobs.
+pub trait ObjectStore: Send + Sync {
+    /// Read an object. Returns `Ok(None)` when the object does not exist.
+    fn get(&self, path: &str) -> impl Future<Output = Result<Option<Vec<u8>>, StorageError>> + Send;
+
+    /// Create or overwrite an object.
+    fn put(&self, path: &str, data: Vec<u8>) -> impl Future<Output = Result<(), StorageError>> + Send;
+}
+
+/// Read-modify-write a JSON object: load it (or start from `T::default()` when missing),
+/// apply `update`, and write it back. Returns the updated value.
+///
+/// A corrupt object is an error rather than silently replaced, so state isn't lost.
+pub async fn update_json<S, T, F>(store: &S, path: &str, update: F) -> Result<T, StorageError>
+where
+    S: ObjectStore,
+    T: Serialize + DeserializeOwned + Default,
+    F: FnOnce(&mut T),
+{
+    let mut value = match store.get(path).await? {
+        Some(data) => serde_json::from_slice(&data)
+            .map_err(|e| StorageError::new(None, format!("failed to parse {}: {}", path, e)))?,
+        None => T::default(),
+    };
+    update(&mut value);
+    let data = serde_json::to_vec_pretty(&value).map_err(|e| StorageError::new(None, e.to_string()))?;
+    store.put(path, data).await?;
+    Ok(value)
+}
+
+/// A shared store, e.g. one also handed to an [`AuditSink`](crate::AuditSink).
+impl<S: ObjectStore> ObjectStore for std::sync::Arc<S> {
+    fn get(&self, path: &str) -> impl Future<Output = Result<Option<Vec<u8>>, StorageError>> + Send {
+        (**self).get(path)
+    }
+
+    fn put(&self, path: &str, data: Vec<u8>) -> impl Future<Output = Result<(), StorageError>> + Send {
+        (**self).put(path, data)
+    }
+}
+
+/// In-memory store used by tests and dry runs.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    objects: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Snapshot of a stored object, bypassing the async API.
+    pub fn object(&self, path: &str) -> Option<Vec<u8>> {
+        self.objects.lock().unwrap_or_else(|e| e.into_inner()).get(path).cloned()
+    }
+
+    /// Sorted list of stored object paths.
+    pub fn paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self.objects.lock().unwrap_or_else(|e| e.into_inner()).keys().cloned().collect();
+        paths.sort();
+        paths
+    }
+}
+
+impl ObjectStore for MemoryStore {
+    async fn get(&self, path: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self.object(path))
+    }
+
+    async fn put(&self, path: &str, data: Vec<u8>) -> Result<(), StorageError> {
+        self.objects.lock().unwrap_or_else(|e| e.into_inner()).insert(path.to_string(), data);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "gcs")]
+pub use self::gcs::GcsStore;
+
+#[cfg(feature = "gcs")]
+mod gcs {
+    use super::{ObjectStore, StorageError};
+    use crate::gcs_retry::{delete_object_with, download_object_with, upload_object_with};
+    use crate::retry::RetryPolicy;
+    use gcloud_storage::client::Client;
+
+    /// [`ObjectStore`] backed by a single GCS bucket.
+    #[derive(Clone)]
+    pub struct GcsStore {
+        client: Client,
+        bucket: String,
+    }
+
+    impl GcsStore {
+        pub fn new(client: Client, bucket: &str) -> Self {
+            Self { client, bucket: bucket.to_string() }
+        }
+
+        pub fn bucket(&self) -> &str {
+            &self.bucket
+        }
+
+        pub fn client(&self) -> &Client {
+            &self.client
+        }
+
+        /// Delete an object, retrying transient failures.
+        pub async fn delete(&self, path: &str) -> Result<(), StorageError> {
+            delete_object_with(&self.client, &self.bucket, path, &RetryPolicy::gcs_from_env()).await
+        }
+    }
+
+    impl ObjectStore for GcsStore {
+        async fn get(&self, path: &str) -> Result<Option<Vec<u8>>, StorageError> {
+            match download_object_with(&self.client, &self.bucket, path, &RetryPolicy::gcs_from_env()).await {
+                Ok(data) => Ok(Some(data)),
+                Err(e) if e.is_not_found() => Ok(None),
+                Err(e) => Err(e),
+            }
+        }
+
+        async fn put(&self, path: &str, data: Vec<u8>) -> Result<(), StorageError> {
+            upload_object_with(&self.client, &self.bucket, path, data, &RetryPolicy::gcs_from_env()).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_store_roundtrip() {
+        let store = MemoryStore::new();
+        assert_eq!(store.get("a/b.json").await.unwrap(), None);
+        store.put("a/b.json", b"{}".to_vec()).await.unwrap();
+        assert_eq!(store.get("a/b.json").await.unwrap(), Some(b"{}".to_vec()));
+        assert_eq!(store.paths(), vec!["a/b.json".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_update_json_creates_and_updates() {
+        let store = MemoryStore::new();
+        let v: Vec<u32> = update_json(&store, "list.json", |v: &mut Vec<u32>| v.push(1)).await.unwrap();
+        assert_eq!(v, vec![1]);
+        let v: Vec<u32> = update_json(&store, "list.json", |v: &mut Vec<u32>| v.push(2)).await.unwrap();
+        assert_eq!(v, vec![1, 2]);
+        assert_eq!(store.object("list.json").map(|d| serde_json::from_slice::<Vec<u32>>(&d).unwrap()), Some(vec![1, 2]));
+    }
+
+    #[tokio::test]
+    async fn test_update_json_refuses_corrupt_object() {
+        let store = MemoryStore::new();
+        store.put("list.json", b"{oops".to_vec()).await.unwrap();
+        let result = update_json(&store, "list.json", |v: &mut Vec<u32>| v.push(1)).await;
+        assert!(result.is_err());
+        assert_eq!(store.object("list.json"), Some(b"{oops".to_vec()));
+    }
+
+    #[test]
+    fn test_storage_error_display() {
+        assert_eq!(StorageError::new(Some(503), "unavailable").to_string(), "storage error (503): unavailable");
+        assert_eq!(StorageError::new(None, "reset").to_string(), "storage error: reset");
+    }
+
+    #[test]
+    fn test_storage_error_is_not_found() {
+        for (error, expected) in [
+            (StorageError::new(Some(404), "Not Found"), true),
+            (StorageError::new(None, "No such object: bucket/manifest.json"), true),
+            (StorageError::new(Some(403), "Forbidden"), false),
+            (StorageError::new(Some(503), "Service Unavailable"), false),
+        ] {
+            assert_eq!(error.is_not_found(), expected, "{}", error);
+        }
+    }
+}
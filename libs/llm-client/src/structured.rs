@@ -0,0 +1,282 @@
+//! Structured JSON output from LLM calls.
+//!
+//! `call_llm_json` asks the provider for JSON ([`ResponseFormat::Json`]), parses the reply
+//! defensively ([`parse_llm_json`]) and, when it still isn't valid, sends the parse error
+//! back once and asks for corrected output.
+
+use serde::de::{DeserializeOwned, IgnoredAny};
+use tracing::{instrument, warn};
+
+use crate::retry::RetryPolicy;
+use crate::{send_with_policy, ChatMessage, CostTracker, LlmError, LlmOptions, LlmProvider, ResponseFormat};
+
+/// Follow-up requests after an invalid JSON reply.
+const JSON_CORRECTIONS: usize = 1;
+
+/// Length in bytes of the bracketed value at the start of `text`, skipping brackets inside
+/// JSON strings. `None` if it is never closed.
+fn balanced_len(text: &str) -> Option<usize> {
+    let (mut depth, mut in_string, mut escaped) = (0usize, false, false);
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Every valid JSON object or array in `text`, in order of where it starts.
+fn json_values(text: &str) -> impl Iterator<Item = &str> {
+    text.char_indices()
+        .filter(|&(_, c)| c == '{' || c == '[')
+        .filter_map(move |(start, _)| balanced_len(&text[start..]).map(|len| &text[start..start + len]))
+        .filter(|span| serde_json::from_str::<IgnoredAny>(span).is_ok())
+}
+
+/// JSON values in an LLM response: those inside markdown code fences first, then the rest.
+pub(crate) fn json_candidates(response: &str) -> impl Iterator<Item = &str> {
+    let fenced = response.split("```").skip(1).step_by(2);
+    fenced.chain(std::iter::once(response)).flat_map(json_values)
+}
+
+/// The JSON object or array in an LLM response, without the markdown fence (with or without
+/// a language tag), prose or whitespace around it. A fenced value wins over one in the prose.
+pub fn extract_json_block(response: &str) -> Option<&str> {
+    json_candidates(response).next()
+}
+
+/// The first run of digits in an LLM response, e.g. 3 in "Article #3 is the best".
+pub fn extract_first_integer(response: &str) -> Option<usize> {
+    let start = response.find(|c: char| c.is_ascii_digit())?;
+    let digits = &response[start..];
+    let end = digits.find(|c: char| !c.is_ascii_digit()).unwrap_or(digits.len());
+    digits[..end].parse().ok()
+}
+
+/// Parse an LLM response as JSON: the raw response first, then each value
+/// [`extract_json_block`] would consider until one deserializes into `T`. The error is the
+/// raw response's.
+pub fn parse_llm_json<T: DeserializeOwned>(response: &str) -> Result<T, serde_json::Error> {
+    serde_json::from_str(response.trim()).or_else(|e| {
+        json_candidates(response).find_map(|block| serde_json::from_str(block).ok()).ok_or(e)
+    })
+}
+
+fn correction_prompt(error: &serde_json::Error) -> String {
+    format!(
+        "Your previous output was invalid JSON: {}. Reply again with only the corrected JSON value, no markdown fences or explanation.",
+        error
+    )
+}
+
+/// Call an LLM for JSON output and deserialize it into `T`, with exponential backoff retry.
+/// Every call's usage is recorded in `cost`. Output that is still invalid after one
+/// correction request is an [`LlmError::Parse`].
+pub async fn call_llm_json<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    provider: LlmProvider,
+    api_key: &str,
+    prompt: impl Into<String>,
+    options: &LlmOptions,
+    cost: &mut CostTracker,
+) -> Result<T, LlmError> {
+    call_llm_json_with_policy(client, provider, api_key, prompt, options, &RetryPolicy::llm_from_env(), cost).await
+}
+
+/// [`call_llm_json`] retrying transient failures under `policy`.
+#[instrument(skip(client, api_key, prompt, options, policy, cost), fields(provider = %provider.as_str(), prompt_len = tracing::field::Empty))]
+pub async fn call_llm_json_with_policy<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    provider: LlmProvider,
+    api_key: &str,
+    prompt: impl Into<String>,
+    options: &LlmOptions,
+    policy: &RetryPolicy,
+    cost: &mut CostTracker,
+) -> Result<T, LlmError> {
+    let prompt = prompt.into();
+    tracing::Span::current().record("prompt_len", prompt.len());
+    // Keep a caller-provided schema; otherwise plain JSON mode
+    let mut options = options.clone();
+    if options.response_format == ResponseFormat::Text {
+        options.response_format = ResponseFormat::Json(None);
+    }
+
+    let mut messages = vec![ChatMessage::user(prompt)];
+    let mut corrections = 0;
+    loop {
+        let response = send_with_policy(client, provider, api_key, &messages, &options, policy).await?;
+        cost.record(&response);
+        let error = match parse_llm_json(&response.text) {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+        if corrections == JSON_CORRECTIONS {
+            warn!(error = %error, raw_response = %response.text, "LLM output is still invalid JSON");
+            return Err(LlmError::Parse(error));
+        }
+        warn!(error = %error, "Invalid JSON from LLM, asking for a correction");
+        corrections += 1;
+        messages.push(ChatMessage::assistant(response.text));
+        messages.push(ChatMessage::user(correction_prompt(&error)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Pick {
+        index: usize,
+    }
+
+    #[test]
+    fn test_extract_json_block() {
+        let cases = [
+            ("```json\n[{\"name\": \"Test\"}]\n```", "[{\"name\": \"Test\"}]"),
+            ("```\n{\"key\": \"value\"}\n```", "{\"key\": \"value\"}"),
+            ("```JSON\n{\"a\": 1}\n```", "{\"a\": 1}"),
+            ("```jsonc\n{\"a\": 1}```", "{\"a\": 1}"),
+            ("  \n  {\"a\": 1}  \n  ", "{\"a\": 1}"),
+            ("[{\"name\": \"Test\"}]", "[{\"name\": \"Test\"}]"),
+            ("Sure! Here's the JSON:\n\n{\"index\": 3}", "{\"index\": 3}"),
+            ("Sure! Here's the JSON:\n```json\n{\"index\": 3}\n```\nLet me know if you need more.", "{\"index\": 3}"),
+            ("I'd pick {\"index\": 7} because it goes deepest.", "{\"index\": 7}"),
+            ("The shortlist is [3, 7, 12] in my view.", "[3, 7, 12]"),
+            ("```json\n{\"sources\": [{\"name\": \"Blog\"}]}\n```", "{\"sources\": [{\"name\": \"Blog\"}]}"),
+            // Brackets and fences inside strings don't end the value
+            ("{\"note\": \"see [1] and {x}\", \"index\": 2}", "{\"note\": \"see [1] and {x}\", \"index\": 2}"),
+            ("```json\n{\"code\": \"```rust\\nfn main() {}\\n```\"}\n```", "{\"code\": \"```rust\\nfn main() {}\\n```\"}"),
+            ("{\"quote\": \"she said \\\"{\\\"\"}", "{\"quote\": \"she said \\\"{\\\"\"}"),
+            // A fenced value wins over bracketed prose before it
+            ("Per [the docs]:\n```json\n{\"index\": 4}\n```", "{\"index\": 4}"),
+            // Invalid braces are skipped for the first valid value
+            ("Use {curly} braces: {\"index\": 5}", "{\"index\": 5}"),
+        ];
+        for (response, expected) in cases {
+            assert_eq!(extract_json_block(response), Some(expected), "{:?}", response);
+        }
+        for response in ["", "```json\n```", "I pick article 3", "{\"unterminated\": [1, 2}", "{not json}"] {
+            assert_eq!(extract_json_block(response), None, "{:?}", response);
+        }
+    }
+
+    #[test]
+    fn test_extract_first_integer() {
+        assert_eq!(extract_first_integer("3"), Some(3));
+        assert_eq!(extract_first_integer("  42\n"), Some(42));
+        assert_eq!(extract_first_integer("Article #3"), Some(3));
+        assert_eq!(extract_first_integer("I'd go with article 12, then 7."), Some(12));
+        assert_eq!(extract_first_integer("**[5]** Deep dive into io_uring"), Some(5));
+        assert_eq!(extract_first_integer("{\"index\": 9}"), Some(9));
+        assert_eq!(extract_first_integer("no number here"), None);
+        assert_eq!(extract_first_integer(""), None);
+        assert_eq!(extract_first_integer("99999999999999999999999"), None);
+    }
+
+    #[test]
+    fn test_parse_fence_wrapped_output() {
+        let pick: Pick = parse_llm_json("```json\n{\"index\": 3}\n```").unwrap();
+        assert_eq!(pick, Pick { index: 3 });
+    }
+
+    #[test]
+    fn test_parse_output_with_surrounding_prose() {
+        let pick: Pick = parse_llm_json("Here is my choice:\n{\"index\": 7}\nIt has the most depth.").unwrap();
+        assert_eq!(pick, Pick { index: 7 });
+
+        let picks: Vec<Pick> = parse_llm_json("```json\n[{\"index\": 1}, {\"index\": 2}]\n```\nHope this helps!").unwrap();
+        assert_eq!(picks.len(), 2);
+
+        // A citation-like array before the object doesn't shadow it
+        let pick: Pick = parse_llm_json("Based on sources [1, 4], my pick is {\"index\": 2}").unwrap();
+        assert_eq!(pick, Pick { index: 2 });
+    }
+
+    #[test]
+    fn test_parse_invalid_output_is_an_error() {
+        assert!(parse_llm_json::<Pick>("I pick article 3").is_err());
+        assert!(parse_llm_json::<Pick>("{\"index\": \"three\"}").is_err());
+        assert!(parse_llm_json::<Pick>("").is_err());
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_invalid_json_is_corrected_once() {
+        use wiremock::matchers::{body_string_contains, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let gemini_reply = |text: &str| {
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "candidates": [{ "content": { "parts": [{ "text": text }] } }],
+                "usageMetadata": { "promptTokenCount": 10, "candidatesTokenCount": 2 }
+            }))
+        };
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/v1beta/models/test-model:generateContent"))
+            .and(body_string_contains("previous output was invalid JSON"))
+            .respond_with(gemini_reply("{\"index\": 4}"))
+            .expect(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST")).and(path("/v1beta/models/test-model:generateContent"))
+            .and(body_string_contains("application/json"))
+            .respond_with(gemini_reply("The best article is 4"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        std::env::set_var("GEMINI_BASE_URL", server.uri());
+        std::env::set_var("GEMINI_MODEL", "test-model");
+
+        let client = reqwest::Client::new();
+        let mut cost = CostTracker::default();
+        let result: Result<Pick, _> = call_llm_json(&client, LlmProvider::Gemini, "key", "Pick one".to_string(), &LlmOptions::default(), &mut cost).await;
+        std::env::remove_var("GEMINI_BASE_URL");
+        std::env::remove_var("GEMINI_MODEL");
+        assert_eq!(result.unwrap(), Pick { index: 4 });
+        assert_eq!(cost.usage.calls, 2);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_still_invalid_json_is_a_parse_error() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{ "message": { "content": "no JSON here" } }]
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+        std::env::set_var("OPENAI_BASE_URL", server.uri());
+
+        let client = reqwest::Client::new();
+        let mut cost = CostTracker::default();
+        let result: Result<Pick, _> = call_llm_json(&client, LlmProvider::OpenAI, "key", "Pick one".to_string(), &LlmOptions::default(), &mut cost).await;
+        std::env::remove_var("OPENAI_BASE_URL");
+        assert!(matches!(result, Err(LlmError::Parse(_))));
+    }
+}
@@ -0,0 +1,25 @@
+//! Fixtures shared by the crate's tests.
+
+use std::time::Duration;
+
+use wiremock::ResponseTemplate;
+
+use crate::retry::RetryPolicy;
+
+/// [`RetryPolicy::PATIENT_LLM`] with millisecond delays, giving up after `max_attempts`.
+pub(crate) fn fast_policy(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        initial_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(5),
+        max_attempts: Some(max_attempts),
+        ..RetryPolicy::PATIENT_LLM
+    }
+}
+
+/// A Claude Messages API reply of `text`, reporting 10 prompt and 5 completion tokens.
+pub(crate) fn claude_reply(text: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "content": [{ "text": text }],
+        "usage": { "input_tokens": 10, "output_tokens": 5 }
+    }))
+}
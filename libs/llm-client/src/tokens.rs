@@ -0,0 +1,136 @@
+//! Cheap token estimates for sizing prompts against model context windows.
+//!
+//! No tokenizer: English-like text averages about 4 characters or 3/4 of a word per
+//! token, and the estimate takes the larger of the two so it errs on the high side.
+
+/// Estimated token count of `text`.
+pub fn estimate_tokens(text: &str) -> usize {
+    estimate(text.chars().count(), text.split_whitespace().count())
+}
+
+fn estimate(chars: usize, words: usize) -> usize {
+    chars.div_ceil(4).max((words * 4).div_ceil(3))
+}
+
+/// The longest prefix of `text` estimated at no more than `max_tokens`, cut at a word
+/// boundary (never inside a UTF-8 character) with trailing whitespace removed. `text` itself
+/// when it already fits. When not even the first word fits, as with CJK text, a long URL or
+/// minified JSON, the cut falls inside that word instead.
+pub fn truncate_to_tokens(text: &str, max_tokens: usize) -> &str {
+    if estimate_tokens(text) <= max_tokens {
+        return text;
+    }
+    let (mut chars, mut words, mut in_word) = (0, 0, false);
+    let mut end = 0;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() {
+            if in_word {
+                // `text[..i]` ends right after a word
+                if estimate(chars, words) > max_tokens {
+                    break;
+                }
+                end = i;
+            }
+            in_word = false;
+        } else if !in_word {
+            in_word = true;
+            words += 1;
+        }
+        chars += 1;
+    }
+    if end == 0 {
+        return truncate_anywhere(text, max_tokens);
+    }
+    &text[..end]
+}
+
+/// [`truncate_to_tokens`] cutting at any character boundary.
+fn truncate_anywhere(text: &str, max_tokens: usize) -> &str {
+    let (mut chars, mut words, mut in_word) = (0, 0, false);
+    let mut end = 0;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() {
+            in_word = false;
+        } else if !in_word {
+            in_word = true;
+            words += 1;
+        }
+        chars += 1;
+        if estimate(chars, words) > max_tokens {
+            break;
+        }
+        end = i + c.len_utf8();
+    }
+    text[..end].trim_end()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("hi"), 2); // one word still counts as 4/3 tokens
+        assert_eq!(estimate_tokens("abcdefghijklmnop"), 4);
+        // Many short words weigh more than their characters
+        assert_eq!(estimate_tokens("a b c d e f"), 8);
+        let article = "The quick brown fox jumps over the lazy dog. ".repeat(100);
+        let estimate = estimate_tokens(&article);
+        assert!((1000..1400).contains(&estimate), "{}", estimate);
+    }
+
+    #[test]
+    fn test_truncate_fitting_text_is_unchanged() {
+        assert_eq!(truncate_to_tokens("", 0), "");
+        assert_eq!(truncate_to_tokens("short text", 10), "short text");
+        let text = "abcdefgh ijklmnop";
+        assert_eq!(truncate_to_tokens(text, estimate_tokens(text)), text);
+    }
+
+    #[test]
+    fn test_truncate_cuts_at_word_boundary() {
+        let text = "alpha beta gamma delta epsilon";
+        let cut = truncate_to_tokens(text, 5);
+        assert_eq!(cut, "alpha beta gamma");
+        assert!(estimate_tokens(cut) <= 5);
+        // One token more than the estimate of a prefix keeps that prefix
+        assert_eq!(truncate_to_tokens(text, estimate_tokens("alpha beta gamma delta")), "alpha beta gamma delta");
+        // Trailing whitespace and newlines before the cut are dropped
+        assert_eq!(truncate_to_tokens("alpha beta \n\n gamma delta epsilon", 4), "alpha beta");
+    }
+
+    #[test]
+    fn test_truncate_never_splits_words_or_characters() {
+        let text = "naïve café — 東京 データベース résumé Ünïcödé ✓✓✓ emoji 🦀🦀 end ".repeat(20);
+        for max_tokens in 0..estimate_tokens(&text) {
+            let cut = truncate_to_tokens(&text, max_tokens);
+            assert!(estimate_tokens(cut) <= max_tokens, "{} > {}", estimate_tokens(cut), max_tokens);
+            assert!(text.starts_with(cut));
+            // A cut ends at the end of a word: the rest starts with whitespace
+            if !cut.is_empty() && cut.contains(char::is_whitespace) {
+                assert!(text[cut.len()..].starts_with(char::is_whitespace), "cut mid-word at {}", cut.len());
+                assert!(!cut.ends_with(char::is_whitespace));
+            }
+        }
+    }
+
+    #[test]
+    fn test_truncate_first_word_too_long() {
+        assert_eq!(truncate_to_tokens("https://example.com/a/very/long/url/that/is/one/word more", 3), "https://exam");
+        assert_eq!(truncate_to_tokens(r#"{"a":1,"b":[2,3],"c":{"d":"e"}}"#, 2), r#"{"a":1,""#);
+        assert_eq!(truncate_to_tokens("   leading space", 1), "");
+        assert_eq!(truncate_to_tokens("word", 1), "");
+    }
+
+    #[test]
+    fn test_truncate_text_without_whitespace() {
+        let text = "分布式数据库在高并发场景下的一致性与可用性权衡。".repeat(50);
+        let cut = truncate_to_tokens(&text, 100);
+        assert_eq!(cut.chars().count(), 400);
+        assert!(text.starts_with(cut));
+        for max_tokens in [2, 3, 17, 250] {
+            assert!(estimate_tokens(truncate_to_tokens(&text, max_tokens)) <= max_tokens);
+        }
+    }
+}
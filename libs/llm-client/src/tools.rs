@@ -0,0 +1,298 @@
+//! Tool (function) calling: the model answers with a structured invocation of one of the
+//! tools it was offered, e.g. `add_source(name, url)`, instead of free-form JSON.
+//!
+//! [`LlmOptions::tools`](crate::LlmOptions) offers [`ToolSpec`]s as Gemini
+//! `functionDeclarations`, OpenAI `tools` or Claude `tools`. The invocation comes back as
+//! [`LlmResponse::tool_call`](crate::LlmResponse) with its arguments parsed, whatever the
+//! provider's wire format; [`call_llm_with_tools`] returns it as a [`ToolReply`].
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::{call_llm_with_usage, LlmOptions, LlmProvider, LlmResponse};
+
+/// A tool the model may call.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ToolSpec {
+    pub name: String,
+    /// What the tool does and when to call it; the model chooses tools by this.
+    pub description: String,
+    /// JSON schema of the arguments object.
+    pub parameters: serde_json::Value,
+}
+
+impl ToolSpec {
+    pub fn new(name: impl Into<String>, description: impl Into<String>, parameters: serde_json::Value) -> Self {
+        Self { name: name.into(), description: description.into(), parameters }
+    }
+}
+
+/// A tool invocation returned by the model.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ToolCall {
+    pub name: String,
+    /// Arguments object; `{}` when the provider sent none.
+    pub arguments: serde_json::Value,
+}
+
+impl ToolCall {
+    /// The arguments deserialized into `T`.
+    pub fn parse_arguments<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_value(self.arguments.clone())
+    }
+}
+
+/// Reply to a call that offered tools: a tool invocation, or text when the model chose none.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolReply {
+    Text(String),
+    ToolCall { name: String, arguments: serde_json::Value },
+}
+
+impl From<LlmResponse> for ToolReply {
+    /// A tool call wins over any text sent alongside it.
+    fn from(response: LlmResponse) -> Self {
+        match response.tool_call {
+            Some(call) => ToolReply::ToolCall { name: call.name, arguments: call.arguments },
+            None => ToolReply::Text(response.text),
+        }
+    }
+}
+
+/// Send `prompt` offering `tools`, with exponential backoff retry.
+#[instrument(skip(client, api_key, prompt, tools, options), fields(provider = %provider.as_str(), tools = tools.len()))]
+pub async fn call_llm_with_tools(
+    client: &reqwest::Client,
+    provider: LlmProvider,
+    api_key: &str,
+    prompt: impl Into<String>,
+    tools: &[ToolSpec],
+    options: &LlmOptions,
+) -> Result<ToolReply, Box<dyn std::error::Error + Send + Sync>> {
+    let options = LlmOptions { tools: tools.to_vec(), ..options.clone() };
+    Ok(call_llm_with_usage(client, provider, api_key, prompt, &options).await?.into())
+}
+
+// --- Provider wire formats ---
+
+/// OpenAI `tools` entry.
+#[derive(Serialize, Debug)]
+pub(crate) struct OpenAITool {
+    r#type: &'static str,
+    function: ToolSpec,
+}
+
+impl From<&ToolSpec> for OpenAITool {
+    fn from(spec: &ToolSpec) -> Self {
+        Self { r#type: "function", function: spec.clone() }
+    }
+}
+
+/// Claude `tools` entry.
+#[derive(Serialize, Debug)]
+pub(crate) struct ClaudeTool {
+    name: String,
+    description: String,
+    input_schema: serde_json::Value,
+}
+
+impl From<&ToolSpec> for ClaudeTool {
+    fn from(spec: &ToolSpec) -> Self {
+        Self { name: spec.name.clone(), description: spec.description.clone(), input_schema: spec.parameters.clone() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChatMessage, ClaudeRequest, GeminiRequest, GeminiResponse, OpenAIRequest, OpenAIResponse};
+    use serde_json::json;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn add_source() -> ToolSpec {
+        ToolSpec::new(
+            "add_source",
+            "Add a blog to the sources",
+            json!({
+                "type": "object",
+                "properties": { "name": { "type": "string" }, "url": { "type": "string" } },
+                "required": ["name", "url"]
+            }),
+        )
+    }
+
+    fn tool_options(server: &MockServer, model: &str) -> LlmOptions {
+        LlmOptions { base_url: Some(server.uri()), model: Some(model.to_string()), ..Default::default() }
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct AddSource {
+        name: String,
+        url: String,
+    }
+
+    fn expected_call() -> ToolReply {
+        ToolReply::ToolCall { name: "add_source".to_string(), arguments: json!({ "name": "Dropbox Tech", "url": "https://dropbox.tech/feed" }) }
+    }
+
+    #[test]
+    fn test_tool_request_shapes() {
+        let options = LlmOptions { tools: vec![add_source()], google_search: true, ..Default::default() };
+        let messages = [ChatMessage::user("hi")];
+
+        let gemini = serde_json::to_value(GeminiRequest::new(&messages, &options)).unwrap();
+        assert_eq!(gemini["tools"], json!([
+            { "google_search": {} },
+            { "functionDeclarations": [{ "name": "add_source", "description": "Add a blog to the sources", "parameters": add_source().parameters }] }
+        ]));
+
+        let openai = serde_json::to_value(OpenAIRequest::new("gpt-4".to_string(), &messages, &options)).unwrap();
+        assert_eq!(openai["tools"], json!([
+            { "type": "function", "function": { "name": "add_source", "description": "Add a blog to the sources", "parameters": add_source().parameters } }
+        ]));
+
+        let claude = serde_json::to_value(ClaudeRequest::new("claude".to_string(), &messages, &options)).unwrap();
+        assert_eq!(claude["tools"], json!([
+            { "name": "add_source", "description": "Add a blog to the sources", "input_schema": add_source().parameters }
+        ]));
+
+        // No tools, no field
+        let plain = LlmOptions::default();
+        assert!(serde_json::to_value(GeminiRequest::new(&messages, &plain)).unwrap().get("tools").is_none());
+        assert!(serde_json::to_value(OpenAIRequest::new("gpt-4".to_string(), &messages, &plain)).unwrap().get("tools").is_none());
+        assert!(serde_json::to_value(ClaudeRequest::new("claude".to_string(), &messages, &plain)).unwrap().get("tools").is_none());
+    }
+
+    #[test]
+    fn test_gemini_function_call_deserialization() {
+        let response: GeminiResponse = serde_json::from_value(json!({
+            "candidates": [{
+                "content": { "role": "model", "parts": [{ "functionCall": { "name": "reject_source", "args": { "reason": "marketing" } } }] },
+                "finishReason": "STOP"
+            }]
+        })).unwrap();
+        let part = &response.candidates.unwrap()[0].content.parts[0];
+        assert_eq!(part.text(), None);
+        let call = part.function_call().unwrap();
+        assert_eq!(call.name, "reject_source");
+        assert_eq!(call.args, json!({ "reason": "marketing" }));
+    }
+
+    #[test]
+    fn test_openai_tool_call_deserialization() {
+        let response: OpenAIResponse = serde_json::from_value(json!({
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{ "id": "call_1", "type": "function", "function": { "name": "reject_source", "arguments": "{\"reason\":\"marketing\"}" } }]
+                },
+                "finish_reason": "tool_calls"
+            }]
+        })).unwrap();
+        let message = &response.choices.unwrap()[0].message;
+        assert_eq!(message.content, None);
+        assert_eq!(message.tool_calls[0].function.name, "reject_source");
+        assert_eq!(message.tool_calls[0].function.arguments, "{\"reason\":\"marketing\"}");
+    }
+
+    #[test]
+    fn test_tool_reply_from_response() {
+        let response = LlmResponse {
+            text: "Adding it".to_string(),
+            prompt_tokens: None,
+            completion_tokens: None,
+            model: "m".to_string(),
+            truncated: false,
+            continuations: 0,
+            alternatives: Vec::new(),
+            grounding: None,
+            tool_call: None,
+            thoughts: None,
+        };
+        assert_eq!(ToolReply::from(response.clone()), ToolReply::Text("Adding it".to_string()));
+        let call = ToolCall { name: "add_source".to_string(), arguments: json!({ "name": "Dropbox Tech", "url": "https://dropbox.tech/feed" }) };
+        assert_eq!(call.parse_arguments::<AddSource>().unwrap(), AddSource { name: "Dropbox Tech".to_string(), url: "https://dropbox.tech/feed".to_string() });
+        assert_eq!(ToolReply::from(LlmResponse { tool_call: Some(call), ..response }), expected_call());
+    }
+
+    #[tokio::test]
+    async fn test_gemini_tool_call() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/v1beta/models/gemini-test:generateContent"))
+            .and(body_partial_json(json!({ "tools": [{ "functionDeclarations": [{ "name": "add_source" }] }] })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "candidates": [{
+                    "content": { "parts": [{ "functionCall": { "name": "add_source", "args": { "name": "Dropbox Tech", "url": "https://dropbox.tech/feed" } } }] },
+                    "finishReason": "STOP"
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let reply = call_llm_with_tools(&reqwest::Client::new(), LlmProvider::Gemini, "key", "Recommend".to_string(), &[add_source()], &tool_options(&server, "gemini-test")).await;
+        assert_eq!(reply.unwrap(), expected_call());
+    }
+
+    #[tokio::test]
+    async fn test_openai_tool_call() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/chat/completions"))
+            .and(body_partial_json(json!({ "tools": [{ "type": "function", "function": { "name": "add_source" } }] })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "choices": [{
+                    "message": {
+                        "content": null,
+                        "tool_calls": [{
+                            "id": "call_1",
+                            "type": "function",
+                            "function": { "name": "add_source", "arguments": "{\"name\": \"Dropbox Tech\", \"url\": \"https://dropbox.tech/feed\"}" }
+                        }]
+                    },
+                    "finish_reason": "tool_calls"
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let reply = call_llm_with_tools(&reqwest::Client::new(), LlmProvider::OpenAI, "key", "Recommend".to_string(), &[add_source()], &tool_options(&server, "gpt-test")).await;
+        assert_eq!(reply.unwrap(), expected_call());
+    }
+
+    #[tokio::test]
+    async fn test_claude_tool_call() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .and(body_partial_json(json!({ "tools": [{ "name": "add_source", "input_schema": { "type": "object" } }] })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "content": [
+                    { "type": "text", "text": "I'll add it." },
+                    { "type": "tool_use", "id": "toolu_1", "name": "add_source", "input": { "name": "Dropbox Tech", "url": "https://dropbox.tech/feed" } }
+                ],
+                "stop_reason": "tool_use"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let options = tool_options(&server, "claude-test");
+        let response = call_llm_with_usage(&reqwest::Client::new(), LlmProvider::Claude, "key", "Recommend".to_string(), &LlmOptions { tools: vec![add_source()], ..options }).await.unwrap();
+        assert_eq!(response.text, "I'll add it.");
+        assert_eq!(ToolReply::from(response), expected_call());
+    }
+
+    #[tokio::test]
+    async fn test_text_reply_when_no_tool_is_called() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "content": [{ "type": "text", "text": "None of these fit." }],
+                "stop_reason": "end_turn"
+            })))
+            .mount(&server)
+            .await;
+        let reply = call_llm_with_tools(&reqwest::Client::new(), LlmProvider::Claude, "key", "Recommend".to_string(), &[add_source()], &tool_options(&server, "claude-test")).await;
+        assert_eq!(reply.unwrap(), ToolReply::Text("None of these fit.".to_string()));
+    }
+}
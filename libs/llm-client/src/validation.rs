@@ -0,0 +1,372 @@
+//! Checks of `sources.json` entries, so a bad entry is reported when the list is loaded
+//! instead of surfacing as a confusing fetch error mid-run.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::IpAddr;
+
+use reqwest::header::{HeaderName, HeaderValue};
+use url::{Host, Url};
+
+use crate::bluesky::bluesky_feed_url;
+use crate::fetch::expand_env_vars;
+use crate::youtube::youtube_feed_url;
+use crate::{BasicAuth, SourceConfig, SourceType};
+
+/// Environment variable allowing sources on localhost or IP literals, e.g. for a local mirror.
+pub const ALLOW_LOCAL_SOURCES_ENV_VAR: &str = "SOURCES_ALLOW_LOCAL_HOSTS";
+
+/// Longest source name accepted by default; names are shown in prompts and reports.
+pub const DEFAULT_MAX_SOURCE_NAME_LEN: usize = 100;
+
+/// What [`SourceConfig::validate_with`] accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceRules {
+    pub max_name_len: usize,
+    /// Accept `localhost` and IP-literal hosts.
+    pub allow_local_hosts: bool,
+}
+
+impl Default for SourceRules {
+    fn default() -> Self {
+        Self { max_name_len: DEFAULT_MAX_SOURCE_NAME_LEN, allow_local_hosts: false }
+    }
+}
+
+impl SourceRules {
+    /// Defaults, with local hosts allowed when `SOURCES_ALLOW_LOCAL_HOSTS` is `true` or `1`.
+    pub fn from_env() -> Self {
+        let allow_local_hosts = std::env::var(ALLOW_LOCAL_SOURCES_ENV_VAR)
+            .is_ok_and(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true"));
+        Self { allow_local_hosts, ..Self::default() }
+    }
+}
+
+/// A rule a source breaks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    EmptyName,
+    NameTooLong { len: usize, max: usize },
+    UnknownType { name: String },
+    InvalidUrl { url: String, reason: String },
+    UnsupportedScheme { url: String, scheme: String },
+    LocalHost { host: String },
+    InvalidHeader { name: String, reason: String },
+    /// `basic_auth` could never be sent, or an `Authorization` header would replace it
+    InvalidBasicAuth { reason: String },
+    /// `timeout_secs`, `max_items` or `max_pages` is 0, which would never fetch anything
+    ZeroLimit { field: &'static str },
+    /// A scrape source lacks one of the selectors every item needs
+    MissingSelector { field: &'static str },
+    /// An entry of `languages` is neither a two-letter ISO 639-1 code nor `*`
+    InvalidLanguage { code: String },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::EmptyName => f.write_str("name is empty"),
+            ValidationError::NameTooLong { len, max } => write!(f, "name is {} characters long; shorten it to at most {}", len, max),
+            ValidationError::UnknownType { name } => write!(f, "type '{}' is not supported; use rss, atom, hackernews, lobsters, youtube, scrape or bluesky", name),
+            ValidationError::InvalidUrl { url, reason } => {
+                write!(f, "url '{}' is not a valid URL ({}); give the full address, e.g. https://example.com/feed", url, reason)
+            }
+            ValidationError::UnsupportedScheme { url, scheme } => write!(f, "url '{}' uses {}; only http and https are fetched", url, scheme),
+            ValidationError::LocalHost { host } => {
+                write!(f, "url host '{}' is local or an IP address; use a public hostname or set {}=true", host, ALLOW_LOCAL_SOURCES_ENV_VAR)
+            }
+            ValidationError::InvalidHeader { name, reason } => write!(f, "header '{}' {}", name, reason),
+            ValidationError::InvalidBasicAuth { reason } => write!(f, "basic_auth {}", reason),
+            ValidationError::ZeroLimit { field } => write!(f, "{} is 0; remove it to use the default", field),
+            ValidationError::MissingSelector { field } => write!(f, "{} is missing; scrape sources need item_selector, title_selector and link_selector", field),
+            ValidationError::InvalidLanguage { code } => write!(f, "language '{}' is not an ISO 639-1 code such as en or de, nor *", code),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl SourceConfig {
+    /// Every rule this source breaks under [`SourceRules::default`]; empty when it's valid.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        self.validate_with(&SourceRules::default())
+    }
+
+    /// Every rule this source breaks under `rules`.
+    pub fn validate_with(&self, rules: &SourceRules) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        let name_len = self.name.trim().chars().count();
+        if name_len == 0 {
+            errors.push(ValidationError::EmptyName);
+        } else if name_len > rules.max_name_len {
+            errors.push(ValidationError::NameTooLong { len: name_len, max: rules.max_name_len });
+        }
+        if !self.source_type.is_known() {
+            errors.push(ValidationError::UnknownType { name: self.source_type.to_string() });
+        }
+        // A YouTube channel may be given by ID and a Bluesky account by handle; their feeds
+        // are what gets fetched
+        let feed_url = match self.source_type {
+            SourceType::Youtube => youtube_feed_url(&self.url),
+            SourceType::Bluesky => bluesky_feed_url(&self.url),
+            _ => None,
+        };
+        errors.extend(check_url(feed_url.as_deref().unwrap_or(&self.url), rules));
+        errors.extend(self.headers.iter().filter_map(|(name, value)| check_header(name, value)));
+        if let Some(auth) = &self.basic_auth {
+            errors.extend(check_basic_auth(auth, &self.headers));
+        }
+        if self.timeout_secs == Some(0) {
+            errors.push(ValidationError::ZeroLimit { field: "timeout_secs" });
+        }
+        if self.max_items == Some(0) {
+            errors.push(ValidationError::ZeroLimit { field: "max_items" });
+        }
+        if self.max_pages == Some(0) {
+            errors.push(ValidationError::ZeroLimit { field: "max_pages" });
+        }
+        for code in self.languages.iter().map(|code| code.trim()).filter(|code| !code.is_empty()) {
+            if code != "*" && !(code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic())) {
+                errors.push(ValidationError::InvalidLanguage { code: code.to_string() });
+            }
+        }
+        if self.source_type == SourceType::Scrape {
+            for (field, selector) in [("item_selector", &self.item_selector), ("title_selector", &self.title_selector), ("link_selector", &self.link_selector)] {
+                if selector.as_deref().is_none_or(|s| s.trim().is_empty()) {
+                    errors.push(ValidationError::MissingSelector { field });
+                }
+            }
+        }
+        errors
+    }
+}
+
+/// Header names and values that could never be sent. Variables aren't looked up here, only
+/// the `${VAR}` syntax is checked, so a list can be validated without the secrets.
+fn check_header(name: &str, value: &str) -> Option<ValidationError> {
+    let invalid = |reason: &str| Some(ValidationError::InvalidHeader { name: name.to_string(), reason: reason.to_string() });
+    if HeaderName::from_bytes(name.trim().as_bytes()).is_err() {
+        return invalid("is not a valid header name; use letters, digits and dashes, e.g. Authorization");
+    }
+    match expand_env_vars(value, |_| Some(String::new())) {
+        Err(reason) => invalid(&reason),
+        Ok(value) if HeaderValue::from_str(&value).is_err() => invalid("has characters not allowed in a header value"),
+        Ok(_) => None,
+    }
+}
+
+/// A `basic_auth` that couldn't be sent, checked like [`check_header`] without the secrets.
+fn check_basic_auth(auth: &BasicAuth, headers: &BTreeMap<String, String>) -> Option<ValidationError> {
+    let invalid = |reason: String| Some(ValidationError::InvalidBasicAuth { reason });
+    if headers.keys().any(|name| name.trim().eq_ignore_ascii_case("authorization")) {
+        return invalid("is set along with an Authorization header; keep one of them".to_string());
+    }
+    for (field, value) in [("username", &auth.username), ("password", &auth.password)] {
+        if let Err(reason) = expand_env_vars(value, |_| Some(String::new())) {
+            return invalid(format!("{}: {}", field, reason));
+        }
+    }
+    if auth.username.trim().is_empty() {
+        return invalid("username is empty".to_string());
+    }
+    if auth.username.contains(':') {
+        return invalid("username contains ':', which basic auth can't send".to_string());
+    }
+    None
+}
+
+fn check_url(url: &str, rules: &SourceRules) -> Option<ValidationError> {
+    let parsed = match Url::parse(url.trim()) {
+        Ok(parsed) => parsed,
+        Err(e) => return Some(ValidationError::InvalidUrl { url: url.to_string(), reason: e.to_string() }),
+    };
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Some(ValidationError::UnsupportedScheme { url: url.to_string(), scheme: parsed.scheme().to_string() });
+    }
+    let host = parsed.host()?;
+    let local = match &host {
+        Host::Domain(domain) => {
+            let domain = domain.trim_end_matches('.');
+            domain == "localhost" || domain.ends_with(".localhost") || domain.parse::<IpAddr>().is_ok()
+        }
+        Host::Ipv4(_) | Host::Ipv6(_) => true,
+    };
+    (local && !rules.allow_local_hosts).then(|| ValidationError::LocalHost { host: host.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(name: &str, url: &str) -> SourceConfig {
+        SourceConfig::new(name, SourceType::Rss, url)
+    }
+
+    #[test]
+    fn test_valid_sources() {
+        for url in ["https://netflixtechblog.com/feed", "http://blog.example.com/rss.xml", "https://EXAMPLE.com:8443/atom"] {
+            assert_eq!(source("Blog", url).validate(), vec![], "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_url_rules() {
+        for (url, expected) in [
+            ("example.com/feed", "not a valid URL"),
+            ("", "not a valid URL"),
+            ("https://", "not a valid URL"),
+            ("ftp://example.com/feed", "uses ftp"),
+            ("file:///etc/feed.xml", "uses file"),
+            ("http://localhost:8080/feed", "is local"),
+            ("http://api.localhost/feed", "is local"),
+            ("http://127.0.0.1/feed", "is local"),
+            ("http://10.0.0.8:3000/rss", "is local"),
+            ("http://[::1]/feed", "is local"),
+        ] {
+            let errors = source("Blog", url).validate();
+            assert_eq!(errors.len(), 1, "{}: {:?}", url, errors);
+            assert!(errors[0].to_string().contains(expected), "{}: {}", url, errors[0]);
+        }
+    }
+
+    #[test]
+    fn test_youtube_channel_ids_are_valid() {
+        for url in ["UCP9g8dHw5VmPVJpy1Dqq4Lw", "https://www.youtube.com/@StrangeLoopConf"] {
+            assert_eq!(SourceConfig::new("Strange Loop", SourceType::Youtube, url).validate(), vec![], "{}", url);
+        }
+        assert_eq!(SourceConfig::new("Strange Loop", SourceType::Rss, "UCP9g8dHw5VmPVJpy1Dqq4Lw").validate().len(), 1);
+    }
+
+    #[test]
+    fn test_bluesky_accounts_are_valid() {
+        for url in ["@simonwillison.net", "at://did:plc:z72i7hdynmk6r22z27h6tvur", "https://bsky.app/profile/danabra.mov"] {
+            assert_eq!(SourceConfig::new("Bluesky", SourceType::Bluesky, url).validate(), vec![], "{}", url);
+        }
+        assert_eq!(SourceConfig::new("Bluesky", SourceType::Bluesky, "not a handle").validate().len(), 1);
+    }
+
+    #[test]
+    fn test_scrape_sources_need_selectors() {
+        let scrape = SourceConfig {
+            item_selector: Some("article.post-card".to_string()),
+            title_selector: Some("h2".to_string()),
+            link_selector: Some("a".to_string()),
+            ..SourceConfig::new("Blog", SourceType::Scrape, "https://blog.example.com/")
+        };
+        assert_eq!(scrape.validate(), vec![]);
+
+        let missing = SourceConfig { title_selector: Some(" ".to_string()), link_selector: None, ..scrape.clone() };
+        assert_eq!(
+            missing.validate(),
+            vec![ValidationError::MissingSelector { field: "title_selector" }, ValidationError::MissingSelector { field: "link_selector" }]
+        );
+        assert!(missing.validate()[0].to_string().contains("title_selector is missing"));
+        // Selectors mean nothing to other types
+        assert_eq!(SourceConfig { source_type: SourceType::Rss, item_selector: None, ..scrape }.validate(), vec![]);
+    }
+
+    #[test]
+    fn test_local_hosts_can_be_allowed() {
+        let rules = SourceRules { allow_local_hosts: true, ..SourceRules::default() };
+        assert_eq!(source("Mirror", "http://127.0.0.1:8080/feed").validate_with(&rules), vec![]);
+        assert_eq!(source("Mirror", "http://localhost/feed").validate_with(&rules), vec![]);
+    }
+
+    #[test]
+    fn test_name_rules() {
+        for (name, expected) in [
+            ("", Some(ValidationError::EmptyName)),
+            ("   ", Some(ValidationError::EmptyName)),
+            ("Blog", None),
+            (&"x".repeat(100), None),
+            (&"x".repeat(101), Some(ValidationError::NameTooLong { len: 101, max: 100 })),
+        ] {
+            assert_eq!(source(name, "https://example.com/feed").validate().first(), expected.as_ref(), "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn test_header_rules() {
+        for (name, value, expected) in [
+            ("Authorization", "Bearer ${ENG_BLOG_TOKEN}", None),
+            ("Referer", "https://example.com/", None),
+            ("X-Api-Key", "${UNSET_IS_FINE_HERE}", None),
+            ("", "x", Some("not a valid header name")),
+            ("Bad Header", "x", Some("not a valid header name")),
+            ("X-Token:", "x", Some("not a valid header name")),
+            ("Authorization", "Bearer ${TOKEN", Some("unclosed")),
+            ("Referer", "https://example.com/\r\nX-Injected: 1", Some("characters not allowed")),
+        ] {
+            let mut source = source("Blog", "https://example.com/feed");
+            source.headers.insert(name.to_string(), value.to_string());
+            let errors = source.validate();
+            match expected {
+                None => assert_eq!(errors, vec![], "{:?}", (name, value)),
+                Some(expected) => {
+                    assert_eq!(errors.len(), 1, "{:?}: {:?}", (name, value), errors);
+                    assert!(errors[0].to_string().contains(expected), "{:?}: {}", (name, value), errors[0]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_basic_auth_rules() {
+        let auth = |username: &str, password: &str| Some(BasicAuth { username: username.to_string(), password: password.to_string() });
+        for (basic_auth, authorization, expected) in [
+            (auth("reader", "${ENG_BLOG_PASSWORD}"), None, None),
+            (auth("${ENG_BLOG_USER}", "${ENG_BLOG_PASSWORD}"), None, None),
+            (auth("reader", "${ENG_BLOG_PASSWORD"), None, Some("password: unclosed")),
+            (auth(" ", "x"), None, Some("username is empty")),
+            (auth("team:reader", "x"), None, Some("contains ':'")),
+            (auth("reader", "x"), Some("Bearer ${TOKEN}"), Some("along with an Authorization header")),
+        ] {
+            let mut source = source("Blog", "https://example.com/feed");
+            source.basic_auth = basic_auth.clone();
+            if let Some(value) = authorization {
+                source.headers.insert("authorization".to_string(), value.to_string());
+            }
+            let errors = source.validate();
+            match expected {
+                None => assert_eq!(errors, vec![], "{:?}", basic_auth),
+                Some(expected) => {
+                    assert_eq!(errors.len(), 1, "{:?}: {:?}", basic_auth, errors);
+                    assert!(errors[0].to_string().contains(expected), "{:?}: {}", basic_auth, errors[0]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_limit_rules() {
+        let limited = SourceConfig { timeout_secs: Some(120), max_items: Some(30), max_pages: Some(2), ..source("Podcast", "https://example.com/feed") };
+        assert_eq!(limited.validate(), vec![]);
+        let zeroed = SourceConfig { timeout_secs: Some(0), max_items: Some(0), max_pages: Some(0), ..limited };
+        assert_eq!(zeroed.validate(), vec![
+            ValidationError::ZeroLimit { field: "timeout_secs" },
+            ValidationError::ZeroLimit { field: "max_items" },
+            ValidationError::ZeroLimit { field: "max_pages" },
+        ]);
+    }
+
+    #[test]
+    fn test_language_rule() {
+        let german = SourceConfig { languages: vec!["DE".to_string(), " en ".to_string(), "*".to_string()], ..source("Heise", "https://example.com/feed") };
+        assert_eq!(german.validate(), vec![]);
+        let bad = SourceConfig { languages: vec!["german".to_string(), "de-AT".to_string()], ..german };
+        assert_eq!(bad.validate(), vec![
+            ValidationError::InvalidLanguage { code: "german".to_string() },
+            ValidationError::InvalidLanguage { code: "de-AT".to_string() },
+        ]);
+        assert!(bad.validate()[0].to_string().contains("such as en or de"));
+    }
+
+    #[test]
+    fn test_type_rule_and_several_errors() {
+        let bad = SourceConfig::new("", SourceType::Unknown("reddit".to_string()), "example.com");
+        let errors = bad.validate();
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert_eq!(errors[1], ValidationError::UnknownType { name: "reddit".to_string() });
+        assert!(errors[1].to_string().contains("use rss, atom, hackernews, lobsters, youtube, scrape or bluesky"));
+    }
+}
@@ -0,0 +1,97 @@
+//! YouTube channel sources: from a channel URL or ID to the channel's Atom feed.
+
+use crate::fetch::{fetch_bytes_with, FetchError, FetchOptions};
+use crate::retry::RetryPolicy;
+
+const FEED_BASE: &str = "https://www.youtube.com/feeds/videos.xml?channel_id=";
+const CHANNEL_ID_LEN: usize = 24;
+
+fn is_channel_id(id: &str) -> bool {
+    id.len() == CHANNEL_ID_LEN && id.starts_with("UC") && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The first channel ID after `/channel/` in `text`.
+fn channel_id_after_path(text: &str) -> Option<&str> {
+    text.match_indices("/channel/").find_map(|(at, prefix)| {
+        let id = text.get(at + prefix.len()..at + prefix.len() + CHANNEL_ID_LEN)?;
+        is_channel_id(id).then_some(id)
+    })
+}
+
+/// Feed URL of a YouTube source given as a channel ID (`UC...`), a `/channel/<id>` URL or
+/// the feed URL itself. `None` for other forms, such as `@handle` URLs.
+pub fn youtube_feed_url(source_url: &str) -> Option<String> {
+    let source_url = source_url.trim();
+    if source_url.contains("/feeds/videos.xml") {
+        return Some(source_url.to_string());
+    }
+    if is_channel_id(source_url) {
+        return Some(format!("{}{}", FEED_BASE, source_url));
+    }
+    channel_id_after_path(source_url).map(|id| format!("{}{}", FEED_BASE, id))
+}
+
+/// Feed URL of the channel whose page is `page`, read from its canonical link; how
+/// [`resolve_youtube_feed_url`] handles URLs like `https://www.youtube.com/@GOTO-`.
+pub fn youtube_feed_url_from_page(page: &str) -> Option<String> {
+    channel_id_after_path(page).map(|id| format!("{}{}", FEED_BASE, id))
+}
+
+/// [`youtube_feed_url`], falling back to reading the channel ID from the channel page's
+/// canonical link for URLs like `https://www.youtube.com/@GOTO-`. The page is fetched with
+/// `options`, e.g. the source's headers and timeout.
+pub async fn resolve_youtube_feed_url(client: &reqwest::Client, source_url: &str, options: &FetchOptions, policy: &RetryPolicy) -> Result<String, FetchError> {
+    if let Some(feed_url) = youtube_feed_url(source_url) {
+        return Ok(feed_url);
+    }
+    let page = fetch_bytes_with(client, source_url.trim(), options, policy).await?;
+    youtube_feed_url_from_page(&String::from_utf8_lossy(&page))
+        .ok_or_else(|| FetchError { url: source_url.to_string(), status: None, message: "no YouTube channel ID on the page".to_string(), network: None })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const FEED: &str = "https://www.youtube.com/feeds/videos.xml?channel_id=UCP9g8dHw5VmPVJpy1Dqq4Lw";
+
+    #[test]
+    fn test_youtube_feed_url() {
+        for (source_url, expected) in [
+            ("UCP9g8dHw5VmPVJpy1Dqq4Lw", Some(FEED)),
+            (" https://www.youtube.com/channel/UCP9g8dHw5VmPVJpy1Dqq4Lw/videos ", Some(FEED)),
+            (FEED, Some(FEED)),
+            ("https://www.youtube.com/@StrangeLoopConf", None),
+            ("https://www.youtube.com/channel/UCshort", None),
+            ("UC-not-an-id", None),
+        ] {
+            assert_eq!(youtube_feed_url(source_url).as_deref(), expected, "{}", source_url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_urls_resolve_through_the_channel_page() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET")).and(path("/@StrangeLoopConf")).and(header("referer", "https://example.com/"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<html><head><link rel="canonical" href="https://www.youtube.com/channel/UCP9g8dHw5VmPVJpy1Dqq4Lw"></head></html>"#,
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET")).and(path("/@nobody"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let url = format!("{}/@StrangeLoopConf", server.uri());
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::REFERER, "https://example.com/".parse().unwrap());
+        let options = FetchOptions { headers, ..Default::default() };
+        assert_eq!(resolve_youtube_feed_url(&client, &url, &options, &RetryPolicy::FAST_LOCAL).await.unwrap(), FEED);
+        let err = resolve_youtube_feed_url(&client, &format!("{}/@nobody", server.uri()), &options, &RetryPolicy::FAST_LOCAL).await.unwrap_err();
+        assert!(err.to_string().contains("no YouTube channel ID"), "{}", err);
+    }
+}